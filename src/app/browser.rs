@@ -0,0 +1,200 @@
+use crate::widget::list::StatefulList;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entry of the file browser.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileEntry {
+	/// Path of the entry.
+	pub path: PathBuf,
+	/// Whether if the entry is a directory.
+	pub is_dir: bool,
+}
+
+impl FileEntry {
+	/// Returns the name of the entry.
+	///
+	/// Directories end with `/`.
+	pub fn get_name(&self) -> String {
+		let name = self
+			.path
+			.file_name()
+			.map(|name| name.to_string_lossy().to_string())
+			.unwrap_or_else(|| String::from(".."));
+		if self.is_dir {
+			format!("{name}/")
+		} else {
+			name
+		}
+	}
+}
+
+/// Built-in file browser for selecting files.
+///
+/// It is used when the file explorer is not installed.
+#[derive(Debug)]
+pub struct FileBrowser {
+	/// Current directory.
+	pub dir: PathBuf,
+	/// Entries of the current directory.
+	pub entries: StatefulList<FileEntry>,
+	/// Selected files.
+	pub selected: Vec<PathBuf>,
+	/// Whether if the files are selected for encryption.
+	pub encrypt: bool,
+	/// Error of reading the current directory.
+	pub error: Option<String>,
+}
+
+impl FileBrowser {
+	/// Constructs a new instance of `FileBrowser`.
+	pub fn new(dir: PathBuf, encrypt: bool) -> Self {
+		let mut browser = Self {
+			dir,
+			entries: StatefulList::with_items(Vec::new()),
+			selected: Vec::new(),
+			encrypt,
+			error: None,
+		};
+		browser.read_dir();
+		browser
+	}
+
+	/// Reads the entries of the current directory.
+	///
+	/// Directories are listed first.
+	fn read_dir(&mut self) {
+		let mut entries = match fs::read_dir(&self.dir) {
+			Ok(entries) => {
+				self.error = None;
+				entries
+					.filter_map(|entry| entry.ok())
+					.map(|entry| FileEntry {
+						is_dir: entry.path().is_dir(),
+						path: entry.path(),
+					})
+					.collect::<Vec<FileEntry>>()
+			}
+			Err(e) => {
+				self.error = Some(e.to_string());
+				Vec::new()
+			}
+		};
+		entries
+			.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.path.cmp(&b.path)));
+		self.entries = StatefulList::with_items(entries);
+		if !self.entries.items.is_empty() {
+			self.entries.state.select(Some(0));
+		}
+	}
+
+	/// Changes the current directory.
+	fn change_dir(&mut self, dir: &Path) {
+		self.dir = dir.to_path_buf();
+		self.read_dir();
+	}
+
+	/// Highlights the next entry.
+	pub fn select_next(&mut self) {
+		if !self.entries.items.is_empty() {
+			self.entries.next();
+		}
+	}
+
+	/// Highlights the previous entry.
+	pub fn select_previous(&mut self) {
+		if !self.entries.items.is_empty() {
+			self.entries.previous();
+		}
+	}
+
+	/// Switches to the parent directory.
+	pub fn go_up(&mut self) {
+		if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+			self.change_dir(&parent);
+		}
+	}
+
+	/// Toggles the selection of the highlighted file.
+	pub fn toggle(&mut self) {
+		if let Some(entry) = self.entries.selected().filter(|v| !v.is_dir) {
+			let path = entry.path.clone();
+			match self.selected.iter().position(|v| v == &path) {
+				Some(i) => {
+					self.selected.remove(i);
+				}
+				None => self.selected.push(path),
+			}
+		}
+	}
+
+	/// Checks if the given path is selected.
+	pub fn is_selected(&self, path: &Path) -> bool {
+		self.selected.iter().any(|v| v == path)
+	}
+
+	/// Opens the highlighted entry.
+	///
+	/// Returns the selected files (or the highlighted file if
+	/// nothing is selected) if the entry is not a directory.
+	pub fn open(&mut self) -> Option<Vec<String>> {
+		let entry = self.entries.selected()?.clone();
+		if entry.is_dir {
+			self.change_dir(&entry.path);
+			None
+		} else if self.selected.is_empty() {
+			Some(vec![entry.path.to_string_lossy().to_string()])
+		} else {
+			Some(
+				self.selected
+					.iter()
+					.map(|path| path.to_string_lossy().to_string())
+					.collect(),
+			)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use pretty_assertions::assert_eq;
+	use std::fs::File;
+	#[test]
+	fn test_app_browser() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let dir = temp_dir.path().to_path_buf();
+		fs::create_dir(dir.join("subdir"))?;
+		File::create(dir.join("a.asc"))?;
+		File::create(dir.join("b.asc"))?;
+		let mut browser = FileBrowser::new(dir.to_path_buf(), false);
+		assert_eq!(
+			vec!["subdir/", "a.asc", "b.asc"],
+			browser
+				.entries
+				.items
+				.iter()
+				.map(|v| v.get_name())
+				.collect::<Vec<String>>()
+		);
+		browser.toggle();
+		assert!(browser.selected.is_empty());
+		browser.select_next();
+		browser.select_next();
+		browser.toggle();
+		assert!(browser.is_selected(&dir.join("b.asc")));
+		browser.select_previous();
+		assert_eq!(
+			Some(vec![dir.join("b.asc").to_string_lossy().to_string()]),
+			browser.open()
+		);
+		browser.select_previous();
+		assert_eq!(None, browser.open());
+		assert_eq!(dir.join("subdir"), browser.dir);
+		assert!(browser.entries.selected().is_none());
+		browser.go_up();
+		assert_eq!(dir, browser.dir);
+		Ok(())
+	}
+}
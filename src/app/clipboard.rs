@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use copypasta_ext::display::DisplayServer;
+use copypasta_ext::ClipboardProviderExt;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Default command for copying to the clipboard.
+#[cfg(target_os = "windows")]
+pub const DEFAULT_COPY_COMMAND: &str = "powershell -NoProfile -Command \
+	[Console]::InputEncoding = [Text.Encoding]::UTF8; \
+	Set-Clipboard -Value $input";
+
+/// Default command for pasting from the clipboard.
+#[cfg(target_os = "windows")]
+pub const DEFAULT_PASTE_COMMAND: &str =
+	"powershell -NoProfile -Command Get-Clipboard -Raw";
+
+/// Default command for copying to the clipboard.
+#[cfg(target_os = "macos")]
+pub const DEFAULT_COPY_COMMAND: &str = "pbcopy";
+
+/// Default command for pasting from the clipboard.
+#[cfg(target_os = "macos")]
+pub const DEFAULT_PASTE_COMMAND: &str = "pbpaste";
+
+/// Default command for copying to the clipboard.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const DEFAULT_COPY_COMMAND: &str = "xclip -selection clipboard -i";
+
+/// Default command for pasting from the clipboard.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const DEFAULT_PASTE_COMMAND: &str = "xclip -selection clipboard -o";
+
+/// Type of the clipboard backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ClipboardType {
+	/// Select the backend depending on the environment.
+	Auto,
+	/// X11 clipboard.
+	X11,
+	/// Wayland clipboard.
+	Wayland,
+	/// OSC 52 escape sequence. (works over SSH)
+	Osc52,
+	/// Custom copy/paste commands.
+	Command,
+	/// Disable the clipboard.
+	None,
+}
+
+impl Default for ClipboardType {
+	fn default() -> Self {
+		Self::Auto
+	}
+}
+
+impl Display for ClipboardType {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(f, "{}", format!("{self:?}").to_lowercase())
+	}
+}
+
+/// Clipboard backend for copying/pasting values.
+pub trait ClipboardBackend {
+	/// Returns the type of the backend.
+	fn get_type(&self) -> ClipboardType;
+
+	/// Returns the clipboard contents.
+	fn get_contents(&mut self) -> Result<String>;
+
+	/// Sets the clipboard contents.
+	fn set_contents(&mut self, contents: String) -> Result<()>;
+}
+
+/// Clipboard backend that uses a [`copypasta_ext`] provider.
+pub struct ProviderClipboard {
+	/// Type of the backend.
+	clipboard_type: ClipboardType,
+	/// Clipboard provider.
+	provider: Box<dyn ClipboardProviderExt>,
+}
+
+impl ClipboardBackend for ProviderClipboard {
+	fn get_type(&self) -> ClipboardType {
+		self.clipboard_type
+	}
+
+	fn get_contents(&mut self) -> Result<String> {
+		self.provider.get_contents().map_err(|e| anyhow!("{e}"))
+	}
+
+	fn set_contents(&mut self, contents: String) -> Result<()> {
+		self.provider
+			.set_contents(contents)
+			.map_err(|e| anyhow!("{e}"))
+	}
+}
+
+/// Clipboard backend that runs OS commands.
+#[derive(Debug)]
+pub struct CommandClipboard {
+	/// Command for copying to the clipboard.
+	copy_command: String,
+	/// Command for pasting from the clipboard.
+	paste_command: String,
+}
+
+impl CommandClipboard {
+	/// Returns the shell command for running the given command.
+	fn get_shell_command(cmd: &str) -> Command {
+		let mut command;
+		if cfg!(target_os = "windows") {
+			command = Command::new("cmd");
+			command.args(["/C", cmd]);
+		} else {
+			command = Command::new("sh");
+			command.args(["-c", cmd]);
+		}
+		command
+	}
+}
+
+impl ClipboardBackend for CommandClipboard {
+	fn get_type(&self) -> ClipboardType {
+		ClipboardType::Command
+	}
+
+	fn get_contents(&mut self) -> Result<String> {
+		let output = Self::get_shell_command(&self.paste_command).output()?;
+		if output.status.success() {
+			Ok(String::from_utf8(output.stdout)?
+				.trim_end_matches(['\r', '\n'])
+				.to_string())
+		} else {
+			Err(anyhow!("paste command exited with {}", output.status))
+		}
+	}
+
+	fn set_contents(&mut self, contents: String) -> Result<()> {
+		let mut child = Self::get_shell_command(&self.copy_command)
+			.stdin(Stdio::piped())
+			.spawn()?;
+		if let Some(mut stdin) = child.stdin.take() {
+			stdin.write_all(contents.as_bytes())?;
+		}
+		let status = child.wait()?;
+		if status.success() {
+			Ok(())
+		} else {
+			Err(anyhow!("copy command exited with {status}"))
+		}
+	}
+}
+
+/// Disabled clipboard.
+#[derive(Debug)]
+pub struct NoClipboard;
+
+impl ClipboardBackend for NoClipboard {
+	fn get_type(&self) -> ClipboardType {
+		ClipboardType::None
+	}
+
+	fn get_contents(&mut self) -> Result<String> {
+		Err(anyhow!("clipboard not available"))
+	}
+
+	fn set_contents(&mut self, _: String) -> Result<()> {
+		Err(anyhow!("clipboard not available"))
+	}
+}
+
+/// Initializes the clipboard backend of the given type.
+///
+/// On Windows, [`Auto`] falls back to the [`Command`] backend
+/// if the native clipboard is not available.
+///
+/// [`Auto`]: ClipboardType::Auto
+/// [`Command`]: ClipboardType::Command
+pub fn init(
+	clipboard_type: ClipboardType,
+	copy_command: &str,
+	paste_command: &str,
+) -> Result<Box<dyn ClipboardBackend>> {
+	let display_server = match clipboard_type {
+		ClipboardType::Auto => DisplayServer::select(),
+		ClipboardType::X11 => DisplayServer::X11,
+		ClipboardType::Wayland => DisplayServer::Wayland,
+		ClipboardType::Osc52 => DisplayServer::Tty,
+		ClipboardType::Command => {
+			return Ok(Box::new(CommandClipboard {
+				copy_command: copy_command.to_string(),
+				paste_command: paste_command.to_string(),
+			}))
+		}
+		ClipboardType::None => return Ok(Box::new(NoClipboard)),
+	};
+	match display_server.try_context() {
+		Some(provider) => Ok(Box::new(ProviderClipboard {
+			clipboard_type,
+			provider,
+		})),
+		None if cfg!(target_os = "windows")
+			&& clipboard_type == ClipboardType::Auto =>
+		{
+			log::debug!(target: "clipboard", "falling back to OS commands");
+			init(ClipboardType::Command, copy_command, paste_command)
+		}
+		None => Err(anyhow!(
+			"no suitable clipboard provider found for {clipboard_type}"
+		)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_app_clipboard_backend() -> Result<()> {
+		assert_eq!("osc52", ClipboardType::Osc52.to_string());
+		assert_eq!(
+			ClipboardType::Wayland,
+			ClipboardType::from_str("wayland", true).unwrap()
+		);
+		let mut clipboard = init(ClipboardType::None, "", "")?;
+		assert_eq!(ClipboardType::None, clipboard.get_type());
+		assert!(clipboard.get_contents().is_err());
+		assert!(clipboard.set_contents(String::from("test")).is_err());
+		let clipboard = init(
+			ClipboardType::Command,
+			DEFAULT_COPY_COMMAND,
+			DEFAULT_PASTE_COMMAND,
+		)?;
+		assert_eq!(ClipboardType::Command, clipboard.get_type());
+		Ok(())
+	}
+}
@@ -1,12 +1,19 @@
 use crate::app::mode::Mode;
-use crate::app::prompt::OutputType;
+use crate::app::prompt::{OutputType, COMMAND_PREFIX};
 use crate::app::selection::Selection;
 use crate::app::style::Style;
-use crate::gpg::key::KeyType;
+use crate::gpg::edit::{
+	format_user_id, parse_trust_level, KeyEdit, EDIT_COMMANDS,
+};
+use crate::gpg::key::{KeyFilter, KeyState, KeyType};
+use crate::gpg::keygen::{self, KeyGenParams};
+use crate::gpg::signature::SignatureType;
 use crate::widget::row::ScrollDirection;
+use crate::widget::secret::SecretInput;
 use clap::ValueEnum;
 use crossterm::event::KeyCode as Key;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::Path;
 use std::str::FromStr;
 use tui_logger::TuiWidgetEvent;
 
@@ -37,6 +44,48 @@ impl LoggerCommand {
 	}
 }
 
+/// Default number of days for warning about the expiring keys.
+pub const EXPIRY_WARNING_DAYS: u64 = 30;
+
+/// Default age (in days) of the export files to clean.
+pub const EXPORT_CLEANUP_DAYS: u64 = 30;
+
+/// Commands that take free text which might contain `>`.
+const FREE_TEXT_COMMANDS: &[&str] = &["search", "set", "s", "adduid", "edit"];
+
+/// Set of keys for the bulk operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySet {
+	/// Pinned keys.
+	Pinned,
+	/// Marked keys in visual mode.
+	Marked,
+}
+
+impl Display for KeySet {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Pinned => "pinned",
+				Self::Marked => "marked",
+			}
+		)
+	}
+}
+
+impl FromStr for KeySet {
+	type Err = ();
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"pinned" => Ok(Self::Pinned),
+			"marked" => Ok(Self::Marked),
+			_ => Err(()),
+		}
+	}
+}
+
 /// Command to run on rendering process.
 ///
 /// It specifies the main operation to perform on [`App`].
@@ -46,6 +95,8 @@ impl LoggerCommand {
 pub enum Command {
 	/// Confirm the execution of a command.
 	Confirm(Box<Command>),
+	/// Write the output of a command to a file.
+	Redirect(Box<Command>, String),
 	/// Show help.
 	ShowHelp,
 	/// Change application style.
@@ -57,27 +108,171 @@ pub enum Command {
 	/// List the public/secret keys.
 	ListKeys(KeyType),
 	/// Import public/secret keys from files or a keyserver.
-	ImportKeys(Vec<String>, bool),
+	///
+	/// Keyserver to receive the keys from can be optionally specified.
+	ImportKeys(Vec<String>, bool, Option<String>),
 	/// Import public/secret keys from clipboard.
 	ImportClipboard,
+	/// Show what would be imported from files. (dry run)
+	PreviewImport(Vec<String>),
+	/// Switch to a temporary keyring for inspecting the keys.
+	EnterSandbox,
+	/// Switch back to the actual keyring.
+	ExitSandbox,
+	/// Copy the key from the temporary keyring to the actual keyring.
+	PromoteKey(Option<String>),
 	/// Export the public/secret keys.
 	ExportKeys(KeyType, Vec<String>, bool),
+	/// Export the secret portions of the key as a paper backup.
+	///
+	/// Raw (QR-ready) data is exported instead of text if `true`.
+	ExportPaperKey(String, bool),
+	/// Export the authentication subkey in SSH format.
+	ExportSshKey(String),
+	/// Export the public key along with the keys of its signers.
+	ExportWithSigners(String),
+	/// Serve the public key over HTTP on the local network.
+	ServeKey(String),
+	/// Fetch the keys from the given URL for importing.
+	FetchKeys(String),
+	/// Import the fetched keys with the given URL and fingerprints.
+	ImportFetched(String, Vec<String>, Vec<u8>),
+	/// Export the public/secret keys to the given file.
+	ExportKeysTo(KeyType, Vec<String>, String),
+	/// Export the public keys in the set to separate files.
+	///
+	/// Files are saved to the given directory if it is specified.
+	ExportEach(KeySet, Option<String>),
+	/// Export the public keys as JSON.
+	///
+	/// Output file can be optionally specified.
+	ExportJson(Option<String>),
+	/// Clean the files in the output directory that are
+	/// older than the given number of days.
+	///
+	/// Secret key exports are overwritten before deleting if `true`.
+	CleanExports(u64, bool),
+	/// Delete the given files in the output directory.
+	RemoveExports(Vec<String>, bool),
+	/// Encrypt files for the picked recipients.
+	EncryptFiles(Vec<String>),
+	/// Encrypt files with a passphrase. (symmetric)
+	///
+	/// Passphrase is asked via prompt if it is not given.
+	EncryptSymmetric(Vec<String>, Option<SecretInput>),
+	/// Encrypt the clipboard contents for the given recipient.
+	EncryptClipboard(String),
+	/// Decrypt the clipboard contents.
+	DecryptClipboard,
+	/// Sign a file with the default key.
+	SignFile(String, SignatureType),
+	/// Convert a file to/from the ASCII armored form.
+	///
+	/// Existing output file is overwritten if the last flag is set.
+	ConvertArmor(String, bool, bool),
+	/// Verify the signature of a file.
+	///
+	/// Path of the detached signature can be optionally specified.
+	VerifyFile(String, Option<String>),
+	/// Verify a downloaded release with its detached signature.
+	///
+	/// Signature can be given as a path or URL.
+	VerifyRelease(String, String),
 	/// Delete the public/secret key.
 	DeleteKey(KeyType, String),
-	/// Send the key to the default keyserver.
-	SendKey(String),
+	/// Generate a revocation certificate for a key.
+	RevokeKey(String, Option<String>),
+	/// Apply the revocation certificate to the keyring.
+	ApplyRevocation(String, String),
+	/// Send the key to the given/default keyserver.
+	SendKey(String, Option<String>),
+	/// Select a keyserver for sending the key.
+	SelectKeyserver(String),
+	/// Compare the key with its keyserver copy.
+	DiffKey(String),
+	/// Compare the given fingerprint with the selected key.
+	CompareFingerprint(String),
+	/// Receive the unknown signer keys of a key.
+	LocateSigners(String),
+	/// Search the keyserver for keys.
+	SearchKeyserver(String),
+	/// Find the key of an email address.
+	///
+	/// Local keyring, WKD and keyserver are checked in order.
+	ResolveEmail(String),
+	/// Show the smartcard status.
+	ShowCard,
+	/// Select the key that contains the given (sub)key.
+	GoToKey(String),
+	/// Generate an HTML report of the keyring.
+	GenerateReport,
+	/// Show the keys that expire within the given number of days.
+	ShowExpiring(u64),
+	/// Show the history of the selected key.
+	ShowHistory,
+	/// Show all the details of the key in a separate tab.
+	InspectKey(String),
+	/// Show the selection as QR code.
+	ShowQrCode(Selection),
 	/// Edit a key.
 	EditKey(String),
-	/// Sign a key.
-	SignKey(String),
+	/// Run an edit operation on a key.
+	RunKeyEdit(String, KeyEdit),
+	/// Set the expiration time of a key (or its subkeys).
+	SetExpiration(String, String, Vec<String>),
+	/// Set the owner trust of the selected key.
+	SetOwnerTrust(Option<usize>),
+	/// Add a user ID to the selected key.
+	AddUserId(String),
+	/// Revoke the user ID of the selected key at the given index.
+	RevokeUserId(usize),
+	/// Set the user ID of the selected key at the given index as primary.
+	SetPrimaryUserId(usize),
+	/// Show the wizard for adding a subkey to a key.
+	AddSubkey(String),
+	/// Add a subkey to a key with the parameters from the wizard.
+	CreateSubkey(String, KeyGenParams),
+	/// Show/update the algorithm preferences of a key.
+	EditPreferences(String, bool),
+	/// Preset the passphrase of a key in gpg-agent.
+	///
+	/// Passphrase is asked via prompt if it is not given.
+	PresetPassphrase(String, Option<SecretInput>),
+	/// Change the passphrase of a key.
+	///
+	/// Current and new passphrases are asked via prompt
+	/// if they are not given.
+	ChangePassphrase(String, Option<SecretInput>, Option<SecretInput>),
+	/// Sign a key (with an optional signer).
+	SignKey(String, Option<String>),
 	/// Generate a new key pair.
 	GenerateKey,
+	/// Generate a new key pair using a profile (with the user ID).
+	GenerateKeyWithProfile(String, Option<String>),
+	/// Create a new key pair with the parameters from the wizard.
+	CreateKey(KeyGenParams),
 	/// Refresh the keyring.
 	RefreshKeys,
 	/// Copy a property to clipboard.
 	Copy(Selection),
+	/// Add/remove a key to/from the recipients.
+	ToggleRecipient(String),
+	/// Mark a key in visual mode.
+	///
+	/// Keys in range are marked if the key is not given.
+	Mark(Option<String>),
+	/// Run the commands for the marked keys.
+	Batch(Vec<Command>),
+	/// Pin/unpin a key.
+	///
+	/// Selected key is used if the key is not given.
+	TogglePin(Option<String>),
+	/// Select the next pinned key.
+	NextPinned,
 	/// Toggle the detail level.
 	ToggleDetail(bool),
+	/// Collapse/expand the subkeys and user IDs of the selected key.
+	ToggleCollapse,
 	/// Toggle the table size.
 	ToggleTableSize,
 	/// Scroll the current widget.
@@ -86,6 +281,8 @@ pub enum Command {
 	Set(String, String),
 	/// Get the value of an option.
 	Get(String),
+	/// Set the trust model in the GnuPG configuration.
+	SetTrustModel(String),
 	/// Switch the application mode.
 	SwitchMode(Mode),
 	/// Paste the clipboard contents.
@@ -94,6 +291,8 @@ pub enum Command {
 	EnableInput,
 	/// Search for a value.
 	Search(Option<String>),
+	/// Filter the keys by capability or state.
+	Filter(Option<KeyFilter>),
 	/// Select the next tab.
 	NextTab,
 	/// Select the previous tab.
@@ -102,10 +301,20 @@ pub enum Command {
 	Logs,
 	/// Logger event.
 	LoggerEvent(LoggerCommand),
+	/// Lock the screen.
+	Lock,
+	/// Sync the configuration with the GnuPG configuration files.
+	SyncConfig,
+	/// Reload the configuration file.
+	ReloadConfig,
+	/// Write the current settings to the configuration file.
+	WriteConfig,
 	/// Refresh the application.
 	Refresh,
 	/// Quit the application.
 	Quit,
+	/// Quit the application even if there are background operations.
+	ForceQuit,
 	/// Do nothing.
 	None,
 }
@@ -124,6 +333,9 @@ impl Display for Command {
 					match style {
 						Style::Plain => String::from("disable colors"),
 						Style::Colored => String::from("enable colors"),
+						Style::HighContrast => {
+							String::from("enable high contrast")
+						}
 					}
 				}
 				Command::ListKeys(key_type) => {
@@ -135,6 +347,18 @@ impl Display for Command {
 				Command::ImportClipboard => {
 					String::from("import key(s) from clipboard")
 				}
+				Command::PreviewImport(_) => {
+					String::from("preview the import (dry run)")
+				}
+				Command::EnterSandbox => {
+					String::from("switch to a temporary keyring")
+				}
+				Command::ExitSandbox => {
+					String::from("switch back to the actual keyring")
+				}
+				Command::PromoteKey(_) => {
+					String::from("copy the key to the actual keyring")
+				}
 				Command::ExportKeys(key_type, patterns, ref export_subkeys) => {
 					if patterns.is_empty() {
 						format!("export all the keys ({key_type})")
@@ -144,20 +368,185 @@ impl Display for Command {
 						format!("export the selected key ({key_type})")
 					}
 				}
+				Command::ExportPaperKey(_, false) => {
+					String::from("export the secret key as paper backup")
+				}
+				Command::ExportPaperKey(_, true) => {
+					String::from("export the secret key as paper backup (raw)")
+				}
+				Command::ExportSshKey(_) => {
+					String::from("export the authentication subkey (ssh)")
+				}
+				Command::ExportWithSigners(_) => {
+					String::from("export the key with its signers")
+				}
+				Command::ServeKey(_) => {
+					String::from("serve the key on the local network")
+				}
+				Command::FetchKeys(url) =>
+					format!("fetch the key(s) from {url}"),
+				Command::ImportFetched(url, fingerprints, _) => {
+					format!("import {} key(s) from {url}", fingerprints.len())
+				}
+				Command::ExportKeysTo(key_type, _, path) => {
+					format!("export the key(s) to {path} ({key_type})")
+				}
+				Command::ExportEach(key_set, dir) => format!(
+					"export the {key_set} keys to separate files{}",
+					dir.as_ref()
+						.map(|dir| format!(" in {dir}"))
+						.unwrap_or_default()
+				),
+				Command::ExportJson(_) =>
+					String::from("export the keys as JSON"),
+				Command::CleanExports(days, secure) => format!(
+					"clean the exports older than {days} days{}",
+					if *secure { " (secure)" } else { "" }
+				),
+				Command::RemoveExports(files, _) => format!(
+					"delete {} export file(s): {}",
+					files.len(),
+					files
+						.iter()
+						.filter_map(|file| Path::new(file).file_name())
+						.map(|file_name| file_name.to_string_lossy())
+						.collect::<Vec<_>>()
+						.join(", ")
+				),
+				Command::EncryptFiles(_) => {
+					String::from("encrypt file(s) for the picked recipients")
+				}
+				Command::EncryptSymmetric(_, _) => {
+					String::from("encrypt file(s) with a passphrase")
+				}
+				Command::EncryptClipboard(recipient) => {
+					format!("encrypt the clipboard for {recipient}")
+				}
+				Command::DecryptClipboard => {
+					String::from("decrypt the clipboard")
+				}
+				Command::SignFile(_, signature_type) => {
+					format!("sign the file ({signature_type} signature)")
+				}
+				Command::ConvertArmor(_, armor, overwrite) => format!(
+					"{}armor the file{}",
+					if *armor { "" } else { "de" },
+					if *overwrite {
+						" (overwrite the output)"
+					} else {
+						""
+					}
+				),
+				Command::VerifyFile(_, _) => String::from("verify the file"),
+				Command::VerifyRelease(file, _) => {
+					format!("verify the release ({file})")
+				}
 				Command::DeleteKey(key_type, _) =>
 					format!("delete the selected key ({key_type})"),
-				Command::SendKey(_) =>
-					String::from("send key to the keyserver"),
+				Command::RevokeKey(_, _) =>
+					String::from("generate a revocation certificate"),
+				Command::ApplyRevocation(_, path) =>
+					format!("apply the revocation certificate ({path})"),
+				Command::SendKey(_, keyserver) => match keyserver {
+					Some(keyserver) => format!("send key to {keyserver}"),
+					None => String::from("send key to the keyserver"),
+				},
+				Command::SelectKeyserver(_) =>
+					String::from("send key to a specific keyserver"),
+				Command::ImportKeys(_, true, keyserver) => match keyserver {
+					Some(keyserver) =>
+						format!("receive key(s) from {keyserver}"),
+					None => String::from("receive key(s) from keyserver"),
+				},
+				Command::DiffKey(_) =>
+					String::from("compare with the keyserver copy"),
+				Command::CompareFingerprint(_) =>
+					String::from("compare the fingerprint"),
+				Command::LocateSigners(_) =>
+					String::from("receive the unknown signer keys"),
+				Command::SearchKeyserver(query) =>
+					format!("search the keyserver for {query}"),
+				Command::ResolveEmail(email) =>
+					format!("find the key of {email}"),
+				Command::ShowCard => String::from("show the smartcard status"),
+				Command::GoToKey(_) => String::from("go to the key in keyring"),
+				Command::GenerateReport =>
+					String::from("generate a keyring report (html)"),
+				Command::ShowExpiring(days) =>
+					format!("show the keys expiring within {days} days"),
+				Command::ShowHistory => String::from("show the key history"),
+				Command::InspectKey(_) => String::from("show the key details"),
+				Command::ShowQrCode(selection) => {
+					format!("show the {selection} as QR code")
+				}
 				Command::EditKey(_) => String::from("edit the selected key"),
-				Command::SignKey(_) => String::from("sign the selected key"),
+				Command::RunKeyEdit(_, edit) => edit.to_string(),
+				Command::SetExpiration(_, expiry, _) =>
+					format!("set expiration time to {expiry}"),
+				Command::SetOwnerTrust(level) => match level {
+					Some(level) => KeyEdit::Trust(*level).to_string(),
+					None => String::from("set the owner trust"),
+				},
+				Command::AddUserId(user_id) =>
+					format!("add user ID: {user_id}"),
+				Command::RevokeUserId(index) =>
+					format!("revoke user ID #{index}"),
+				Command::SetPrimaryUserId(index) =>
+					format!("set user ID #{index} as primary"),
+				Command::AddSubkey(_) => String::from("add a subkey"),
+				Command::CreateSubkey(key_id, params) => format!(
+					"add a {} subkey to {key_id}",
+					params.get_added_subkey_algo()
+				),
+				Command::EditPreferences(_, false) =>
+					String::from("show the algorithm preferences"),
+				Command::EditPreferences(_, true) =>
+					String::from("update the preferences to modern defaults"),
+				Command::SignKey(_, signer) => match signer {
+					Some(signer) =>
+						format!("sign the selected key with {signer}"),
+					None => String::from("sign the selected key"),
+				},
+				Command::PresetPassphrase(_, _) =>
+					String::from("preset the passphrase in gpg-agent"),
+				Command::ChangePassphrase(_, _, _) =>
+					String::from("change the passphrase of the key"),
 				Command::GenerateKey => String::from("generate a new key pair"),
+				Command::GenerateKeyWithProfile(profile, _) =>
+					format!("generate a new key pair ({profile} profile)"),
+				Command::CreateKey(params) =>
+					format!("create a new key pair for {}", params.user_id),
 				Command::Copy(copy_type) =>
 					format!("copy {}", copy_type.to_string().to_lowercase()),
+				Command::ToggleRecipient(_) =>
+					String::from("toggle the selected recipient"),
+				Command::Mark(Some(_)) => String::from("toggle the mark"),
+				Command::Mark(None) => String::from("mark the keys in range"),
+				Command::Batch(commands) => match commands.first() {
+					Some(command) => {
+						let description = command.to_string().replace(
+							"the selected key",
+							&format!("{} marked keys", commands.len()),
+						);
+						if description == command.to_string() {
+							format!("{description} ({} keys)", commands.len())
+						} else {
+							description
+						}
+					}
+					None => String::from("run the batch"),
+				},
+				Command::TogglePin(_) =>
+					String::from("pin/unpin the selected key"),
+				Command::NextPinned =>
+					String::from("go to the next pinned key"),
 				Command::Paste => String::from("paste from clipboard"),
 				Command::ToggleDetail(all) => format!(
 					"toggle detail ({})",
 					if *all { "all" } else { "selected" }
 				),
+				Command::ToggleCollapse =>
+					String::from("collapse/expand the selected key"),
 				Command::ToggleTableSize => String::from("toggle table size"),
 				Command::Set(option, ref value) => {
 					let action =
@@ -171,6 +560,29 @@ impl Display for Command {
 								String::from("import key(s) from a file")
 							} else if value == ":receive " {
 								String::from("receive key(s) from keyserver")
+							} else if value == ":fetch http://" {
+								String::from("fetch key(s) from another device")
+							} else if value == ":encrypt " {
+								String::from(
+									"encrypt a file for the recipients",
+								)
+							} else if value.starts_with(":sign ") {
+								String::from("sign the selected key with...")
+							} else if value.starts_with(":expire ") {
+								String::from("change the expiration time...")
+							} else if value == ":adduid " {
+								String::from("add a user ID...")
+							} else if value == ":revuid " {
+								String::from("revoke a user ID...")
+							} else if value == ":primary-uid " {
+								String::from("set the primary user ID...")
+							} else if let Some((_, description)) =
+								EDIT_COMMANDS.iter().find(|(command, _)| {
+									value.starts_with(":edit ")
+										&& value.split_whitespace().nth(2)
+											== Some(*command)
+								}) {
+								format!("{description}...")
 							} else {
 								format!("set prompt text to {value}")
 							}
@@ -178,22 +590,446 @@ impl Display for Command {
 						_ => format!("set {option} to {value}"),
 					}
 				}
+				Command::SetTrustModel(trust_model) => {
+					format!("set the trust model to {trust_model}")
+				}
 				Command::SwitchMode(mode) => format!(
 					"switch to {} mode",
 					format!("{mode:?}").to_lowercase()
 				),
 				Command::Quit => String::from("quit application"),
+				Command::ForceQuit => String::from("quit anyway"),
 				Command::Confirm(command) => (*command).to_string(),
+				Command::Redirect(command, path) =>
+					format!("{command} (write to {path})"),
 				Command::Logs => String::from("show logs"),
+				Command::Lock => String::from("lock the screen"),
+				Command::SyncConfig => String::from("sync the configuration"),
+				Command::ReloadConfig =>
+					String::from("reload the configuration file"),
+				Command::WriteConfig =>
+					String::from("write the configuration file"),
+				Command::Filter(Some(KeyFilter::Capability(capability))) => {
+					format!(
+						"show the keys that {}",
+						capability.to_string().replace('-', " ")
+					)
+				}
+				Command::Filter(Some(KeyFilter::State(
+					KeyState::HasSecret,
+				))) => {
+					String::from("show the keys that have a secret key")
+				}
+				Command::Filter(Some(KeyFilter::State(state))) => {
+					format!("show the {state} keys")
+				}
+				Command::Filter(None) => String::from("clear the filter"),
 				_ => format!("{self:?}"),
 			}
 		)
 	}
 }
 
+impl Command {
+	/// Returns the name of the command as it is used in the prompt.
+	pub fn get_name(&self) -> &str {
+		match self {
+			Command::Confirm(_) => "confirm",
+			Command::Redirect(_, _) => "redirect",
+			Command::ShowHelp => "help",
+			Command::ChangeStyle(_) => "style",
+			Command::ShowOutput(_, _) => "output",
+			Command::ShowOptions => "options",
+			Command::ListKeys(_) => "list",
+			Command::ImportKeys(_, false, _) | Command::PreviewImport(_) => {
+				"import"
+			}
+			Command::ImportKeys(_, true, _) => "receive",
+			Command::ImportClipboard => "import-clipboard",
+			Command::EnterSandbox | Command::ExitSandbox => "sandbox",
+			Command::PromoteKey(_) => "promote",
+			Command::ExportKeys(_, _, _) => "export",
+			Command::ExportPaperKey(_, _)
+			| Command::ExportSshKey(_)
+			| Command::ExportWithSigners(_) => "export",
+			Command::ServeKey(_) => "serve",
+			Command::FetchKeys(_) | Command::ImportFetched(_, _, _) => "fetch",
+			Command::ExportKeysTo(_, _, _) => "export-to",
+			Command::ExportEach(_, _) => "export-each",
+			Command::ExportJson(_) => "export-json",
+			Command::CleanExports(_, _) | Command::RemoveExports(_, _) => {
+				"clean-exports"
+			}
+			Command::EncryptFiles(_) => "encrypt",
+			Command::EncryptSymmetric(_, _) => "encrypt-symmetric",
+			Command::EncryptClipboard(_) => "encrypt-clipboard",
+			Command::DecryptClipboard => "decrypt-clipboard",
+			Command::SignFile(_, _) => "sign-file",
+			Command::ConvertArmor(_, true, _) => "armor",
+			Command::ConvertArmor(_, false, _) => "dearmor",
+			Command::VerifyFile(_, _) => "verify",
+			Command::VerifyRelease(_, _) => "verify-release",
+			Command::DeleteKey(_, _) => "delete",
+			Command::RevokeKey(_, _) | Command::ApplyRevocation(_, _) => {
+				"revoke"
+			}
+			Command::SendKey(_, _) => "send",
+			Command::SelectKeyserver(_) => "keyserver",
+			Command::DiffKey(_) => "diff",
+			Command::CompareFingerprint(_) => "compare",
+			Command::LocateSigners(_) => "locate-signers",
+			Command::SearchKeyserver(_) => "search-keyserver",
+			Command::ResolveEmail(_) => "who",
+			Command::ShowCard => "card",
+			Command::GoToKey(_) => "goto",
+			Command::GenerateReport => "report",
+			Command::ShowExpiring(_) => "expiring",
+			Command::ShowHistory => "history",
+			Command::InspectKey(_) => "inspect",
+			Command::ShowQrCode(_) => "qr",
+			Command::EditKey(_) | Command::RunKeyEdit(_, _) => "edit",
+			Command::SetOwnerTrust(_) => "trust",
+			Command::SetExpiration(_, _, _) => "expire",
+			Command::AddUserId(_) => "adduid",
+			Command::RevokeUserId(_) => "revuid",
+			Command::SetPrimaryUserId(_) => "primary-uid",
+			Command::AddSubkey(_) | Command::CreateSubkey(_, _) => "addsubkey",
+			Command::EditPreferences(_, _) => "prefs",
+			Command::SignKey(_, _) => "sign",
+			Command::PresetPassphrase(_, _) => "agent",
+			Command::ChangePassphrase(_, _, _) => "passwd",
+			Command::GenerateKey
+			| Command::GenerateKeyWithProfile(_, _)
+			| Command::CreateKey(_) => "generate",
+			Command::RefreshKeys | Command::Refresh => "refresh",
+			Command::Copy(_) => "copy",
+			Command::ToggleRecipient(_) => "recipient",
+			Command::Mark(_) => "mark",
+			Command::Batch(_) => "batch",
+			Command::TogglePin(_) | Command::NextPinned => "pin",
+			Command::ToggleDetail(_)
+			| Command::ToggleCollapse
+			| Command::ToggleTableSize => "toggle",
+			Command::Scroll(_, _) => "scroll",
+			Command::Set(_, _) | Command::SetTrustModel(_) => "set",
+			Command::Get(_) => "get",
+			Command::SwitchMode(_) => "mode",
+			Command::Paste => "paste",
+			Command::EnableInput => "input",
+			Command::Search(_) => "search",
+			Command::Filter(_) => "filter",
+			Command::NextTab => "next",
+			Command::PreviousTab => "previous",
+			Command::Logs | Command::LoggerEvent(_) => "logs",
+			Command::Lock => "lock",
+			Command::SyncConfig => "sync-config",
+			Command::ReloadConfig => "reload-config",
+			Command::WriteConfig => "write-config",
+			Command::Quit | Command::ForceQuit => "quit",
+			Command::None => "none",
+		}
+	}
+
+	/// Returns the equivalent command-line syntax of the command.
+	///
+	/// `None` is returned for the commands that cannot be
+	/// (or should not be) expressed as a command line.
+	pub fn get_syntax(&self) -> Option<String> {
+		let optional = |value: &Option<String>| {
+			value.as_ref().map(|v| format!(" {v}")).unwrap_or_default()
+		};
+		let syntax = match self {
+			Command::ShowHelp => String::from("help"),
+			Command::ChangeStyle(style) => format!("style {style}"),
+			Command::ListKeys(key_type) => format!("list {key_type}"),
+			Command::ImportKeys(keys, false, _) => {
+				format!("import {}", keys.join(" "))
+			}
+			Command::ImportKeys(keys, true, keyserver) => format!(
+				"receive {}{}",
+				keys.join(" "),
+				keyserver
+					.as_ref()
+					.map(|v| format!(" --from {v}"))
+					.unwrap_or_default()
+			),
+			Command::ImportClipboard => String::from("import-clipboard"),
+			Command::EnterSandbox => String::from("sandbox"),
+			Command::ExitSandbox => String::from("sandbox off"),
+			Command::PromoteKey(id) => format!("promote{}", optional(id)),
+			Command::ExportKeys(key_type, patterns, export_subkeys) => {
+				let mut values = vec![key_type.to_string()];
+				values.extend(patterns.iter().cloned());
+				if *export_subkeys {
+					values.push(String::from("subkey"));
+				}
+				format!("export {}", values.join(" "))
+			}
+			Command::ExportSshKey(key_id) => format!("export ssh {key_id}"),
+			Command::ExportWithSigners(key_id) => {
+				format!("export with-signers {key_id}")
+			}
+			Command::ServeKey(key_id) => format!("serve {key_id}"),
+			Command::FetchKeys(url) => format!("fetch {url}"),
+			Command::ExportPaperKey(key_id, raw) => format!(
+				"export sec {key_id} paper{}",
+				if *raw { " raw" } else { "" }
+			),
+			Command::ExportEach(key_set, directory) => {
+				format!("export-each {key_set}{}", optional(directory))
+			}
+			Command::CleanExports(days, secure) => format!(
+				"clean-exports {days}{}",
+				if *secure { " secure" } else { "" }
+			),
+			Command::DeleteKey(key_type, key_id) => {
+				format!("delete {key_type} {key_id}")
+			}
+			Command::RevokeKey(id, reason) => {
+				format!("revoke {id}{}", optional(reason))
+			}
+			Command::SendKey(id, keyserver) => {
+				format!("send {id}{}", optional(keyserver))
+			}
+			Command::SelectKeyserver(key_id) => format!("keyserver {key_id}"),
+			Command::DiffKey(key_id) => format!("diff {key_id}"),
+			Command::LocateSigners(key_id) => {
+				format!("locate-signers {key_id}")
+			}
+			Command::SearchKeyserver(query) => {
+				format!("search-keyserver {query}")
+			}
+			Command::ShowCard => String::from("card"),
+			Command::GoToKey(key_id) => format!("goto {key_id}"),
+			Command::GenerateReport => String::from("report"),
+			Command::ShowExpiring(days) => format!("expiring {days}"),
+			Command::ShowHistory => String::from("history"),
+			Command::InspectKey(key_id) => format!("inspect {key_id}"),
+			Command::ShowQrCode(selection) => format!(
+				"qr {}",
+				selection.to_possible_value()?.get_name().replace('-', "_")
+			),
+			Command::EditKey(key_id) => format!("edit {key_id}"),
+			Command::SetOwnerTrust(level) => {
+				format!("trust{}", optional(&level.map(|v| v.to_string())))
+			}
+			Command::AddSubkey(key_id) => format!("addsubkey {key_id}"),
+			Command::EditPreferences(key_id, update) => format!(
+				"prefs {key_id}{}",
+				if *update { " update" } else { "" }
+			),
+			Command::SignKey(id, with) => format!(
+				"sign {id}{}",
+				with.as_ref()
+					.map(|v| format!(" --with {v}"))
+					.unwrap_or_default()
+			),
+			// do not leak the passphrase
+			Command::PresetPassphrase(key_id, None) => {
+				format!("agent preset {key_id}")
+			}
+			Command::ChangePassphrase(key_id, None, None) => {
+				format!("passwd {key_id}")
+			}
+			Command::EncryptSymmetric(files, None) => {
+				format!("encrypt-symmetric {}", files.join(" "))
+			}
+			Command::GenerateKey => String::from("generate"),
+			Command::Copy(selection) => format!(
+				"copy {}",
+				selection.to_possible_value()?.get_name().replace('-', "_")
+			),
+			Command::ToggleRecipient(key_id) => format!("recipient {key_id}"),
+			Command::TogglePin(id) => format!("pin{}", optional(id)),
+			Command::NextPinned => String::from("pin next"),
+			Command::ToggleDetail(all) => {
+				format!("toggle detail{}", if *all { " all" } else { "" })
+			}
+			Command::ToggleCollapse => String::from("toggle collapse"),
+			Command::ToggleTableSize => String::from("toggle"),
+			Command::Set(option, value) if option != "prompt" => {
+				format!("set {option} {value}")
+			}
+			Command::SwitchMode(mode) => {
+				format!("mode {}", format!("{mode:?}").to_lowercase())
+			}
+			Command::Paste => String::from("paste"),
+			Command::Refresh => String::from("refresh"),
+			Command::RefreshKeys => String::from("refresh keys"),
+			Command::Lock => String::from("lock"),
+			Command::Quit => String::from("quit"),
+			Command::ForceQuit => String::from("quit!"),
+			_ => return None,
+		};
+		Some(format!("{COMMAND_PREFIX}{syntax}"))
+	}
+
+	/// Returns the command with the given passphrase from the prompt.
+	///
+	/// Commands that don't expect a passphrase are returned as is.
+	pub fn with_passphrase(self, passphrase: SecretInput) -> Self {
+		match self {
+			Command::PresetPassphrase(key_id, None) => {
+				Command::PresetPassphrase(key_id, Some(passphrase))
+			}
+			Command::EncryptSymmetric(files, None) => {
+				Command::EncryptSymmetric(files, Some(passphrase))
+			}
+			Command::ChangePassphrase(key_id, None, new_passphrase) => {
+				Command::ChangePassphrase(
+					key_id,
+					Some(passphrase),
+					new_passphrase,
+				)
+			}
+			Command::ChangePassphrase(key_id, current_passphrase, None) => {
+				Command::ChangePassphrase(
+					key_id,
+					current_passphrase,
+					Some(passphrase),
+				)
+			}
+			command => command,
+		}
+	}
+
+	/// Restores the case of the given (lowercased) key ID.
+	fn parse_key_id(key_id: String) -> String {
+		if let Some(key) = key_id.strip_prefix("0x") {
+			format!("0x{}", key.to_string().to_uppercase())
+		} else {
+			key_id
+		}
+	}
+
+	/// Returns the rest of the command after the given number of words.
+	///
+	/// It is used for the arguments that might contain whitespace.
+	fn parse_remainder(s: &str, skip: usize) -> Option<String> {
+		let mut remainder = s.trim();
+		for _ in 0..skip {
+			remainder =
+				remainder.split_once(char::is_whitespace)?.1.trim_start();
+		}
+		(!remainder.is_empty()).then(|| remainder.to_string())
+	}
+
+	/// Splits the arguments by whitespace except in double quotes.
+	///
+	/// Fails if a quote is not closed.
+	fn parse_quoted_args(s: &str) -> Result<Vec<String>, ()> {
+		let mut args = Vec::new();
+		let mut arg = None;
+		let mut quoted = false;
+		for c in s.chars() {
+			match c {
+				'"' => {
+					quoted = !quoted;
+					arg.get_or_insert_with(String::new);
+				}
+				c if c.is_whitespace() && !quoted => {
+					args.extend(arg.take());
+				}
+				c => arg.get_or_insert_with(String::new).push(c),
+			}
+		}
+		if quoted {
+			return Err(());
+		}
+		args.extend(arg);
+		Ok(args)
+	}
+
+	/// Checks if the command is allowed by the given list.
+	///
+	/// Entries of the list are command names with an optional
+	/// argument for narrowing down the allowed values.
+	/// (e.g. `list`, `copy key_fingerprint`)
+	///
+	/// Navigation and internal commands are always allowed.
+	pub fn is_allowed(&self, allowed_commands: &[String]) -> bool {
+		match self {
+			Command::Confirm(command) => command.is_allowed(allowed_commands),
+			Command::Batch(commands) => commands
+				.iter()
+				.all(|command| command.is_allowed(allowed_commands)),
+			Command::Redirect(command, _) => {
+				command.is_allowed(allowed_commands)
+					&& allowed_commands.iter().any(|allowed_command| {
+						allowed_command.trim_start_matches(':') == "redirect"
+					})
+			}
+			Command::ShowOutput(_, _)
+			| Command::Scroll(_, _)
+			| Command::SwitchMode(_)
+			| Command::Quit
+			| Command::ForceQuit
+			| Command::None => true,
+			_ => allowed_commands.iter().any(|allowed_command| {
+				let mut values =
+					allowed_command.trim_start_matches(':').split_whitespace();
+				values.next() == Some(self.get_name())
+					&& match (values.next(), self) {
+						(None, _) => true,
+						(Some(value), Command::Copy(selection)) => {
+							Selection::from_str(value, true) == Ok(*selection)
+						}
+						(Some(value), Command::ListKeys(key_type)) => {
+							KeyType::from_str(value) == Ok(*key_type)
+						}
+						_ => false,
+					}
+			}),
+		}
+	}
+}
+
 impl FromStr for Command {
 	type Err = ();
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match Self::split_redirect(s) {
+			Some((_, "")) => Err(()),
+			Some((command, path)) => Ok(Command::Redirect(
+				Box::new(Command::from_str(command)?),
+				path.to_string(),
+			)),
+			None => Self::parse_without_redirect(s),
+		}
+	}
+}
+
+impl Command {
+	/// Splits the output redirection (`<command> > <path>`) of the command.
+	///
+	/// `>` should be a separate token that is followed by a single path.
+	/// Commands that take free text (e.g. `search`) are not redirected.
+	pub fn split_redirect(s: &str) -> Option<(&str, &str)> {
+		let name = s
+			.trim_start()
+			.trim_start_matches(':')
+			.split_whitespace()
+			.next()
+			.unwrap_or_default()
+			.to_lowercase();
+		if FREE_TEXT_COMMANDS.contains(&name.as_str()) {
+			return None;
+		}
+		let (command, path) = s.trim_end().rsplit_once(char::is_whitespace)?;
+		if path == ">" {
+			return Some((command, ""));
+		}
+		let command = command.trim_end().strip_suffix('>')?;
+		command
+			.ends_with(char::is_whitespace)
+			.then_some((command, path))
+	}
+
+	/// Parses the command without checking for the output redirection.
+	///
+	/// It is used for the commands with expanded placeholders
+	/// (e.g. key bindings) so that the key details cannot
+	/// redirect the output to a file.
+	pub fn parse_without_redirect(s: &str) -> Result<Self, ()> {
 		let mut values = s
 			.replacen(':', "", 1)
 			.to_lowercase()
@@ -232,16 +1068,63 @@ impl FromStr for Command {
 			"list" | "ls" => Ok(Command::ListKeys(KeyType::from_str(
 				&args.first().cloned().unwrap_or_else(|| String::from("pub")),
 			)?)),
-			"import" | "receive" => Ok(Command::ImportKeys(
-				s.replacen(':', "", 1)
+			"import" | "receive" => {
+				let mut keys = s
+					.replacen(':', "", 1)
 					.split_whitespace()
 					.map(String::from)
 					.skip(1)
-					.collect(),
-				command.as_str() == "receive",
-			)),
+					.collect::<Vec<String>>();
+				let receive = command.as_str() == "receive";
+				let keyserver = match keys.iter().position(|v| v == "--from") {
+					Some(index) if receive && index + 1 < keys.len() => {
+						let keyserver = keys.remove(index + 1);
+						keys.remove(index);
+						Some(keyserver)
+					}
+					Some(_) => return Err(()),
+					None => None,
+				};
+				if let Some(index) = keys.iter().position(|v| v == "--dry-run")
+				{
+					if receive {
+						return Err(());
+					}
+					keys.remove(index);
+					return Ok(Command::PreviewImport(keys));
+				}
+				Ok(Command::ImportKeys(keys, receive, keyserver))
+			}
 			"import-clipboard" => Ok(Command::ImportClipboard),
+			"sandbox" => match args.first().map(String::as_str) {
+				Some("off") => Ok(Command::ExitSandbox),
+				Some(_) => Err(()),
+				None => Ok(Command::EnterSandbox),
+			},
+			"promote" => Ok(Command::PromoteKey(
+				args.first().cloned().map(Self::parse_key_id),
+			)),
 			"export" | "exp" => {
+				if args.first() == Some(&String::from("ssh")) {
+					return Ok(Command::ExportSshKey(Self::parse_key_id(
+						args.get(1).cloned().ok_or(())?,
+					)));
+				}
+				if args.first() == Some(&String::from("with-signers")) {
+					return Ok(Command::ExportWithSigners(Self::parse_key_id(
+						args.get(1).cloned().ok_or(())?,
+					)));
+				}
+				let raw = args.last() == Some(&String::from("raw"));
+				if args.len() >= 3 + usize::from(raw)
+					&& args[args.len() - 1 - usize::from(raw)] == "paper"
+					&& KeyType::from_str(&args[0]) == Ok(KeyType::Secret)
+				{
+					return Ok(Command::ExportPaperKey(
+						args[1].to_string(),
+						raw,
+					));
+				}
 				let mut patterns = if !args.is_empty() {
 					args[1..].to_vec()
 				} else {
@@ -263,6 +1146,131 @@ impl FromStr for Command {
 					export_subkeys,
 				))
 			}
+			"serve" => Ok(Command::ServeKey(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"fetch" => Ok(Command::FetchKeys(
+				s.split_whitespace().nth(1).map(String::from).ok_or(())?,
+			)),
+			"export-to" => {
+				let key_type = KeyType::from_str(args.first().ok_or(())?)?;
+				// path is the rest of the line so that it can contain spaces
+				if args.len() == 2 {
+					return Ok(Command::ExportKeysTo(
+						key_type,
+						Vec::new(),
+						Self::parse_remainder(s, 2).ok_or(())?,
+					));
+				}
+				Ok(Command::ExportKeysTo(
+					key_type,
+					args.get(1)
+						.ok_or(())?
+						.split(',')
+						.filter(|v| !v.is_empty() && v != &"*")
+						.map(String::from)
+						.collect(),
+					Self::parse_remainder(s, 3).ok_or(())?,
+				))
+			}
+			"export-each" => Ok(Command::ExportEach(
+				KeySet::from_str(args.first().ok_or(())?)?,
+				Self::parse_remainder(s, 2),
+			)),
+			"export-json" => {
+				Ok(Command::ExportJson(Self::parse_remainder(s, 1)))
+			}
+			"clean-exports" => {
+				let mut days = EXPORT_CLEANUP_DAYS;
+				let mut secure = false;
+				for arg in args {
+					if arg == "secure" {
+						secure = true;
+					} else {
+						days = arg.parse().map_err(|_| ())?;
+					}
+				}
+				Ok(Command::CleanExports(days, secure))
+			}
+			"encrypt" | "enc" => Ok(Command::EncryptFiles(
+				s.replacen(':', "", 1)
+					.split_whitespace()
+					.map(String::from)
+					.skip(1)
+					.collect(),
+			)),
+			"encrypt-symmetric" => Ok(Command::EncryptSymmetric(
+				s.replacen(':', "", 1)
+					.split_whitespace()
+					.map(String::from)
+					.skip(1)
+					.collect(),
+				None,
+			)),
+			"encrypt-clipboard" => Ok(Command::EncryptClipboard(
+				s.split_whitespace().nth(1).map(String::from).ok_or(())?,
+			)),
+			"decrypt-clipboard" => Ok(Command::DecryptClipboard),
+			"sign-file" => {
+				let mut values = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(1)
+					.map(String::from)
+					.collect::<Vec<String>>();
+				let signature_type = match values.last() {
+					Some(value) if values.len() > 1 => {
+						SignatureType::from_str(&value.to_lowercase()).ok()
+					}
+					_ => None,
+				};
+				if signature_type.is_some() {
+					values.pop();
+				}
+				if values.len() != 1 {
+					return Err(());
+				}
+				Ok(Command::SignFile(
+					values.remove(0),
+					signature_type.unwrap_or_default(),
+				))
+			}
+			"armor" | "dearmor" => Ok(Command::ConvertArmor(
+				Self::parse_remainder(s, 1).ok_or(())?,
+				command == "armor",
+				false,
+			)),
+			"verify" => {
+				let values = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(1)
+					.map(String::from)
+					.collect::<Vec<String>>();
+				match values.as_slice() {
+					[file] => Ok(Command::VerifyFile(file.to_string(), None)),
+					[file, signature] => Ok(Command::VerifyFile(
+						file.to_string(),
+						Some(signature.to_string()),
+					)),
+					_ => Err(()),
+				}
+			}
+			"verify-release" => {
+				let values = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(1)
+					.map(String::from)
+					.collect::<Vec<String>>();
+				match values.as_slice() {
+					[file, signature] => Ok(Command::VerifyRelease(
+						file.to_string(),
+						signature.to_string(),
+					)),
+					_ => Err(()),
+				}
+			}
 			"delete" | "del" => {
 				let key_id = args.get(1).cloned().unwrap_or_default();
 				Ok(Command::DeleteKey(
@@ -272,17 +1280,199 @@ impl FromStr for Command {
 							.cloned()
 							.unwrap_or_else(|| String::from("pub")),
 					)?,
-					if let Some(key) = key_id.strip_prefix("0x") {
-						format!("0x{}", key.to_string().to_uppercase())
-					} else {
-						key_id
-					},
+					Self::parse_key_id(key_id),
+				))
+			}
+			"revoke" => {
+				let reason = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(2)
+					.collect::<Vec<&str>>()
+					.join(" ");
+				Ok(Command::RevokeKey(
+					Self::parse_key_id(args.first().cloned().ok_or(())?),
+					(!reason.is_empty()).then_some(reason),
 				))
 			}
-			"send" => Ok(Command::SendKey(args.first().cloned().ok_or(())?)),
-			"edit" => Ok(Command::EditKey(args.first().cloned().ok_or(())?)),
-			"sign" => Ok(Command::SignKey(args.first().cloned().ok_or(())?)),
-			"generate" | "gen" => Ok(Command::GenerateKey),
+			"send" => Ok(Command::SendKey(
+				args.first().cloned().ok_or(())?,
+				s.split_whitespace().nth(2).map(String::from),
+			)),
+			"keyserver" => Ok(Command::SelectKeyserver(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"diff" => Ok(Command::DiffKey(args.first().cloned().ok_or(())?)),
+			"compare" => {
+				let fingerprint = args.join("");
+				if fingerprint.is_empty() {
+					Err(())
+				} else {
+					Ok(Command::CompareFingerprint(fingerprint))
+				}
+			}
+			"locate-signers" => Ok(Command::LocateSigners(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"search-keyserver" | "sk" => {
+				let query = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(1)
+					.collect::<Vec<&str>>()
+					.join(" ");
+				if query.is_empty() {
+					Err(())
+				} else {
+					Ok(Command::SearchKeyserver(query))
+				}
+			}
+			"who" => match args.first() {
+				Some(email) if email.contains('@') => {
+					Ok(Command::ResolveEmail(email.to_string()))
+				}
+				_ => Err(()),
+			},
+			"card" => Ok(Command::ShowCard),
+			"goto" => Ok(Command::GoToKey(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"report" => match args.first().map(String::as_str) {
+				Some("html") | None => Ok(Command::GenerateReport),
+				_ => Err(()),
+			},
+			"expiring" => Ok(Command::ShowExpiring(match args.first() {
+				Some(days) => days.parse().map_err(|_| ())?,
+				None => EXPIRY_WARNING_DAYS,
+			})),
+			"history" => Ok(Command::ShowHistory),
+			"inspect" => Ok(Command::InspectKey(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"qr" => Ok(Command::ShowQrCode(match args.first() {
+				Some(arg) => Selection::from_str(arg, true).map_err(|_| ())?,
+				None => Selection::Key,
+			})),
+			"edit" => {
+				let key_id = args.first().cloned().ok_or(())?;
+				let edit = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(2)
+					.collect::<Vec<&str>>()
+					.join(" ");
+				if edit.is_empty() {
+					Ok(Command::EditKey(key_id))
+				} else {
+					Ok(Command::RunKeyEdit(key_id, KeyEdit::from_str(&edit)?))
+				}
+			}
+			"expire" => {
+				let values = s
+					.replacen(':', "", 1)
+					.split_whitespace()
+					.skip(1)
+					.map(String::from)
+					.collect::<Vec<String>>();
+				match values.as_slice() {
+					[key_id, expiry, subkeys @ ..] => {
+						keygen::parse_expiry(expiry).map_err(|_| ())?;
+						Ok(Command::SetExpiration(
+							Self::parse_key_id(key_id.to_string()),
+							expiry.to_lowercase(),
+							subkeys.to_vec(),
+						))
+					}
+					_ => Err(()),
+				}
+			}
+			"trust" => match args.first() {
+				Some(level) => Ok(Command::SetOwnerTrust(Some(
+					parse_trust_level(level).ok_or(())?,
+				))),
+				None => Ok(Command::SetOwnerTrust(None)),
+			},
+			"adduid" => {
+				let values = Self::parse_quoted_args(
+					&Self::parse_remainder(s, 1).unwrap_or_default(),
+				)?;
+				let user_id = format_user_id(&match values.as_slice() {
+					[name, email] if email.contains('@') => {
+						format!("{name} <{}>", email.trim_matches(['<', '>']))
+					}
+					[name, email, comment] if email.contains('@') => format!(
+						"{name} ({comment}) <{}>",
+						email.trim_matches(['<', '>'])
+					),
+					_ => values.join(" "),
+				});
+				if user_id.is_empty() {
+					Err(())
+				} else {
+					Ok(Command::AddUserId(user_id))
+				}
+			}
+			"addsubkey" => Ok(Command::AddSubkey(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"revuid" | "primary-uid" => {
+				let index = args
+					.first()
+					.and_then(|v| v.parse().ok())
+					.filter(|v| *v != 0)
+					.ok_or(())?;
+				if command == "revuid" {
+					Ok(Command::RevokeUserId(index))
+				} else {
+					Ok(Command::SetPrimaryUserId(index))
+				}
+			}
+			"prefs" | "setpref" => Ok(Command::EditPreferences(
+				Self::parse_key_id(args.first().cloned().ok_or(())?),
+				match args.get(1).map(String::as_str) {
+					Some("update") => true,
+					Some(_) => return Err(()),
+					None => command.as_str() == "setpref",
+				},
+			)),
+			"sign" => Ok(Command::SignKey(
+				args.first().cloned().ok_or(())?,
+				match args.get(1).map(String::as_str) {
+					Some("--with") => Some(args.get(2).cloned().ok_or(())?),
+					Some(_) => return Err(()),
+					None => None,
+				},
+			)),
+			"agent" => match args.first().map(String::as_str) {
+				Some("preset") => Ok(Command::PresetPassphrase(
+					Self::parse_key_id(args.get(1).cloned().ok_or(())?),
+					None,
+				)),
+				_ => Err(()),
+			},
+			"passwd" => Ok(Command::ChangePassphrase(
+				Self::parse_key_id(args.first().cloned().ok_or(())?),
+				None,
+				None,
+			)),
+			"generate" | "gen" => match args.first().map(String::as_str) {
+				Some("profile") => {
+					let values = s
+						.replacen(':', "", 1)
+						.split_whitespace()
+						.skip(2)
+						.map(String::from)
+						.collect::<Vec<String>>();
+					let profile = values.first().cloned().ok_or(())?;
+					let user_id = values[1..].join(" ");
+					Ok(Command::GenerateKeyWithProfile(
+						profile,
+						(!user_id.is_empty()).then_some(user_id),
+					))
+				}
+				Some(_) => Err(()),
+				None => Ok(Command::GenerateKey),
+			},
 			"copy" | "c" => {
 				if let Some(arg) = args.first().cloned() {
 					Ok(Command::Copy(
@@ -292,11 +1482,33 @@ impl FromStr for Command {
 					Ok(Command::SwitchMode(Mode::Copy))
 				}
 			}
+			"recipient" => Ok(Command::ToggleRecipient(Self::parse_key_id(
+				args.first().cloned().ok_or(())?,
+			))),
+			"mark" => {
+				Ok(Command::Mark(match args.first().map(String::as_str) {
+					Some("range") => None,
+					Some(key_id) => {
+						Some(Self::parse_key_id(key_id.to_string()))
+					}
+					None => return Err(()),
+				}))
+			}
+			"pin" => match args.first().map(String::as_str) {
+				Some("next") => Ok(Command::NextPinned),
+				Some(key_id) => Ok(Command::TogglePin(Some(
+					Self::parse_key_id(key_id.to_string()),
+				))),
+				None => Ok(Command::TogglePin(None)),
+			},
+			"pick" => Ok(Command::SwitchMode(Mode::Pick)),
 			"toggle" | "t" => {
 				if args.first() == Some(&String::from("detail")) {
 					Ok(Command::ToggleDetail(
 						args.get(1) == Some(&String::from("all")),
 					))
+				} else if args.first() == Some(&String::from("collapse")) {
+					Ok(Command::ToggleCollapse)
 				} else {
 					Ok(Command::ToggleTableSize)
 				}
@@ -315,7 +1527,15 @@ impl FromStr for Command {
 			}
 			"set" | "s" => Ok(Command::Set(
 				args.first().cloned().unwrap_or_default(),
-				args.get(1).cloned().unwrap_or_default(),
+				if args.first().map(String::as_str) == Some("date_format") {
+					s.replacen(':', "", 1)
+						.split_whitespace()
+						.skip(2)
+						.collect::<Vec<&str>>()
+						.join(" ")
+				} else {
+					args.get(1).cloned().unwrap_or_default()
+				},
 			)),
 			"get" | "g" => {
 				Ok(Command::Get(args.first().cloned().unwrap_or_default()))
@@ -328,6 +1548,12 @@ impl FromStr for Command {
 			"paste" | "p" => Ok(Command::Paste),
 			"input" => Ok(Command::EnableInput),
 			"search" => Ok(Command::Search(args.first().cloned())),
+			"filter" => match args.first().map(String::as_str) {
+				None | Some("none") => Ok(Command::Filter(None)),
+				Some(filter) => Ok(Command::Filter(Some(
+					KeyFilter::from_str(filter).map_err(|_| ())?,
+				))),
+			},
 			"next" => Ok(Command::NextTab),
 			"previous" | "prev" => Ok(Command::PreviousTab),
 			"refresh" | "r" => {
@@ -337,8 +1563,13 @@ impl FromStr for Command {
 					Ok(Command::Refresh)
 				}
 			}
-			"quit" | "q" | "q!" => Ok(Command::Quit),
+			"quit" | "q" => Ok(Command::Quit),
+			"quit!" | "q!" => Ok(Command::ForceQuit),
 			"logs" | "l" => Ok(Command::Logs),
+			"lock" => Ok(Command::Lock),
+			"sync-config" => Ok(Command::SyncConfig),
+			"reload-config" => Ok(Command::ReloadConfig),
+			"write-config" => Ok(Command::WriteConfig),
 			"none" => Ok(Command::None),
 			_ => Err(()),
 		}
@@ -348,6 +1579,7 @@ impl FromStr for Command {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::gpg::key::KeyCapability;
 	use pretty_assertions::assert_eq;
 	#[test]
 	fn test_app_command() -> Result<(), ()> {
@@ -371,6 +1603,10 @@ mod tests {
 			Command::ChangeStyle(Style::Plain),
 			Command::from_str(":style plain")?
 		);
+		assert_eq!(
+			Command::ChangeStyle(Style::HighContrast),
+			Command::from_str(":style high-contrast")?
+		);
 		assert_eq!(Command::ShowOptions, Command::from_str(":options")?);
 		for cmd in &[":list", ":list pub", ":ls", ":ls pub"] {
 			let command = Command::from_str(cmd)?;
@@ -387,18 +1623,42 @@ mod tests {
 					String::from("Test2"),
 					String::from("tesT3")
 				],
-				false
+				false,
+				None
 			),
 			Command::from_str(":import Test1 Test2 tesT3")?
 		);
 		assert_eq!(
-			Command::ImportKeys(vec![String::from("Test"),], true),
+			Command::ImportKeys(vec![String::from("Test"),], true, None),
 			Command::from_str(":receive Test")?
 		);
+		assert_eq!(
+			Command::ImportKeys(
+				vec![String::from("Test")],
+				true,
+				Some(String::from("hkp://keys.example.com"))
+			),
+			Command::from_str(":receive Test --from hkp://keys.example.com")?
+		);
+		assert!(Command::from_str(":receive Test --from").is_err());
+		assert!(Command::from_str(":import Test --from x").is_err());
+		assert_eq!(
+			Command::PreviewImport(vec![String::from("Test.asc")]),
+			Command::from_str(":import --dry-run Test.asc")?
+		);
+		assert!(Command::from_str(":receive --dry-run Test").is_err());
 		assert_eq!(
 			Command::ImportClipboard,
 			Command::from_str(":import-clipboard")?
 		);
+		assert_eq!(Command::EnterSandbox, Command::from_str(":sandbox")?);
+		assert_eq!(Command::ExitSandbox, Command::from_str(":sandbox off")?);
+		assert!(Command::from_str(":sandbox on").is_err());
+		assert_eq!(Command::PromoteKey(None), Command::from_str(":promote")?);
+		assert_eq!(
+			Command::PromoteKey(Some(String::from("0xABC"))),
+			Command::from_str(":promote 0xabc")?
+		);
 		for cmd in &[":export", ":export pub", ":exp", ":exp pub"] {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(
@@ -422,6 +1682,34 @@ mod tests {
 			),
 			Command::from_str(":export sec test3 test4 subkey")?
 		);
+		assert_eq!(
+			Command::ExportPaperKey(String::from("0xabc"), false),
+			Command::from_str(":export sec 0xABC paper")?
+		);
+		assert_eq!(
+			Command::ExportPaperKey(String::from("0xabc"), true),
+			Command::from_str(":exp sec 0xABC paper raw")?
+		);
+		assert_eq!(
+			Command::ExportSshKey(String::from("0xABC")),
+			Command::from_str(":export ssh 0xabc")?
+		);
+		assert!(Command::from_str(":export ssh").is_err());
+		assert_eq!(
+			Command::ExportWithSigners(String::from("0xABC")),
+			Command::from_str(":export with-signers 0xabc")?
+		);
+		assert!(Command::from_str(":export with-signers").is_err());
+		assert_eq!(
+			Command::ServeKey(String::from("0xABC")),
+			Command::from_str(":serve 0xabc")?
+		);
+		assert!(Command::from_str(":serve").is_err());
+		assert_eq!(
+			Command::FetchKeys(String::from("http://10.0.0.2:8000/0xABC.asc")),
+			Command::from_str(":fetch http://10.0.0.2:8000/0xABC.asc")?
+		);
+		assert!(Command::from_str(":fetch").is_err());
 		for cmd in &[":export sec", ":exp sec"] {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(
@@ -441,31 +1729,375 @@ mod tests {
 			),
 			Command::from_str(":export sec test1 test2 test3")?
 		);
-		for cmd in &[":delete pub xyz", ":del pub xyz"] {
-			let command = Command::from_str(cmd)?;
-			assert_eq!(
-				Command::DeleteKey(KeyType::Public, String::from("xyz")),
-				command
-			);
-		}
 		assert_eq!(
-			Command::SendKey(String::from("test")),
+			Command::ExportKeysTo(
+				KeyType::Public,
+				vec![String::from("0xabc")],
+				String::from("/tmp/Keys/Test.asc")
+			),
+			Command::from_str(":export-to pub 0xABC /tmp/Keys/Test.asc")?
+		);
+		assert_eq!(
+			Command::ExportKeysTo(
+				KeyType::Secret,
+				Vec::new(),
+				String::from("out.asc")
+			),
+			Command::from_str(":export-to sec out.asc")?
+		);
+		assert_eq!(
+			Command::ExportKeysTo(
+				KeyType::Public,
+				vec![String::from("0xa"), String::from("0xb")],
+				String::from("/tmp/My Keys/Test.asc")
+			),
+			Command::from_str(":export-to pub 0xA,0xB /tmp/My Keys/Test.asc")?
+		);
+		assert_eq!(
+			Command::ExportKeysTo(
+				KeyType::Secret,
+				Vec::new(),
+				String::from("my keys.asc")
+			),
+			Command::from_str(":export-to sec * my keys.asc")?
+		);
+		assert!(Command::from_str(":export-to pub").is_err());
+		assert_eq!(
+			Command::ExportEach(
+				KeySet::Pinned,
+				Some(String::from("/tmp/Keys"))
+			),
+			Command::from_str(":export-each pinned /tmp/Keys")?
+		);
+		assert_eq!(
+			Command::ExportEach(KeySet::Marked, None),
+			Command::from_str(":export-each marked")?
+		);
+		assert_eq!(
+			Command::ExportEach(
+				KeySet::Marked,
+				Some(String::from("/tmp/Team Keys"))
+			),
+			Command::from_str(":export-each  marked /tmp/Team Keys ")?
+		);
+		assert!(Command::from_str(":export-each all").is_err());
+		assert_eq!(
+			Command::ExportJson(Some(String::from("My Keys.json"))),
+			Command::from_str(":export-json My Keys.json")?
+		);
+		assert_eq!(
+			Command::ExportJson(None),
+			Command::from_str(":export-json")?
+		);
+		assert_eq!(
+			Command::CleanExports(EXPORT_CLEANUP_DAYS, false),
+			Command::from_str(":clean-exports")?
+		);
+		assert_eq!(
+			Command::CleanExports(7, true),
+			Command::from_str(":clean-exports 7 secure")?
+		);
+		assert!(Command::from_str(":clean-exports old").is_err());
+		assert_eq!(
+			Command::SignFile(String::from("Test.txt"), SignatureType::Normal),
+			Command::from_str(":sign-file Test.txt")?
+		);
+		assert_eq!(
+			Command::SignFile(String::from("Test.txt"), SignatureType::Clear),
+			Command::from_str(":sign-file Test.txt clear")?
+		);
+		assert!(Command::from_str(":sign-file").is_err());
+		assert_eq!(
+			Command::VerifyFile(String::from("Test.txt.asc"), None),
+			Command::from_str(":verify Test.txt.asc")?
+		);
+		assert_eq!(
+			Command::VerifyFile(
+				String::from("Test.txt"),
+				Some(String::from("Test.txt.sig"))
+			),
+			Command::from_str(":verify Test.txt Test.txt.sig")?
+		);
+		assert!(Command::from_str(":verify").is_err());
+		assert_eq!(
+			Command::ConvertArmor(String::from("Key.gpg"), true, false),
+			Command::from_str(":armor Key.gpg")?
+		);
+		assert_eq!(
+			Command::ConvertArmor(String::from("My Key.asc"), false, false),
+			Command::from_str(":dearmor My Key.asc")?
+		);
+		assert_eq!(
+			"dearmor the file (overwrite the output)",
+			Command::ConvertArmor(String::new(), false, true).to_string()
+		);
+		assert!(Command::from_str(":armor").is_err());
+		assert_eq!(
+			Command::VerifyRelease(
+				String::from("App.tar.gz"),
+				String::from("https://example.org/App.tar.gz.sig")
+			),
+			Command::from_str(
+				":verify-release App.tar.gz https://example.org/App.tar.gz.sig"
+			)?
+		);
+		assert!(Command::from_str(":verify-release App.tar.gz").is_err());
+		assert_eq!(
+			Command::RevokeKey(String::from("0xABC"), None),
+			Command::from_str(":revoke 0xabc")?
+		);
+		assert_eq!(
+			Command::RevokeKey(
+				String::from("0xABC"),
+				Some(String::from("Key is Lost"))
+			),
+			Command::from_str(":revoke 0xabc Key is Lost")?
+		);
+		assert!(Command::from_str(":revoke").is_err());
+		for cmd in &[":encrypt Test.txt /tmp/x", ":enc Test.txt /tmp/x"] {
+			assert_eq!(
+				Command::EncryptFiles(vec![
+					String::from("Test.txt"),
+					String::from("/tmp/x")
+				]),
+				Command::from_str(cmd)?
+			);
+		}
+		assert_eq!(
+			Command::EncryptClipboard(String::from("Test@example.org")),
+			Command::from_str(":encrypt-clipboard Test@example.org")?
+		);
+		assert!(Command::from_str(":encrypt-clipboard").is_err());
+		assert_eq!(
+			Command::EncryptSymmetric(vec![String::from("Test.txt")], None),
+			Command::from_str(":encrypt-symmetric Test.txt")?
+		);
+		assert_eq!(
+			Command::DecryptClipboard,
+			Command::from_str(":decrypt-clipboard")?
+		);
+		for cmd in &[":delete pub xyz", ":del pub xyz"] {
+			let command = Command::from_str(cmd)?;
+			assert_eq!(
+				Command::DeleteKey(KeyType::Public, String::from("xyz")),
+				command
+			);
+		}
+		assert_eq!(
+			Command::SendKey(String::from("test"), None),
 			Command::from_str(":send test")?
 		);
+		assert_eq!(
+			Command::SendKey(
+				String::from("test"),
+				Some(String::from("hkps://keys.openpgp.org"))
+			),
+			Command::from_str(":send test hkps://keys.openpgp.org")?
+		);
+		assert_eq!(
+			Command::SelectKeyserver(String::from("0xABC")),
+			Command::from_str(":keyserver 0xabc")?
+		);
+		assert_eq!(
+			Command::DiffKey(String::from("0x00")),
+			Command::from_str(":diff 0x00")?
+		);
+		assert!(Command::from_str(":diff").is_err());
+		assert_eq!(
+			Command::CompareFingerprint(String::from("1234abcd")),
+			Command::from_str(":compare 1234 ABCD")?
+		);
+		assert!(Command::from_str(":compare").is_err());
+		assert_eq!(
+			Command::LocateSigners(String::from("0xABC")),
+			Command::from_str(":locate-signers 0xabc")?
+		);
+		assert!(Command::from_str(":locate-signers").is_err());
+		assert_eq!(
+			Command::SearchKeyserver(String::from("Test User")),
+			Command::from_str(":search-keyserver Test User")?
+		);
+		assert_eq!(
+			Command::SearchKeyserver(String::from("test@example.org")),
+			Command::from_str(":sk test@example.org")?
+		);
+		assert!(Command::from_str(":search-keyserver").is_err());
+		assert_eq!(
+			Command::ResolveEmail(String::from("test@example.org")),
+			Command::from_str(":who Test@example.org")?
+		);
+		assert!(Command::from_str(":who test").is_err());
+		assert_eq!(Command::ShowCard, Command::from_str(":card")?);
+		assert_eq!(
+			Command::GoToKey(String::from("0xABC")),
+			Command::from_str(":goto 0xabc")?
+		);
+		assert!(Command::from_str(":goto").is_err());
+		for cmd in &[":report", ":report html"] {
+			let command = Command::from_str(cmd)?;
+			assert_eq!(Command::GenerateReport, command);
+		}
+		assert!(Command::from_str(":report pdf").is_err());
+		assert_eq!(
+			Command::ShowExpiring(EXPIRY_WARNING_DAYS),
+			Command::from_str(":expiring")?
+		);
+		assert_eq!(
+			Command::ShowExpiring(90),
+			Command::from_str(":expiring 90")?
+		);
+		assert!(Command::from_str(":expiring soon").is_err());
+		assert_eq!(Command::ShowHistory, Command::from_str(":history")?);
+		assert_eq!(
+			Command::InspectKey(String::from("0xABC")),
+			Command::from_str(":inspect 0xabc")?
+		);
+		assert!(Command::from_str(":inspect").is_err());
+		assert_eq!(
+			Command::ShowQrCode(Selection::Key),
+			Command::from_str(":qr")?
+		);
+		assert_eq!(
+			Command::ShowQrCode(Selection::KeyFingerprint),
+			Command::from_str(":qr fingerprint")?
+		);
+		assert!(Command::from_str(":qr x").is_err());
 		assert_eq!(
 			Command::EditKey(String::from("test")),
 			Command::from_str(":edit test")?
 		);
 		assert_eq!(
-			Command::SignKey(String::from("test")),
+			Command::RunKeyEdit(
+				String::from("0xabc"),
+				KeyEdit::AddUid(String::from("Test <test@example.org>"))
+			),
+			Command::from_str(":edit 0xABC adduid Test <test@example.org>")?
+		);
+		assert!(Command::from_str(":edit 0xabc trust x").is_err());
+		assert_eq!(Command::SetOwnerTrust(None), Command::from_str(":trust")?);
+		assert_eq!(
+			Command::SetOwnerTrust(Some(5)),
+			Command::from_str(":trust Ultimate")?
+		);
+		assert!(Command::from_str(":trust 9").is_err());
+		assert_eq!(
+			Command::AddUserId(String::from("Test (Work) <test@example.org>")),
+			Command::from_str(":adduid \"Test <test@example.org> (Work)\"")?
+		);
+		assert_eq!(
+			Command::AddUserId(String::from(
+				"Doe, John (Work) <john@example.org>"
+			)),
+			Command::from_str(":adduid \"Doe, John\" john@example.org Work")?
+		);
+		assert_eq!(
+			Command::AddUserId(String::from("John  Doe <john@example.org>")),
+			Command::from_str(":adduid \"John  Doe\" <john@example.org>")?
+		);
+		assert!(Command::from_str(":adduid \"Test <test@example.org>").is_err());
+		assert!(Command::from_str(":adduid \"\"").is_err());
+		assert!(Command::from_str(":adduid").is_err());
+		assert_eq!(Command::RevokeUserId(2), Command::from_str(":revuid 2")?);
+		assert_eq!(
+			Command::SetPrimaryUserId(1),
+			Command::from_str(":primary-uid 1")?
+		);
+		assert!(Command::from_str(":primary-uid 0").is_err());
+		assert!(Command::from_str(":revuid x").is_err());
+		assert_eq!(
+			Command::AddSubkey(String::from("0xABC")),
+			Command::from_str(":addsubkey 0xabc")?
+		);
+		assert!(Command::from_str(":addsubkey").is_err());
+		assert_eq!(
+			Command::SetExpiration(
+				String::from("0xABC"),
+				String::from("never"),
+				Vec::new()
+			),
+			Command::from_str(":expire 0xabc Never")?
+		);
+		assert_eq!(
+			Command::SetExpiration(
+				String::from("0xABC"),
+				String::from("2y"),
+				vec![String::from("*")]
+			),
+			Command::from_str(":expire 0xabc 2y *")?
+		);
+		assert!(Command::from_str(":expire 0xabc x").is_err());
+		assert!(Command::from_str(":expire 0xabc").is_err());
+		assert_eq!(
+			Command::EditPreferences(String::from("0xABC"), false),
+			Command::from_str(":prefs 0xabc")?
+		);
+		for cmd in &[":prefs 0xabc update", ":setpref 0xabc"] {
+			let command = Command::from_str(cmd)?;
+			assert_eq!(
+				Command::EditPreferences(String::from("0xABC"), true),
+				command
+			);
+		}
+		assert!(Command::from_str(":prefs 0xabc x").is_err());
+		assert_eq!(
+			Command::SignKey(String::from("test"), None),
 			Command::from_str(":sign test")?
 		);
+		assert_eq!(
+			Command::SignKey(String::from("test"), Some(String::from("0x0"))),
+			Command::from_str(":sign test --with 0x0")?
+		);
+		assert!(Command::from_str(":sign test --with").is_err());
+		assert_eq!(
+			Command::PresetPassphrase(String::from("0xABC"), None),
+			Command::from_str(":agent preset 0xabc")?
+		);
+		assert!(Command::from_str(":agent preset").is_err());
+		assert!(Command::from_str(":agent clear 0xabc").is_err());
+		assert_eq!(
+			Command::ChangePassphrase(String::from("0xABC"), None, None),
+			Command::from_str(":passwd 0xabc")?
+		);
+		assert!(Command::from_str(":passwd").is_err());
+		assert_eq!(
+			Command::ChangePassphrase(
+				String::from("0x0"),
+				Some(SecretInput::from("old")),
+				Some(SecretInput::from("new"))
+			),
+			Command::ChangePassphrase(String::from("0x0"), None, None)
+				.with_passphrase(SecretInput::from("old"))
+				.with_passphrase(SecretInput::from("new"))
+		);
+		assert_eq!(
+			Command::None,
+			Command::None.with_passphrase(SecretInput::default())
+		);
 		assert_eq!(Command::GenerateKey, Command::from_str(":generate")?);
+		assert_eq!(
+			Command::GenerateKeyWithProfile(String::from("work"), None),
+			Command::from_str(":generate profile work")?
+		);
+		assert_eq!(
+			Command::GenerateKeyWithProfile(
+				String::from("work"),
+				Some(String::from("Test (Work) <test@example.org>"))
+			),
+			Command::from_str(
+				":gen profile work Test (Work) <test@example.org>"
+			)?
+		);
+		assert!(Command::from_str(":generate profile").is_err());
+		assert!(Command::from_str(":generate x").is_err());
 		assert_eq!(Command::RefreshKeys, Command::from_str(":refresh keys")?);
 		for cmd in &[":toggle detail all", ":t detail all"] {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(Command::ToggleDetail(true), command);
 		}
+		assert_eq!(
+			Command::ToggleCollapse,
+			Command::from_str(":toggle collapse")?
+		);
 		assert_eq!(Command::ToggleTableSize, Command::from_str(":toggle")?);
 		for cmd in &[":scroll up 1", ":scroll u 1"] {
 			let command = Command::from_str(cmd)?;
@@ -482,10 +2114,46 @@ mod tests {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(Command::Get(String::from("armor")), command);
 		}
+		assert_eq!(
+			Command::Redirect(
+				Box::new(Command::Get(String::from("armor"))),
+				String::from("Armor.txt")
+			),
+			Command::from_str(":get armor > Armor.txt")?
+		);
+		assert!(Command::from_str(":get armor > ").is_err());
+		assert_eq!(
+			Command::Search(Some(String::from("foo"))),
+			Command::from_str(":search foo > bar")?
+		);
+		assert_eq!(
+			Command::RunKeyEdit(
+				String::from("0x0"),
+				KeyEdit::AddUid(String::from("A <a@b.c> > x"))
+			),
+			Command::from_str(":edit 0x0 adduid A <a@b.c> > x")?
+		);
+		assert!(!matches!(
+			Command::from_str(":adduid A <a@b.c> > x"),
+			Ok(Command::Redirect(_, _))
+		));
+		assert_eq!(
+			Command::Get(String::from("armor>x")),
+			Command::from_str(":get armor>x")?
+		);
+		assert_eq!(
+			Command::ListKeys(KeyType::Public),
+			Command::parse_without_redirect(":list pub > keys.txt")?
+		);
+		assert!(Command::from_str(":xyz > test.txt").is_err());
 		assert_eq!(
 			Command::Set(String::from("test"), String::from("_")),
 			Command::from_str(":set test _")?
 		);
+		assert_eq!(
+			Command::Set(String::from("date_format"), String::from("%d %B %Y")),
+			Command::from_str(":set date_format %d %B %Y")?
+		);
 		for cmd in &[":normal", ":n"] {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(Command::SwitchMode(Mode::Normal), command);
@@ -498,6 +2166,26 @@ mod tests {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(Command::SwitchMode(Mode::Copy), command);
 		}
+		assert_eq!(
+			Command::SwitchMode(Mode::Pick),
+			Command::from_str(":pick")?
+		);
+		assert_eq!(
+			Command::ToggleRecipient(String::from("0xAB00")),
+			Command::from_str(":recipient 0xab00")?
+		);
+		assert_eq!(
+			Command::Mark(Some(String::from("0xAB00"))),
+			Command::from_str(":mark 0xab00")?
+		);
+		assert_eq!(Command::Mark(None), Command::from_str(":mark range")?);
+		assert_eq!(Command::TogglePin(None), Command::from_str(":pin")?);
+		assert_eq!(
+			Command::TogglePin(Some(String::from("0xAB00"))),
+			Command::from_str(":pin 0xab00")?
+		);
+		assert_eq!(Command::NextPinned, Command::from_str(":pin next")?);
+		assert!(Command::from_str(":mark").is_err());
 		for cmd in &[":paste", ":p"] {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(Command::Paste, command);
@@ -510,13 +2198,41 @@ mod tests {
 		assert_eq!(Command::NextTab, Command::from_str(":next")?);
 		assert_eq!(Command::PreviousTab, Command::from_str(":prev")?);
 		assert_eq!(Command::Refresh, Command::from_str(":refresh")?);
-		for cmd in &[":quit", ":q", ":q!"] {
+		for cmd in &[":quit", ":q"] {
 			let command = Command::from_str(cmd)?;
 			assert_eq!(Command::Quit, command);
 		}
+		for cmd in &[":quit!", ":q!"] {
+			let command = Command::from_str(cmd)?;
+			assert_eq!(Command::ForceQuit, command);
+		}
 		assert_eq!(Command::None, Command::from_str(":none")?);
 		assert!(Command::from_str("test").is_err());
 		assert_eq!(Command::Logs, Command::from_str(":logs")?);
+		assert_eq!(Command::Lock, Command::from_str(":lock")?);
+		assert_eq!(Command::SyncConfig, Command::from_str(":sync-config")?);
+		assert_eq!(Command::ReloadConfig, Command::from_str(":reload-config")?);
+		assert_eq!(Command::WriteConfig, Command::from_str(":write-config")?);
+		assert_eq!(
+			Command::Filter(Some(KeyFilter::Capability(
+				KeyCapability::Encrypt
+			))),
+			Command::from_str(":filter can-encrypt")?
+		);
+		assert_eq!(
+			Command::Filter(Some(KeyFilter::Capability(KeyCapability::Auth))),
+			Command::from_str(":filter auth")?
+		);
+		assert_eq!(
+			Command::Filter(Some(KeyFilter::State(KeyState::Expired))),
+			Command::from_str(":filter expired")?
+		);
+		assert_eq!(
+			Command::Filter(Some(KeyFilter::State(KeyState::HasSecret))),
+			Command::from_str(":filter has-secret")?
+		);
+		assert_eq!(Command::Filter(None), Command::from_str(":filter")?);
+		assert!(Command::from_str(":filter can-fly").is_err());
 
 		assert_eq!("close menu", Command::None.to_string());
 		assert_eq!("show help", Command::ShowHelp.to_string());
@@ -534,6 +2250,11 @@ mod tests {
 			"list public keys",
 			Command::ListKeys(KeyType::Public).to_string()
 		);
+		assert_eq!(
+			"apply the revocation certificate (rev.asc)",
+			Command::ApplyRevocation(String::new(), String::from("rev.asc"))
+				.to_string()
+		);
 		assert_eq!(
 			"export all the keys (sec)",
 			Command::ExportKeys(KeyType::Secret, Vec::new(), false).to_string()
@@ -548,28 +2269,189 @@ mod tests {
 			Command::ExportKeys(KeyType::Public, vec![String::new()], false)
 				.to_string()
 		);
+		assert_eq!(
+			"export the authentication subkey (ssh)",
+			Command::ExportSshKey(String::new()).to_string()
+		);
+		assert_eq!(
+			"export the key with its signers",
+			Command::ExportWithSigners(String::new()).to_string()
+		);
+		assert_eq!(
+			"serve the key on the local network",
+			Command::ServeKey(String::new()).to_string()
+		);
+		assert_eq!(
+			"import 2 key(s) from http://test",
+			Command::ImportFetched(
+				String::from("http://test"),
+				vec![String::new(); 2],
+				Vec::new()
+			)
+			.to_string()
+		);
+		assert_eq!(
+			"export the secret key as paper backup",
+			Command::ExportPaperKey(String::new(), false).to_string()
+		);
+		assert_eq!(
+			"export the pinned keys to separate files in keys/",
+			Command::ExportEach(KeySet::Pinned, Some(String::from("keys/")))
+				.to_string()
+		);
+		assert_eq!(
+			"delete 2 export file(s): pub_0x0.asc, sec_0x0.asc",
+			Command::RemoveExports(
+				vec![
+					String::from("out/pub_0x0.asc"),
+					String::from("sec_0x0.asc")
+				],
+				true
+			)
+			.to_string()
+		);
 		assert_eq!(
 			"delete the selected key (pub)",
 			Command::DeleteKey(KeyType::Public, String::new()).to_string()
 		);
 		assert_eq!(
 			"send key to the keyserver",
-			Command::SendKey(String::new()).to_string()
+			Command::SendKey(String::new(), None).to_string()
+		);
+		assert_eq!(
+			"send key to hkps://keys.openpgp.org",
+			Command::SendKey(
+				String::new(),
+				Some(String::from("hkps://keys.openpgp.org"))
+			)
+			.to_string()
+		);
+		assert_eq!(
+			"compare with the keyserver copy",
+			Command::DiffKey(String::new()).to_string()
+		);
+		assert_eq!(
+			"receive the unknown signer keys",
+			Command::LocateSigners(String::new()).to_string()
+		);
+		assert_eq!(
+			"search the keyserver for test",
+			Command::SearchKeyserver(String::from("test")).to_string()
+		);
+		assert_eq!(
+			"find the key of test@example.org",
+			Command::ResolveEmail(String::from("test@example.org")).to_string()
+		);
+		assert_eq!("show the smartcard status", Command::ShowCard.to_string());
+		assert_eq!(
+			"go to the key in keyring",
+			Command::GoToKey(String::new()).to_string()
+		);
+		assert_eq!(
+			"receive key(s) from keyserver",
+			Command::ImportKeys(Vec::new(), true, None).to_string()
+		);
+		assert_eq!(
+			"receive key(s) from hkp://keys.example.com",
+			Command::ImportKeys(
+				Vec::new(),
+				true,
+				Some(String::from("hkp://keys.example.com"))
+			)
+			.to_string()
+		);
+		assert_eq!(
+			"generate a keyring report (html)",
+			Command::GenerateReport.to_string()
+		);
+		assert_eq!(
+			"show the keys expiring within 7 days",
+			Command::ShowExpiring(7).to_string()
+		);
+		assert_eq!("show the key history", Command::ShowHistory.to_string());
+		assert_eq!(
+			"show the key details",
+			Command::InspectKey(String::new()).to_string()
+		);
+		assert_eq!(
+			"show the exported key as QR code",
+			Command::ShowQrCode(Selection::Key).to_string()
 		);
 		assert_eq!(
 			"edit the selected key",
 			Command::EditKey(String::new()).to_string()
 		);
+		assert_eq!(
+			"change the passphrase",
+			Command::RunKeyEdit(String::new(), KeyEdit::Passwd).to_string()
+		);
+		assert_eq!(
+			"set owner trust to marginal",
+			Command::SetOwnerTrust(Some(3)).to_string()
+		);
+		assert_eq!(
+			"add a user ID...",
+			Command::Set(
+				String::from("prompt"),
+				String::from(":edit 0xabc adduid ")
+			)
+			.to_string()
+		);
+		assert_eq!(
+			"revoke a user ID...",
+			Command::Set(String::from("prompt"), String::from(":revuid "))
+				.to_string()
+		);
+		assert_eq!(
+			"set user ID #2 as primary",
+			Command::SetPrimaryUserId(2).to_string()
+		);
+		assert_eq!(
+			"add a subkey",
+			Command::AddSubkey(String::new()).to_string()
+		);
 		assert_eq!(
 			"sign the selected key",
-			Command::SignKey(String::new()).to_string()
+			Command::SignKey(String::new(), None).to_string()
+		);
+		assert_eq!(
+			"sign the selected key with 0x0",
+			Command::SignKey(String::new(), Some(String::from("0x0")))
+				.to_string()
 		);
 		assert_eq!("generate a new key pair", Command::GenerateKey.to_string());
+		assert_eq!(
+			"generate a new key pair (work profile)",
+			Command::GenerateKeyWithProfile(String::from("work"), None)
+				.to_string()
+		);
 		assert_eq!(
 			"copy exported key",
 			Command::Copy(Selection::Key).to_string()
 		);
 		assert_eq!("paste from clipboard", Command::Paste.to_string());
+		assert_eq!(
+			"toggle the selected recipient",
+			Command::ToggleRecipient(String::new()).to_string()
+		);
+		assert_eq!(
+			"delete 2 marked keys (pub)",
+			Command::Batch(vec![
+				Command::DeleteKey(KeyType::Public, String::new()),
+				Command::DeleteKey(KeyType::Public, String::new()),
+			])
+			.to_string()
+		);
+		assert_eq!(
+			"send key to the keyserver (2 keys)",
+			Command::Batch(vec![
+				Command::SendKey(String::new(), None),
+				Command::SendKey(String::new(), None),
+			])
+			.to_string()
+		);
+		assert!(Command::Batch(vec![Command::SignKey(String::new(), None)])
+			.is_allowed(&[String::from("sign")]));
 		assert_eq!(
 			"toggle detail (all)",
 			Command::ToggleDetail(true).to_string()
@@ -578,6 +2460,10 @@ mod tests {
 			"toggle detail (selected)",
 			Command::ToggleDetail(false).to_string()
 		);
+		assert_eq!(
+			"collapse/expand the selected key",
+			Command::ToggleCollapse.to_string()
+		);
 		assert_eq!("toggle table size", Command::ToggleTableSize.to_string());
 		assert_eq!(
 			"disable armored output",
@@ -602,6 +2488,10 @@ mod tests {
 			"import key(s) from clipboard",
 			Command::ImportClipboard.to_string()
 		);
+		assert_eq!(
+			"switch to a temporary keyring",
+			Command::EnterSandbox.to_string()
+		);
 		assert_eq!(
 			"receive key(s) from keyserver",
 			Command::Set(String::from("prompt"), String::from(":receive "))
@@ -625,8 +2515,97 @@ mod tests {
 			Command::Confirm(Box::new(Command::Refresh)).to_string()
 		);
 		assert_eq!("quit application", Command::Quit.to_string());
+		assert_eq!("quit anyway", Command::ForceQuit.to_string());
+		assert_eq!(
+			"generate a keyring report (html) (write to report.txt)",
+			Command::Redirect(
+				Box::new(Command::GenerateReport),
+				String::from("report.txt")
+			)
+			.to_string()
+		);
 		assert_eq!("NextTab", Command::NextTab.to_string());
 		assert_eq!("show logs", Command::Logs.to_string());
+		assert_eq!("lock the screen", Command::Lock.to_string());
+		assert_eq!("sync the configuration", Command::SyncConfig.to_string());
+		assert_eq!(
+			"write the configuration file",
+			Command::WriteConfig.to_string()
+		);
+		assert_eq!(
+			"show the keys that can sign",
+			Command::Filter(Some(KeyFilter::Capability(KeyCapability::Sign)))
+				.to_string()
+		);
+
+		let allowed_commands = vec![
+			String::from("list"),
+			String::from(":search"),
+			String::from("copy key_fingerprint"),
+			String::from("copy fingerprints"),
+		];
+		for command in [
+			Command::ListKeys(KeyType::Secret),
+			Command::Search(None),
+			Command::Copy(Selection::KeyFingerprint),
+			Command::Copy(Selection::Fingerprints),
+			Command::Scroll(ScrollDirection::Down(1), false),
+			Command::Quit,
+			Command::ForceQuit,
+		] {
+			assert!(command.is_allowed(&allowed_commands));
+		}
+		for command in [
+			Command::Copy(Selection::Key),
+			Command::DeleteKey(KeyType::Public, String::new()),
+			Command::Confirm(Box::new(Command::GenerateKey)),
+			Command::Redirect(
+				Box::new(Command::ListKeys(KeyType::Public)),
+				String::from("test.txt"),
+			),
+		] {
+			assert!(!command.is_allowed(&allowed_commands));
+		}
+
+		for command in [
+			Command::ExportKeys(
+				KeyType::Public,
+				vec![String::from("0x0")],
+				true,
+			),
+			Command::ExportPaperKey(String::from("0x0"), true),
+			Command::RevokeKey(String::from("0x0"), Some(String::from("lost"))),
+			Command::ShowQrCode(Selection::KeyFingerprint),
+			Command::Copy(Selection::SshKey),
+			Command::ChangeStyle(Style::HighContrast),
+			Command::SetOwnerTrust(Some(5)),
+			Command::SignKey(String::from("0x0"), Some(String::from("0x1"))),
+			Command::SwitchMode(Mode::Visual),
+			Command::ToggleDetail(true),
+			Command::RefreshKeys,
+		] {
+			assert_eq!(
+				Ok(command.clone()),
+				Command::from_str(&command.get_syntax().ok_or(())?)
+			);
+		}
+		assert_eq!(
+			Some(String::from(":export sec 0x0 paper")),
+			Command::ExportPaperKey(String::from("0x0"), false).get_syntax()
+		);
+		assert_eq!(
+			None,
+			Command::PresetPassphrase(
+				String::from("0x0"),
+				Some(SecretInput::default())
+			)
+			.get_syntax()
+		);
+		assert_eq!(
+			None,
+			Command::Set(String::from("prompt"), String::from(":import "))
+				.get_syntax()
+		);
 		Ok(())
 	}
 }
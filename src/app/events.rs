@@ -0,0 +1,146 @@
+use crate::app::prompt::OutputType;
+use crate::gpg::handler;
+use anyhow::Result;
+use serde_json::json;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::time::SystemTime;
+
+/// Path for writing the events to stdout.
+pub const STDOUT_PATH: &str = "-";
+
+/// Significant event of an operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperationEvent {
+	/// Name of the operation. (e.g. `import`)
+	pub name: String,
+	/// Fingerprints of the affected keys.
+	pub fingerprints: Vec<String>,
+	/// Paths of the affected files.
+	pub paths: Vec<String>,
+}
+
+impl OperationEvent {
+	/// Constructs a new instance of `OperationEvent`.
+	pub fn new(name: &str) -> Self {
+		Self {
+			name: name.to_string(),
+			..Self::default()
+		}
+	}
+
+	/// Sets the fingerprints of the affected keys.
+	pub fn with_fingerprints(mut self, fingerprints: Vec<String>) -> Self {
+		self.fingerprints = fingerprints;
+		self
+	}
+
+	/// Sets the paths of the affected files.
+	pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+		self.paths = paths;
+		self
+	}
+}
+
+/// Stream of the significant events as JSON lines.
+///
+/// Each line is an object that contains the time, name of the
+/// operation, status (`success`, `warning` or `failure`) and the
+/// message of the event along with the affected `fingerprints`
+/// and `paths` (if any).
+pub struct EventStream {
+	/// Output of the events.
+	output: Box<dyn Write>,
+}
+
+impl EventStream {
+	/// Constructs a new instance of `EventStream`.
+	///
+	/// Events are appended to the given file. (e.g. `/dev/fd/3`)
+	pub fn new(path: &str) -> Result<Self> {
+		Ok(Self {
+			output: if path == STDOUT_PATH {
+				Box::new(io::stdout())
+			} else {
+				Box::new(
+					OpenOptions::new().create(true).append(true).open(path)?,
+				)
+			},
+		})
+	}
+
+	/// Writes the event if it has a significant output type.
+	pub fn write(
+		&mut self,
+		event: &OperationEvent,
+		output_type: &OutputType,
+		message: &str,
+	) -> Result<()> {
+		let status = match output_type {
+			OutputType::Success => "success",
+			OutputType::Warning => "warning",
+			OutputType::Failure => "failure",
+			_ => return Ok(()),
+		};
+		let mut line = json!({
+			"time": handler::get_iso_timestamp(SystemTime::now()),
+			"event": event.name,
+			"status": status,
+			"message": message,
+		});
+		if !event.fingerprints.is_empty() {
+			line["fingerprints"] = json!(event.fingerprints);
+		}
+		if !event.paths.is_empty() {
+			line["paths"] = json!(event.paths);
+		}
+		writeln!(self.output, "{line}")?;
+		self.output.flush()?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use serde_json::Value;
+	use std::fs;
+	#[test]
+	fn test_app_events() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("events.jsonl");
+		let mut events = EventStream::new(&path.to_string_lossy())?;
+		events.write(
+			&OperationEvent::new("import")
+				.with_paths(vec![String::from("key.asc")]),
+			&OutputType::Success,
+			"1 key imported",
+		)?;
+		events.write(
+			&OperationEvent::new("style"),
+			&OutputType::Action,
+			"ignored",
+		)?;
+		events.write(
+			&OperationEvent::new("export")
+				.with_fingerprints(vec![String::from("ABC123")]),
+			&OutputType::Failure,
+			"export error",
+		)?;
+		let lines = fs::read_to_string(&path)?
+			.lines()
+			.map(serde_json::from_str)
+			.collect::<Result<Vec<Value>, _>>()?;
+		assert_eq!(2, lines.len());
+		assert_eq!("import", lines[0]["event"]);
+		assert_eq!("success", lines[0]["status"]);
+		assert_eq!("1 key imported", lines[0]["message"]);
+		assert_eq!("key.asc", lines[0]["paths"][0]);
+		assert!(lines[0]["fingerprints"].is_null());
+		assert_eq!("failure", lines[1]["status"]);
+		assert_eq!("ABC123", lines[1]["fingerprints"][0]);
+		assert!(lines[1]["time"].as_str().is_some());
+		Ok(())
+	}
+}
@@ -1,3 +1,4 @@
+use crate::app::browser::FileBrowser;
 use crate::app::command::Command;
 use crate::app::launcher::App;
 use crate::app::mode::Mode;
@@ -5,15 +6,20 @@ use crate::app::prompt::OutputType;
 use crate::app::selection::Selection;
 use crate::app::tab::Tab;
 use crate::app::util;
-use crate::config::CustomKeyBinding;
+use crate::config::{CustomKeyBinding, KeyBindingContext};
 use crate::gpg::key::KeyType;
 use crate::term::tui::Tui;
 use crate::widget::row::ScrollDirection;
 use anyhow::Result;
-use crossterm::event::{KeyCode as Key, KeyEvent, KeyModifiers as Modifiers};
+use crossterm::event::{
+	KeyCode as Key, KeyEvent, KeyModifiers as Modifiers, MouseEvent,
+	MouseEventKind,
+};
 use log::Level;
 use ratatui::backend::Backend;
+use std::env;
 use std::str::FromStr;
+use std::time::Instant;
 
 use super::command::LoggerCommand;
 
@@ -24,11 +30,81 @@ pub fn handle_events<B: Backend>(
 	tui: &mut Tui<B>,
 	app: &mut App,
 ) -> Result<()> {
+	app.state.last_activity = Instant::now();
+	if app.state.locked {
+		handle_lock_event(key_event, app);
+		return Ok(());
+	}
 	handle_command_execution(
 		handle_key_event(key_event, key_bindings, app),
 		tui,
 		app,
-	)
+	)?;
+	app.state.last_activity = Instant::now();
+	Ok(())
+}
+
+/// Handles the mouse events and executes the application command.
+pub fn handle_mouse_events<B: Backend>(
+	mouse_event: MouseEvent,
+	tui: &mut Tui<B>,
+	app: &mut App,
+) -> Result<()> {
+	if app.state.locked {
+		return Ok(());
+	} else if app.state.show_splash {
+		app.state.show_splash = false;
+		return Ok(());
+	}
+	let command = handle_mouse_event(mouse_event);
+	if command != Command::None {
+		app.state.last_activity = Instant::now();
+		handle_command_execution(command, tui, app)?;
+	}
+	Ok(())
+}
+
+/// Returns the corresponding application command for a mouse event.
+///
+/// Scroll events are routed to the active widget
+/// (e.g. options menu, help tab) via [`Command::Scroll`].
+fn handle_mouse_event(mouse_event: MouseEvent) -> Command {
+	match mouse_event.kind {
+		MouseEventKind::ScrollDown => {
+			Command::Scroll(ScrollDirection::Down(1), false)
+		}
+		MouseEventKind::ScrollUp => {
+			Command::Scroll(ScrollDirection::Up(1), false)
+		}
+		_ => Command::None,
+	}
+}
+
+/// Handles the key events while the screen is locked.
+fn handle_lock_event(key_event: KeyEvent, app: &mut App) {
+	if app.state.lock_phrase.is_none() {
+		app.state.unlock();
+		return;
+	}
+	match key_event.code {
+		Key::Char(c) => app.state.unlock_input.push(c),
+		Key::Backspace => {
+			app.state.unlock_input.pop();
+		}
+		Key::Esc => app.state.unlock_input.clear(),
+		Key::Enter => {
+			app.state.unlock();
+		}
+		_ => {}
+	}
+}
+
+/// Saves the prompt text to the command/search history.
+fn save_history(app: &mut App) {
+	let text = app.prompt.text.clone();
+	if let Err(e) = app.prompt.push_history(&text) {
+		log::error!(target: "history", "failed to save: {e}");
+	}
 }
 
 /// Returns the corresponding application command for a key event.
@@ -38,43 +114,210 @@ fn handle_key_event(
 	app: &mut App,
 ) -> Command {
 	let mut command = Command::None;
-	if app.prompt.is_enabled() {
+	let context = if app.prompt.is_enabled() {
+		KeyBindingContext::Prompt
+	} else if app.tab == Tab::Help {
+		KeyBindingContext::Help
+	} else if app.tab == Tab::Card {
+		KeyBindingContext::Card
+	} else {
+		KeyBindingContext::Keys
+	};
+	if app.prompt.is_passphrase_input_enabled() {
+		match key_event.code {
+			Key::Char(c) => app.prompt.push_passphrase(c),
+			Key::Backspace => app.prompt.pop_passphrase(),
+			Key::Esc => app.prompt.clear(),
+			Key::Enter => {
+				if let Some((passphrase_command, passphrase)) =
+					app.prompt.passphrase_input.take()
+				{
+					command = passphrase_command.with_passphrase(passphrase);
+				}
+				app.prompt.clear();
+			}
+			_ => {}
+		}
+	} else if let Some(confirm_command) = app.prompt.command.clone() {
 		match key_event.code {
+			Key::Left
+			| Key::Right
+			| Key::Tab
+			| Key::BackTab
+			| Key::Char('h')
+			| Key::Char('l') => app.prompt.confirm = !app.prompt.confirm,
+			Key::Enter | Key::Char(' ') => {
+				if app.prompt.confirm {
+					command = confirm_command;
+				} else {
+					app.prompt.clear();
+				}
+			}
+			Key::Char('y') | Key::Char('Y') => command = confirm_command,
+			Key::Char('n') | Key::Char('N') | Key::Esc => app.prompt.clear(),
+			Key::Char('c') if key_event.modifiers == Modifiers::CONTROL => {
+				command = if confirm_command == Command::ForceQuit {
+					confirm_command
+				} else {
+					Command::Quit
+				};
+			}
+			_ => {}
+		}
+	} else if let Some(browser) = app.file_browser.as_mut() {
+		match key_event.code {
+			Key::Char('c') if key_event.modifiers == Modifiers::CONTROL => {
+				command = Command::Quit;
+			}
+			Key::Esc => app.file_browser = None,
+			Key::Enter | Key::Right | Key::Char('l') => {
+				if let Some(files) = browser.open() {
+					command = if browser.encrypt {
+						Command::EncryptFiles(files)
+					} else {
+						Command::ImportKeys(files, false, None)
+					};
+					app.file_browser = None;
+				}
+			}
+			Key::Backspace | Key::Left | Key::Char('h') => browser.go_up(),
+			Key::Char(' ') => browser.toggle(),
+			Key::Down | Key::Char('j') => browser.select_next(),
+			Key::Up | Key::Char('k') => browser.select_previous(),
+			_ => {}
+		}
+	} else if let Some(wizard) = app.wizard.as_mut() {
+		match key_event.code {
+			Key::Char('c') if key_event.modifiers == Modifiers::CONTROL => {
+				command = Command::Quit;
+			}
+			Key::Esc => app.wizard = None,
+			Key::Enter | Key::Tab => {
+				if let Some(params) = wizard.next_step() {
+					command = match wizard.key_id.clone() {
+						Some(key_id) => Command::CreateSubkey(key_id, params),
+						None => Command::CreateKey(params),
+					};
+				}
+			}
+			Key::BackTab => wizard.previous_step(),
+			Key::Backspace => wizard.pop(),
+			Key::Char(c) if wizard.step.is_input() => wizard.push(c),
+			Key::Char(' ') => wizard.select(),
+			Key::Down | Key::Char('j') => wizard.select_next(),
+			Key::Up | Key::Char('k') => wizard.select_previous(),
+			_ => {}
+		}
+	} else if app.verifications.is_some()
+		|| app.receive_results.is_some()
+		|| app.expiring_keys.is_some()
+		|| app.fingerprint_comparison.is_some()
+		|| app.key_history.is_some()
+		|| app.key_preferences.is_some()
+		|| app.qr_code.is_some()
+	{
+		match key_event.code {
+			Key::Char('c') if key_event.modifiers == Modifiers::CONTROL => {
+				command = Command::Quit;
+			}
+			_ => {
+				app.verifications = None;
+				app.receive_results = None;
+				app.expiring_keys = None;
+				app.fingerprint_comparison = None;
+				app.key_history = None;
+				app.key_preferences = None;
+				app.qr_code = None;
+			}
+		}
+	} else if let Some(key_binding) = key_bindings
+		.iter()
+		.find(|key_binding| key_binding.matches(&key_event, context))
+	{
+		// placeholders are expanded after the output redirection is parsed
+		let (text, path) = match Command::split_redirect(&key_binding.command) {
+			Some((text, path)) => (app.expand_command(text), Some(path)),
+			None => (app.expand_command(&key_binding.command), None),
+		};
+		match Command::parse_without_redirect(&text).and_then(
+			|cmd| match path {
+				Some("") => Err(()),
+				Some(path) => {
+					Ok(Command::Redirect(Box::new(cmd), path.to_string()))
+				}
+				None => Ok(cmd),
+			},
+		) {
+			Ok(cmd) => command = cmd,
+			Err(_) => app.prompt.set_output((
+				OutputType::Failure,
+				format!("invalid command: {}", text.replacen(':', "", 1)),
+			)),
+		}
+	} else if app.prompt.history_search.is_some()
+		&& key_event.code != Key::Enter
+	{
+		match key_event.code {
+			Key::Char('r') if key_event.modifiers == Modifiers::CONTROL => {
+				app.prompt.search_history()
+			}
+			Key::Char(c) => app.prompt.push_history_search(c),
+			Key::Backspace => app.prompt.pop_history_search(),
+			_ => app.prompt.finish_history_search(),
+		}
+	} else if app.prompt.is_enabled() {
+		match key_event.code {
+			Key::Char('r') if key_event.modifiers == Modifiers::CONTROL => {
+				app.prompt.search_history()
+			}
 			Key::Char(c) => {
 				app.prompt.text.push(c);
 				if app.prompt.is_search_enabled() {
 					app.keys_table.reset_state();
+					app.filter_keys();
 				}
 			}
 			Key::Up => app.prompt.previous(),
 			Key::Down => app.prompt.next(),
-			Key::Tab => {
+			Key::Tab
+				if app.prompt.is_command_input_enabled()
+					&& app.prompt.text.len() > 1 =>
+			{
+				let keys = app.get_completion_keys();
+				app.prompt.complete(&keys);
+			}
+			Key::Tab | Key::BackTab => {
 				if app.prompt.is_command_input_enabled() {
 					app.prompt.enable_search();
 				} else if app.prompt.is_search_enabled() {
 					app.prompt.enable_command_input();
-					app.keys_table
-						.items
-						.clone_from(&app.keys_table.default_items);
+					app.filter_keys();
 				}
 			}
 			Key::Backspace => {
 				app.prompt.text.pop();
 				if app.prompt.is_search_enabled() {
 					app.keys_table.reset_state();
+					app.filter_keys();
 				}
 			}
 			Key::Esc => {
 				app.prompt.clear();
 				if app.prompt.is_search_enabled() {
 					app.keys_table.reset_state();
+					app.filter_keys();
 				}
 			}
 			Key::Enter => {
 				if app.prompt.is_search_enabled() || app.prompt.text.len() < 2 {
+					if app.prompt.text.len() > 1 {
+						save_history(app);
+					}
 					app.prompt.clear();
-				} else if let Ok(cmd) = Command::from_str(&app.prompt.text) {
-					app.prompt.history.push(app.prompt.text.clone());
+				} else if let Ok(cmd) =
+					Command::from_str(&app.expand_command(&app.prompt.text))
+				{
+					save_history(app);
 					app.prompt.clear();
 					command = cmd;
 				} else {
@@ -89,11 +332,6 @@ fn handle_key_event(
 			}
 			_ => {}
 		}
-	} else if let Some(key_binding) = key_bindings
-		.iter()
-		.find(|key_binding| key_binding.keys.contains(&key_event))
-	{
-		command = key_binding.command.clone();
 	} else if app.state.show_logs {
 		if let Some(logger_command) =
 			LoggerCommand::parse(key_event.code).map(Command::LoggerEvent)
@@ -121,18 +359,35 @@ fn handle_key_event(
 					Command::SwitchMode(Mode::Normal)
 				} else if app.state.show_options {
 					Command::None
-				} else if app.prompt.command.is_some() {
-					app.prompt.clear();
-					Command::None
+				} else if let Tab::KeyDetail(key_type) = app.tab {
+					Command::ListKeys(key_type)
 				} else {
 					Command::Quit
 				}
 			}
+			Key::Enter | Key::Char('c') | Key::Char('C')
+				if matches!(app.tab, Tab::KeyDetail(_))
+					&& key_event.modifiers != Modifiers::CONTROL
+					&& !app.state.show_options =>
+			{
+				Command::Copy(Selection::DetailLine)
+			}
 			Key::Char('d') | Key::Char('D') | Key::Backspace => {
 				if key_event.modifiers == Modifiers::CONTROL
 					&& key_event.code != Key::Backspace
 				{
 					Command::Quit
+				} else if let Some(key_ids) = get_marked_keys(app) {
+					let key_type = match app.tab {
+						Tab::Keys(key_type) => key_type,
+						_ => KeyType::Public,
+					};
+					Command::Confirm(Box::new(Command::Batch(
+						key_ids
+							.into_iter()
+							.map(|key_id| Command::DeleteKey(key_type, key_id))
+							.collect(),
+					)))
 				} else {
 					match app.keys_table.selected() {
 						Some(selected_key) => {
@@ -154,6 +409,8 @@ fn handle_key_event(
 			Key::Char('c') | Key::Char('C') => {
 				if key_event.modifiers == Modifiers::CONTROL {
 					Command::Quit
+				} else if app.mode == Mode::Copy {
+					Command::Copy(Selection::Created)
 				} else {
 					Command::SwitchMode(Mode::Copy)
 				}
@@ -161,6 +418,12 @@ fn handle_key_event(
 			Key::Char('v') | Key::Char('V') => {
 				if key_event.modifiers == Modifiers::CONTROL {
 					Command::Paste
+				} else if app.mode == Mode::Copy {
+					Command::Copy(Selection::Vcard)
+				} else if app.mode == Mode::Visual
+					&& key_event.code == Key::Char('V')
+				{
+					Command::Mark(None)
 				} else {
 					Command::SwitchMode(Mode::Visual)
 				}
@@ -168,7 +431,14 @@ fn handle_key_event(
 			Key::Char('p') | Key::Char('P') => Command::Paste,
 			Key::Char('r') | Key::Char('R') | Key::F(5) => {
 				if key_event.modifiers == Modifiers::CONTROL {
-					Command::RefreshKeys
+					match get_marked_keys(app) {
+						Some(key_ids) => {
+							Command::ImportKeys(key_ids, true, None)
+						}
+						None => Command::RefreshKeys,
+					}
+				} else if app.tab == Tab::Card {
+					Command::ShowCard
 				} else {
 					Command::Refresh
 				}
@@ -209,10 +479,25 @@ fn handle_key_event(
 					Command::PreviousTab
 				}
 			}
-			Key::PageUp => Command::Scroll(ScrollDirection::Top, false),
-			Key::PageDown => Command::Scroll(ScrollDirection::Bottom, false),
+			Key::PageUp => Command::Scroll(
+				if app.keys_table.state.paging {
+					ScrollDirection::PageUp
+				} else {
+					ScrollDirection::Top
+				},
+				false,
+			),
+			Key::PageDown => Command::Scroll(
+				if app.keys_table.state.paging {
+					ScrollDirection::PageDown
+				} else {
+					ScrollDirection::Bottom
+				},
+				false,
+			),
 			Key::Char('t') | Key::Char('T') => Command::ToggleDetail(true),
 			Key::Tab => Command::ToggleDetail(false),
+			Key::BackTab => Command::ToggleCollapse,
 			Key::Char('`') => Command::Set(
 				String::from("margin"),
 				String::from(if app.keys_table_margin == 1 {
@@ -224,10 +509,19 @@ fn handle_key_event(
 			Key::Char('s') | Key::Char('S') => {
 				if key_event.modifiers == Modifiers::CONTROL {
 					Command::ChangeStyle(app.state.style.next())
+				} else if app.mode == Mode::Copy {
+					Command::Copy(Selection::SshKey)
+				} else if let Some(key_ids) = get_marked_keys(app) {
+					Command::Batch(
+						key_ids
+							.into_iter()
+							.map(|key_id| Command::SignKey(key_id, None))
+							.collect(),
+					)
 				} else {
 					match app.keys_table.selected() {
 						Some(selected_key) => {
-							Command::SignKey(selected_key.get_id())
+							Command::SignKey(selected_key.get_id(), None)
 						}
 						None => Command::ShowOutput(
 							OutputType::Failure,
@@ -237,19 +531,32 @@ fn handle_key_event(
 				}
 			}
 			Key::Char('e') | Key::Char('E') => {
-				match app.keys_table.selected() {
-					Some(selected_key) => {
-						Command::EditKey(selected_key.get_id())
+				if app.mode == Mode::Copy {
+					Command::Copy(Selection::Expires)
+				} else {
+					match app.keys_table.selected() {
+						Some(selected_key) => {
+							Command::EditKey(selected_key.get_id())
+						}
+						None => Command::ShowOutput(
+							OutputType::Failure,
+							String::from("invalid selection"),
+						),
 					}
-					None => Command::ShowOutput(
-						OutputType::Failure,
-						String::from("invalid selection"),
-					),
 				}
 			}
 			Key::Char('x') | Key::Char('X') => {
 				if app.mode == Mode::Copy {
 					Command::Copy(Selection::Key)
+				} else if let Some(key_ids) = get_marked_keys(app) {
+					Command::ExportKeys(
+						match app.tab {
+							Tab::Keys(key_type) => key_type,
+							_ => KeyType::Public,
+						},
+						key_ids,
+						false,
+					)
 				} else {
 					match app.keys_table.selected() {
 						Some(selected_key) => Command::ExportKeys(
@@ -268,18 +575,26 @@ fn handle_key_event(
 				}
 			}
 			Key::Char('g') | Key::Char('G') => Command::GenerateKey,
-			Key::Char('a') | Key::Char('A') => Command::Set(
-				String::from("armor"),
-				(!app.gpgme.config.armor).to_string(),
-			),
-			Key::Char('n') | Key::Char('N') => {
-				if app.prompt.command.is_some() {
-					app.prompt.clear();
-					Command::None
+			Key::Char('b') | Key::Char('B') => {
+				if key_event.modifiers == Modifiers::CONTROL {
+					Command::TogglePin(None)
 				} else {
-					Command::SwitchMode(Mode::Normal)
+					Command::NextPinned
 				}
 			}
+			Key::Char('a') | Key::Char('A') => {
+				if app.mode == Mode::Copy {
+					Command::Copy(Selection::Fingerprints)
+				} else {
+					Command::Set(
+						String::from("armor"),
+						(!app.gpgme.config.armor).to_string(),
+					)
+				}
+			}
+			Key::Char('n') | Key::Char('N') => {
+				Command::SwitchMode(Mode::Normal)
+			}
 			Key::Char('1') => {
 				if app.mode == Mode::Copy {
 					Command::Copy(Selection::Row1)
@@ -306,6 +621,18 @@ fn handle_key_event(
 			Key::Char('i') | Key::Char('I') => {
 				if app.mode == Mode::Copy {
 					Command::Copy(Selection::KeyId)
+				} else if app.tab == Tab::KeyserverResults {
+					match app.keys_table.selected() {
+						Some(selected_key) => Command::ImportKeys(
+							vec![selected_key.get_id()],
+							true,
+							None,
+						),
+						None => Command::ShowOutput(
+							OutputType::Failure,
+							String::from("invalid selection"),
+						),
+					}
 				} else {
 					Command::Set(
 						String::from("prompt"),
@@ -326,10 +653,17 @@ fn handle_key_event(
 			Key::Char('u') | Key::Char('U') => {
 				if app.mode == Mode::Copy {
 					Command::Copy(Selection::UserId)
+				} else if let Some(key_ids) = get_marked_keys(app) {
+					Command::Confirm(Box::new(Command::Batch(
+						key_ids
+							.into_iter()
+							.map(|key_id| Command::SendKey(key_id, None))
+							.collect(),
+					)))
 				} else {
 					match app.keys_table.selected() {
 						Some(selected_key) => Command::Confirm(Box::new(
-							Command::SendKey(selected_key.get_id()),
+							Command::SendKey(selected_key.get_id(), None),
 						)),
 						None => Command::ShowOutput(
 							OutputType::Failure,
@@ -339,18 +673,70 @@ fn handle_key_event(
 				}
 			}
 			Key::Char('m') | Key::Char('M') => Command::ToggleTableSize,
-			Key::Char('y') | Key::Char('Y') => {
-				if let Some(command) = &app.prompt.command {
-					command.clone()
-				} else {
-					Command::None
+			Key::Enter if app.tab == Tab::Card && !app.state.show_options => {
+				match app
+					.card_slots
+					.selected()
+					.and_then(|slot| slot.fingerprint.clone())
+				{
+					Some(fingerprint) => Command::GoToKey(fingerprint),
+					None => Command::ShowOutput(
+						OutputType::Failure,
+						String::from("empty key slot"),
+					),
+				}
+			}
+			Key::Enter
+				if app.state.detail_view
+					&& app.state.select.is_none()
+					&& matches!(app.tab, Tab::Keys(_))
+					&& !app.state.show_options =>
+			{
+				match app.keys_table.selected() {
+					Some(selected_key) => {
+						Command::InspectKey(selected_key.get_id())
+					}
+					None => Command::ShowOutput(
+						OutputType::Failure,
+						String::from("invalid selection"),
+					),
 				}
 			}
 			Key::Char('o') | Key::Char(' ') | Key::Enter => {
 				if let Some(select_type) = app.state.select {
 					Command::Copy(select_type)
+				} else if app.mode == Mode::Pick
+					&& key_event.code == Key::Char(' ')
+					&& !app.state.show_options
+				{
+					match app.keys_table.selected() {
+						Some(selected_key) => {
+							Command::ToggleRecipient(selected_key.get_id())
+						}
+						None => Command::ShowOutput(
+							OutputType::Failure,
+							String::from("invalid selection"),
+						),
+					}
+				} else if app.mode == Mode::Visual
+					&& key_event.code == Key::Char(' ')
+					&& !app.state.show_options
+					&& matches!(app.tab, Tab::Keys(_))
+				{
+					match app.keys_table.selected() {
+						Some(selected_key) => {
+							Command::Mark(Some(selected_key.get_id()))
+						}
+						None => Command::ShowOutput(
+							OutputType::Failure,
+							String::from("invalid selection"),
+						),
+					}
 				} else if app.state.show_options {
-					app.options.selected().cloned().unwrap_or(Command::None)
+					app.options
+						.selected_item()
+						.cloned()
+						.unwrap_or(Command::None)
 				} else {
 					Command::ShowOptions
 				}
@@ -364,6 +750,20 @@ fn handle_key_event(
 	command
 }
 
+/// Returns the identifiers of the marked keys.
+///
+/// Keys can only be marked in the key list while in visual mode.
+fn get_marked_keys(app: &App) -> Option<Vec<String>> {
+	if app.mode == Mode::Visual
+		&& matches!(app.tab, Tab::Keys(_))
+		&& !app.keys_table.marked.is_empty()
+	{
+		Some(app.keys_table.marked.clone())
+	} else {
+		None
+	}
+}
+
 /// Handles the execution of an application command.
 ///
 /// It checks the additional conditions for determining
@@ -378,7 +778,11 @@ fn handle_command_execution<B: Backend>(
 	tui: &mut Tui<B>,
 	app: &mut App,
 ) -> Result<()> {
-	if app.state.show_splash && command != Command::Quit {
+	if app.state.show_splash
+		&& !matches!(command, Command::Quit | Command::ForceQuit)
+	{
+		// skip the rest of the splash screen animation
+		app.state.show_splash = false;
 		command = Command::None;
 	}
 	if let Tab::Help = app.tab {
@@ -394,6 +798,7 @@ fn handle_command_execution<B: Backend>(
 			| Command::PreviousTab
 			| Command::Refresh
 			| Command::Quit
+			| Command::ForceQuit
 			| Command::None => {}
 			Command::Set(ref option, _) => {
 				if option != "style" {
@@ -402,6 +807,80 @@ fn handle_command_execution<B: Backend>(
 			}
 			_ => command = Command::None,
 		}
+	} else if app.tab == Tab::KeyserverResults {
+		match command {
+			Command::ShowOptions
+			| Command::ShowHelp
+			| Command::Scroll(_, _)
+			| Command::ListKeys(_)
+			| Command::ImportKeys(_, true, _)
+			| Command::SearchKeyserver(_)
+			| Command::Search(_)
+			| Command::Copy(Selection::KeyId)
+			| Command::Copy(Selection::KeyFingerprint)
+			| Command::Copy(Selection::UserId)
+			| Command::SwitchMode(_)
+			| Command::EnableInput
+			| Command::NextTab
+			| Command::PreviousTab
+			| Command::Refresh
+			| Command::Quit
+			| Command::ForceQuit
+			| Command::None => {}
+			Command::Set(ref option, _) => {
+				if option != "style" && option != "prompt" {
+					command = Command::None
+				}
+			}
+			_ => command = Command::None,
+		}
+	} else if app.tab == Tab::Card {
+		match command {
+			Command::ShowOptions
+			| Command::ShowHelp
+			| Command::ShowOutput(_, _)
+			| Command::ShowCard
+			| Command::GoToKey(_)
+			| Command::Scroll(_, _)
+			| Command::ListKeys(_)
+			| Command::SwitchMode(_)
+			| Command::EnableInput
+			| Command::NextTab
+			| Command::PreviousTab
+			| Command::Refresh
+			| Command::Quit
+			| Command::ForceQuit
+			| Command::None => {}
+			Command::Set(ref option, _) => {
+				if option != "style" && option != "prompt" {
+					command = Command::None
+				}
+			}
+			_ => command = Command::None,
+		}
+	} else if let Tab::KeyDetail(_) = app.tab {
+		match command {
+			Command::ShowOptions
+			| Command::ShowHelp
+			| Command::ShowOutput(_, _)
+			| Command::InspectKey(_)
+			| Command::Copy(Selection::DetailLine)
+			| Command::Scroll(_, _)
+			| Command::ListKeys(_)
+			| Command::EnableInput
+			| Command::NextTab
+			| Command::PreviousTab
+			| Command::Refresh
+			| Command::Quit
+			| Command::ForceQuit
+			| Command::None => {}
+			Command::Set(ref option, _) => {
+				if option != "style" && option != "prompt" {
+					command = Command::None
+				}
+			}
+			_ => command = Command::None,
+		}
 	}
 	let mut toggle_pause = false;
 	match command {
@@ -416,45 +895,79 @@ fn handle_command_execution<B: Backend>(
 					Ok(Mode::Visual) => tui.disable_mouse_capture()?,
 					_ => {}
 				}
-			} else if option == "prompt" && value == ":import " {
-				tui.toggle_pause()?;
-				toggle_pause = true;
-				match util::run_os_command(&app.state.file_explorer) {
-					Ok(files) => {
-						command = Command::ImportKeys(files, false);
+			} else if option == "prompt"
+				&& (value == ":import " || value == ":encrypt ")
+			{
+				match util::get_file_explorer(&app.state.file_explorer) {
+					Some(explorer) => {
+						tui.toggle_pause()?;
+						toggle_pause = true;
+						match util::run_file_explorer(&explorer) {
+							Ok(files) if value == ":encrypt " => {
+								command = Command::EncryptFiles(files);
+							}
+							Ok(files) => {
+								command =
+									Command::ImportKeys(files, false, None);
+							}
+							Err(e) => {
+								log::error!(
+									target: "cmd",
+									"failed to run OS command: {e:?}"
+								)
+							}
+						}
+					}
+					None => {
+						app.file_browser = Some(FileBrowser::new(
+							env::current_dir().unwrap_or_default(),
+							value == ":encrypt ",
+						));
 					}
-					Err(e) => log::error!("failed to run OS command: {e:?}"),
 				}
 			}
 		}
 		Command::ExportKeys(_, _, _)
+		| Command::ExportKeysTo(_, _, _)
+		| Command::ExportPaperKey(_, _)
 		| Command::DeleteKey(_, _)
-		| Command::GenerateKey
+		| Command::RevokeKey(_, _)
+		| Command::SetExpiration(_, _, _)
+		| Command::AddUserId(_)
+		| Command::RevokeUserId(_)
+		| Command::SetPrimaryUserId(_)
+		| Command::CreateSubkey(_, _)
+		| Command::Batch(_)
 		| Command::RefreshKeys
-		| Command::EditKey(_)
-		| Command::SignKey(_)
-		| Command::ImportKeys(_, true) => {
+		| Command::EditPreferences(_, true)
+		| Command::SignKey(_, _)
+		| Command::SignFile(_, _)
+		| Command::DecryptClipboard
+		| Command::ImportKeys(_, true, _)
+		| Command::VerifyRelease(_, _)
+		| Command::LocateSigners(_) => {
 			tui.toggle_pause()?;
 			toggle_pause = true;
 		}
-		Command::Copy(Selection::Key) => {
-			if app.gpgme.config.armor {
-				tui.toggle_pause()?;
-				toggle_pause = true;
-			} else {
-				command = Command::ShowOutput(
-					OutputType::Warning,
-					String::from(
-						"enable armored output for copying the exported key(s)",
-					),
-				);
-			}
+		Command::RunKeyEdit(_, ref edit) if edit.needs_passphrase() => {
+			tui.toggle_pause()?;
+			toggle_pause = true;
+		}
+		Command::Copy(Selection::Key) | Command::ShowQrCode(Selection::Key) => {
+			tui.toggle_pause()?;
+			toggle_pause = true;
 		}
 		_ => {}
 	}
 	if !app.state.show_logs {
 		match command {
-			Command::Scroll(_, _) | Command::None => {}
+			// do not leak the passphrase (or the fetched data) to the logs
+			Command::Scroll(_, _)
+			| Command::PresetPassphrase(_, Some(_))
+			| Command::EncryptSymmetric(_, Some(_))
+			| Command::ChangePassphrase(_, Some(_), _)
+			| Command::ImportFetched(_, _, _)
+			| Command::None => {}
 			_ => {
 				log::log!(target: "tui", Level::Trace, "running command: {:?}", command);
 			}
@@ -473,9 +986,12 @@ mod tests {
 	use super::*;
 	use crate::app::command::Command;
 	use crate::app::style::Style;
+	use crate::app::wizard::KeyGenWizard;
 	use crate::args::Args;
+	use crate::gpg::card::CardSlot;
 	use crate::gpg::config::GpgConfig;
 	use crate::gpg::context::GpgContext;
+	use crate::widget::list::StatefulList;
 	use pretty_assertions::assert_eq;
 	use std::env;
 	#[test]
@@ -509,6 +1025,7 @@ mod tests {
 			(
 				Command::Confirm(Box::new(Command::SendKey(
 					key_id.to_string(),
+					None,
 				))),
 				vec![KeyEvent::new(Key::Char('u'), Modifiers::NONE)],
 			),
@@ -525,7 +1042,7 @@ mod tests {
 				vec![KeyEvent::new(Key::Char('e'), Modifiers::NONE)],
 			),
 			(
-				Command::SignKey(key_id),
+				Command::SignKey(key_id, None),
 				vec![KeyEvent::new(Key::Char('s'), Modifiers::NONE)],
 			),
 			(
@@ -556,6 +1073,10 @@ mod tests {
 				Command::ToggleDetail(false),
 				vec![KeyEvent::new(Key::Tab, Modifiers::NONE)],
 			),
+			(
+				Command::ToggleCollapse,
+				vec![KeyEvent::new(Key::BackTab, Modifiers::SHIFT)],
+			),
 			(
 				Command::Scroll(ScrollDirection::Top, false),
 				vec![
@@ -708,6 +1229,195 @@ mod tests {
 				assert_eq!(command, handle_key_event(key_event, &[], &mut app));
 			}
 		}
+		for (command, kind) in [
+			(
+				Command::Scroll(ScrollDirection::Down(1), false),
+				MouseEventKind::ScrollDown,
+			),
+			(
+				Command::Scroll(ScrollDirection::Up(1), false),
+				MouseEventKind::ScrollUp,
+			),
+			(Command::None, MouseEventKind::Moved),
+		] {
+			assert_eq!(
+				command,
+				handle_mouse_event(MouseEvent {
+					kind,
+					column: 0,
+					row: 0,
+					modifiers: Modifiers::NONE,
+				})
+			);
+		}
+		app.mode = Mode::Copy;
+		for (selection, key) in [
+			(Selection::Created, 'c'),
+			(Selection::Expires, 'e'),
+			(Selection::SshKey, 's'),
+			(Selection::Fingerprints, 'a'),
+		] {
+			assert_eq!(
+				Command::Copy(selection),
+				handle_key_event(
+					KeyEvent::new(Key::Char(key), Modifiers::NONE),
+					&[],
+					&mut app,
+				)
+			);
+		}
+		app.mode = Mode::Normal;
+		app.prompt.set_command(Command::Refresh);
+		assert_eq!(
+			Command::None,
+			handle_key_event(
+				KeyEvent::new(Key::Enter, Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		assert!(app.prompt.command.is_none());
+		app.prompt.set_command(Command::Refresh);
+		handle_key_event(
+			KeyEvent::new(Key::Left, Modifiers::NONE),
+			&[],
+			&mut app,
+		);
+		assert!(app.prompt.confirm);
+		assert_eq!(
+			Command::Refresh,
+			handle_key_event(
+				KeyEvent::new(Key::Enter, Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		app.prompt.set_command(Command::Refresh);
+		assert_eq!(
+			Command::Refresh,
+			handle_key_event(
+				KeyEvent::new(Key::Char('y'), Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		app.prompt.clear();
+		app.verifications = Some(Vec::new());
+		assert_eq!(
+			Command::None,
+			handle_key_event(
+				KeyEvent::new(Key::Char('q'), Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		assert!(app.verifications.is_none());
+		app.receive_results = Some(vec![(String::from("0x0"), Ok(()))]);
+		handle_key_event(
+			KeyEvent::new(Key::Enter, Modifiers::NONE),
+			&[],
+			&mut app,
+		);
+		assert!(app.receive_results.is_none());
+		app.wizard = Some(KeyGenWizard::default());
+		for key in [Key::Enter, Key::Enter, Key::Enter, Key::Char('t')] {
+			assert_eq!(
+				Command::None,
+				handle_key_event(
+					KeyEvent::new(key, Modifiers::NONE),
+					&[],
+					&mut app,
+				)
+			);
+		}
+		assert_eq!(
+			Some(String::from("t")),
+			app.wizard.as_ref().map(|wizard| wizard.user_id.to_string())
+		);
+		handle_key_event(
+			KeyEvent::new(Key::Esc, Modifiers::NONE),
+			&[],
+			&mut app,
+		);
+		assert!(app.wizard.is_none());
+		app.tab = Tab::Card;
+		app.card_slots = StatefulList::with_items(vec![
+			CardSlot {
+				name: String::from("signature"),
+				fingerprint: Some(String::from("0123456789ABCDEF")),
+			},
+			CardSlot {
+				name: String::from("encryption"),
+				fingerprint: None,
+			},
+		]);
+		app.card_slots.state.select(Some(0));
+		assert_eq!(
+			Command::GoToKey(String::from("0123456789ABCDEF")),
+			handle_key_event(
+				KeyEvent::new(Key::Enter, Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		app.card_slots.next();
+		assert_eq!(
+			Command::ShowOutput(
+				OutputType::Failure,
+				String::from("empty key slot")
+			),
+			handle_key_event(
+				KeyEvent::new(Key::Enter, Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		assert_eq!(
+			Command::ShowCard,
+			handle_key_event(
+				KeyEvent::new(Key::Char('r'), Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		for (context, command) in [
+			(KeyBindingContext::Card, Command::ShowHelp),
+			(KeyBindingContext::Keys, Command::ToggleTableSize),
+		] {
+			assert_eq!(
+				command,
+				handle_key_event(
+					KeyEvent::new(Key::Char('M'), Modifiers::NONE),
+					&[CustomKeyBinding {
+						keys: vec![KeyEvent::new(
+							Key::Char('M'),
+							Modifiers::NONE
+						)],
+						command: String::from(":help"),
+						context: Some(context),
+					}],
+					&mut app,
+				)
+			);
+		}
+		app.tab = Tab::KeyDetail(KeyType::Secret);
+		assert_eq!(
+			Command::Copy(Selection::DetailLine),
+			handle_key_event(
+				KeyEvent::new(Key::Enter, Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		assert_eq!(
+			Command::ListKeys(KeyType::Secret),
+			handle_key_event(
+				KeyEvent::new(Key::Esc, Modifiers::NONE),
+				&[],
+				&mut app,
+			)
+		);
+		app.tab = Tab::Keys(KeyType::Public);
 		app.prompt.enable_command_input();
 		handle_key_event(
 			KeyEvent::new(Key::Esc, Modifiers::NONE),
@@ -743,6 +1453,21 @@ mod tests {
 			)
 		);
 		app.prompt.enable_command_input();
+		for c in String::from("sync-c").chars() {
+			handle_key_event(
+				KeyEvent::new(Key::Char(c), Modifiers::NONE),
+				&[],
+				&mut app,
+			);
+		}
+		handle_key_event(
+			KeyEvent::new(Key::Tab, Modifiers::NONE),
+			&[],
+			&mut app,
+		);
+		assert_eq!(":sync-config ", app.prompt.text);
+		app.prompt.clear();
+		app.prompt.enable_command_input();
 		handle_key_event(
 			KeyEvent::new(Key::Down, Modifiers::NONE),
 			&[],
@@ -765,7 +1490,48 @@ mod tests {
 				KeyEvent::new(Key::Char('M'), Modifiers::NONE),
 				&[CustomKeyBinding {
 					keys: vec![KeyEvent::new(Key::Char('M'), Modifiers::NONE)],
-					command: Command::SwitchMode(Mode::Visual),
+					command: String::from(":visual"),
+					context: None,
+				}],
+				&mut app,
+			)
+		);
+		assert_eq!(
+			Command::ToggleTableSize,
+			handle_key_event(
+				KeyEvent::new(Key::Char('M'), Modifiers::NONE),
+				&[CustomKeyBinding {
+					keys: vec![KeyEvent::new(Key::Char('M'), Modifiers::NONE)],
+					command: String::from(":visual"),
+					context: Some(KeyBindingContext::Help),
+				}],
+				&mut app,
+			)
+		);
+		assert_eq!(
+			Command::None,
+			handle_key_event(
+				KeyEvent::new(Key::Char('M'), Modifiers::NONE),
+				&[CustomKeyBinding {
+					keys: vec![KeyEvent::new(Key::Char('M'), Modifiers::NONE)],
+					command: String::from(":goto"),
+					context: None,
+				}],
+				&mut app,
+			)
+		);
+		assert_eq!(OutputType::Failure, app.prompt.output_type);
+		assert_eq!(
+			Command::Redirect(
+				Box::new(Command::Get(String::from("armor"))),
+				String::from("armor.txt")
+			),
+			handle_key_event(
+				KeyEvent::new(Key::Char('M'), Modifiers::NONE),
+				&[CustomKeyBinding {
+					keys: vec![KeyEvent::new(Key::Char('M'), Modifiers::NONE)],
+					command: String::from(":get armor > armor.txt"),
+					context: None,
 				}],
 				&mut app,
 			)
@@ -0,0 +1,109 @@
+use crate::gpg::handler;
+use anyhow::Result;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Name of the file that stores the history.
+const HISTORY_FILE: &str = "history";
+
+/// Action that is performed on a key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+	/// Time of the action. (ISO 8601)
+	pub time: String,
+	/// Fingerprint of the key.
+	pub fingerprint: String,
+	/// Description of the action.
+	pub action: String,
+}
+
+/// History of the actions that are performed on keys.
+///
+/// Entries are appended to a file (one per line with tab-separated
+/// fields) so that it serves as an audit trail across sessions.
+#[derive(Clone, Debug, Default)]
+pub struct KeyHistory {
+	/// File for storing the entries.
+	path: Option<PathBuf>,
+}
+
+impl KeyHistory {
+	/// Constructs a new instance of `KeyHistory`.
+	///
+	/// Nothing is recorded if the path is not given.
+	pub fn new(path: Option<PathBuf>) -> Self {
+		Self { path }
+	}
+
+	/// Returns the default location of the file.
+	pub fn get_default_location() -> Option<PathBuf> {
+		dirs_next::data_dir().map(|data_dir| {
+			data_dir.join(env!("CARGO_PKG_NAME")).join(HISTORY_FILE)
+		})
+	}
+
+	/// Records the given action for the key.
+	pub fn record(&self, fingerprint: &str, action: &str) -> Result<()> {
+		if let Some(path) = &self.path {
+			if let Some(parent) = path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			writeln!(
+				OpenOptions::new().create(true).append(true).open(path)?,
+				"{}\t{}\t{}",
+				handler::get_iso_timestamp(SystemTime::now()),
+				fingerprint.to_uppercase(),
+				action.replace(['\t', '\n'], " ")
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Returns the recorded actions of the key.
+	pub fn get_entries(&self, fingerprint: &str) -> Vec<HistoryEntry> {
+		self.path
+			.as_ref()
+			.and_then(|path| fs::read_to_string(path).ok())
+			.map(|contents| {
+				contents
+					.lines()
+					.filter_map(|line| {
+						let mut values = line.splitn(3, '\t');
+						Some(HistoryEntry {
+							time: values.next()?.to_string(),
+							fingerprint: values.next()?.to_string(),
+							action: values.next()?.to_string(),
+						})
+					})
+					.filter(|entry| {
+						entry.fingerprint.eq_ignore_ascii_case(fingerprint)
+					})
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_app_history() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("history");
+		let history = KeyHistory::new(Some(path.to_path_buf()));
+		history.record("abc123", "signed")?;
+		history.record("DEF456", "exported")?;
+		history.record("ABC123", "sent to\tkeyserver")?;
+		let entries = history.get_entries("ABC123");
+		assert_eq!(2, entries.len());
+		assert_eq!("signed", entries[0].action);
+		assert_eq!("sent to keyserver", entries[1].action);
+		assert_eq!("ABC123", entries[1].fingerprint);
+		assert!(KeyHistory::new(None).get_entries("ABC123").is_empty());
+		Ok(())
+	}
+}
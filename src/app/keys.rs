@@ -1,3 +1,4 @@
+use crate::app::style;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::ListItem;
@@ -28,7 +29,7 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
 		description: r#"
         Scrolls the current widget or selects the next/previous tab.
         M-<key>: scroll the table rows
-        C-<key>,pgup,pgdown: scroll to top/bottom
+        C-<key>,pgup,pgdown: scroll to top/bottom (or page)
         :scroll (row) up/down/left/right <amount>
         "#,
 	},
@@ -56,10 +57,25 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
         i: Copy the key id
         f: Copy the key fingerprint
         u: Copy the user id
+        s: Copy the SSH key
+        v: Copy the vCard
+        a: Copy the fingerprints of the visible keys
+        c: Copy the creation time (ISO 8601)
+        e: Copy the expiration time (ISO 8601)
         1,2: Copy the content of the row
         :copy
         "#,
 	},
+	KeyBinding {
+		key: "space",
+		action: "pick recipient (pick mode)",
+		description: r#"
+        Adds/removes the selected key to/from the recipients.
+        Keys without a usable encryption subkey are warned about.
+        :pick
+        :recipient <keyid>
+        "#,
+	},
 	KeyBinding {
 		key: "p,C-v",
 		action: "paste from clipboard",
@@ -79,7 +95,7 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
 		description: r#"
         Signs the key with the default secret key.
         Same as `gpg --sign-key`
-        :sign <keyid>
+        :sign <keyid> [--with <signer>]
         "#,
 	},
 	KeyBinding {
@@ -87,8 +103,9 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
 		action: "edit key",
 		description: r#"
         Presents a menu for key management.
-        Same as `gpg --edit-key`
-        :edit <keyid>
+        (adduid, revuid, primary, expire, trust, passwd, keytocard)
+        :edit <keyid> [<command> <args>]
+        :prefs <keyid> [update]
         "#,
 	},
 	KeyBinding {
@@ -105,7 +122,7 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
 		description: r#"
         Imports the keys with the given key IDs from default keyserver.
         Same as `gpg --receive-keys`
-        :receive <keyids>
+        :receive <keyids> [--from <keyserver>]
         "#,
 	},
 	KeyBinding {
@@ -113,15 +130,15 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
 		action: "send key",
 		description: r#"
         Sends the key to the default keyserver.
-        :send <keyid>
+        :send <keyid> [<keyserver>]
         "#,
 	},
 	KeyBinding {
 		key: "g",
 		action: "generate key",
 		description: r#"
-        Generates a new key pair with dialogs for all options.
-        Same as `gpg --full-generate-key`
+        Generates a new key pair via a step-by-step form.
+        (algorithm, key size, usage, user ID, expiry, passphrase)
         :generate
         "#,
 	},
@@ -142,6 +159,25 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
         :refresh keys
         "#,
 	},
+	KeyBinding {
+		key: "b,C-b",
+		action: "go to pinned key",
+		description: r#"
+        Selects the next pinned key. (C-b: pin/unpin the selected key)
+        Pinned keys are shown at the top of the table.
+        :pin [<keyid>]
+        :pin next
+        "#,
+	},
+	KeyBinding {
+		key: "space,V",
+		action: "mark key(s)",
+		description: r#"
+        Marks the selected key (or the keys in range) in visual mode.
+        Export, delete, send, sign and refresh apply to the marked keys.
+        :mark <keyid/range>
+        "#,
+	},
 	KeyBinding {
 		key: "a",
 		action: "toggle armored output",
@@ -166,6 +202,14 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
 		action: "toggle detail (all/selected)",
 		description: ":toggle detail (all)",
 	},
+	KeyBinding {
+		key: "S-tab",
+		action: "collapse/expand key",
+		description: r#"
+        Collapses the subkeys and user IDs of the selected key.
+        :toggle collapse
+        "#,
+	},
 	KeyBinding {
 		key: "`",
 		action: "toggle table margin",
@@ -257,17 +301,20 @@ impl<'a> KeyBinding<'a> {
 	}
 
 	/// Returns the key binding as a list item.
+	///
+	/// Box-drawing characters are replaced if `ascii` is set.
 	pub fn as_list_item(
 		&self,
 		colored: bool,
 		highlighted: bool,
+		ascii: bool,
 	) -> ListItem<'a> {
 		let highlight_style = if highlighted {
 			Style::default().fg(Color::Reset)
 		} else {
 			Style::default()
 		};
-		ListItem::new(if colored {
+		let text = if colored {
 			Text::from(vec![
 				Line::from(self.key.split(',').fold(
 					Vec::new(),
@@ -291,6 +338,11 @@ impl<'a> KeyBinding<'a> {
 			])
 		} else {
 			Text::raw(self.to_string())
+		};
+		ListItem::new(if ascii {
+			style::get_ascii_text(text)
+		} else {
+			text
 		})
 	}
 }
@@ -354,7 +406,11 @@ mod tests {
 				],
 				..Default::default()
 			}),
-			key_binding.as_list_item(false, false)
+			key_binding.as_list_item(false, false, false)
+		);
+		assert_eq!(
+			ListItem::new(Text::raw("[q] [esc] \n `-quit\n ")),
+			key_binding.as_list_item(false, false, true)
 		);
 		assert_eq!(
 			ListItem::new(Text {
@@ -435,7 +491,7 @@ mod tests {
 				],
 				..Default::default()
 			}),
-			key_binding.as_list_item(true, true)
+			key_binding.as_list_item(true, true, false)
 		);
 	}
 }
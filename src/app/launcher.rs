@@ -1,35 +1,65 @@
-use crate::app::command::Command;
+use crate::app::browser::FileBrowser;
+use crate::app::clipboard::{self, ClipboardBackend, ClipboardType};
+use crate::app::command::{Command, KeySet, EXPIRY_WARNING_DAYS};
+use crate::app::events::{EventStream, OperationEvent};
+use crate::app::history::{HistoryEntry, KeyHistory};
 use crate::app::keys::{KeyBinding, KEY_BINDINGS};
 use crate::app::mode::Mode;
+use crate::app::pin::PinnedKeys;
 use crate::app::prompt::{OutputType, Prompt, COMMAND_PREFIX, SEARCH_PREFIX};
-use crate::app::selection::Selection;
+use crate::app::selection::{FingerprintFormat, SelectFormat, Selection};
+use crate::app::server::{self, SERVE_TIMEOUT};
 use crate::app::state::State;
 use crate::app::style::Style;
 use crate::app::tab::Tab;
+use crate::app::wizard::KeyGenWizard;
 use crate::args::Args;
+use crate::config::{Config, GeneralConfig, GpgConfig as GpgFileConfig};
+use crate::gpg::card::{CardSlot, CardStatus};
+use crate::gpg::config::{GpgConfig, TRUST_MODELS};
 use crate::gpg::context::GpgContext;
-use crate::gpg::key::{GpgKey, KeyDetail, KeyType};
-use crate::widget::list::StatefulList;
+use crate::gpg::edit::{KeyEdit, EDIT_COMMANDS, TRUST_LEVELS};
+use crate::gpg::handler::{self, MODERN_PREFERENCES};
+use crate::gpg::key::{
+	GpgKey, KeyCapability, KeyDetail, KeyDetailEntry, KeyState, KeyType,
+};
+use crate::gpg::keygen;
+use crate::gpg::photo;
+use crate::gpg::signature::Verification;
+use crate::term::event::Event;
+use crate::term::graphics::{GraphicsProtocol, ImagePlacement};
+use crate::widget::list::{ListEntry, StatefulList};
+use crate::widget::photo::Photo;
 use crate::widget::row::ScrollDirection;
 use crate::widget::style::Color as WidgetColor;
 use crate::widget::table::{StatefulTable, TableSize, TableState};
-use anyhow::{Error as AnyhowError, Result};
+use anyhow::{anyhow, Result};
 use clap::ValueEnum;
 use colorsys::Rgb;
-use copypasta_ext::display::DisplayServer as ClipboardDisplayServer;
-use copypasta_ext::ClipboardProviderExt;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
 use ratatui::style::Color;
 use ratatui_splash_screen::{SplashConfig, SplashScreen};
+use serde_json::Value;
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
 use std::process::Command as OsCommand;
-use std::str;
 use std::str::FromStr;
-use std::time::Instant;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Max duration of prompt messages.
 const MESSAGE_DURATION: u128 = 1750;
 
+/// Number of keys that are sent at once while loading in the background.
+const KEY_BATCH_SIZE: usize = 100;
+
+/// Duration for not repeating the failed email lookups.
+const LOOKUP_CACHE_DURATION: Duration = Duration::from_secs(60 * 60);
+
 /// Splash screen config.
 static SPLASH_CONFIG: SplashConfig = SplashConfig {
 	image_data: include_bytes!("../../assets/splash.jpg"),
@@ -40,6 +70,24 @@ static SPLASH_CONFIG: SplashConfig = SplashConfig {
 	use_colors: true,
 };
 
+/// Subkey and user information of the keys.
+///
+/// Entries are stored by the fingerprint and
+/// detail level of the key and the table size.
+type KeyInfoCache =
+	HashMap<(String, KeyDetail, TableSize), (Vec<String>, Vec<String>)>;
+
+/// Information of a key that is shown in the keys table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyRow {
+	/// Information about the subkeys.
+	pub subkey_info: Vec<String>,
+	/// Information about the user IDs.
+	pub user_info: Vec<String>,
+	/// Whether if the details of the key are collapsed.
+	pub collapsed: bool,
+}
+
 /// Main application.
 ///
 /// It is responsible for running the commands
@@ -54,9 +102,11 @@ pub struct App<'a> {
 	/// Current tab.
 	pub tab: Tab,
 	/// Content of the options menu.
-	pub options: StatefulList<Command>,
+	pub options: StatefulList<ListEntry<Command>>,
 	/// Splash screen of the application.
 	pub splash_screen: SplashScreen,
+	/// Splash image for showing via the graphics protocol.
+	pub splash_photo: Option<Photo>,
 	/// Content of the key bindings list.
 	pub key_bindings: StatefulList<KeyBinding<'a>>,
 	/// Public/secret keys.
@@ -67,8 +117,73 @@ pub struct App<'a> {
 	pub keys_table_states: HashMap<KeyType, TableState>,
 	/// Bottom margin value of the keys table.
 	pub keys_table_margin: u16,
-	/// Clipboard context.
-	pub clipboard: Option<Box<dyn ClipboardProviderExt>>,
+	/// Prepared rows of the keys table.
+	pub keys_table_rows: Option<Vec<KeyRow>>,
+	/// Cached information of the keys. (cleared on reload)
+	key_info_cache: KeyInfoCache,
+	/// Keys that are pinned to the top of the table.
+	pub pinned_keys: PinnedKeys,
+	/// History of the actions performed on keys.
+	pub history: KeyHistory,
+	/// IDs of the keys that are picked as recipients.
+	pub recipients: Vec<String>,
+	/// Files that are waiting for the recipients to be picked.
+	pub pending_files: Vec<String>,
+	/// Keys that are found on the keyserver.
+	pub keyserver_results: Vec<GpgKey>,
+	/// Email addresses that could not be resolved to a key.
+	failed_lookups: HashMap<String, Instant>,
+	/// Email addresses that are being looked up in the background.
+	pending_lookups: Vec<String>,
+	/// IDs of the keys that are being received in the background.
+	pending_receives: Vec<String>,
+	/// Keys that are received in the background so far.
+	received_keys: Vec<(String, Result<(), String>)>,
+	/// Clipboard backend.
+	pub clipboard: Box<dyn ClipboardBackend>,
+	/// Results of the last signature verification. (shown in a popup)
+	pub verifications: Option<Vec<Verification>>,
+	/// Results of receiving multiple keys. (shown in a popup)
+	pub receive_results: Option<Vec<(String, Result<(), String>)>>,
+	/// Keys that expire soon with the remaining days. (shown in a popup)
+	pub expiring_keys: Option<(u64, Vec<(String, String, i64)>)>,
+	/// Groups of the compared fingerprints. (shown in a popup)
+	pub fingerprint_comparison: Option<Vec<(String, String)>>,
+	/// History of the selected key with its ID. (shown in a popup)
+	pub key_history: Option<(String, Vec<HistoryEntry>)>,
+	/// Preferences of each user ID of the key with its ID.
+	/// (shown in a popup)
+	pub key_preferences: Option<(String, Vec<(String, Vec<String>)>)>,
+	/// QR code of the selection with its title. (shown in a popup)
+	pub qr_code: Option<(String, String)>,
+	/// Key generation wizard. (shown in a popup)
+	pub wizard: Option<KeyGenWizard>,
+	/// Built-in file browser. (shown in a popup)
+	pub file_browser: Option<FileBrowser>,
+	/// Status of the connected smartcard.
+	pub card: Option<CardStatus>,
+	/// Key slots of the smartcard.
+	pub card_slots: StatefulList<CardSlot>,
+	/// Entries of the key detail view.
+	pub key_detail: StatefulList<KeyDetailEntry>,
+	/// Photo ID of the key in the detail view.
+	pub key_photo: Option<Photo>,
+	/// Image to show via the graphics protocol after rendering.
+	pub image_placement: Option<ImagePlacement>,
+	/// Sender for loading the keys in the background.
+	pub event_sender: Option<mpsc::Sender<Event>>,
+	/// Whether if the keys are being loaded in the background.
+	pub loading_keys: bool,
+	/// Keys that are loaded in the background for replacing the current ones.
+	loaded_keys: HashMap<KeyType, Vec<GpgKey>>,
+	/// Identifier of the last background loading of keys.
+	key_load_id: usize,
+	/// Time until a key is served over HTTP.
+	serving_until: Option<Instant>,
+	/// Stream for writing the events as JSON lines.
+	json_events: Option<EventStream>,
+	/// Command-line arguments. (configuration is applied over them)
+	pub cli_args: Args,
 	/// GPGME context.
 	pub gpgme: &'a mut GpgContext,
 }
@@ -77,12 +192,57 @@ impl<'a> App<'a> {
 	/// Constructs a new instance of `App`.
 	pub fn new(gpgme: &'a mut GpgContext, args: &'a Args) -> Result<Self> {
 		let keys = gpgme.get_all_keys(Some(args.detail_level))?;
-		let keys_table = StatefulTable::with_items(
+		let mut app = Self::with_keys(gpgme, args, keys)?;
+		app.warn_expiring_keys();
+		Ok(app)
+	}
+
+	/// Constructs a new instance of `App` that loads
+	/// the keys in the background.
+	///
+	/// Batches of keys are sent via the given sender and
+	/// they are expected to be passed to [`append_keys`].
+	///
+	/// [`append_keys`]: App::append_keys
+	pub fn with_key_loader(
+		gpgme: &'a mut GpgContext,
+		args: &'a Args,
+		sender: mpsc::Sender<Event>,
+	) -> Result<Self> {
+		let keys = HashMap::from([
+			(KeyType::Public, Vec::new()),
+			(KeyType::Secret, Vec::new()),
+		]);
+		let mut app = Self::with_keys(gpgme, args, keys)?;
+		app.event_sender = Some(sender);
+		app.load_keys()?;
+		if let Some(pattern) = &args.focus {
+			app.focus_keys(pattern)?;
+		}
+		Ok(app)
+	}
+
+	/// Constructs a new instance of `App` with the given keys.
+	fn with_keys(
+		gpgme: &'a mut GpgContext,
+		args: &'a Args,
+		mut keys: HashMap<KeyType, Vec<GpgKey>>,
+	) -> Result<Self> {
+		let pinned_keys = PinnedKeys::load(PinnedKeys::get_default_location());
+		keys.values_mut().for_each(|keys| pinned_keys.sort(keys));
+		let state = State::from(args);
+		let keys_table = StatefulTable::new(
 			keys.get(&KeyType::Public)
 				.expect("failed to get public keys")
 				.to_vec(),
+			state.table_state.clone(),
 		);
-		let state = State::from(args);
+		let splash_photo =
+			if state.show_splash && state.graphics != GraphicsProtocol::None {
+				Photo::new(SPLASH_CONFIG.image_data).ok()
+			} else {
+				None
+			};
 		Ok(Self {
 			mode: Mode::Normal,
 			prompt: if state.select.is_some() {
@@ -99,45 +259,909 @@ impl<'a> App<'a> {
 			tab: Tab::Keys(KeyType::Public),
 			options: StatefulList::with_items(Vec::new()),
 			splash_screen: SplashScreen::new(SPLASH_CONFIG)?,
+			splash_photo,
 			key_bindings: StatefulList::with_items(KEY_BINDINGS.to_vec()),
 			keys,
 			keys_table,
 			keys_table_states: HashMap::new(),
 			keys_table_margin: 1,
-			clipboard: match ClipboardDisplayServer::select().try_context() {
-				None => {
-					log::error!("failed to initialize clipboard, no suitable clipboard provider found");
-					None
-				}
-				clipboard => clipboard,
-			},
+			keys_table_rows: None,
+			key_info_cache: HashMap::new(),
+			pinned_keys,
+			history: KeyHistory::new(KeyHistory::get_default_location()),
+			recipients: Vec::new(),
+			pending_files: Vec::new(),
+			keyserver_results: Vec::new(),
+			failed_lookups: HashMap::new(),
+			pending_lookups: Vec::new(),
+			pending_receives: Vec::new(),
+			received_keys: Vec::new(),
+			clipboard: clipboard::init(
+				args.clipboard,
+				&args.copy_command,
+				&args.paste_command,
+			)
+			.unwrap_or_else(|e| {
+				log::error!(target: "clipboard", "failed to initialize: {e}");
+				Box::new(clipboard::NoClipboard)
+			}),
+			verifications: None,
+			receive_results: None,
+			expiring_keys: None,
+			fingerprint_comparison: None,
+			key_history: None,
+			key_preferences: None,
+			qr_code: None,
+			wizard: None,
+			file_browser: None,
+			card: None,
+			card_slots: StatefulList::with_items(Vec::new()),
+			key_detail: StatefulList::with_items(Vec::new()),
+			key_photo: None,
+			image_placement: None,
+			event_sender: None,
+			loading_keys: false,
+			loaded_keys: HashMap::new(),
+			key_load_id: 0,
+			serving_until: None,
+			json_events: args
+				.json_events
+				.as_deref()
+				.map(EventStream::new)
+				.transpose()?,
+			cli_args: args.clone(),
 			gpgme,
 		})
 	}
 
+	/// Returns the operations that are running in the background.
+	pub fn get_background_operations(&self) -> Vec<&str> {
+		let mut operations = Vec::new();
+		if self.loading_keys {
+			operations.push("loading keys");
+		}
+		if !self.pending_lookups.is_empty() {
+			operations.push("looking up keys");
+		}
+		if !self.pending_receives.is_empty() {
+			operations.push("receiving keys");
+		}
+		if self
+			.serving_until
+			.is_some_and(|serving_until| serving_until > Instant::now())
+		{
+			operations.push("serving a key");
+		}
+		operations
+	}
+
+	/// Filters the keys table with the given pattern
+	/// and selects the first matching key.
+	///
+	/// Search term is kept in the prompt, so the keys
+	/// that are still being loaded are filtered as well.
+	pub fn focus_keys(&mut self, pattern: &str) -> Result<()> {
+		self.prompt.clear();
+		self.run_command(Command::Search(Some(pattern.to_string())))?;
+		self.keys_table.reset_state();
+		self.filter_keys();
+		Ok(())
+	}
+
+	/// Filters the keys table with the key filter and the search term.
+	///
+	/// Keys are filtered when the search term or the filter
+	/// changes instead of on every draw.
+	pub fn filter_keys(&mut self) {
+		let mut cache = mem::take(&mut self.key_info_cache);
+		self.keys_table.items = self
+			.keys_table
+			.default_items
+			.iter()
+			.filter(|key| self.is_key_visible(key, &mut cache))
+			.cloned()
+			.collect();
+		self.key_info_cache = cache;
+		self.keys_table_rows = None;
+	}
+
+	/// Checks if the key matches the key filter and the search term.
+	fn is_key_visible(&self, key: &GpgKey, cache: &mut KeyInfoCache) -> bool {
+		if let Some(filter) = self.state.key_filter {
+			if !key.matches_filter(filter) {
+				return false;
+			}
+		}
+		if !self.prompt.is_search_enabled() {
+			return true;
+		}
+		let search_term = self.prompt.text.replacen('/', "", 1).to_lowercase();
+		let (subkey_info, user_info) = self.get_key_info(key, cache);
+		subkey_info.join("\n").to_lowercase().contains(&search_term)
+			|| user_info.join("\n").to_lowercase().contains(&search_term)
+	}
+
+	/// Returns the subkey and user information of the key.
+	///
+	/// Information is formatted once and then taken from the cache.
+	fn get_key_info(
+		&self,
+		key: &GpgKey,
+		cache: &mut KeyInfoCache,
+	) -> (Vec<String>, Vec<String>) {
+		let size = self.keys_table.state.size;
+		cache
+			.entry((key.get_fingerprint(), key.detail, size))
+			.or_insert_with(|| {
+				(
+					key.get_subkey_info(
+						self.gpgme.config.default_key.as_deref(),
+						size != TableSize::Normal,
+						self.state.get_date_format(),
+					),
+					key.get_user_info(
+						size == TableSize::Minimized,
+						self.state.get_date_format(),
+					),
+				)
+			})
+			.clone()
+	}
+
+	/// Prepares the rows of the keys table.
+	///
+	/// Rows are cached until the keys table is changed.
+	pub fn update_keys_table_rows(&mut self) {
+		if self.keys_table_rows.as_ref().map(Vec::len)
+			== Some(self.keys_table.items.len())
+		{
+			return;
+		}
+		let mut cache = mem::take(&mut self.key_info_cache);
+		self.keys_table_rows = Some(
+			self.keys_table
+				.items
+				.iter()
+				.map(|key| self.get_key_row(key, &mut cache))
+				.collect(),
+		);
+		self.key_info_cache = cache;
+	}
+
+	/// Returns the row of the key for the keys table.
+	fn get_key_row(&self, key: &GpgKey, cache: &mut KeyInfoCache) -> KeyRow {
+		let (mut subkey_info, mut user_info) = self.get_key_info(key, cache);
+		let collapsed = key.detail != KeyDetail::Minimum
+			&& self.keys_table.state.is_collapsed(&key.get_id());
+		if collapsed {
+			subkey_info.truncate(2);
+			if let Some(time_info) = subkey_info.get_mut(1) {
+				*time_info = time_info.replacen('|', " ", 1);
+			}
+			user_info.truncate(1);
+			user_info.push(format!(
+				" └─(+{} subkey(s), +{} user ID(s))",
+				key.get_raw().subkeys().count().saturating_sub(1),
+				key.get_raw().user_ids().count().saturating_sub(1)
+			));
+		}
+		if let Some(primary_key_info) = subkey_info.first_mut() {
+			if self.gpgme.config.validate {
+				primary_key_info
+					.push_str(&format!(" [{}]", key.get_validity()));
+			}
+			if let Some(trust) = key.get_owner_trust() {
+				primary_key_info.push_str(&format!(" (trust: {trust})"));
+			}
+			if self.pinned_keys.contains(key) {
+				primary_key_info.push_str(" (pinned)");
+			}
+		}
+		KeyRow {
+			subkey_info,
+			user_info,
+			collapsed,
+		}
+	}
+
+	/// Loads the public/secret keys.
+	///
+	/// If the event sender is set, keys are listed in a background
+	/// thread so that the interface does not freeze on large keyrings.
+	/// In that case, the current keys are kept until the loading is
+	/// finished unless it is the first load.
+	fn load_keys(&mut self) -> Result<()> {
+		self.key_info_cache.clear();
+		let sender = match &self.event_sender {
+			Some(sender) => sender.clone(),
+			None => {
+				self.keys =
+					self.gpgme.get_all_keys(Some(self.state.detail_level))?;
+				self.sort_pinned_keys();
+				return Ok(());
+			}
+		};
+		self.key_load_id += 1;
+		self.loading_keys = true;
+		self.loaded_keys = HashMap::from([
+			(KeyType::Public, Vec::new()),
+			(KeyType::Secret, Vec::new()),
+		]);
+		let load_id = self.key_load_id;
+		let config = self.gpgme.config.clone();
+		let detail_level = self.state.detail_level;
+		thread::spawn(move || {
+			let result = GpgContext::new(config).and_then(|mut context| {
+				for key_type in [KeyType::Public, KeyType::Secret] {
+					context.get_keys_in_batches(
+						key_type,
+						detail_level,
+						KEY_BATCH_SIZE,
+						|keys| {
+							sender
+								.send(Event::Keys(load_id, key_type, keys))
+								.is_ok()
+						},
+					)?;
+				}
+				Ok(())
+			});
+			if let Err(e) = sender.send(Event::KeysLoaded(
+				load_id,
+				result.err().map(|e| e.to_string()),
+			)) {
+				log::error!(target: "gpg", "failed to send keys: {e}");
+			}
+		});
+		Ok(())
+	}
+
+	/// Appends the keys that are loaded in the background.
+	///
+	/// Keys are shown as they arrive only on the first load, otherwise
+	/// they are stored until the loading is finished.
+	/// Keys of an outdated load (e.g. before a refresh) are discarded.
+	pub fn append_keys(
+		&mut self,
+		load_id: usize,
+		key_type: KeyType,
+		keys: Vec<GpgKey>,
+	) {
+		if load_id != self.key_load_id {
+			return;
+		} else if load_id != 1 {
+			self.loaded_keys.entry(key_type).or_default().extend(keys);
+			return;
+		}
+		if self.tab == Tab::Keys(key_type) {
+			self.keys_table.default_items.extend(keys.iter().cloned());
+			let mut cache = mem::take(&mut self.key_info_cache);
+			let visible_keys = keys
+				.iter()
+				.filter(|key| self.is_key_visible(key, &mut cache))
+				.cloned()
+				.collect::<Vec<GpgKey>>();
+			self.key_info_cache = cache;
+			self.keys_table.items.extend(visible_keys);
+		}
+		self.keys.entry(key_type).or_default().extend(keys);
+		self.sort_pinned_keys();
+	}
+
+	/// Sorts the keys so that the pinned keys are at the top.
+	///
+	/// The selected key stays selected.
+	fn sort_pinned_keys(&mut self) {
+		for keys in self.keys.values_mut() {
+			self.pinned_keys.sort(keys);
+		}
+		if let Tab::Keys(_) = self.tab {
+			let selected =
+				self.keys_table.selected().map(|key| key.get_fingerprint());
+			self.pinned_keys.sort(&mut self.keys_table.items);
+			self.pinned_keys.sort(&mut self.keys_table.default_items);
+			self.keys_table_rows = None;
+			if let Some(fingerprint) = selected {
+				self.keys_table.state.tui.select(
+					self.keys_table
+						.items
+						.iter()
+						.position(|key| key.get_fingerprint() == fingerprint),
+				);
+			}
+		}
+	}
+
+	/// Finishes loading the keys in the background.
+	pub fn finish_loading_keys(
+		&mut self,
+		load_id: usize,
+		error: Option<String>,
+	) {
+		if load_id != self.key_load_id {
+			return;
+		}
+		self.loading_keys = false;
+		let loaded_keys = mem::take(&mut self.loaded_keys);
+		if let Some(e) = error {
+			self.prompt
+				.set_output((OutputType::Failure, format!("load error: {e}")));
+			self.emit_event(OperationEvent::new("load"));
+		} else {
+			if load_id != 1 {
+				let selected =
+					self.keys_table.selected().map(|key| key.get_fingerprint());
+				self.keys = loaded_keys;
+				self.sort_pinned_keys();
+				self.reset_keys_table();
+				self.filter_keys();
+				if let Some(index) = selected.and_then(|fingerprint| {
+					self.keys_table
+						.items
+						.iter()
+						.position(|key| key.get_fingerprint() == fingerprint)
+				}) {
+					self.keys_table.state.tui.select(Some(index));
+				}
+			}
+			let key_count = self.keys.values().map(Vec::len).sum::<usize>();
+			self.write_event(
+				OperationEvent::new("load"),
+				(OutputType::Success, format!("{key_count} key(s) loaded")),
+			);
+			// the first load is the one at startup
+			if load_id == 1 {
+				// keep the search (e.g. `--focus`) instead of the warning
+				if self.prompt.is_search_enabled() {
+					self.keys_table.reset_state();
+					self.filter_keys();
+				} else {
+					self.warn_expiring_keys();
+				}
+			}
+		}
+	}
+
+	/// Takes the output of the last command as plain text.
+	///
+	/// Contents of the popups (e.g. verification results) are
+	/// included after the prompt output, and the popups are closed.
+	fn take_command_output(&mut self) -> Option<String> {
+		let mut lines = Vec::new();
+		if self.prompt.command.is_none() && self.prompt.clock.is_some() {
+			lines.push(self.prompt.text.to_string());
+		}
+		if let Some(verifications) = self.verifications.take() {
+			for verification in verifications {
+				lines.extend(
+					verification.get_info(self.state.get_date_format()),
+				);
+			}
+		}
+		if let Some(results) = self.receive_results.take() {
+			lines.extend(results.into_iter().map(
+				|(key_id, result)| match result {
+					Ok(()) => format!("{key_id}  received"),
+					Err(e) => format!("{key_id}  failed ({e})"),
+				},
+			));
+		}
+		if let Some((_, keys)) = self.expiring_keys.take() {
+			lines.extend(keys.into_iter().map(|(key_id, user_id, days)| {
+				format!("{key_id}  {days}d  {user_id}")
+			}));
+		}
+		if let Some(groups) = self.fingerprint_comparison.take() {
+			let (expected, given): (Vec<String>, Vec<String>) =
+				groups.into_iter().unzip();
+			lines.push(format!("key:   {}", expected.join(" ")));
+			lines.push(format!("given: {}", given.join(" ")));
+		}
+		if let Some((_, entries)) = self.key_history.take() {
+			lines.extend(
+				entries
+					.into_iter()
+					.map(|entry| format!("{}  {}", entry.time, entry.action)),
+			);
+		}
+		if let Some((_, preferences)) = self.key_preferences.take() {
+			for (user_id, values) in preferences {
+				lines.push(user_id);
+				lines.extend(values.into_iter().map(|v| format!("  {v}")));
+			}
+		}
+		(!lines.is_empty()).then(|| format!("{}\n", lines.join("\n")))
+	}
+
+	/// Finishes receiving a key from the keyserver in the background.
+	///
+	/// Results are shown when all of the keys are received.
+	pub fn finish_receiving_key(
+		&mut self,
+		key_id: String,
+		result: Result<(), String>,
+	) -> Result<()> {
+		match self.pending_receives.iter().position(|v| v == &key_id) {
+			Some(index) => self.pending_receives.remove(index),
+			None => return Ok(()),
+		};
+		self.received_keys.push((key_id, result));
+		match self.pending_receives.first() {
+			Some(next_key) => {
+				self.prompt.set_output((
+					OutputType::Action,
+					format!(
+						"({}/{}) receiving {next_key}...",
+						self.received_keys.len() + 1,
+						self.received_keys.len() + self.pending_receives.len()
+					),
+				));
+				Ok(())
+			}
+			None => {
+				let results = mem::take(&mut self.received_keys);
+				self.finish_receiving_keys(results)
+			}
+		}
+	}
+
+	/// Shows the results of receiving the keys from the keyserver.
+	fn finish_receiving_keys(
+		&mut self,
+		results: Vec<(String, Result<(), String>)>,
+	) -> Result<()> {
+		self.refresh()?;
+		let received =
+			results.iter().filter(|(_, result)| result.is_ok()).count();
+		self.prompt.set_output((
+			if received == results.len() {
+				OutputType::Success
+			} else if received == 0 {
+				OutputType::Failure
+			} else {
+				OutputType::Warning
+			},
+			format!("received {received}/{} key(s)", results.len()),
+		));
+		// the received keys might not be loaded yet
+		let fingerprints = results
+			.iter()
+			.filter(|(_, result)| result.is_ok())
+			.filter_map(|(key_id, _)| {
+				self.gpgme.get_key(KeyType::Public, key_id.to_string()).ok()
+			})
+			.filter_map(|key| key.fingerprint().ok().map(String::from))
+			.collect();
+		self.emit_event(
+			OperationEvent::new("receive").with_fingerprints(fingerprints),
+		);
+		if results.len() > 1 {
+			self.receive_results = Some(results);
+		}
+		Ok(())
+	}
+
+	/// Finishes looking up the key of the given email address.
+	///
+	/// Import of the found key is asked for confirmation.
+	pub fn finish_locating_key(
+		&mut self,
+		email: String,
+		result: Option<(Command, String)>,
+	) {
+		self.pending_lookups.retain(|v| v != &email);
+		if let Some((command, source)) = result {
+			self.prompt.set_command(command);
+			self.prompt.text =
+				format!("{email}: {source}, press 'y' to import");
+		} else {
+			self.prompt.set_output((
+				OutputType::Failure,
+				format!("{email}: no key found"),
+			));
+			self.failed_lookups.insert(email, Instant::now());
+		}
+	}
+
+	/// Returns the keys that expire within the given number of days.
+	///
+	/// Each entry contains the key ID, user ID and the remaining days.
+	/// Revoked and already expired keys are skipped.
+	pub fn get_expiring_keys(
+		&self,
+		key_type: KeyType,
+		days: u64,
+	) -> Vec<(String, String, i64)> {
+		let mut expiring_keys = self
+			.keys
+			.get(&key_type)
+			.map(|keys| {
+				keys.iter()
+					.filter(|key| !key.has_state(KeyState::Revoked))
+					.filter_map(|key| {
+						key.get_days_until_expiry()
+							.filter(|v| {
+								u64::try_from(*v).map_or(false, |v| v <= days)
+							})
+							.map(|v| (key.get_id(), key.get_user_id(), v))
+					})
+					.collect::<Vec<(String, String, i64)>>()
+			})
+			.unwrap_or_default();
+		expiring_keys.sort_by_key(|(_, _, days)| *days);
+		expiring_keys
+	}
+
+	/// Returns the best match among the given keys.
+	///
+	/// Valid keys are preferred over the expired/revoked ones.
+	fn get_best_match<'b>(
+		keys: impl Iterator<Item = &'b GpgKey>,
+	) -> Option<&'b GpgKey> {
+		let keys = keys.collect::<Vec<&GpgKey>>();
+		keys.iter()
+			.find(|key| key.has_state(KeyState::Valid))
+			.or(keys.first())
+			.copied()
+	}
+
+	/// Looks up the key of the given email address via WKD
+	/// and then the keyserver.
+	///
+	/// Returns the command for importing the key and its source.
+	fn locate_remote_key(
+		gpgme: &mut GpgContext,
+		email: &str,
+	) -> Option<(Command, String)> {
+		match gpgme.locate_wkd_key(email) {
+			Ok((key, data)) => {
+				return Some((
+					Command::ImportFetched(
+						String::from("WKD"),
+						vec![key.get_fingerprint()],
+						data,
+					),
+					format!("{} (WKD)", key.get_id()),
+				));
+			}
+			Err(e) => log::debug!(target: "gpg", "{email}: {e}"),
+		}
+		let keys = gpgme.search_keyserver(email).ok()?;
+		Self::get_best_match(keys.iter().filter(|key| key.has_email(email)))
+			.map(|key| {
+				(
+					Command::ImportKeys(
+						vec![key.get_fingerprint()],
+						true,
+						None,
+					),
+					format!("{} (keyserver)", key.get_id()),
+				)
+			})
+	}
+
+	/// Returns the fingerprint of the key that has the given (sub)key ID.
+	fn get_fingerprint(&self, key_id: &str) -> Option<String> {
+		self.keys
+			.values()
+			.flatten()
+			.find(|key| key.has_subkey(key_id))
+			.map(|key| key.get_fingerprint())
+	}
+
+	/// Returns the fingerprints of the keys that have the given IDs.
+	///
+	/// IDs that do not resolve to a key are skipped.
+	fn get_fingerprints(&self, key_ids: &[String]) -> Vec<String> {
+		key_ids
+			.iter()
+			.filter_map(|key_id| self.get_fingerprint(key_id))
+			.collect()
+	}
+
+	/// Writes the event of an operation with the output of the prompt.
+	fn emit_event(&mut self, event: OperationEvent) {
+		let output =
+			(self.prompt.output_type.clone(), self.prompt.text.clone());
+		self.write_event(event, output);
+	}
+
+	/// Writes the event of an operation with the given output.
+	fn write_event(
+		&mut self,
+		event: OperationEvent,
+		(output_type, message): (OutputType, String),
+	) {
+		if let Some(json_events) = self.json_events.as_mut() {
+			if let Err(e) = json_events.write(&event, &output_type, &message) {
+				log::error!(target: "events", "failed to write: {e}");
+			}
+		}
+	}
+
+	/// Records the action performed on the given keys to the history.
+	///
+	/// Keys are looked up by their (sub)key IDs for using the
+	/// fingerprints so this should be called before refreshing.
+	/// Patterns that do not resolve to a key are skipped.
+	fn record_history(&self, key_ids: &[String], action: &str) {
+		for key_id in key_ids {
+			let fingerprint = match self.get_fingerprint(key_id) {
+				Some(fingerprint) => fingerprint,
+				None => {
+					log::debug!(
+						target: "history",
+						"skipped {key_id}: {action}"
+					);
+					continue;
+				}
+			};
+			if let Err(e) = self.history.record(&fingerprint, action) {
+				log::error!(target: "history", "failed to record: {e}");
+			}
+		}
+	}
+
+	/// Shows a warning if any of the secret keys expire soon.
+	///
+	/// It is shown once at startup after the keys are loaded.
+	fn warn_expiring_keys(&mut self) {
+		let expiring_keys =
+			self.get_expiring_keys(KeyType::Secret, EXPIRY_WARNING_DAYS);
+		if !expiring_keys.is_empty() {
+			self.prompt.set_output((
+				OutputType::Warning,
+				format!(
+					"expiring soon: {} (see :expiring)",
+					expiring_keys
+						.iter()
+						.map(|(key_id, _, days)| format!("{key_id} ({days}d)"))
+						.collect::<Vec<String>>()
+						.join(", ")
+				),
+			));
+		}
+	}
+
 	/// Resets the application state.
 	pub fn refresh(&mut self) -> Result<()> {
 		self.state.refresh();
 		self.mode = Mode::Normal;
 		self.prompt.clear();
 		self.options.state.select(Some(0));
-		self.keys = self.gpgme.get_all_keys(Some(self.state.detail_level))?;
+		self.load_keys()?;
 		self.keys_table_states.clear();
-		self.keys_table_margin = 1;
+		self.keys_table_margin = self.state.theme.get_table_margin();
+		self.reset_keys_table();
+		Ok(())
+	}
+
+	/// Recreates the keys table for the current tab.
+	fn reset_keys_table(&mut self) {
 		match self.tab {
 			Tab::Keys(key_type) => {
-				self.keys_table = StatefulTable::with_items(
+				self.keys_table = StatefulTable::new(
 					self.keys
 						.get(&key_type)
 						.unwrap_or_else(|| {
 							panic!("failed to get {key_type} keys")
 						})
 						.to_vec(),
+					self.state.table_state.clone(),
+				)
+			}
+			Tab::KeyserverResults => {
+				self.keys_table = StatefulTable::new(
+					self.keyserver_results.clone(),
+					self.state.table_state.clone(),
 				)
 			}
-			Tab::Help => {}
+			Tab::Help | Tab::Card | Tab::KeyDetail(_) => {}
 		};
-		Ok(())
+		self.filter_keys();
+	}
+
+	/// Stores the state and items of the keys table
+	/// before switching to another tab.
+	fn store_keys_table(&mut self) {
+		if let Tab::Keys(key_type) = self.tab {
+			self.keys_table_states
+				.insert(key_type, self.keys_table.state.clone());
+			self.keys
+				.insert(key_type, self.keys_table.default_items.clone());
+		}
+	}
+
+	/// Returns the first (JPEG) photo ID of the given key.
+	///
+	/// Photo IDs are not listed by GPGME, so they
+	/// are extracted from the exported key instead.
+	fn get_photo(&mut self, key: &GpgKey) -> Option<Photo> {
+		let photo = self
+			.gpgme
+			.get_exported_keys(
+				KeyType::Public,
+				Some(vec![key.get_fingerprint()]),
+			)
+			.and_then(|data| photo::extract_photos(&data))
+			.and_then(|photos| {
+				photos.first().map(|data| Photo::new(data)).transpose()
+			});
+		match photo {
+			Ok(photo) => photo,
+			Err(e) => {
+				log::error!(target: "gpg", "failed to get the photo ID: {e}");
+				None
+			}
+		}
+	}
+
+	/// Returns the key IDs and email addresses of the loaded keys.
+	///
+	/// These are used for completing the arguments in the prompt.
+	pub fn get_completion_keys(&self) -> Vec<String> {
+		self.keys
+			.values()
+			.flatten()
+			.flat_map(|key| {
+				let mut values = vec![key.get_id()];
+				values.extend(key.get_emails());
+				values
+			})
+			.collect()
+	}
+
+	/// Replaces the placeholders in the command with
+	/// the details of the selected key.
+	///
+	/// * `%id`: key ID
+	/// * `%fpr`: fingerprint
+	/// * `%user`: primary user ID
+	pub fn expand_command(&self, command: &str) -> String {
+		match self.keys_table.selected() {
+			Some(key) if matches!(self.tab, Tab::Keys(_)) => command
+				.replace("%id", &key.get_id())
+				.replace("%fpr", &key.get_fingerprint())
+				.replace("%user", &key.get_user_id()),
+			_ => command.to_string(),
+		}
+	}
+
+	/// Checks if the smartcard has a key in the given slot. (1-3)
+	fn is_card_slot_used(&self, slot: usize) -> bool {
+		self.gpgme
+			.get_card_status()
+			.ok()
+			.and_then(|card| {
+				card.slots
+					.get(slot.checked_sub(1)?)
+					.and_then(|slot| slot.fingerprint.clone())
+			})
+			.is_some()
+	}
+
+	/// Returns the value of the given selection for the selected key.
+	fn get_selection_content(
+		&mut self,
+		selection: Selection,
+	) -> Result<String> {
+		let selected_key = self
+			.keys_table
+			.selected()
+			.ok_or_else(|| anyhow!("invalid selection"))?;
+		match selection {
+			Selection::Row1 => Ok(selected_key
+				.get_subkey_info(
+					self.gpgme.config.default_key.as_deref(),
+					self.keys_table.state.size != TableSize::Normal,
+					self.state.get_date_format(),
+				)
+				.join("\n")),
+			Selection::Row2 => Ok(selected_key
+				.get_user_info(
+					self.keys_table.state.size == TableSize::Minimized,
+					self.state.get_date_format(),
+				)
+				.join("\n")),
+			Selection::Key => self.gpgme.get_armored_keys(
+				match self.tab {
+					Tab::Keys(key_type) => key_type,
+					_ => KeyType::Public,
+				},
+				Some(vec![selected_key.get_id()]),
+			),
+			Selection::KeyId => Ok(selected_key.get_id()),
+			Selection::KeyFingerprint => Ok(self
+				.state
+				.fingerprint_format
+				.format(&selected_key.get_fingerprint())),
+			Selection::UserId => Ok(selected_key.get_user_id()),
+			Selection::SshKey => self.gpgme.get_ssh_key(selected_key.get_id()),
+			Selection::Vcard => Ok(selected_key.get_vcard()),
+			Selection::Created => selected_key
+				.get_creation_time()
+				.ok_or_else(|| anyhow!("creation time is not available")),
+			Selection::Expires => selected_key
+				.get_expiration_time()
+				.ok_or_else(|| anyhow!("key does not expire")),
+			Selection::DetailLine => self
+				.key_detail
+				.selected()
+				.map(|entry| entry.value.to_string())
+				.ok_or_else(|| anyhow!("invalid selection")),
+			Selection::Fingerprints => Ok(self
+				.keys_table
+				.items
+				.iter()
+				.filter(|key| {
+					self.recipients.is_empty()
+						|| self.recipients.contains(&key.get_id())
+				})
+				.map(|key| key.get_fingerprint())
+				.collect::<Vec<String>>()
+				.join("\n")),
+		}
+	}
+
+	/// Returns the selection as JSON.
+	///
+	/// Fields of the selected key are included along with the
+	/// selected value. An array of keys is returned for the
+	/// [`Fingerprints`] selection.
+	///
+	/// [`Fingerprints`]: Selection::Fingerprints
+	fn get_selection_json(&self, selection: Selection, value: String) -> Value {
+		match selection {
+			Selection::Fingerprints => Value::from(
+				self.keys_table
+					.items
+					.iter()
+					.filter(|key| {
+						value.lines().any(|v| v == key.get_fingerprint())
+					})
+					.map(GpgKey::get_json)
+					.collect::<Vec<Value>>(),
+			),
+			_ => match self.keys_table.selected() {
+				Some(key) => {
+					let mut json = key.get_json();
+					json["value"] = Value::from(value);
+					json
+				}
+				None => Value::from(value),
+			},
+		}
+	}
+
+	/// Returns the selection formatted with the given template.
+	///
+	/// A line is rendered for each key in the case of an array.
+	fn get_selection_template(
+		&self,
+		selection: Selection,
+		value: String,
+		template: &str,
+	) -> String {
+		match self.get_selection_json(selection, value) {
+			Value::Array(keys) => keys
+				.iter()
+				.map(|key| SelectFormat::render(template, key))
+				.collect::<Vec<String>>()
+				.join("\n"),
+			fields => SelectFormat::render(template, &fields),
+		}
+	}
+
+	/// Sets whether if the terminal is focused.
+	///
+	/// Output messages are deferred while the terminal is unfocused.
+	pub fn set_focused(&mut self, focused: bool) {
+		self.state.focused = focused;
+		if focused {
+			self.prompt.flush_output();
+		} else {
+			self.prompt.defer_output();
+		}
 	}
 
 	/// Handles the tick event of the application.
@@ -151,12 +1175,204 @@ impl<'a> App<'a> {
 				self.prompt.clear()
 			}
 		}
+		if !self.state.locked && self.state.is_idle() {
+			self.state.lock();
+			self.prompt.clear();
+		}
+		if self.state.sync_interval != 0
+			&& self.state.last_sync.elapsed().as_secs()
+				>= self.state.sync_interval
+		{
+			self.state.last_sync = Instant::now();
+			match self.sync_config() {
+				Ok(changes)
+					if !changes.is_empty() && !self.prompt.is_enabled() =>
+				{
+					self.prompt.set_output((
+						OutputType::Warning,
+						format!(
+							"configuration changed: {}",
+							changes.join(", ")
+						),
+					));
+				}
+				Ok(_) => {}
+				Err(e) => log::error!(target: "config", "failed to sync: {e}"),
+			}
+		}
+	}
+
+	/// Syncs the GnuPG configuration and applies the changes.
+	///
+	/// Cached key information is invalidated since it depends on
+	/// the configuration. (e.g. default key)
+	/// Returns the list of changes.
+	fn sync_config(&mut self) -> Result<Vec<String>> {
+		let changes = self.gpgme.config.sync()?;
+		if !changes.is_empty() {
+			log::info!(target: "config", "{}", changes.join(", "));
+			self.gpgme.apply_config();
+			self.key_info_cache.clear();
+			self.keys_table_rows = None;
+		}
+		Ok(changes)
+	}
+
+	/// Reloads the configuration file and applies its settings.
+	///
+	/// Settings are applied over the command-line arguments.
+	/// Returns the path of the configuration file.
+	fn reload_config(&mut self) -> Result<String> {
+		let config_file = self
+			.state
+			.config_file
+			.clone()
+			.ok_or_else(|| anyhow!("configuration file is not found"))?;
+		let config = Config::parse_config(&config_file)?;
+		let args = config.update_args(self.cli_args.clone());
+		let context = GpgContext::new(GpgConfig::new(&args)?)?;
+		match clipboard::init(
+			args.clipboard,
+			&args.copy_command,
+			&args.paste_command,
+		) {
+			Ok(clipboard) => self.clipboard = clipboard,
+			Err(e) => {
+				log::error!(target: "clipboard", "failed to initialize: {e}")
+			}
+		}
+		let state = State::from(&args);
+		self.state.style = state.style;
+		self.state.color = state.color;
+		self.state.file_explorer = state.file_explorer;
+		self.state.detail_level = state.detail_level;
+		self.state.date_format = state.date_format;
+		self.state.fingerprint_format = state.fingerprint_format;
+		self.state.table_state = state.table_state;
+		self.state.rename_output = state.rename_output;
+		self.state.detail_view = state.detail_view;
+		self.state.passphrase_check = state.passphrase_check;
+		self.state.graphics = state.graphics;
+		self.state.copy_command = state.copy_command;
+		self.state.paste_command = state.paste_command;
+		self.state.lock_timeout = state.lock_timeout;
+		self.state.sync_interval = state.sync_interval;
+		self.state.serve_address = state.serve_address;
+		let general = config.general.unwrap_or_default();
+		self.state.allowed_commands = general.allowed_commands;
+		self.state.lock_phrase = general.lock_phrase;
+		self.state.custom_key_bindings =
+			general.key_bindings.unwrap_or_default();
+		self.state.keygen_profiles = config.keygen.unwrap_or_default();
+		self.state.theme = config.theme.unwrap_or_default();
+		*self.gpgme = context;
+		self.refresh()?;
+		Ok(config_file)
+	}
+
+	/// Writes the current settings to the configuration file.
+	///
+	/// The settings that can't be changed at runtime are kept as is.
+	/// Returns the path of the configuration file.
+	fn write_config(&mut self) -> Result<String> {
+		let config_file = self
+			.state
+			.config_file
+			.clone()
+			.or_else(Config::get_write_location)
+			.ok_or_else(|| anyhow!("configuration directory is not found"))?;
+		let mut config = if Path::new(&config_file).exists() {
+			Config::parse_config(&config_file)?
+		} else {
+			Config::default()
+		};
+		let general = config.general.get_or_insert_with(GeneralConfig::default);
+		if self.state.color != Color::Reset {
+			general.color =
+				Some(WidgetColor::from(self.state.color).to_string());
+		}
+		general.style = Some(self.state.style.to_string());
+		general.file_explorer = Some(self.state.file_explorer.to_string());
+		general.detail_level = Some(self.state.detail_level);
+		general.table_size = Some(self.keys_table.state.size);
+		general.minimize_threshold =
+			Some(self.keys_table.state.minimize_threshold);
+		general.rename_output = Some(self.state.rename_output);
+		general.detail_view = Some(self.state.detail_view);
+		general.passphrase_check = Some(self.state.passphrase_check);
+		general.lock_timeout = Some(self.state.lock_timeout);
+		general.sync_interval = Some(self.state.sync_interval);
+		general.date_format = Some(self.state.date_format.to_string());
+		general.fingerprint_format =
+			Some(self.state.fingerprint_format.to_string());
+		general.clipboard = Some(self.clipboard.get_type().to_string());
+		general.copy_command = Some(self.state.copy_command.to_string());
+		general.paste_command = Some(self.state.paste_command.to_string());
+		general.key_bindings = Some(self.state.custom_key_bindings.clone())
+			.filter(|key_bindings| !key_bindings.is_empty());
+		let gpg = config.gpg.get_or_insert_with(GpgFileConfig::default);
+		gpg.armor = Some(self.gpgme.config.armor);
+		gpg.validate = Some(self.gpgme.config.validate);
+		gpg.default_key.clone_from(&self.gpgme.config.default_key);
+		gpg.keyserver.clone_from(&self.gpgme.config.keyserver);
+		config
+			.theme
+			.get_or_insert_with(Default::default)
+			.table_margin = Some(self.keys_table_margin);
+		config.write_config(&config_file)?;
+		self.state.config_file = Some(config_file.to_string());
+		Ok(config_file)
+	}
+
+	/// Runs the given command without the interface and returns its output.
+	///
+	/// The command that waits for confirmation is run only if
+	/// `confirm` is `true`, otherwise an error is returned.
+	pub fn run_batch_command(
+		&mut self,
+		command: Command,
+		confirm: bool,
+	) -> Result<String> {
+		self.prompt.clear();
+		self.run_command(command)?;
+		if let Some(command) = self.prompt.command.take() {
+			if !confirm {
+				return Err(anyhow!(
+					"confirmation is required to {command} (use --yes)"
+				));
+			}
+			self.run_command(command)?;
+		}
+		if self.prompt.output_type == OutputType::Failure {
+			return Err(anyhow!("{}", self.prompt.text));
+		}
+		Ok(self.prompt.text.to_string())
 	}
 
 	/// Runs the given command which is used to specify
 	/// the widget to render or action to perform.
 	pub fn run_command(&mut self, command: Command) -> Result<()> {
+		if let Some(allowed_commands) = &self.state.allowed_commands {
+			if !command.is_allowed(allowed_commands) {
+				self.state.show_options = false;
+				self.prompt.set_output((
+					OutputType::Failure,
+					format!(
+						"command is not allowed in restricted mode: {}",
+						command.get_name()
+					),
+				));
+				return Ok(());
+			}
+		}
 		let mut show_options = false;
+		let update_rows =
+			!matches!(command, Command::Scroll(_, _) | Command::None);
+		let syntax = if self.state.show_options {
+			command.get_syntax()
+		} else {
+			None
+		};
 		if let Command::Confirm(ref cmd) = command {
 			self.prompt.set_command(*cmd.clone())
 		} else if self.prompt.command.is_some() {
@@ -179,273 +1395,1918 @@ impl<'a> App<'a> {
 			Command::ShowOutput(output_type, message) => {
 				self.prompt.set_output((output_type, message))
 			}
-			Command::ShowOptions => {
-				let prev_selection = self.options.state.selected();
-				let prev_item_count = self.options.items.len();
+			Command::Redirect(command, path) => {
+				self.prompt.clear();
+				// discard the output of the previous commands
+				self.take_command_output();
+				self.run_command(*command)?;
+				if self.prompt.output_type != OutputType::Failure {
+					let output = self.take_command_output();
+					self.prompt.clear();
+					self.prompt.set_output(match output {
+						Some(output) => {
+							let path = shellexpand::tilde(&path).to_string();
+							match fs::write(&path, output) {
+								Ok(()) => (
+									OutputType::Success,
+									format!("output written to {path}"),
+								),
+								Err(e) => (
+									OutputType::Failure,
+									format!("redirect error: {e}"),
+								),
+							}
+						}
+						None => (
+							OutputType::Warning,
+							String::from("no output to redirect"),
+						),
+					});
+				}
+			}
+			Command::ShowOptions => {
+				let prev_selection = self.options.state.selected();
+				let prev_item_count = self.options.items.len();
 				self.options = StatefulList::with_items(match self.tab {
 					Tab::Keys(key_type) => {
-						if let Some(selected_key) = &self.keys_table.selected()
+						let sections = if let Some(selected_key) =
+							&self.keys_table.selected()
 						{
+							let key_id = selected_key.get_id();
+							let is_secret = key_type == KeyType::Secret;
+							let can_authenticate = selected_key
+								.has_capability(KeyCapability::Auth);
 							vec![
-								Command::None,
-								Command::ShowHelp,
-								Command::Refresh,
-								Command::RefreshKeys,
-								Command::Set(
-									String::from("prompt"),
-									String::from(":import "),
-								),
-								Command::ImportClipboard,
-								Command::Set(
-									String::from("prompt"),
-									String::from(":receive "),
-								),
-								Command::ExportKeys(
-									key_type,
-									vec![selected_key.get_id()],
-									false,
-								),
-								if key_type == KeyType::Secret {
-									Command::ExportKeys(
-										key_type,
-										vec![selected_key.get_id()],
-										true,
-									)
-								} else {
-									Command::None
-								},
-								Command::ExportKeys(
-									key_type,
-									Vec::new(),
-									false,
+								(
+									"Key actions",
+									vec![
+										Command::GenerateKey,
+										Command::EditKey(key_id.to_string()),
+										Command::SetOwnerTrust(None),
+										if is_secret {
+											Command::Set(
+												String::from("prompt"),
+												String::from(":adduid "),
+											)
+										} else {
+											Command::None
+										},
+										if is_secret {
+											Command::Set(
+												String::from("prompt"),
+												String::from(":revuid "),
+											)
+										} else {
+											Command::None
+										},
+										if is_secret {
+											Command::Set(
+												String::from("prompt"),
+												String::from(":primary-uid "),
+											)
+										} else {
+											Command::None
+										},
+										if is_secret {
+											Command::AddSubkey(
+												key_id.to_string(),
+											)
+										} else {
+											Command::None
+										},
+										if is_secret {
+											Command::EditPreferences(
+												key_id.to_string(),
+												false,
+											)
+										} else {
+											Command::None
+										},
+										if is_secret {
+											Command::Set(
+												String::from("signer"),
+												key_id.to_string(),
+											)
+										} else {
+											Command::None
+										},
+										if is_secret {
+											Command::PresetPassphrase(
+												key_id.to_string(),
+												None,
+											)
+										} else {
+											Command::None
+										},
+										Command::SignKey(
+											key_id.to_string(),
+											None,
+										),
+										Command::Set(
+											String::from("prompt"),
+											format!(":sign {key_id} --with "),
+										),
+										Command::Confirm(Box::new(
+											Command::DeleteKey(
+												key_type,
+												key_id.to_string(),
+											),
+										)),
+										if is_secret {
+											Command::Confirm(Box::new(
+												Command::RevokeKey(
+													key_id.to_string(),
+													None,
+												),
+											))
+										} else {
+											Command::None
+										},
+										Command::Confirm(Box::new(
+											Command::SendKey(
+												key_id.to_string(),
+												None,
+											),
+										)),
+										if self
+											.gpgme
+											.config
+											.keyservers
+											.is_empty()
+										{
+											Command::None
+										} else {
+											Command::SelectKeyserver(
+												key_id.to_string(),
+											)
+										},
+										if selected_key
+											.get_unknown_signers()
+											.is_empty()
+										{
+											Command::None
+										} else {
+											Command::LocateSigners(
+												key_id.to_string(),
+											)
+										},
+										Command::DiffKey(key_id.to_string()),
+										Command::ShowHistory,
+										Command::TogglePin(Some(
+											key_id.to_string(),
+										)),
+										if key_type == KeyType::Public {
+											if self.mode == Mode::Pick {
+												Command::ToggleRecipient(
+													key_id.to_string(),
+												)
+											} else {
+												Command::SwitchMode(Mode::Pick)
+											}
+										} else {
+											Command::None
+										},
+										if key_type == KeyType::Public
+											&& !self.recipients.is_empty()
+										{
+											if self.pending_files.is_empty() {
+												Command::Set(
+													String::from("prompt"),
+													String::from(":encrypt "),
+												)
+											} else {
+												Command::EncryptFiles(
+													self.pending_files.clone(),
+												)
+											}
+										} else {
+											Command::None
+										},
+									],
 								),
-								Command::Confirm(Box::new(Command::DeleteKey(
-									key_type,
-									selected_key.get_id(),
-								))),
-								Command::Confirm(Box::new(Command::SendKey(
-									selected_key.get_id(),
-								))),
-								Command::EditKey(selected_key.get_id()),
-								if key_type == KeyType::Secret {
-									Command::Set(
-										String::from("signer"),
-										selected_key.get_id(),
-									)
-								} else {
-									Command::None
-								},
-								Command::SignKey(selected_key.get_id()),
-								Command::GenerateKey,
-								Command::Set(
-									String::from("armor"),
-									(!self.gpgme.config.armor).to_string(),
+								(
+									"Import & export",
+									vec![
+										Command::Set(
+											String::from("prompt"),
+											String::from(":import "),
+										),
+										Command::ImportClipboard,
+										Command::Set(
+											String::from("prompt"),
+											String::from(":receive "),
+										),
+										Command::Set(
+											String::from("prompt"),
+											String::from(":fetch http://"),
+										),
+										Command::ExportKeys(
+											key_type,
+											vec![key_id.to_string()],
+											false,
+										),
+										if is_secret {
+											Command::ExportKeys(
+												key_type,
+												vec![key_id.to_string()],
+												true,
+											)
+										} else {
+											Command::None
+										},
+										if can_authenticate {
+											Command::ExportSshKey(
+												key_id.to_string(),
+											)
+										} else {
+											Command::None
+										},
+										Command::ExportWithSigners(
+											key_id.to_string(),
+										),
+										if is_secret {
+											Command::ExportPaperKey(
+												key_id.to_string(),
+												false,
+											)
+										} else {
+											Command::None
+										},
+										Command::ServeKey(key_id.to_string()),
+										Command::ExportKeys(
+											key_type,
+											Vec::new(),
+											false,
+										),
+										if self
+											.pinned_keys
+											.fingerprints
+											.is_empty()
+										{
+											Command::None
+										} else {
+											Command::ExportEach(
+												KeySet::Pinned,
+												None,
+											)
+										},
+										Command::GenerateReport,
+										Command::Set(
+											String::from("armor"),
+											(!self.gpgme.config.armor)
+												.to_string(),
+										),
+										Command::Copy(Selection::Key),
+										Command::Copy(Selection::KeyId),
+										Command::Copy(
+											Selection::KeyFingerprint,
+										),
+										Command::Copy(Selection::UserId),
+										if can_authenticate {
+											Command::Copy(Selection::SshKey)
+										} else {
+											Command::None
+										},
+										Command::Copy(Selection::Vcard),
+										Command::Copy(Selection::Fingerprints),
+										Command::Copy(Selection::Created),
+										Command::Copy(Selection::Expires),
+										Command::Copy(Selection::Row1),
+										Command::Copy(Selection::Row2),
+										Command::Paste,
+									],
 								),
-								Command::Copy(Selection::Key),
-								Command::Copy(Selection::KeyId),
-								Command::Copy(Selection::KeyFingerprint),
-								Command::Copy(Selection::UserId),
-								Command::Copy(Selection::Row1),
-								Command::Copy(Selection::Row2),
-								Command::Paste,
-								Command::ToggleDetail(false),
-								Command::ToggleDetail(true),
-								Command::Set(
-									String::from("margin"),
-									String::from(
-										if self.keys_table_margin == 1 {
-											"0"
+								(
+									"Display",
+									vec![
+										Command::InspectKey(key_id.to_string()),
+										Command::ShowQrCode(Selection::Key),
+										Command::ShowQrCode(
+											Selection::KeyFingerprint,
+										),
+										Command::ShowExpiring(
+											EXPIRY_WARNING_DAYS,
+										),
+										Command::ToggleDetail(false),
+										Command::ToggleDetail(true),
+										Command::ToggleCollapse,
+										Command::Set(
+											String::from("margin"),
+											String::from(
+												if self.keys_table_margin == 1 {
+													"0"
+												} else {
+													"1"
+												},
+											),
+										),
+										Command::ToggleTableSize,
+										Command::ChangeStyle(
+											self.state.style.next(),
+										),
+										if self.mode == Mode::Visual {
+											Command::SwitchMode(Mode::Normal)
 										} else {
-											"1"
+											Command::SwitchMode(Mode::Visual)
 										},
-									),
+									],
+								),
+								(
+									"Application",
+									vec![
+										Command::ShowHelp,
+										Command::Refresh,
+										Command::RefreshKeys,
+										Command::ShowCard,
+										Command::Quit,
+									],
 								),
-								Command::ToggleTableSize,
-								Command::ChangeStyle(self.state.style.next()),
-								if self.mode == Mode::Visual {
-									Command::SwitchMode(Mode::Normal)
-								} else {
-									Command::SwitchMode(Mode::Visual)
-								},
-								Command::Quit,
 							]
-							.into_iter()
-							.enumerate()
-							.filter(|(i, c)| {
-								if c == &Command::None {
-									*i == 0
-								} else {
-									true
-								}
-							})
-							.map(|(_, c)| c)
-							.collect()
 						} else {
 							vec![
-								Command::None,
-								Command::ShowHelp,
-								Command::Refresh,
-								Command::RefreshKeys,
-								Command::Set(
-									String::from("prompt"),
-									String::from(":import "),
+								("Key actions", vec![Command::GenerateKey]),
+								(
+									"Import & export",
+									vec![
+										Command::Set(
+											String::from("prompt"),
+											String::from(":import "),
+										),
+										Command::ImportClipboard,
+										Command::Set(
+											String::from("prompt"),
+											String::from(":receive "),
+										),
+										Command::Set(
+											String::from("prompt"),
+											String::from(":fetch http://"),
+										),
+										Command::Paste,
+									],
 								),
-								Command::ImportClipboard,
-								Command::Set(
-									String::from("prompt"),
-									String::from(":receive "),
+								(
+									"Display",
+									vec![
+										Command::ChangeStyle(
+											self.state.style.next(),
+										),
+										if self.mode == Mode::Visual {
+											Command::SwitchMode(Mode::Normal)
+										} else {
+											Command::SwitchMode(Mode::Visual)
+										},
+									],
+								),
+								(
+									"Application",
+									vec![
+										Command::ShowHelp,
+										Command::Refresh,
+										Command::RefreshKeys,
+										Command::ShowCard,
+										Command::Quit,
+									],
 								),
-								Command::GenerateKey,
-								Command::Paste,
-								Command::ChangeStyle(self.state.style.next()),
-								if self.mode == Mode::Visual {
-									Command::SwitchMode(Mode::Normal)
-								} else {
-									Command::SwitchMode(Mode::Visual)
-								},
-								Command::Quit,
 							]
-							.into_iter()
-							.enumerate()
-							.filter(|(i, c)| {
-								if c == &Command::None {
-									*i == 0
-								} else {
-									true
-								}
-							})
-							.map(|(_, c)| c)
-							.collect()
+						};
+						let mut entries = vec![ListEntry::Item(Command::None)];
+						entries.extend(ListEntry::from_sections(
+							sections
+								.into_iter()
+								.map(|(title, commands)| {
+									(
+										title,
+										commands
+											.into_iter()
+											.filter(|c| c != &Command::None)
+											.collect(),
+									)
+								})
+								.collect(),
+						));
+						entries
+					}
+					Tab::KeyserverResults => {
+						let mut commands = vec![Command::None];
+						if let Some(selected_key) = self.keys_table.selected() {
+							commands.push(Command::ImportKeys(
+								vec![selected_key.get_id()],
+								true,
+								None,
+							));
 						}
+						commands.extend([
+							Command::Set(
+								String::from("prompt"),
+								String::from(":search-keyserver "),
+							),
+							Command::ListKeys(KeyType::Public),
+							Command::ListKeys(KeyType::Secret),
+							Command::ShowHelp,
+							Command::Quit,
+						]);
+						commands.into_iter().map(ListEntry::Item).collect()
 					}
-					Tab::Help => {
-						vec![
-							Command::None,
+					Tab::KeyDetail(key_type) => vec![
+						Command::None,
+						Command::Copy(Selection::DetailLine),
+						Command::ListKeys(key_type),
+						Command::ShowHelp,
+						Command::Quit,
+					]
+					.into_iter()
+					.map(ListEntry::Item)
+					.collect(),
+					Tab::Card => {
+						let mut commands = vec![Command::None];
+						if let Some(fingerprint) = self
+							.card_slots
+							.selected()
+							.and_then(|slot| slot.fingerprint.clone())
+						{
+							commands.push(Command::GoToKey(fingerprint));
+						}
+						commands.extend([
+							Command::ShowCard,
 							Command::ListKeys(KeyType::Public),
 							Command::ListKeys(KeyType::Secret),
-							Command::ChangeStyle(self.state.style.next()),
-							if self.mode == Mode::Visual {
-								Command::SwitchMode(Mode::Normal)
-							} else {
-								Command::SwitchMode(Mode::Visual)
-							},
-							Command::Refresh,
+							Command::ShowHelp,
 							Command::Quit,
-						]
+						]);
+						commands.into_iter().map(ListEntry::Item).collect()
 					}
+					Tab::Help => vec![
+						Command::None,
+						Command::ListKeys(KeyType::Public),
+						Command::ListKeys(KeyType::Secret),
+						Command::ChangeStyle(self.state.style.next()),
+						if self.mode == Mode::Visual {
+							Command::SwitchMode(Mode::Normal)
+						} else {
+							Command::SwitchMode(Mode::Visual)
+						},
+						Command::Refresh,
+						Command::Quit,
+					]
+					.into_iter()
+					.map(ListEntry::Item)
+					.collect(),
 				});
 				if prev_item_count == 0
 					|| self.options.items.len() == prev_item_count
 				{
 					self.options.state.select(prev_selection.or(Some(0)));
 				} else {
-					self.options.state.select(Some(0));
+					self.options.state.select(Some(0));
+				}
+				show_options = true;
+			}
+			Command::ListKeys(key_type) => {
+				if self.tab == Tab::KeyserverResults {
+					self.key_info_cache.clear();
+				}
+				self.store_keys_table();
+				self.keys_table = StatefulTable::new(
+					self.keys
+						.get(&key_type)
+						.unwrap_or_else(|| {
+							panic!("failed to get {key_type} keys")
+						})
+						.to_vec(),
+					self.state.table_state.clone(),
+				);
+				if let Some(state) = self.keys_table_states.get(&key_type) {
+					self.keys_table.state = state.clone();
+				}
+				self.filter_keys();
+				self.tab = Tab::Keys(key_type);
+			}
+			Command::ImportKeys(_, false, _) | Command::ImportClipboard => {
+				let mut keys = Vec::new();
+				let mut import_error = String::from("no files given");
+				if let Command::ImportKeys(ref key_files, _, _) = command {
+					keys.clone_from(key_files);
+				} else {
+					match self.clipboard.get_contents() {
+						Ok(content) => {
+							keys = vec![content];
+						}
+						Err(e) => {
+							import_error = e.to_string();
+						}
+					}
+				}
+				let event = OperationEvent::new("import").with_paths(
+					if command == Command::ImportClipboard {
+						Vec::new()
+					} else {
+						keys.clone()
+					},
+				);
+				if keys.is_empty() {
+					self.prompt.set_output((
+						OutputType::Failure,
+						format!("import error: {import_error}"),
+					))
+				} else {
+					match self
+						.gpgme
+						.import_keys(keys, command != Command::ImportClipboard)
+					{
+						Ok(key_count) => {
+							self.refresh()?;
+							self.prompt.set_output((
+								OutputType::Success,
+								format!("{key_count} key(s) imported"),
+							))
+						}
+						Err(e) => self.prompt.set_output((
+							OutputType::Failure,
+							format!("import error: {e}"),
+						)),
+					}
+				}
+				self.emit_event(event);
+			}
+			Command::PresetPassphrase(key_id, None) => {
+				self.prompt.enable_passphrase_input(
+					&format!("passphrase for {key_id}"),
+					Command::PresetPassphrase(key_id, None),
+				);
+			}
+			Command::ChangePassphrase(key_id, None, _) => {
+				self.prompt.enable_passphrase_input(
+					&format!("current passphrase for {key_id} (empty if none)"),
+					Command::ChangePassphrase(key_id, None, None),
+				);
+			}
+			Command::ChangePassphrase(key_id, Some(passphrase), None) => {
+				self.prompt.enable_passphrase_input(
+					&format!("new passphrase for {key_id}"),
+					Command::ChangePassphrase(key_id, Some(passphrase), None),
+				);
+			}
+			Command::ChangePassphrase(
+				ref key_id,
+				Some(ref passphrase),
+				Some(ref new_passphrase),
+			) => {
+				match self.gpgme.change_passphrase(
+					key_id.to_string(),
+					passphrase,
+					new_passphrase,
+				) {
+					Ok(()) => {
+						self.record_history(
+							&[key_id.to_string()],
+							"passphrase changed",
+						);
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("passphrase changed ({key_id})"),
+						))
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("passphrase error: {e}"),
+					)),
+				}
+			}
+			Command::PresetPassphrase(key_id, Some(passphrase)) => {
+				self.prompt.set_output(
+					match self.gpgme.preset_passphrase(key_id, &passphrase) {
+						Ok(count) => (
+							OutputType::Success,
+							format!("passphrase preset for {count} subkey(s)"),
+						),
+						Err(e) => {
+							(OutputType::Failure, format!("agent error: {e}"))
+						}
+					},
+				);
+			}
+			Command::PreviewImport(files) => {
+				self.prompt.set_output(if files.is_empty() {
+					(
+						OutputType::Failure,
+						String::from("import error: no files given"),
+					)
+				} else {
+					match self.gpgme.get_import_preview(files) {
+						Ok(stats) => {
+							(OutputType::Success, format!("dry run: {stats}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("import error: {e}"))
+						}
+					}
+				});
+			}
+			Command::EnterSandbox => {
+				match self.gpgme.enter_sandbox().and_then(|_| self.refresh()) {
+					Ok(()) => self.prompt.set_output((
+						OutputType::Success,
+						String::from(
+							"sandbox: switched to a temporary keyring",
+						),
+					)),
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("sandbox error: {e}"),
+					)),
+				}
+			}
+			Command::ExitSandbox => {
+				match self.gpgme.exit_sandbox().and_then(|_| self.refresh()) {
+					Ok(()) => self.prompt.set_output((
+						OutputType::Success,
+						String::from("sandbox: switched back to the keyring"),
+					)),
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("sandbox error: {e}"),
+					)),
+				}
+			}
+			Command::PromoteKey(ref key_id) => {
+				let result = match key_id.clone().or_else(|| {
+					self.keys_table.selected().map(|key| key.get_id())
+				}) {
+					Some(key_id) => self
+						.gpgme
+						.promote_key(key_id.to_string())
+						.map(|key_count| (key_id, key_count)),
+					None => Err(anyhow!("invalid selection")),
+				};
+				self.prompt.set_output(match result {
+					Ok((key_id, key_count)) => {
+						self.record_history(
+							&[key_id],
+							"promoted from the sandbox",
+						);
+						(
+							OutputType::Success,
+							format!("promote: {key_count} key(s) imported"),
+						)
+					}
+					Err(e) => {
+						(OutputType::Failure, format!("promote error: {e}"))
+					}
+				});
+			}
+			Command::ExportKeys(key_type, ref patterns, false)
+				if self.state.rename_output =>
+			{
+				let path =
+					self.gpgme.get_output_file(key_type, patterns.to_vec())?;
+				self.prompt.clear();
+				self.prompt.text = format!(
+					"{COMMAND_PREFIX}export-to {key_type} {} {}",
+					if patterns.is_empty() {
+						String::from("*")
+					} else {
+						patterns.join(",")
+					},
+					path.to_string_lossy()
+				);
+			}
+			Command::ExportKeys(key_type, ref patterns, false) => {
+				let mut event = OperationEvent::new("export")
+					.with_fingerprints(self.get_fingerprints(patterns));
+				self.prompt.set_output(
+					match self
+						.gpgme
+						.export_keys(key_type, Some(patterns.to_vec()))
+					{
+						Ok(path) => {
+							self.record_history(
+								patterns,
+								&format!("exported ({key_type}) to {path}"),
+							);
+							event.paths.push(path.to_string());
+							(OutputType::Success, format!("export: {path}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					},
+				);
+				self.emit_event(event);
+			}
+			Command::ExportKeysTo(key_type, ref patterns, ref path) => {
+				let mut event = OperationEvent::new("export")
+					.with_fingerprints(self.get_fingerprints(patterns));
+				self.prompt.set_output(
+					match self.gpgme.export_keys_to(
+						key_type,
+						Some(patterns.to_vec()),
+						Path::new(path),
+					) {
+						Ok(path) => {
+							self.record_history(
+								patterns,
+								&format!("exported ({key_type}) to {path}"),
+							);
+							event.paths.push(path.to_string());
+							(OutputType::Success, format!("export: {path}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					},
+				);
+				self.emit_event(event);
+			}
+			Command::ExportSshKey(ref key_id) => {
+				self.prompt.set_output(
+					match self.gpgme.export_ssh_key(key_id.to_string()) {
+						Ok(path) => {
+							self.record_history(
+								&[key_id.to_string()],
+								&format!("exported (ssh) to {path}"),
+							);
+							(OutputType::Success, format!("export: {path}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					},
+				);
+			}
+			Command::ExportWithSigners(ref key_id) => {
+				self.prompt.set_output(
+					match self.gpgme.export_with_signers(key_id.to_string()) {
+						Ok((path, signers)) => {
+							self.record_history(
+								&[key_id.to_string()],
+								&format!(
+									"exported ({signers} signer(s)) to {path}"
+								),
+							);
+							(
+								OutputType::Success,
+								format!("export: {path} ({signers} signer(s))"),
+							)
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					},
+				);
+			}
+			Command::ServeKey(ref key_id) => {
+				match self.gpgme.get_armored_key(key_id.to_string()).and_then(
+					|key| {
+						server::serve(
+							key,
+							format!("{key_id}.asc"),
+							self.state.serve_address,
+							Duration::from_secs(SERVE_TIMEOUT),
+						)
+					},
+				) {
+					Ok(url) => {
+						if let Ok(code) = QrCode::new(url.as_bytes()) {
+							self.qr_code = Some((
+								url.to_string(),
+								code.render::<Dense1x2>().build(),
+							));
+						}
+						self.serving_until = Some(
+							Instant::now() + Duration::from_secs(SERVE_TIMEOUT),
+						);
+						self.record_history(
+							&[key_id.to_string()],
+							&format!("served at {url}"),
+						);
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("serving at {url} for {SERVE_TIMEOUT}s"),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("serve error: {e}"),
+					)),
+				}
+			}
+			Command::FetchKeys(url) => {
+				match server::fetch(&url).and_then(|data| {
+					Ok((self.gpgme.get_fingerprints(&data)?, data))
+				}) {
+					Ok((fingerprints, _)) if fingerprints.is_empty() => {
+						self.prompt.set_output((
+							OutputType::Failure,
+							String::from("fetch error: no keys found"),
+						))
+					}
+					Ok((fingerprints, data)) => self.prompt.set_command(
+						Command::ImportFetched(url, fingerprints, data),
+					),
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("fetch error: {e}"),
+					)),
+				}
+			}
+			Command::ImportFetched(ref url, ref fingerprints, ref data) => {
+				let event = OperationEvent::new("import")
+					.with_fingerprints(fingerprints.to_vec());
+				match self.gpgme.import_data(data) {
+					Ok(key_count) => {
+						self.record_history(
+							fingerprints,
+							&format!("imported from {url}"),
+						);
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("{key_count} key(s) imported"),
+						))
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("import error: {e}"),
+					)),
+				}
+				self.emit_event(event);
+			}
+			Command::ExportPaperKey(ref key_id, raw) => {
+				self.prompt.set_output(
+					match self.gpgme.export_paper_key(key_id.to_string(), raw) {
+						Ok(path) => {
+							self.record_history(
+								&[key_id.to_string()],
+								&format!("exported as paper backup to {path}"),
+							);
+							(OutputType::Success, format!("export: {path}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					},
+				);
+			}
+			Command::ExportJson(ref path) => {
+				self.prompt.set_output(
+					match self
+						.gpgme
+						.export_keys_json(path.as_ref().map(Path::new))
+					{
+						Ok(path) => {
+							(OutputType::Success, format!("export: {path}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					},
+				);
+			}
+			Command::CleanExports(days, secure) => {
+				match self.gpgme.get_stale_exports(days) {
+					Ok(files) if files.is_empty() => self.prompt.set_output((
+						OutputType::Success,
+						format!("no exports older than {days} days"),
+					)),
+					Ok(files) => {
+						self.prompt.set_command(Command::RemoveExports(
+							files
+								.iter()
+								.map(|file| file.to_string_lossy().into_owned())
+								.collect(),
+							secure,
+						))
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("clean error: {e}"),
+					)),
+				}
+			}
+			Command::RemoveExports(ref files, secure) => {
+				let files = files.iter().map(PathBuf::from).collect::<Vec<_>>();
+				self.prompt.set_output(
+					match self.gpgme.remove_exports(&files, secure) {
+						Ok(()) => (
+							OutputType::Success,
+							format!("{} export file(s) deleted", files.len()),
+						),
+						Err(e) => {
+							(OutputType::Failure, format!("clean error: {e}"))
+						}
+					},
+				);
+			}
+			Command::ExportEach(key_set, ref dir) => {
+				let key_ids = match key_set {
+					KeySet::Pinned => self
+						.keys
+						.get(&KeyType::Public)
+						.map(|keys| {
+							keys.iter()
+								.filter(|key| self.pinned_keys.contains(key))
+								.map(|key| key.get_id())
+								.collect()
+						})
+						.unwrap_or_default(),
+					KeySet::Marked => self.keys_table.marked.clone(),
+				};
+				let mut event = OperationEvent::new("export")
+					.with_fingerprints(self.get_fingerprints(&key_ids));
+				self.prompt.set_output(if key_ids.is_empty() {
+					(OutputType::Failure, format!("no {key_set} keys"))
+				} else {
+					match self.gpgme.export_keys_separately(
+						key_ids,
+						dir.as_ref().map(Path::new),
+					) {
+						Ok(paths) => {
+							for path in &paths {
+								log::info!(target: "export", "{path}");
+							}
+							let output = (
+								OutputType::Success,
+								format!("export: {} file(s)", paths.len()),
+							);
+							event.paths = paths;
+							output
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("export error: {e}"))
+						}
+					}
+				});
+				self.emit_event(event);
+			}
+			Command::EncryptFiles(ref files) => {
+				// files are kept until the recipients are picked
+				let files = if files.is_empty() {
+					self.pending_files.clone()
+				} else {
+					files.to_vec()
+				};
+				if files.is_empty() {
+					self.prompt.set_output((
+						OutputType::Failure,
+						String::from("usage: encrypt <file>"),
+					));
+				} else if self.recipients.is_empty() {
+					self.run_command(Command::ListKeys(KeyType::Public))?;
+					self.run_command(Command::SwitchMode(Mode::Pick))?;
+					self.prompt.set_output((
+						OutputType::Warning,
+						format!(
+							"pick the recipients and run :encrypt \
+							 for encrypting {} file(s)",
+							files.len()
+						),
+					));
+					self.pending_files = files;
+				} else {
+					self.pending_files.clear();
+					let recipients = self.recipients.clone();
+					let mut event = OperationEvent::new("encrypt")
+						.with_fingerprints(self.get_fingerprints(&recipients));
+					self.prompt.set_output(
+						match files
+							.iter()
+							.map(|file| {
+								self.gpgme.encrypt_file(
+									recipients.clone(),
+									Path::new(file),
+								)
+							})
+							.collect::<Result<Vec<String>>>()
+						{
+							Ok(paths) => {
+								let output = (
+									OutputType::Success,
+									format!("encrypt: {}", paths.join(", ")),
+								);
+								event.paths = paths;
+								output
+							}
+							Err(e) => (
+								OutputType::Failure,
+								format!("encryption error: {e}"),
+							),
+						},
+					);
+					self.emit_event(event);
+				}
+			}
+			Command::EncryptSymmetric(ref files, _) if files.is_empty() => {
+				self.prompt.set_output((
+					OutputType::Failure,
+					String::from("usage: encrypt-symmetric <file>"),
+				));
+			}
+			Command::EncryptSymmetric(files, None) => {
+				self.prompt.enable_passphrase_input(
+					"passphrase for encryption",
+					Command::EncryptSymmetric(files, None),
+				);
+			}
+			Command::EncryptSymmetric(ref files, Some(ref passphrase)) => {
+				let mut event = OperationEvent::new("encrypt");
+				self.prompt.set_output(if passphrase.is_empty() {
+					(
+						OutputType::Failure,
+						String::from("encryption error: empty passphrase"),
+					)
+				} else {
+					match files
+						.iter()
+						.map(|file| {
+							self.gpgme.encrypt_file_symmetric(
+								Path::new(file),
+								passphrase,
+							)
+						})
+						.collect::<Result<Vec<String>>>()
+					{
+						Ok(paths) => {
+							let output = (
+								OutputType::Success,
+								format!("encrypt: {}", paths.join(", ")),
+							);
+							event.paths = paths;
+							output
+						}
+						Err(e) => (
+							OutputType::Failure,
+							format!("encryption error: {e}"),
+						),
+					}
+				});
+				self.emit_event(event);
+			}
+			Command::EncryptClipboard(ref recipient) => {
+				self.prompt.set_output(
+					match self
+						.clipboard
+						.get_contents()
+						.and_then(|contents| {
+							self.gpgme.encrypt_text(
+								vec![recipient.to_string()],
+								&contents,
+							)
+						})
+						.and_then(|output| self.clipboard.set_contents(output))
+					{
+						Ok(_) => (
+							OutputType::Success,
+							format!("clipboard encrypted for {recipient}"),
+						),
+						Err(e) => (
+							OutputType::Failure,
+							format!("clipboard error: {e}"),
+						),
+					},
+				);
+			}
+			Command::DecryptClipboard => {
+				self.prompt.set_output(
+					match self
+						.clipboard
+						.get_contents()
+						.and_then(|contents| self.gpgme.decrypt_text(&contents))
+						.and_then(|output| self.clipboard.set_contents(output))
+					{
+						Ok(_) => (
+							OutputType::Success,
+							String::from("clipboard decrypted"),
+						),
+						Err(e) => (
+							OutputType::Failure,
+							format!("clipboard error: {e}"),
+						),
+					},
+				);
+			}
+			Command::SignFile(ref file, signature_type) => {
+				self.prompt.set_output(
+					match self.gpgme.sign_file(Path::new(file), signature_type)
+					{
+						Ok(path) => {
+							(OutputType::Success, format!("sign: {path}"))
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("signing error: {e}"))
+						}
+					},
+				);
+			}
+			Command::ConvertArmor(file, armor, false)
+				if self
+					.gpgme
+					.get_armor_output(Path::new(&file), armor)
+					.exists() =>
+			{
+				self.run_command(Command::Confirm(Box::new(
+					Command::ConvertArmor(file, armor, true),
+				)))?;
+			}
+			Command::ConvertArmor(ref file, armor, _) => {
+				self.prompt.set_output(
+					match self.gpgme.convert_armor(Path::new(file), armor) {
+						Ok(path) => (
+							OutputType::Success,
+							format!("{}: {path}", command.get_name()),
+						),
+						Err(e) => (
+							OutputType::Failure,
+							format!("conversion error: {e}"),
+						),
+					},
+				);
+			}
+			Command::VerifyFile(ref file, ref signature) => {
+				match self.gpgme.verify_file(
+					Path::new(file),
+					signature.as_deref().map(Path::new),
+				) {
+					Ok(verifications) => {
+						let good = verifications
+							.iter()
+							.filter(|v| v.is_good())
+							.count();
+						self.prompt.set_output((
+							if good == verifications.len() {
+								OutputType::Success
+							} else {
+								OutputType::Warning
+							},
+							format!(
+								"verify: {good}/{} good signature(s)",
+								verifications.len()
+							),
+						));
+						self.verifications = Some(verifications);
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("verification error: {e}"),
+					)),
+				}
+			}
+			Command::VerifyRelease(ref file, ref signature) => {
+				match self.gpgme.verify_release(Path::new(file), signature) {
+					Ok(verifications) => {
+						let verification = verifications
+							.iter()
+							.find(|v| v.is_trusted())
+							.or_else(|| {
+								verifications.iter().find(|v| v.is_good())
+							})
+							.unwrap_or(&verifications[0]);
+						self.prompt.set_output((
+							if verification.is_trusted() {
+								OutputType::Success
+							} else if verification.is_good() {
+								OutputType::Warning
+							} else {
+								OutputType::Failure
+							},
+							verification.get_verdict(),
+						));
+						self.verifications = Some(verifications);
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("verification error: {e}"),
+					)),
+				}
+			}
+			Command::DeleteKey(key_type, ref key_id) => {
+				let event = OperationEvent::new("delete").with_fingerprints(
+					self.get_fingerprints(&[key_id.to_string()]),
+				);
+				match self.gpgme.delete_key(key_type, key_id.to_string()) {
+					Ok(_) => {
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("deleted: {key_id} ({key_type})"),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("delete error: {e}"),
+					)),
+				}
+				self.emit_event(event);
+			}
+			Command::SetExpiration(ref key_id, ref expiry, ref subkeys) => {
+				let event = OperationEvent::new("expire").with_fingerprints(
+					self.get_fingerprints(&[key_id.to_string()]),
+				);
+				match keygen::parse_expiry(expiry).and_then(|duration| {
+					self.gpgme.set_expiration(key_id, duration, subkeys)
+				}) {
+					Ok(()) => {
+						self.record_history(
+							&[key_id.to_string()],
+							&format!("expiration time changed to {expiry}"),
+						);
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("expire: {expiry} ({key_id})"),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("expire error: {e}"),
+					)),
+				}
+				self.emit_event(event);
+			}
+			Command::RevokeKey(ref key_id, ref reason) => {
+				match self.gpgme.generate_revocation(key_id, reason.as_deref())
+				{
+					Ok(path) => {
+						self.prompt.set_command(Command::ApplyRevocation(
+							key_id.to_string(),
+							path.to_string_lossy().to_string(),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("revoke error: {e}"),
+					)),
+				}
+			}
+			Command::ApplyRevocation(ref key_id, ref path) => {
+				let event = OperationEvent::new("revoke")
+					.with_fingerprints(
+						self.get_fingerprints(&[key_id.to_string()]),
+					)
+					.with_paths(vec![path.to_string()]);
+				match self.gpgme.import_keys(vec![path.to_string()], true) {
+					Ok(_) => {
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Warning,
+							format!(
+								"revoked: {0} (publish it via :send {0})",
+								key_id
+							),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("revoke error: {e}"),
+					)),
+				}
+				self.emit_event(event);
+			}
+			Command::SendKey(key_id, keyserver) => {
+				let event = OperationEvent::new("send").with_fingerprints(
+					self.get_fingerprints(&[key_id.to_string()]),
+				);
+				self.prompt.set_output(
+					match self.gpgme.send_key(key_id, keyserver.as_deref()) {
+						Ok(key_id) => {
+							self.record_history(
+								&[key_id.to_string()],
+								&format!(
+									"sent to {}",
+									keyserver
+										.as_deref()
+										.unwrap_or("the keyserver")
+								),
+							);
+							(
+								OutputType::Success,
+								format!(
+									"key sent to the keyserver: 0x{key_id}"
+								),
+							)
+						}
+						Err(e) => {
+							(OutputType::Failure, format!("send error: {e}"))
+						}
+					},
+				);
+				self.emit_event(event);
+			}
+			Command::EditKey(ref key_id) => {
+				let mut commands = vec![Command::None];
+				commands.extend(EDIT_COMMANDS.iter().map(|(command, _)| {
+					match KeyEdit::from_str(command) {
+						Ok(edit) => {
+							Command::RunKeyEdit(key_id.to_string(), edit)
+						}
+						Err(_) if *command == "expire" => Command::Set(
+							String::from("prompt"),
+							format!(":expire {key_id} "),
+						),
+						Err(_) => Command::Set(
+							String::from("prompt"),
+							format!(":edit {key_id} {command} "),
+						),
+					}
+				}));
+				self.options = StatefulList::with_items(
+					commands.into_iter().map(ListEntry::Item).collect(),
+				);
+				self.options.state.select(Some(1));
+				show_options = true;
+			}
+			Command::RunKeyEdit(key_id, KeyEdit::Passwd) => {
+				self.run_command(Command::ChangePassphrase(
+					key_id, None, None,
+				))?;
+			}
+			Command::RunKeyEdit(
+				key_id,
+				KeyEdit::KeyToCard(index, slot, false),
+			) if self.is_card_slot_used(slot) => {
+				self.run_command(Command::Confirm(Box::new(
+					Command::RunKeyEdit(
+						key_id,
+						KeyEdit::KeyToCard(index, slot, true),
+					),
+				)))?;
+			}
+			Command::RunKeyEdit(ref key_id, ref edit) => {
+				match self.gpgme.edit_key(key_id, edit) {
+					Ok(()) => {
+						self.record_history(
+							&[key_id.to_string()],
+							&format!("edited ({edit})"),
+						);
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("edit: {edit} ({key_id})"),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("edit error: {e}"),
+					)),
+				}
+			}
+			Command::SetOwnerTrust(level) => {
+				let key_id = match self.tab {
+					Tab::Keys(_) => {
+						self.keys_table.selected().map(|key| key.get_id())
+					}
+					_ => None,
+				};
+				match (key_id, level) {
+					(Some(key_id), Some(level)) => self.run_command(
+						Command::RunKeyEdit(key_id, KeyEdit::Trust(level)),
+					)?,
+					(Some(_), None) => {
+						let mut levels = vec![Command::None];
+						levels.extend(
+							(1..=TRUST_LEVELS.len()).map(|level| {
+								Command::SetOwnerTrust(Some(level))
+							}),
+						);
+						self.options = StatefulList::with_items(
+							levels.into_iter().map(ListEntry::Item).collect(),
+						);
+						self.options.state.select(Some(1));
+						show_options = true;
+					}
+					(None, _) => self.prompt.set_output((
+						OutputType::Failure,
+						String::from("invalid selection"),
+					)),
+				}
+			}
+			Command::AddUserId(_)
+			| Command::RevokeUserId(_)
+			| Command::SetPrimaryUserId(_) => {
+				let key_id = match self.tab {
+					Tab::Keys(_) => {
+						self.keys_table.selected().map(|key| key.get_id())
+					}
+					_ => None,
+				};
+				let result = match (key_id, &command) {
+					(Some(key_id), Command::AddUserId(user_id)) => self
+						.gpgme
+						.add_user_id(&key_id, user_id)
+						.map(|_| user_id.to_string()),
+					(Some(key_id), Command::RevokeUserId(index)) => {
+						self.gpgme.revoke_user_id(&key_id, *index)
+					}
+					(Some(key_id), Command::SetPrimaryUserId(index)) => {
+						self.gpgme.set_primary_user_id(&key_id, *index)
+					}
+					_ => Err(anyhow!("invalid selection")),
+				};
+				match result {
+					Ok(user_id) => {
+						if let Some(key) = self.keys_table.selected() {
+							self.record_history(
+								&[key.get_id()],
+								&format!("{}: {user_id}", command.get_name()),
+							);
+						}
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("{}: {user_id}", command.get_name()),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("{} error: {e}", command.get_name()),
+					)),
+				}
+			}
+			Command::SelectKeyserver(key_id) => {
+				if self.gpgme.config.keyservers.is_empty() {
+					self.prompt.set_output((
+						OutputType::Failure,
+						String::from("no keyservers are configured"),
+					));
+				} else {
+					let mut keyservers = vec![Command::None];
+					keyservers.extend(self.gpgme.config.keyservers.iter().map(
+						|keyserver| {
+							Command::Confirm(Box::new(Command::SendKey(
+								key_id.to_string(),
+								Some(keyserver.to_string()),
+							)))
+						},
+					));
+					self.options = StatefulList::with_items(
+						keyservers.into_iter().map(ListEntry::Item).collect(),
+					);
+					self.options.state.select(Some(1));
+					show_options = true;
+				}
+			}
+			Command::ImportKeys(ref keys, true, ref keyserver) => {
+				if !self.pending_receives.is_empty() {
+					self.prompt.set_output((
+						OutputType::Warning,
+						String::from("keys are already being received"),
+					));
+				} else if let Some(sender) = self.event_sender.clone() {
+					self.pending_receives.clone_from(keys);
+					self.received_keys.clear();
+					self.prompt.set_output((
+						OutputType::Action,
+						format!(
+							"(1/{}) receiving {}...",
+							keys.len(),
+							keys.first().cloned().unwrap_or_default()
+						),
+					));
+					let config = self.gpgme.config.clone();
+					let (keys, keyserver) = (keys.to_vec(), keyserver.clone());
+					thread::spawn(move || {
+						let mut context =
+							GpgContext::new(config).map_err(|e| e.to_string());
+						for key_id in keys {
+							let result = context
+								.as_mut()
+								.map_err(|e| e.clone())
+								.and_then(|context| {
+									context
+										.receive_key(
+											&key_id,
+											keyserver.as_deref(),
+										)
+										.map_err(|e| e.to_string())
+								});
+							if let Err(e) =
+								sender.send(Event::KeyReceived(key_id, result))
+							{
+								log::error!(
+									target: "gpg",
+									"failed to send the key: {e}"
+								);
+								break;
+							}
+						}
+					});
+				} else {
+					let results = keys
+						.iter()
+						.map(|key_id| {
+							let result = self
+								.gpgme
+								.receive_key(key_id, keyserver.as_deref())
+								.map_err(|e| e.to_string());
+							(key_id.to_string(), result)
+						})
+						.collect();
+					self.finish_receiving_keys(results)?;
+				}
+			}
+			Command::LocateSigners(ref key_id) => {
+				let signers = self
+					.keys
+					.values()
+					.flatten()
+					.find(|key| key.get_id().eq_ignore_ascii_case(key_id))
+					.map(|key| key.get_unknown_signers())
+					.unwrap_or_default();
+				if signers.is_empty() {
+					self.prompt.set_output((
+						OutputType::Warning,
+						String::from("no unknown signers found"),
+					));
+				} else {
+					self.run_command(Command::ImportKeys(signers, true, None))?;
+				}
+			}
+			Command::SearchKeyserver(ref query) => {
+				match self.gpgme.search_keyserver(query) {
+					Ok(keys) => {
+						self.key_info_cache.clear();
+						self.store_keys_table();
+						self.prompt.set_output((
+							OutputType::Success,
+							format!(
+								"found {} key(s) on the keyserver",
+								keys.len()
+							),
+						));
+						self.keyserver_results = keys;
+						self.keys_table = StatefulTable::new(
+							self.keyserver_results.clone(),
+							self.state.table_state.clone(),
+						);
+						self.filter_keys();
+						self.tab = Tab::KeyserverResults;
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("search error: {e}"),
+					)),
+				}
+			}
+			Command::ResolveEmail(ref email) => {
+				let local_key = self
+					.keys
+					.get(&KeyType::Public)
+					.and_then(|keys| {
+						Self::get_best_match(
+							keys.iter().filter(|key| key.has_email(email)),
+						)
+					})
+					.cloned();
+				if let Some(key) = local_key {
+					self.run_command(Command::GoToKey(key.get_id()))?;
+					self.prompt.set_output((
+						OutputType::Success,
+						format!("{email}: {} (keyring)", key.get_id()),
+					));
+				} else if self.failed_lookups.get(email).map_or(false, |time| {
+					time.elapsed() < LOOKUP_CACHE_DURATION
+				}) {
+					self.prompt.set_output((
+						OutputType::Failure,
+						format!("{email}: no key found (cached)"),
+					));
+				} else if let Some(sender) = self.event_sender.clone() {
+					if !self.pending_lookups.contains(email) {
+						self.pending_lookups.push(email.to_string());
+						let config = self.gpgme.config.clone();
+						let email = email.to_string();
+						thread::spawn(move || {
+							let result = GpgContext::new(config).ok().and_then(
+								|mut context| {
+									App::locate_remote_key(&mut context, &email)
+								},
+							);
+							if let Err(e) =
+								sender.send(Event::KeyLocated(email, result))
+							{
+								log::error!(
+									target: "gpg",
+									"failed to send the key: {e}"
+								);
+							}
+						});
+					}
+					self.prompt.set_output((
+						OutputType::Action,
+						format!("{email}: looking up..."),
+					));
+				} else {
+					let result = Self::locate_remote_key(self.gpgme, email);
+					self.finish_locating_key(email.to_string(), result);
 				}
-				show_options = true;
 			}
-			Command::ListKeys(key_type) => {
-				if let Tab::Keys(previous_key_type) = self.tab {
-					self.keys_table_states.insert(
-						previous_key_type,
-						self.keys_table.state.clone(),
-					);
-					self.keys.insert(
-						previous_key_type,
-						self.keys_table.default_items.clone(),
-					);
+			Command::InspectKey(ref key_id) => {
+				match self
+					.keys_table
+					.items
+					.iter()
+					.chain(self.keys.values().flatten())
+					.find(|key| key.has_subkey(key_id))
+					.cloned()
+				{
+					Some(key) => {
+						self.key_detail =
+							StatefulList::with_items(key.get_detail_entries(
+								self.state.get_date_format(),
+							));
+						self.key_detail.state.select(Some(0));
+						self.key_photo = self.get_photo(&key);
+						self.store_keys_table();
+						self.tab = Tab::KeyDetail(match self.tab {
+							Tab::Keys(key_type) | Tab::KeyDetail(key_type) => {
+								key_type
+							}
+							_ => KeyType::Public,
+						});
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("key details: {}", key.get_id()),
+						));
+					}
+					None => self.prompt.set_output((
+						OutputType::Failure,
+						format!("key not found: {key_id}"),
+					)),
 				}
-				self.keys_table = StatefulTable::with_items(
-					self.keys
-						.get(&key_type)
-						.unwrap_or_else(|| {
-							panic!("failed to get {key_type} keys")
+			}
+			Command::ShowCard => match self.gpgme.get_card_status() {
+				Ok(status) => {
+					self.store_keys_table();
+					self.card_slots =
+						StatefulList::with_items(status.slots.clone());
+					self.card_slots.state.select(Some(0));
+					self.prompt.set_output((
+						OutputType::Success,
+						format!("card: {}", status.serial),
+					));
+					self.card = Some(status);
+					self.tab = Tab::Card;
+				}
+				Err(e) => self.prompt.set_output((
+					OutputType::Failure,
+					format!("card error: {e}"),
+				)),
+			},
+			Command::GoToKey(ref key_id) => {
+				match [KeyType::Secret, KeyType::Public].into_iter().find(
+					|key_type| {
+						self.keys.get(key_type).map_or(false, |keys| {
+							keys.iter().any(|key| key.has_subkey(key_id))
 						})
-						.to_vec(),
-				);
-				if let Some(state) = self.keys_table_states.get(&key_type) {
-					self.keys_table.state = state.clone();
+					},
+				) {
+					Some(key_type) => {
+						self.run_command(Command::ListKeys(key_type))?;
+						self.keys_table.state.tui.select(
+							self.keys_table
+								.items
+								.iter()
+								.position(|key| key.has_subkey(key_id)),
+						);
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("key: {key_id} ({key_type})"),
+						));
+					}
+					None => self.prompt.set_output((
+						OutputType::Failure,
+						format!("key not found in the keyring: {key_id}"),
+					)),
 				}
-				self.tab = Tab::Keys(key_type);
 			}
-			Command::ImportKeys(_, false) | Command::ImportClipboard => {
-				let mut keys = Vec::new();
-				let mut import_error = String::from("no files given");
-				if let Command::ImportKeys(ref key_files, _) = command {
-					keys.clone_from(key_files);
-				} else if let Some(clipboard) = self.clipboard.as_mut() {
-					match clipboard.get_contents() {
-						Ok(content) => {
-							keys = vec![content];
-						}
-						Err(e) => {
-							import_error = e.to_string();
+			Command::DiffKey(ref key_id) => {
+				match self.gpgme.get_keyserver_diff(key_id.to_string()) {
+					Ok(diff) if diff.is_empty() => self.prompt.set_output((
+						OutputType::Success,
+						String::from("key is up-to-date with the keyserver"),
+					)),
+					Ok(diff) => {
+						for line in &diff {
+							log::info!(target: "diff", "{key_id}: {line}");
 						}
+						self.state.show_logs = true;
+						self.prompt.set_command(Command::ImportKeys(
+							vec![key_id.to_string()],
+							true,
+							None,
+						));
 					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("diff error: {e}"),
+					)),
 				}
-				if keys.is_empty() {
-					self.prompt.set_output((
+			}
+			Command::ShowHistory => match self.keys_table.selected() {
+				Some(key) => {
+					let entries =
+						self.history.get_entries(&key.get_fingerprint());
+					if entries.is_empty() {
+						self.prompt.set_output((
+							OutputType::Warning,
+							format!("no history for {}", key.get_id()),
+						));
+					} else {
+						self.key_history = Some((key.get_id(), entries));
+					}
+				}
+				None => self.prompt.set_output((
+					OutputType::Failure,
+					String::from("invalid selection"),
+				)),
+			},
+			Command::ShowQrCode(selection) => {
+				let content = match selection {
+					// secret keys are never shown as QR code
+					Selection::Key => match self.keys_table.selected() {
+						Some(key) => self.gpgme.get_armored_keys(
+							KeyType::Public,
+							Some(vec![key.get_id()]),
+						),
+						None => Err(anyhow!("invalid selection")),
+					},
+					_ => self.get_selection_content(selection),
+				};
+				match content.and_then(|content| {
+					QrCode::new(content.trim().as_bytes())
+						.map_err(|e| anyhow!("{e}"))
+				}) {
+					Ok(code) => {
+						self.qr_code = Some((
+							selection.to_string(),
+							code.render::<Dense1x2>().build(),
+						));
+					}
+					Err(e) => self.prompt.set_output((
 						OutputType::Failure,
-						format!("import error: {import_error}"),
-					))
-				} else {
-					match self
-						.gpgme
-						.import_keys(keys, command != Command::ImportClipboard)
-					{
-						Ok(key_count) => {
-							self.refresh()?;
+						format!("qr error: {e}"),
+					)),
+				}
+			}
+			Command::CompareFingerprint(ref fingerprint) => {
+				match self.keys_table.selected() {
+					Some(key) => {
+						let groups = handler::compare_fingerprints(
+							&key.get_fingerprint(),
+							fingerprint,
+						);
+						let mismatches = groups
+							.iter()
+							.filter(|(expected, given)| expected != given)
+							.count();
+						if mismatches == 0 {
 							self.prompt.set_output((
 								OutputType::Success,
-								format!("{key_count} key(s) imported"),
-							))
+								format!(
+									"fingerprint matches: {}",
+									key.get_id()
+								),
+							));
+						} else {
+							self.prompt.set_output((
+								OutputType::Failure,
+								format!(
+									"fingerprint mismatch: \
+									{mismatches} group(s) differ"
+								),
+							));
+							self.fingerprint_comparison = Some(groups);
 						}
-						Err(e) => self.prompt.set_output((
-							OutputType::Failure,
-							format!("import error: {e}"),
-						)),
 					}
+					None => self.prompt.set_output((
+						OutputType::Failure,
+						String::from("no key is selected"),
+					)),
 				}
 			}
-			Command::ExportKeys(key_type, ref patterns, false) => {
-				self.prompt.set_output(
-					match self
-						.gpgme
-						.export_keys(key_type, Some(patterns.to_vec()))
-					{
-						Ok(path) => {
-							(OutputType::Success, format!("export: {path}"))
-						}
-						Err(e) => {
-							(OutputType::Failure, format!("export error: {e}"))
+			Command::ShowExpiring(days) => {
+				let expiring_keys =
+					self.get_expiring_keys(KeyType::Public, days);
+				if expiring_keys.is_empty() {
+					self.prompt.set_output((
+						OutputType::Success,
+						format!("no keys expiring within {days} days"),
+					));
+				} else {
+					self.expiring_keys = Some((days, expiring_keys));
+				}
+			}
+			Command::GenerateReport => {
+				self.prompt.set_output(match self.gpgme.generate_report() {
+					Ok(path) => {
+						(OutputType::Success, format!("report: {path}"))
+					}
+					Err(e) => {
+						(OutputType::Failure, format!("report error: {e}"))
+					}
+				});
+			}
+			Command::EditPreferences(ref key_id, false) => {
+				match OsCommand::new("gpg")
+					.arg("--homedir")
+					.arg(self.gpgme.config.home_dir.as_os_str())
+					.arg("--batch")
+					.arg("--edit-key")
+					.arg(key_id)
+					.arg("showpref")
+					.arg("quit")
+					.output()
+				{
+					Ok(output) if output.status.success() => {
+						self.key_preferences = Some((
+							key_id.to_string(),
+							handler::parse_preferences(
+								&String::from_utf8_lossy(&output.stdout),
+							),
+						));
+					}
+					Ok(output) => self.prompt.set_output((
+						OutputType::Failure,
+						format!(
+							"preferences error: {}",
+							String::from_utf8_lossy(&output.stderr).trim()
+						),
+					)),
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("execution error: {e}"),
+					)),
+				}
+			}
+			Command::GenerateKeyWithProfile(ref name, ref user_id) => {
+				match self
+					.state
+					.keygen_profiles
+					.get(name)
+					.ok_or_else(|| anyhow!("unknown profile: {name}"))
+					.and_then(KeyGenWizard::from_profile)
+				{
+					Ok(mut wizard) => {
+						wizard.check_passphrase = self.state.passphrase_check;
+						if let Some(user_id) = user_id {
+							wizard.user_id = user_id.to_string();
+							wizard.next_step();
 						}
-					},
-				);
+						self.wizard = Some(wizard);
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("generate error: {e}"),
+					)),
+				}
 			}
-			Command::DeleteKey(key_type, ref key_id) => {
-				match self.gpgme.delete_key(key_type, key_id.to_string()) {
-					Ok(_) => {
+			Command::GenerateKey => {
+				self.wizard = Some(KeyGenWizard {
+					check_passphrase: self.state.passphrase_check,
+					..KeyGenWizard::default()
+				});
+			}
+			Command::CreateKey(ref params) => {
+				self.wizard = None;
+				let mut event = OperationEvent::new("generate");
+				match self.gpgme.generate_key(params) {
+					Ok(fingerprint) => {
 						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("generate: {fingerprint}"),
+						));
+						event.fingerprints.push(fingerprint);
 					}
 					Err(e) => self.prompt.set_output((
 						OutputType::Failure,
-						format!("delete error: {e}"),
+						format!("generate error: {e}"),
 					)),
 				}
+				self.emit_event(event);
 			}
-			Command::SendKey(key_id) => {
-				self.prompt.set_output(match self.gpgme.send_key(key_id) {
-					Ok(key_id) => (
-						OutputType::Success,
-						format!("key sent to the keyserver: 0x{key_id}"),
-					),
-					Err(e) => (OutputType::Failure, format!("send error: {e}")),
-				});
+			Command::AddSubkey(ref key_id) => {
+				self.wizard =
+					Some(KeyGenWizard::for_subkey(key_id.to_string()));
+			}
+			Command::CreateSubkey(ref key_id, ref params) => {
+				self.wizard = None;
+				match self.gpgme.add_subkey(key_id, params) {
+					Ok(fingerprint) => {
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("addsubkey: {fingerprint} ({key_id})"),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("addsubkey error: {e}"),
+					)),
+				}
 			}
-			Command::GenerateKey
-			| Command::RefreshKeys
-			| Command::EditKey(_)
-			| Command::SignKey(_)
-			| Command::ImportKeys(_, true)
+			Command::RefreshKeys
+			| Command::EditPreferences(_, true)
+			| Command::SignKey(_, _)
 			| Command::ExportKeys(_, _, true) => {
 				let mut success_msg = None;
 				let mut os_command = OsCommand::new("gpg");
@@ -455,19 +3316,22 @@ impl<'a> App<'a> {
 				if self.gpgme.config.armor {
 					os_command.arg("--armor");
 				}
-				if let Some(default_key) = &self.gpgme.config.default_key {
-					os_command.arg("--default-key").arg(default_key);
+				let signer = match command {
+					Command::SignKey(_, Some(ref signer)) => Some(signer),
+					_ => self.gpgme.config.default_key.as_ref(),
+				};
+				if let Some(signer) = signer {
+					os_command.arg("--default-key").arg(signer);
 				}
 				let os_command = match command {
-					Command::EditKey(ref key) => {
-						os_command.arg("--edit-key").arg(key)
-					}
-					Command::SignKey(ref key) => {
+					Command::EditPreferences(ref key, _) => os_command
+						.arg("--edit-key")
+						.arg(key)
+						.arg(format!("setpref {MODERN_PREFERENCES}"))
+						.arg("save"),
+					Command::SignKey(ref key, _) => {
 						os_command.arg("--sign-key").arg(key)
 					}
-					Command::ImportKeys(ref keys, _) => {
-						os_command.arg("--receive-keys").args(keys)
-					}
 					Command::ExportKeys(key_type, ref keys, true) => {
 						let path = self
 							.gpgme
@@ -480,13 +3344,33 @@ impl<'a> App<'a> {
 							.arg("--export-secret-subkeys")
 							.args(keys)
 					}
-					Command::RefreshKeys => os_command.arg("--refresh-keys"),
-					_ => os_command.arg("--full-gen-key"),
+					_ => os_command.arg("--refresh-keys"),
 				};
 				log::debug!(target: "cmd", "running OS command: {os_command:?}");
 				match os_command.spawn() {
 					Ok(mut child) => {
-						child.wait()?;
+						if child.wait()?.success() {
+							match command {
+								Command::SignKey(ref key, ref signer) => self
+									.record_history(
+										&[key.to_string()],
+										&match signer {
+											Some(signer) => {
+												format!("signed with {signer}")
+											}
+											None => String::from("signed"),
+										},
+									),
+								Command::EditPreferences(ref key, _) => self
+									.record_history(
+										&[key.to_string()],
+										"preferences updated",
+									),
+								Command::ExportKeys(_, ref keys, true) => self
+									.record_history(keys, "exported subkeys"),
+								_ => {}
+							}
+						}
 						self.refresh()?;
 						if let Some(msg) = success_msg {
 							self.prompt.set_output((OutputType::Success, msg))
@@ -523,6 +3407,12 @@ impl<'a> App<'a> {
 					}
 				}
 			}
+			Command::ToggleCollapse => {
+				if let Some(key) = self.keys_table.selected() {
+					let key_id = key.get_id();
+					self.keys_table.state.toggle_collapse(key_id);
+				}
+			}
 			Command::ToggleTableSize => {
 				self.keys_table.state.minimize_threshold = 0;
 				self.keys_table.state.size = self.keys_table.state.size.next();
@@ -538,25 +3428,47 @@ impl<'a> App<'a> {
 			Command::Scroll(direction, false) => match direction {
 				ScrollDirection::Down(_) => {
 					if self.state.show_options {
-						self.options.next();
+						self.options.next_item();
 						show_options = true;
 					} else if Tab::Help == self.tab {
 						self.key_bindings.next();
+					} else if Tab::Card == self.tab {
+						self.card_slots.next();
+					} else if matches!(self.tab, Tab::KeyDetail(_)) {
+						self.key_detail.next();
 					} else {
 						self.keys_table.next();
 					}
 				}
 				ScrollDirection::Up(_) => {
 					if self.state.show_options {
-						self.options.previous();
+						self.options.previous_item();
 						show_options = true;
 					} else if Tab::Help == self.tab {
 						self.key_bindings.previous();
+					} else if Tab::Card == self.tab {
+						self.card_slots.previous();
+					} else if matches!(self.tab, Tab::KeyDetail(_)) {
+						self.key_detail.previous();
 					} else {
 						self.keys_table.previous();
 					}
 				}
-				ScrollDirection::Top => {
+				ScrollDirection::PageUp
+					if self.keys_table.state.paging
+						&& !self.state.show_options
+						&& Tab::Help != self.tab =>
+				{
+					self.keys_table.previous_page();
+				}
+				ScrollDirection::PageDown
+					if self.keys_table.state.paging
+						&& !self.state.show_options
+						&& Tab::Help != self.tab =>
+				{
+					self.keys_table.next_page();
+				}
+				ScrollDirection::Top | ScrollDirection::PageUp => {
 					if self.state.show_options {
 						self.options.state.select(Some(0));
 						show_options = true;
@@ -566,7 +3478,7 @@ impl<'a> App<'a> {
 						self.keys_table.state.tui.select(Some(0));
 					}
 				}
-				ScrollDirection::Bottom => {
+				ScrollDirection::Bottom | ScrollDirection::PageDown => {
 					if self.state.show_options {
 						self.options.state.select(Some(
 							self.options
@@ -596,12 +3508,27 @@ impl<'a> App<'a> {
 				self.keys_table.scroll_row(direction);
 			}
 			Command::Set(option, value) => {
+				if option != *"prompt" {
+					self.key_info_cache.clear();
+				}
 				if option == *"prompt"
 					&& (value.starts_with(COMMAND_PREFIX)
 						| value.starts_with(SEARCH_PREFIX))
 				{
 					self.prompt.clear();
 					self.prompt.text = value;
+				} else if option == "trust_model" || option == "trust-model" {
+					if TRUST_MODELS.contains(&value.as_str()) {
+						self.prompt.set_command(Command::SetTrustModel(value));
+					} else {
+						self.prompt.set_output((
+							OutputType::Failure,
+							format!(
+								"usage: set trust_model <{}>",
+								TRUST_MODELS.join("/")
+							),
+						));
+					}
 				} else {
 					self.prompt.set_output(match option.as_str() {
 						"output" => {
@@ -649,16 +3576,176 @@ impl<'a> App<'a> {
 								(
 									OutputType::Failure,
 									String::from(
-										"usage: set armor <true/false>",
+										"usage: set armor <true/false>",
+									),
+								)
+							}
+						}
+						"validate" => {
+							if let Ok(value) = FromStr::from_str(&value) {
+								self.gpgme.config.validate = value;
+								self.gpgme.apply_config();
+								self.load_keys()?;
+								if let Tab::Keys(key_type) = self.tab {
+									self.keys_table = StatefulTable::new(
+										self.keys
+											.get(&key_type)
+											.cloned()
+											.unwrap_or_default(),
+										self.state.table_state.clone(),
+									);
+									self.filter_keys();
+								}
+								(
+									OutputType::Success,
+									format!("validate: {value}"),
+								)
+							} else {
+								(
+									OutputType::Failure,
+									String::from(
+										"usage: set validate <true/false>",
+									),
+								)
+							}
+						}
+						"rename_output" => {
+							if let Ok(value) = FromStr::from_str(&value) {
+								self.state.rename_output = value;
+								(
+									OutputType::Success,
+									format!("rename output: {value}"),
+								)
+							} else {
+								(
+									OutputType::Failure,
+									String::from(
+										"usage: set rename_output <true/false>",
+									),
+								)
+							}
+						}
+						"detail_view" => {
+							if let Ok(value) = FromStr::from_str(&value) {
+								self.state.detail_view = value;
+								(
+									OutputType::Success,
+									format!("detail view: {value}"),
+								)
+							} else {
+								(
+									OutputType::Failure,
+									String::from(
+										"usage: set detail_view <true/false>",
+									),
+								)
+							}
+						}
+						"relative_dates" => {
+							if let Ok(value) = FromStr::from_str(&value) {
+								self.state.relative_dates = value;
+								(
+									OutputType::Success,
+									format!("relative dates: {value}"),
+								)
+							} else {
+								(
+									OutputType::Failure,
+									String::from(
+										"usage: set relative_dates <true/false>",
+									),
+								)
+							}
+						}
+						"clipboard" => {
+							match ClipboardType::from_str(&value, true)
+								.and_then(|clipboard_type| {
+									clipboard::init(
+										clipboard_type,
+										&self.state.copy_command,
+										&self.state.paste_command,
+									)
+									.map_err(|e| e.to_string())
+								}) {
+								Ok(clipboard) => {
+									self.clipboard = clipboard;
+									(
+										OutputType::Success,
+										format!(
+											"clipboard: {}",
+											self.clipboard.get_type()
+										),
+									)
+								}
+								Err(e) => (
+									OutputType::Failure,
+									format!(
+										"usage: set clipboard <backend> ({e})"
+									),
+								),
+							}
+						}
+						"date_format" => {
+							if handler::is_valid_date_format(&value) {
+								self.state.date_format = value;
+								(
+									OutputType::Success,
+									format!(
+										"date format: {}",
+										self.state.date_format
+									),
+								)
+							} else {
+								(
+									OutputType::Failure,
+									String::from(
+										"usage: set date_format <format/relative>",
 									),
 								)
 							}
 						}
+						"fingerprint_format" => {
+							match FingerprintFormat::from_str(&value) {
+								Ok(format) => {
+									self.state.fingerprint_format = format;
+									(
+										OutputType::Success,
+										format!("fingerprint format: {format}"),
+									)
+								}
+								Err(e) => (
+									OutputType::Failure,
+									format!(
+										"usage: set fingerprint_format \
+										<options> ({e})"
+									),
+								),
+							}
+						}
 						"signer" => {
 							self.gpgme.config.default_key =
 								Some(value.to_string());
 							(OutputType::Success, format!("signer: {value}"))
 						}
+						"keyserver" => {
+							self.gpgme.config.keyserver =
+								if value == "default" || value.is_empty() {
+									None
+								} else {
+									Some(value.to_string())
+								};
+							(
+								OutputType::Success,
+								format!(
+									"keyserver: {}",
+									self.gpgme
+										.config
+										.keyserver
+										.as_deref()
+										.unwrap_or("default")
+								),
+							)
+						}
 						"minimize" => {
 							self.keys_table.state.minimize_threshold =
 								value.parse().unwrap_or_default();
@@ -705,6 +3792,23 @@ impl<'a> App<'a> {
 								)
 							}
 						}
+						"paging" => {
+							if let Ok(value) = FromStr::from_str(&value) {
+								self.keys_table.state.paging = value;
+								self.state.table_state.paging = value;
+								(
+									OutputType::Success,
+									format!("paging: {value}"),
+								)
+							} else {
+								(
+									OutputType::Failure,
+									String::from(
+										"usage: set paging <true/false>",
+									),
+								)
+							}
+						}
 						"margin" => {
 							self.keys_table_margin =
 								value.parse().unwrap_or_default();
@@ -776,6 +3880,47 @@ impl<'a> App<'a> {
 						OutputType::Success,
 						format!("armor: {}", self.gpgme.config.armor),
 					),
+					"validate" => (
+						OutputType::Success,
+						format!("validate: {}", self.gpgme.config.validate),
+					),
+					"rename_output" => (
+						OutputType::Success,
+						format!("rename output: {}", self.state.rename_output),
+					),
+					"detail_view" => (
+						OutputType::Success,
+						format!("detail view: {}", self.state.detail_view),
+					),
+					"trust_model" | "trust-model" => (
+						OutputType::Success,
+						format!(
+							"trust model: {}",
+							self.gpgme.config.trust_model
+						),
+					),
+					"relative_dates" => (
+						OutputType::Success,
+						format!(
+							"relative dates: {}",
+							self.state.relative_dates
+						),
+					),
+					"date_format" => (
+						OutputType::Success,
+						format!("date format: {}", self.state.date_format),
+					),
+					"fingerprint_format" => (
+						OutputType::Success,
+						format!(
+							"fingerprint format: {}",
+							self.state.fingerprint_format
+						),
+					),
+					"clipboard" => (
+						OutputType::Success,
+						format!("clipboard: {}", self.clipboard.get_type()),
+					),
 					"signer" => (
 						OutputType::Success,
 						match &self.gpgme.config.default_key {
@@ -783,6 +3928,17 @@ impl<'a> App<'a> {
 							None => String::from("signer key is not specified"),
 						},
 					),
+					"keyserver" => (
+						OutputType::Success,
+						format!(
+							"keyserver: {}",
+							self.gpgme
+								.config
+								.keyserver
+								.as_deref()
+								.unwrap_or("default")
+						),
+					),
 					"minimize" => (
 						OutputType::Success,
 						format!(
@@ -813,6 +3969,10 @@ impl<'a> App<'a> {
 							)
 						}
 					}
+					"paging" => (
+						OutputType::Success,
+						format!("paging: {}", self.keys_table.state.paging),
+					),
 					"margin" => (
 						OutputType::Success,
 						format!("table margin: {}", self.keys_table_margin),
@@ -844,56 +4004,36 @@ impl<'a> App<'a> {
 				})
 			}
 			Command::SwitchMode(mode) => {
-				if !(mode == Mode::Copy && self.keys_table.items.is_empty()) {
+				if !((mode == Mode::Copy || mode == Mode::Pick)
+					&& self.keys_table.items.is_empty())
+				{
 					self.mode = mode;
+					if mode != Mode::Visual {
+						self.keys_table.clear_marks();
+					}
 					self.prompt
 						.set_output((OutputType::Action, mode.to_string()))
 				}
 			}
 			Command::Copy(copy_type) => {
-				let selected_key =
-					&self.keys_table.selected().expect("invalid selection");
-				let content = match copy_type {
-					Selection::Row1 => Ok(selected_key
-						.get_subkey_info(
-							self.gpgme.config.default_key.as_deref(),
-							self.keys_table.state.size != TableSize::Normal,
-						)
-						.join("\n")),
-					Selection::Row2 => Ok(selected_key
-						.get_user_info(
-							self.keys_table.state.size == TableSize::Minimized,
-						)
-						.join("\n")),
-					Selection::Key => {
-						match self.gpgme.get_exported_keys(
-							match self.tab {
-								Tab::Keys(key_type) => key_type,
-								_ => KeyType::Public,
-							},
-							Some(vec![selected_key.get_id()]),
-						) {
-							Ok(key) => str::from_utf8(&key)
-								.map(|v| v.to_string())
-								.map_err(AnyhowError::from),
-							Err(e) => Err(e),
-						}
-					}
-					Selection::KeyId => Ok(selected_key.get_id()),
-					Selection::KeyFingerprint => {
-						Ok(selected_key.get_fingerprint())
-					}
-					Selection::UserId => Ok(selected_key.get_user_id()),
-				};
-				match content {
+				match self.get_selection_content(copy_type) {
 					Ok(content) => {
 						if self.state.select.is_some() {
-							self.state.exit_message = Some(content);
-							self.run_command(Command::Quit)?;
-						} else if let Some(clipboard) = self.clipboard.as_mut()
-						{
+							self.state.exit_message =
+								Some(match &self.state.select_format {
+									SelectFormat::Plain => content,
+									SelectFormat::Json => self
+										.get_selection_json(copy_type, content)
+										.to_string(),
+									SelectFormat::Template(template) => self
+										.get_selection_template(
+											copy_type, content, template,
+										),
+								});
+							self.run_command(Command::ForceQuit)?;
+						} else {
 							self.prompt.set_output(
-								match clipboard.set_contents(content) {
+								match self.clipboard.set_contents(content) {
 									Ok(_) => (
 										OutputType::Success,
 										format!(
@@ -906,11 +4046,6 @@ impl<'a> App<'a> {
 									),
 								},
 							);
-						} else {
-							self.prompt.set_output((
-								OutputType::Failure,
-								String::from("clipboard not available"),
-							));
 						}
 					}
 					Err(e) => {
@@ -922,34 +4057,172 @@ impl<'a> App<'a> {
 				}
 				self.mode = Mode::Normal;
 			}
-			Command::Paste => {
-				if let Some(clipboard) = self.clipboard.as_mut() {
-					match clipboard.get_contents() {
-						Ok(content) => {
-							self.prompt.clear();
-							self.prompt.text = format!(":{content}");
-						}
-						Err(e) => {
-							self.prompt.set_output((
-								OutputType::Failure,
-								format!("clipboard error: {e}"),
-							));
-						}
+			Command::ToggleRecipient(key_id) => {
+				if let Some(index) =
+					self.recipients.iter().position(|v| v == &key_id)
+				{
+					self.recipients.remove(index);
+					self.prompt.set_output((
+						OutputType::Success,
+						format!(
+							"recipient removed: {key_id} ({} selected)",
+							self.recipients.len()
+						),
+					));
+				} else {
+					let usable = self
+						.keys
+						.get(&KeyType::Public)
+						.and_then(|keys| {
+							keys.iter().find(|key| key.get_id() == key_id)
+						})
+						.map(|key| key.has_capability(KeyCapability::Encrypt));
+					self.recipients.push(key_id.to_string());
+					self.prompt.set_output(match usable {
+						Some(true) => (
+							OutputType::Success,
+							format!(
+								"recipient added: {key_id} ({} selected)",
+								self.recipients.len()
+							),
+						),
+						Some(false) => (
+							OutputType::Warning,
+							format!("no usable encryption subkey: {key_id}"),
+						),
+						None => (
+							OutputType::Warning,
+							format!(
+								"recipient is not in the keyring: {key_id}"
+							),
+						),
+					});
+				}
+			}
+			Command::Mark(key_id) => {
+				match key_id {
+					Some(key_id) => self.keys_table.toggle_mark(key_id),
+					None => {
+						let key_ids = self
+							.keys_table
+							.get_mark_range()
+							.iter()
+							.map(|key| key.get_id())
+							.collect();
+						self.keys_table.mark(key_ids);
 					}
+				}
+				self.prompt.set_output((
+					OutputType::Success,
+					format!("{} key(s) marked", self.keys_table.marked.len()),
+				));
+			}
+			Command::TogglePin(key_id) => {
+				let key = match key_id {
+					Some(key_id) => self
+						.keys
+						.values()
+						.flatten()
+						.find(|key| key.has_subkey(&key_id))
+						.cloned(),
+					None => self.keys_table.selected().cloned(),
+				};
+				match key {
+					Some(key) => {
+						self.prompt.set_output(
+							match self.pinned_keys.toggle(&key) {
+								Ok(true) => (
+									OutputType::Success,
+									format!("key pinned: {}", key.get_id()),
+								),
+								Ok(false) => (
+									OutputType::Success,
+									format!("key unpinned: {}", key.get_id()),
+								),
+								Err(e) => (
+									OutputType::Failure,
+									format!("pin error: {e}"),
+								),
+							},
+						);
+						self.sort_pinned_keys();
+					}
+					None => self.prompt.set_output((
+						OutputType::Failure,
+						String::from("invalid selection"),
+					)),
+				}
+			}
+			Command::NextPinned => {
+				let selected =
+					self.keys_table.state.tui.selected().unwrap_or_default();
+				let len = self.keys_table.items.len();
+				match (1..=len).map(|i| (selected + i) % len).find(|i| {
+					self.pinned_keys.contains(&self.keys_table.items[*i])
+				}) {
+					Some(index) => {
+						self.keys_table.state.tui.select(Some(index))
+					}
+					None => self.prompt.set_output((
+						OutputType::Warning,
+						String::from("no pinned keys"),
+					)),
+				}
+			}
+			Command::Batch(commands) => {
+				let name = commands
+					.first()
+					.map(|command| command.get_name().to_string())
+					.unwrap_or_else(|| String::from("batch"));
+				let total = commands.len();
+				let mut failed = 0;
+				for command in commands {
+					self.run_command(command)?;
+					if self.prompt.output_type == OutputType::Failure {
+						failed += 1;
+					}
+				}
+				self.keys_table.clear_marks();
+				self.prompt.set_output(if failed == 0 {
+					(OutputType::Success, format!("{name}: {total} key(s)"))
 				} else {
+					(
+						OutputType::Failure,
+						format!(
+							"{name}: {failed}/{total} failed (see the logs)"
+						),
+					)
+				});
+			}
+			Command::Paste => match self.clipboard.get_contents() {
+				Ok(content) => {
+					self.prompt.clear();
+					self.prompt.text = format!(":{content}");
+				}
+				Err(e) => {
 					self.prompt.set_output((
 						OutputType::Failure,
-						String::from("clipboard not available"),
+						format!("clipboard error: {e}"),
 					));
 				}
-			}
+			},
 			Command::EnableInput => self.prompt.enable_command_input(),
 			Command::Search(query) => {
 				self.prompt.text = format!("/{}", query.unwrap_or_default());
 				self.prompt.enable_search();
-				self.keys_table
-					.items
-					.clone_from(&self.keys_table.default_items);
+				self.filter_keys();
+			}
+			Command::Filter(filter) => {
+				self.state.key_filter = filter;
+				self.keys_table.reset_state();
+				self.filter_keys();
+				self.prompt.set_output((
+					OutputType::Success,
+					match filter {
+						Some(filter) => format!("filter: {filter}"),
+						None => String::from("filter: none"),
+					},
+				));
 			}
 			Command::NextTab => {
 				self.run_command(self.tab.next().get_command())?
@@ -960,13 +4233,97 @@ impl<'a> App<'a> {
 			Command::Logs => {
 				self.state.show_logs = !self.state.show_logs;
 			}
+			Command::Lock => {
+				self.state.lock();
+				self.prompt.clear();
+			}
+			Command::SetTrustModel(ref trust_model) => {
+				match self
+					.gpgme
+					.config
+					.set_trust_model(trust_model)
+					.and_then(|_| GpgContext::new(self.gpgme.config.clone()))
+				{
+					Ok(context) => {
+						*self.gpgme = context;
+						self.refresh()?;
+						self.prompt.set_output((
+							OutputType::Success,
+							format!("trust model: {trust_model}"),
+						));
+					}
+					Err(e) => self.prompt.set_output((
+						OutputType::Failure,
+						format!("failed to set the trust model: {e}"),
+					)),
+				}
+			}
+			Command::SyncConfig => {
+				self.prompt.set_output(match self.sync_config() {
+					Ok(changes) if changes.is_empty() => (
+						OutputType::Success,
+						String::from("configuration is up to date"),
+					),
+					Ok(changes) => (
+						OutputType::Success,
+						format!("configuration synced: {}", changes.join(", ")),
+					),
+					Err(e) => (OutputType::Failure, format!("sync error: {e}")),
+				});
+			}
+			Command::ReloadConfig => {
+				self.prompt.set_output(match self.reload_config() {
+					Ok(config_file) => (
+						OutputType::Success,
+						format!("configuration reloaded: {config_file}"),
+					),
+					Err(e) => {
+						(OutputType::Failure, format!("reload error: {e}"))
+					}
+				});
+			}
+			Command::WriteConfig => {
+				self.prompt.set_output(match self.write_config() {
+					Ok(config_file) => (
+						OutputType::Success,
+						format!("configuration written: {config_file}"),
+					),
+					Err(e) => {
+						(OutputType::Failure, format!("write error: {e}"))
+					}
+				});
+			}
 			Command::LoggerEvent(event) => {
 				self.state.logger_state.transition(event.0);
 			}
 			Command::Refresh => self.refresh()?,
-			Command::Quit => self.state.running = false,
+			Command::Quit => {
+				if self.get_background_operations().is_empty() {
+					self.state.running = false;
+				} else {
+					self.prompt.set_command(Command::ForceQuit);
+				}
+			}
+			Command::ForceQuit => self.state.running = false,
 			Command::Confirm(_) | Command::None => {}
 		}
+		if update_rows {
+			self.keys_table_rows = None;
+		}
+		// echo the command that is selected from the options menu
+		if let Some(syntax) = syntax.filter(|_| !show_options) {
+			match self.prompt.output_type {
+				_ if self.prompt.command.is_some() => {}
+				OutputType::Failure | OutputType::Warning => {}
+				_ if self.prompt.text.is_empty() => self
+					.prompt
+					.set_output((OutputType::Action, format!("ran {syntax}"))),
+				_ => {
+					self.prompt.text =
+						format!("{} (ran {syntax})", self.prompt.text)
+				}
+			}
+		}
 		self.state.show_options = show_options;
 		Ok(())
 	}
@@ -975,11 +4332,12 @@ impl<'a> App<'a> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::gpg::config::GpgConfig;
+	use crate::app::wizard::WizardStep;
+	use crate::config::KeyGenProfile;
+	use crate::gpg::key::KeyFilter;
+	use crate::widget::secret::SecretInput;
 	use pretty_assertions::assert_eq;
 	use std::convert::TryInto;
-	use std::thread;
-	use std::time::Duration;
 	#[test]
 	fn test_app_launcher() -> Result<()> {
 		let args = Args::default();
@@ -988,10 +4346,73 @@ mod tests {
 		let mut app = App::new(&mut context, &args)?;
 		app.run_command(Command::Refresh)?;
 
+		let temp_dir = tempfile::tempdir()?;
+		let events_file = temp_dir.path().join("events.jsonl");
+		app.json_events =
+			Some(EventStream::new(&events_file.to_string_lossy())?);
+		app.run_command(Command::ImportKeys(Vec::new(), false, None))?;
+		app.run_command(Command::ShowHelp)?;
+		let events = fs::read_to_string(&events_file)?;
+		assert_eq!(1, events.lines().count());
+		assert!(events.contains(r#""event":"import""#));
+		assert!(events.contains(r#""status":"failure""#));
+		app.json_events = None;
+
 		app.run_command(Command::ShowHelp)?;
 		assert_eq!(Tab::Help, app.tab);
 		app.run_command(Command::ShowOptions)?;
 		assert!(app.state.show_options);
+		app.run_command(Command::EditKey(String::from("0x0")))?;
+		assert!(app.state.show_options);
+		assert_eq!(EDIT_COMMANDS.len() + 1, app.options.items.len());
+		assert!(app.options.items.contains(&ListEntry::Item(
+			Command::RunKeyEdit(String::from("0x0"), KeyEdit::Passwd)
+		)));
+		assert!(app.options.items.contains(&ListEntry::Item(Command::Set(
+			String::from("prompt"),
+			String::from(":expire 0x0 ")
+		))));
+		app.run_command(Command::RunKeyEdit(
+			String::from("0x0"),
+			KeyEdit::Passwd,
+		))?;
+		assert_eq!(
+			Some((
+				Command::ChangePassphrase(String::from("0x0"), None, None),
+				SecretInput::default()
+			)),
+			app.prompt.passphrase_input
+		);
+		app.prompt.clear();
+		app.run_command(Command::EncryptSymmetric(Vec::new(), None))?;
+		assert_eq!("usage: encrypt-symmetric <file>", app.prompt.text);
+		app.run_command(Command::ChangeStyle(app.state.style))?;
+		assert_eq!(
+			format!(
+				"style: {} (ran :style {})",
+				app.state.style, app.state.style
+			),
+			app.prompt.text
+		);
+		app.run_command(Command::ChangeStyle(app.state.style))?;
+		assert_eq!(format!("style: {}", app.state.style), app.prompt.text);
+		app.run_command(Command::EditKey(String::from("0x0")))?;
+
+		app.run_command(Command::SetOwnerTrust(None))?;
+		assert_eq!(OutputType::Failure, app.prompt.output_type);
+		app.run_command(Command::RevokeUserId(1))?;
+		assert_eq!("revuid error: invalid selection", app.prompt.text);
+		app.run_command(Command::ApplyRevocation(
+			String::from("0x0"),
+			String::from("/nonexistent/rev.asc"),
+		))?;
+		assert_eq!(OutputType::Failure, app.prompt.output_type);
+		app.run_command(Command::SetExpiration(
+			String::from("0x0"),
+			String::from("never"),
+			Vec::new(),
+		))?;
+		assert_eq!(OutputType::Failure, app.prompt.output_type);
 
 		app.run_command(Command::ChangeStyle(Style::Colored))?;
 		assert_eq!(Style::Colored, app.state.style);
@@ -1005,6 +4426,58 @@ mod tests {
 		app.run_command(Command::ToggleDetail(true))?;
 		assert_eq!(detail, app.state.detail_level);
 
+		if cfg!(feature = "gpg-tests") {
+			let fingerprint = app.keys_table.items[0].get_fingerprint();
+			app.run_command(Command::ShowHelp)?;
+			app.run_command(Command::GoToKey(fingerprint.to_string()))?;
+			assert!(matches!(app.tab, Tab::Keys(_)));
+			assert_eq!(
+				Some(fingerprint),
+				app.keys_table.selected().map(|key| key.get_fingerprint())
+			);
+			let key_id = app.keys_table.items[0].get_id();
+			app.run_command(Command::ToggleCollapse)?;
+			assert!(app.keys_table.state.is_collapsed(&key_id));
+			app.run_command(Command::ToggleCollapse)?;
+			assert!(!app.keys_table.state.is_collapsed(&key_id));
+			app.pinned_keys = PinnedKeys::default();
+			app.run_command(Command::TogglePin(Some(fingerprint.to_string())))?;
+			assert!(app.prompt.text.starts_with("key pinned"));
+			app.run_command(Command::NextPinned)?;
+			assert_eq!(Some(0), app.keys_table.state.tui.selected());
+			app.run_command(Command::TogglePin(None))?;
+			assert!(app.pinned_keys.fingerprints.is_empty());
+		}
+		app.run_command(Command::GoToKey(String::from("0x0")))?;
+		assert_eq!(OutputType::Failure, app.prompt.output_type);
+		app.run_command(Command::PromoteKey(Some(String::from("0x0"))))?;
+		assert_eq!("promote error: sandbox is not in use", app.prompt.text);
+		app.run_command(Command::ListKeys(KeyType::Public))?;
+		if app.keys_table.selected().is_some() {
+			app.run_command(Command::SetOwnerTrust(None))?;
+			assert_eq!(TRUST_LEVELS.len() + 1, app.options.items.len());
+		}
+		if let Some(key) = app.keys_table.selected() {
+			assert_eq!(
+				format!(":goto {}", key.get_fingerprint()),
+				app.expand_command(":goto %fpr")
+			);
+			assert!(app
+				.get_selection_json(Selection::KeyId, key.get_id())
+				.to_string()
+				.contains(&format!("\"value\":\"{}\"", key.get_id())));
+			assert_eq!(
+				format!("{}\t{}", key.get_id(), key.get_fingerprint()),
+				app.get_selection_template(
+					Selection::KeyId,
+					key.get_id(),
+					"{value}\t{fingerprint}"
+				)
+			);
+		}
+		app.run_command(Command::ShowHelp)?;
+		assert_eq!(":send %id", app.expand_command(":send %id"));
+
 		let prompt_text = format!("{COMMAND_PREFIX}test");
 		app.run_command(Command::Set(
 			String::from("prompt"),
@@ -1028,9 +4501,18 @@ mod tests {
 			("output", "/tmp"),
 			("mode", "normal"),
 			("armor", "true"),
+			("validate", "true"),
+			("relative_dates", "true"),
+			("rename_output", "true"),
+			("detail_view", "true"),
+			("date_format", "%d.%m.%Y"),
+			("fingerprint_format", "spaced,0x"),
+			("clipboard", "none"),
 			("signer", "0x0"),
+			("keyserver", "hkps://keys.openpgp.org"),
 			("minimize", "10"),
 			("margin", "2"),
+			("paging", "true"),
 			("style", "plain"),
 			("color", "#123123"),
 		];
@@ -1048,10 +4530,57 @@ mod tests {
 					|| app.prompt.text.contains(value)
 			);
 		}
+		app.run_command(Command::ExportKeys(
+			KeyType::Public,
+			vec![String::from("0xa"), String::from("0xb")],
+			false,
+		))?;
+		assert!(app.prompt.text.starts_with(":export-to pub 0xa,0xb /tmp/"));
+		match Command::from_str(&app.prompt.text) {
+			Ok(Command::ExportKeysTo(KeyType::Public, patterns, path)) => {
+				assert_eq!(
+					vec![String::from("0xa"), String::from("0xb")],
+					patterns
+				);
+				assert!(path.starts_with("/tmp/"));
+			}
+			_ => panic!("invalid export command"),
+		}
+
+		let redirect_dir = tempfile::tempdir()?;
+		let path = redirect_dir.path().join("redirect.txt");
+		app.run_command(Command::Redirect(
+			Box::new(Command::Get(String::from("margin"))),
+			path.to_string_lossy().to_string(),
+		))?;
+		assert_eq!(OutputType::Success, app.prompt.output_type);
+		assert_eq!("table margin: 2\n", fs::read_to_string(&path)?);
+		let expiring_keys = app.get_expiring_keys(KeyType::Public, 36500);
+		app.run_command(Command::Redirect(
+			Box::new(Command::ShowExpiring(36500)),
+			path.to_string_lossy().to_string(),
+		))?;
+		assert!(app.expiring_keys.is_none());
+		let output = fs::read_to_string(&path)?;
+		if expiring_keys.is_empty() {
+			assert_eq!("no keys expiring within 36500 days\n", output);
+		} else {
+			assert_eq!(expiring_keys.len(), output.lines().count());
+		}
+		app.run_command(Command::Redirect(
+			Box::new(Command::ListKeys(KeyType::Public)),
+			String::from("test.txt"),
+		))?;
+		assert_eq!(OutputType::Warning, app.prompt.output_type);
 
 		app.mode = Mode::Normal;
 		app.run_command(Command::SwitchMode(Mode::Visual))?;
 		assert_eq!(Mode::Visual, app.mode);
+		app.run_command(Command::Mark(Some(String::from("0x0"))))?;
+		assert_eq!("1 key(s) marked", app.prompt.text);
+		app.run_command(Command::SwitchMode(Mode::Normal))?;
+		assert!(app.keys_table.marked.is_empty());
+		app.run_command(Command::SwitchMode(Mode::Visual))?;
 
 		app.run_command(Command::EnableInput)?;
 		assert!(app.prompt.is_command_input_enabled());
@@ -1060,6 +4589,221 @@ mod tests {
 		app.run_command(Command::Search(Some(String::from("x"))))?;
 		assert!(app.prompt.is_search_enabled());
 		assert_eq!(format!("{SEARCH_PREFIX}x"), app.prompt.text);
+		app.prompt
+			.set_output((OutputType::Action, String::from("-- select --")));
+		app.focus_keys("test")?;
+		assert_eq!(format!("{SEARCH_PREFIX}test"), app.prompt.text);
+		assert_eq!(Some(0), app.keys_table.state.tui.selected());
+		app.update_keys_table_rows();
+		assert_eq!(
+			Some(app.keys_table.items.len()),
+			app.keys_table_rows.as_ref().map(Vec::len)
+		);
+		app.focus_keys("no-such-key")?;
+		assert!(app.keys_table.items.is_empty());
+		app.update_keys_table_rows();
+		assert_eq!(Some(0), app.keys_table_rows.as_ref().map(Vec::len));
+		app.run_command(Command::Search(None))?;
+		assert_eq!(
+			app.keys_table.default_items.len(),
+			app.keys_table.items.len()
+		);
+		assert_eq!(
+			app.keys_table.default_items.len(),
+			app.key_info_cache.len()
+		);
+		app.run_command(Command::Set(
+			String::from("signer"),
+			String::from("0xDEF"),
+		))?;
+		app.gpgme.config.trust_model = String::from("-");
+		assert!(!app.sync_config()?.is_empty());
+		app.recipients.clear();
+		app.run_command(Command::EncryptFiles(vec![String::from("a.txt")]))?;
+		app.run_command(Command::EncryptFiles(Vec::new()))?;
+		assert_eq!(vec![String::from("a.txt")], app.pending_files);
+		assert_eq!(OutputType::Warning, app.prompt.output_type);
+		app.pending_files.clear();
+		app.run_command(Command::EncryptFiles(Vec::new()))?;
+		assert_eq!("usage: encrypt <file>", app.prompt.text);
+		app.run_command(Command::SwitchMode(Mode::Normal))?;
+		assert_eq!(Some("0xDEF"), app.gpgme.config.default_key.as_deref());
+		assert!(app.key_info_cache.is_empty());
+		assert!(app.keys_table_rows.is_none());
+		app.run_command(Command::Search(None))?;
+		assert!(!app.key_info_cache.is_empty());
+		app.run_command(Command::Refresh)?;
+		assert!(app.key_info_cache.is_empty());
+
+		app.run_command(Command::GenerateKey)?;
+		assert!(app.wizard.is_some());
+		app.run_command(Command::AddSubkey(String::from("0x0")))?;
+		assert_eq!(
+			Some(String::from("0x0")),
+			app.wizard.as_ref().and_then(|wizard| wizard.key_id.clone())
+		);
+		app.wizard = None;
+		app.run_command(Command::GenerateKeyWithProfile(
+			String::from("work"),
+			None,
+		))?;
+		assert!(app.wizard.is_none());
+		assert_eq!("generate error: unknown profile: work", app.prompt.text);
+		app.state.keygen_profiles.insert(
+			String::from("work"),
+			KeyGenProfile {
+				algo: Some(String::from("rsa4096")),
+				..KeyGenProfile::default()
+			},
+		);
+		app.run_command(Command::GenerateKeyWithProfile(
+			String::from("work"),
+			Some(String::from("Test <test@example.org>")),
+		))?;
+		assert_eq!(
+			Some((WizardStep::Expiry, String::from("4096"))),
+			app.wizard
+				.as_ref()
+				.map(|wizard| (wizard.step, wizard.size.clone()))
+		);
+		app.wizard = None;
+
+		app.run_command(Command::Filter(Some(KeyFilter::Capability(
+			KeyCapability::Encrypt,
+		))))?;
+		assert_eq!(
+			Some(KeyFilter::Capability(KeyCapability::Encrypt)),
+			app.state.key_filter
+		);
+		assert_eq!("filter: can-encrypt", app.prompt.text);
+		app.run_command(Command::Filter(Some(KeyFilter::State(
+			KeyState::Revoked,
+		))))?;
+		assert_eq!("filter: revoked", app.prompt.text);
+		app.run_command(Command::Filter(None))?;
+		app.failed_lookups
+			.insert(String::from("test@example.org"), Instant::now());
+		app.run_command(Command::ResolveEmail(String::from(
+			"test@example.org",
+		)))?;
+		assert_eq!("test@example.org: no key found (cached)", app.prompt.text);
+		app.pending_lookups.push(String::from("test@example.net"));
+		assert!(app.get_background_operations().contains(&"looking up keys"));
+		app.finish_locating_key(String::from("test@example.net"), None);
+		assert!(app.pending_lookups.is_empty());
+		assert!(app.failed_lookups.contains_key("test@example.net"));
+		assert_eq!("test@example.net: no key found", app.prompt.text);
+		app.pending_receives = vec![String::from("0x1"), String::from("0x2")];
+		assert!(app.get_background_operations().contains(&"receiving keys"));
+		app.finish_receiving_key(String::from("0x1"), Err(String::new()))?;
+		assert_eq!("(2/2) receiving 0x2...", app.prompt.text);
+		app.finish_receiving_key(String::from("0x3"), Ok(()))?;
+		assert_eq!(1, app.received_keys.len());
+		app.finish_receiving_key(String::from("0x2"), Err(String::new()))?;
+		assert!(app.pending_receives.is_empty());
+		assert_eq!("received 0/2 key(s)", app.prompt.text);
+		assert_eq!(Some(2), app.receive_results.take().as_ref().map(Vec::len));
+		app.run_command(Command::CompareFingerprint(String::from("0x0")))?;
+		assert_eq!(OutputType::Failure, app.prompt.output_type);
+		app.fingerprint_comparison = None;
+		let history_dir = tempfile::tempdir()?;
+		app.history = KeyHistory::new(Some(history_dir.path().join("history")));
+		app.run_command(Command::ShowHistory)?;
+		assert!(app.key_history.is_none());
+		app.prompt.clear();
+		app.key_preferences = Some((
+			String::from("0x0"),
+			vec![(String::from("Test"), vec![String::from("Cipher: AES256")])],
+		));
+		assert_eq!(
+			Some(String::from("Test\n  Cipher: AES256\n")),
+			app.take_command_output()
+		);
+		assert!(app.key_preferences.is_none());
+		app.record_history(&[String::from("0xF00")], "test");
+		assert!(app.history.get_entries("F00").is_empty());
+		app.run_command(Command::ShowQrCode(Selection::KeyId))?;
+		assert_eq!(
+			app.keys_table.selected().is_some(),
+			app.qr_code.take().is_some()
+		);
+		if let Some(key_id) = app.keys_table.selected().map(|v| v.get_id()) {
+			app.tab = Tab::Keys(KeyType::Secret);
+			app.run_command(Command::ShowQrCode(Selection::Key))?;
+			let public_key = app
+				.gpgme
+				.get_armored_keys(KeyType::Public, Some(vec![key_id]))?;
+			assert_eq!(
+				QrCode::new(public_key.trim().as_bytes())
+					.ok()
+					.map(|code| code.render::<Dense1x2>().build()),
+				app.qr_code.take().map(|(_, code)| code)
+			);
+			app.tab = Tab::Keys(KeyType::Public);
+		}
+		let expiring_keys = app.get_expiring_keys(KeyType::Public, 36500);
+		app.run_command(Command::ShowExpiring(36500))?;
+		if expiring_keys.is_empty() {
+			assert_eq!("no keys expiring within 36500 days", app.prompt.text);
+			assert!(app.expiring_keys.is_none());
+		} else {
+			assert_eq!(Some((36500, expiring_keys)), app.expiring_keys.take());
+		}
+		app.prompt.clear();
+		app.warn_expiring_keys();
+		assert_eq!(
+			app.get_expiring_keys(KeyType::Secret, EXPIRY_WARNING_DAYS)
+				.is_empty(),
+			app.prompt.text.is_empty()
+		);
+		app.prompt.clear();
+
+		app.run_command(Command::Set(
+			String::from("trust_model"),
+			String::from("tofu+pgp"),
+		))?;
+		assert_eq!(
+			Some(Command::SetTrustModel(String::from("tofu+pgp"))),
+			app.prompt.command
+		);
+		app.prompt.clear();
+		let armor_file = String::from("key.gpg");
+		let armor_output =
+			app.gpgme.get_armor_output(Path::new(&armor_file), true);
+		fs::create_dir_all(&app.gpgme.config.output_dir)?;
+		fs::write(&armor_output, "")?;
+		app.run_command(Command::ConvertArmor(
+			armor_file.clone(),
+			true,
+			false,
+		))?;
+		assert_eq!(
+			Some(Command::ConvertArmor(armor_file, true, true)),
+			app.prompt.command
+		);
+		app.prompt.clear();
+		fs::remove_file(armor_output)?;
+		assert_eq!(None, app.state.key_filter);
+		assert!(app
+			.run_batch_command(
+				Command::Set(
+					String::from("trust_model"),
+					String::from("tofu+pgp"),
+				),
+				false,
+			)
+			.is_err_and(|e| e.to_string().ends_with("(use --yes)")));
+		assert_eq!(None, app.prompt.command);
+		assert_eq!(
+			"ok",
+			app.run_batch_command(
+				Command::Confirm(Box::new(Command::ShowOutput(
+					OutputType::Success,
+					String::from("ok"),
+				))),
+				true,
+			)?
+		);
 
 		app.tab = Tab::Keys(KeyType::Public);
 		app.run_command(Command::NextTab)?;
@@ -1067,6 +4811,36 @@ mod tests {
 		app.run_command(Command::NextTab)?;
 		assert_eq!(Tab::Keys(KeyType::Public), app.tab);
 
+		app.run_command(Command::CleanExports(u64::MAX, false))?;
+		assert_eq!(
+			format!("no exports older than {} days", u64::MAX),
+			app.prompt.text
+		);
+
+		app.run_command(Command::ReloadConfig)?;
+		assert_eq!(
+			"reload error: configuration file is not found",
+			app.prompt.text
+		);
+		let config_dir = tempfile::tempdir()?;
+		let config_file = config_dir
+			.path()
+			.join(concat!(env!("CARGO_PKG_NAME"), ".toml"));
+		app.state.config_file =
+			Some(config_file.to_string_lossy().into_owned());
+		app.run_command(Command::Set(
+			String::from("margin"),
+			String::from("2"),
+		))?;
+		app.run_command(Command::WriteConfig)?;
+		assert_eq!(OutputType::Success, app.prompt.output_type);
+		app.keys_table_margin = 0;
+		app.cli_args.serve_address = Some("0.0.0.0".parse()?);
+		app.run_command(Command::ReloadConfig)?;
+		assert_eq!(OutputType::Success, app.prompt.output_type);
+		assert_eq!(2, app.keys_table_margin);
+		assert_eq!("0.0.0.0", app.state.serve_address.to_string());
+
 		app.tick();
 		app.run_command(Command::ShowOutput(
 			OutputType::Success,
@@ -1078,10 +4852,80 @@ mod tests {
 		));
 		app.tick();
 		assert_eq!("", app.prompt.text);
+		app.set_focused(false);
+		assert!(!app.state.focused);
+		app.finish_locating_key(String::from("test@example.com"), None);
+		assert_eq!("", app.prompt.text);
+		app.set_focused(true);
+		assert_eq!("test@example.com: no key found", app.prompt.text);
+		app.prompt.clear();
 
+		app.loading_keys = true;
+		assert_eq!(vec!["loading keys"], app.get_background_operations());
+		app.run_command(Command::Quit)?;
+		assert!(app.state.running);
+		assert_eq!(Some(Command::ForceQuit), app.prompt.command);
+		app.loading_keys = false;
 		app.run_command(Command::Quit)?;
 		assert!(!app.state.running);
 
 		app.run_command(Command::None)
 	}
+
+	/// Passes the events of the key loader to the application.
+	fn wait_for_keys(app: &mut App<'_>, receiver: &mpsc::Receiver<Event>) {
+		loop {
+			match receiver.recv_timeout(Duration::from_secs(60)) {
+				Ok(Event::Keys(load_id, key_type, keys)) => {
+					app.append_keys(load_id, key_type, keys)
+				}
+				Ok(Event::KeysLoaded(load_id, error)) => {
+					app.finish_loading_keys(load_id, error);
+					break;
+				}
+				Ok(_) => {}
+				Err(e) => panic!("failed to load keys: {e}"),
+			}
+		}
+	}
+
+	#[test]
+	fn test_app_key_loader() -> Result<()> {
+		let args = Args::default();
+		let config = GpgConfig::new(&args)?;
+		let mut context = GpgContext::new(config)?;
+		let (sender, receiver) = mpsc::channel();
+		let mut app = App::with_key_loader(&mut context, &args, sender)?;
+		wait_for_keys(&mut app, &receiver);
+		let key_count = app.keys_table.default_items.len();
+		assert!(key_count > 0);
+		app.keys_table.state.tui.select(Some(key_count - 1));
+		let selected = app.keys_table.selected().map(GpgKey::get_fingerprint);
+		app.refresh()?;
+		assert!(app.loading_keys);
+		assert_eq!(key_count, app.keys_table.default_items.len());
+		assert_eq!(key_count, app.keys[&KeyType::Public].len());
+		wait_for_keys(&mut app, &receiver);
+		assert!(!app.loading_keys);
+		assert_eq!(key_count, app.keys_table.default_items.len());
+		assert_eq!(
+			selected,
+			app.keys_table.selected().map(GpgKey::get_fingerprint)
+		);
+		let args = Args {
+			focus: Some(String::from("test")),
+			..Args::default()
+		};
+		let (sender, receiver) = mpsc::channel();
+		let mut app = App::with_key_loader(&mut context, &args, sender)?;
+		wait_for_keys(&mut app, &receiver);
+		assert_eq!(format!("{SEARCH_PREFIX}test"), app.prompt.text);
+		assert!(!app.keys_table.items.is_empty());
+		assert_eq!(Some(0), app.keys_table.state.tui.selected());
+		let visible_keys = app.keys_table.items.len();
+		app.load_keys()?;
+		wait_for_keys(&mut app, &receiver);
+		assert_eq!(visible_keys, app.keys_table.items.len());
+		Ok(())
+	}
 }
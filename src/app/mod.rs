@@ -38,3 +38,24 @@ pub mod banner;
 
 /// Utilities.
 pub mod util;
+
+/// Clipboard providers.
+pub mod clipboard;
+
+/// Key generation wizard.
+pub mod wizard;
+
+/// Built-in file browser.
+pub mod browser;
+
+/// Pinned keys.
+pub mod pin;
+
+/// Key history.
+pub mod history;
+
+/// Sharing keys over HTTP on the local network.
+pub mod server;
+
+/// JSON stream of the significant events.
+pub mod events;
@@ -12,6 +12,9 @@ pub enum Mode {
 	/// Copy mode.
 	/// (Makes it easier to copy values)
 	Copy,
+	/// Pick mode.
+	/// (Picks the recipients for encryption)
+	Pick,
 }
 
 impl Display for Mode {
@@ -27,6 +30,7 @@ impl FromStr for Mode {
 			"normal" | "n" => Ok(Self::Normal),
 			"visual" | "v" => Ok(Self::Visual),
 			"copy" | "c" => Ok(Self::Copy),
+			"pick" => Ok(Self::Pick),
 			_ => Err(()),
 		}
 	}
@@ -47,6 +51,9 @@ mod tests {
 		let mode = Mode::from_str("copy")?;
 		assert_eq!(Mode::Copy, mode);
 		assert_eq!(String::from("-- copy --"), mode.to_string());
+		let mode = Mode::from_str("pick")?;
+		assert_eq!(Mode::Pick, mode);
+		assert_eq!(String::from("-- pick --"), mode.to_string());
 		Ok(())
 	}
 }
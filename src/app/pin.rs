@@ -0,0 +1,108 @@
+use crate::gpg::key::GpgKey;
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+
+/// Name of the file that stores the pinned keys.
+const PINNED_KEYS_FILE: &str = "pinned";
+
+/// Keys that are pinned to the top of the table.
+///
+/// Fingerprints of the keys are stored in a file
+/// (one per line) so that they persist across sessions.
+#[derive(Clone, Debug, Default)]
+pub struct PinnedKeys {
+	/// Fingerprints of the pinned keys.
+	pub fingerprints: Vec<String>,
+	/// File for storing the fingerprints.
+	path: Option<PathBuf>,
+}
+
+impl PinnedKeys {
+	/// Returns the default location of the file.
+	pub fn get_default_location() -> Option<PathBuf> {
+		dirs_next::data_dir().map(|data_dir| {
+			data_dir.join(env!("CARGO_PKG_NAME")).join(PINNED_KEYS_FILE)
+		})
+	}
+
+	/// Loads the pinned keys from the given file.
+	///
+	/// Nothing is persisted if the path is not given.
+	pub fn load(path: Option<PathBuf>) -> Self {
+		let fingerprints = path
+			.as_ref()
+			.and_then(|path| fs::read_to_string(path).ok())
+			.map(|contents| {
+				contents
+					.lines()
+					.map(|line| line.trim().to_uppercase())
+					.filter(|line| !line.is_empty())
+					.collect()
+			})
+			.unwrap_or_default();
+		Self { fingerprints, path }
+	}
+
+	/// Checks if the given key is pinned.
+	pub fn contains(&self, key: &GpgKey) -> bool {
+		self.fingerprints
+			.contains(&key.get_fingerprint().to_uppercase())
+	}
+
+	/// Pins/unpins the given key and saves the changes.
+	///
+	/// Returns `true` if the key is pinned.
+	pub fn toggle(&mut self, key: &GpgKey) -> Result<bool> {
+		let fingerprint = key.get_fingerprint().to_uppercase();
+		let pinned =
+			match self.fingerprints.iter().position(|v| v == &fingerprint) {
+				Some(index) => {
+					self.fingerprints.remove(index);
+					false
+				}
+				None => {
+					self.fingerprints.push(fingerprint);
+					true
+				}
+			};
+		self.save()?;
+		Ok(pinned)
+	}
+
+	/// Saves the fingerprints to the file.
+	fn save(&self) -> Result<()> {
+		if let Some(path) = &self.path {
+			if let Some(parent) = path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			fs::write(path, self.fingerprints.join("\n"))?;
+		}
+		Ok(())
+	}
+
+	/// Sorts the given keys so that the pinned keys are at the top.
+	///
+	/// The order of the other keys is preserved.
+	pub fn sort(&self, keys: &mut [GpgKey]) {
+		keys.sort_by_key(|key| !self.contains(key));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_app_pin() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("pinned");
+		fs::write(&path, "abc123\n\n  DEF456 \n")?;
+		let pinned_keys = PinnedKeys::load(Some(path.to_path_buf()));
+		assert_eq!(vec!["ABC123", "DEF456"], pinned_keys.fingerprints);
+		pinned_keys.save()?;
+		assert_eq!("ABC123\nDEF456", fs::read_to_string(&path)?);
+		assert!(PinnedKeys::load(None).fingerprints.is_empty());
+		Ok(())
+	}
+}
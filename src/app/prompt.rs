@@ -1,8 +1,12 @@
 use log::Level;
 
 use crate::app::command::Command;
+use crate::widget::secret::SecretInput;
+use anyhow::Result;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::path::PathBuf;
 use std::time::Instant;
 
 /// Prefix character for indicating command input.
@@ -10,6 +14,211 @@ pub const COMMAND_PREFIX: char = ':';
 /// Prefix character for indicating search input.
 pub const SEARCH_PREFIX: char = '/';
 
+/// Name of the file that stores the command/search history.
+const HISTORY_FILE: &str = "prompt_history";
+
+/// Maximum number of entries to keep in the history.
+const MAX_HISTORY_SIZE: usize = 1000;
+
+/// Names of the commands for completion.
+const COMMAND_NAMES: &[&str] = &[
+	"addsubkey",
+	"adduid",
+	"agent",
+	"armor",
+	"card",
+	"clean-exports",
+	"compare",
+	"copy",
+	"dearmor",
+	"decrypt-clipboard",
+	"delete",
+	"diff",
+	"edit",
+	"encrypt",
+	"encrypt-clipboard",
+	"encrypt-symmetric",
+	"expire",
+	"expiring",
+	"export",
+	"export-each",
+	"export-json",
+	"export-to",
+	"fetch",
+	"filter",
+	"generate",
+	"get",
+	"goto",
+	"help",
+	"history",
+	"import",
+	"import-clipboard",
+	"inspect",
+	"list",
+	"locate-signers",
+	"lock",
+	"logs",
+	"mark",
+	"mode",
+	"next",
+	"normal",
+	"options",
+	"output",
+	"passwd",
+	"paste",
+	"pick",
+	"pin",
+	"prefs",
+	"previous",
+	"primary-uid",
+	"promote",
+	"qr",
+	"quit",
+	"receive",
+	"recipient",
+	"refresh",
+	"reload-config",
+	"report",
+	"revoke",
+	"revuid",
+	"sandbox",
+	"search",
+	"search-keyserver",
+	"send",
+	"serve",
+	"set",
+	"setpref",
+	"sign",
+	"sign-file",
+	"style",
+	"sync-config",
+	"trust",
+	"verify",
+	"verify-release",
+	"visual",
+	"who",
+	"write-config",
+];
+
+/// Options of `:set`/`:get` for completion.
+const OPTION_NAMES: &[&str] = &[
+	"armor",
+	"clipboard",
+	"color",
+	"date_format",
+	"detail",
+	"detail_view",
+	"fingerprint_format",
+	"keyserver",
+	"margin",
+	"minimize",
+	"mode",
+	"output",
+	"paging",
+	"relative_dates",
+	"rename_output",
+	"signer",
+	"style",
+	"trust_model",
+	"validate",
+];
+
+/// Commands that take key IDs/user IDs as arguments.
+const KEY_COMMANDS: &[&str] = &[
+	"addsubkey",
+	"adduid",
+	"compare",
+	"delete",
+	"diff",
+	"edit",
+	"encrypt-clipboard",
+	"expire",
+	"export",
+	"goto",
+	"history",
+	"inspect",
+	"locate-signers",
+	"passwd",
+	"pin",
+	"prefs",
+	"primary-uid",
+	"promote",
+	"qr",
+	"revoke",
+	"revuid",
+	"send",
+	"serve",
+	"setpref",
+	"sign",
+	"trust",
+];
+
+/// Commands that take file paths as arguments.
+const FILE_COMMANDS: &[&str] = &[
+	"armor",
+	"dearmor",
+	"encrypt",
+	"encrypt-symmetric",
+	"import",
+	"sign-file",
+	"verify",
+	"verify-release",
+];
+
+/// Returns the completion candidates for the last word of the input.
+///
+/// Words are the command name and its arguments.
+fn get_candidates(words: &[&str], keys: &[String]) -> Vec<String> {
+	let to_vec =
+		|values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+	match words {
+		[_] => to_vec(COMMAND_NAMES),
+		["set" | "s" | "get" | "g", _] => to_vec(OPTION_NAMES),
+		[command, .., word] if FILE_COMMANDS.contains(command) => {
+			get_paths(word)
+		}
+		[command, ..] if KEY_COMMANDS.contains(command) => keys.to_vec(),
+		_ => Vec::new(),
+	}
+}
+
+/// Returns the paths in the directory of the given (partial) path.
+///
+/// Hidden files are only returned if the file name starts with a dot.
+fn get_paths(path: &str) -> Vec<String> {
+	let (dir, file_name) = match path.rsplit_once('/') {
+		Some((dir, file_name)) => (format!("{dir}/"), file_name),
+		None => (String::new(), path),
+	};
+	let read_dir = if let Some(dir) = dir.strip_prefix("~/") {
+		dirs_next::home_dir().map(|home_dir| home_dir.join(dir))
+	} else if dir.is_empty() {
+		Some(PathBuf::from("."))
+	} else {
+		Some(PathBuf::from(&dir))
+	};
+	read_dir
+		.and_then(|read_dir| fs::read_dir(read_dir).ok())
+		.map(|entries| {
+			entries
+				.flatten()
+				.filter_map(|entry| {
+					let name = entry.file_name().to_string_lossy().into_owned();
+					if name.starts_with('.') && !file_name.starts_with('.') {
+						return None;
+					}
+					let is_dir =
+						entry.file_type().map_or(false, |v| v.is_dir());
+					Some(format!(
+						"{dir}{name}{}",
+						if is_dir { "/" } else { "" }
+					))
+				})
+				.collect()
+		})
+		.unwrap_or_default()
+}
+
 /// Output type of the prompt.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum OutputType {
@@ -91,6 +300,18 @@ pub struct Prompt {
 	pub history: Vec<String>,
 	/// Index of the selected command from history.
 	pub history_index: usize,
+	/// Query of the reverse incremental search in history.
+	pub history_search: Option<String>,
+	/// File for storing the history.
+	history_path: Option<PathBuf>,
+	/// Input without the completed word, candidates and the selected index.
+	completion: Option<(String, Vec<String>, usize)>,
+	/// Command that waits for the passphrase and its hidden input.
+	pub passphrase_input: Option<(Command, SecretInput)>,
+	/// Whether if "yes" is selected in the confirmation popup.
+	pub confirm: bool,
+	/// Output messages that are deferred while the terminal is unfocused.
+	deferred_output: Option<Vec<(OutputType, String)>>,
 }
 
 impl Prompt {
@@ -109,6 +330,7 @@ impl Prompt {
 		self.clock = None;
 		self.command = None;
 		self.history_index = 0;
+		self.history_search = None;
 	}
 
 	/// Checks if the prompt is enabled.
@@ -136,21 +358,90 @@ impl Prompt {
 		self.text.starts_with(SEARCH_PREFIX)
 	}
 
+	/// Enables the passphrase input for the given command.
+	///
+	/// Typed characters are masked and kept out of the history.
+	pub fn enable_passphrase_input(&mut self, label: &str, command: Command) {
+		self.text = format!("{label}: ");
+		self.output_type = OutputType::None;
+		self.clock = None;
+		self.command = None;
+		self.passphrase_input = Some((command, SecretInput::default()));
+	}
+
+	/// Checks if the passphrase input is enabled.
+	pub fn is_passphrase_input_enabled(&self) -> bool {
+		self.passphrase_input.is_some()
+	}
+
+	/// Appends the given character to the passphrase.
+	pub fn push_passphrase(&mut self, c: char) {
+		if let Some((_, passphrase)) = self.passphrase_input.as_mut() {
+			if passphrase.push(c) {
+				self.text.push('*');
+			}
+		}
+	}
+
+	/// Removes the last character of the passphrase.
+	pub fn pop_passphrase(&mut self) {
+		if let Some((_, passphrase)) = self.passphrase_input.as_mut() {
+			if passphrase.pop() {
+				self.text.pop();
+			}
+		}
+	}
+
 	/// Sets the output message.
 	pub fn set_output<S: AsRef<str>>(&mut self, output: (OutputType, S)) {
 		let (output_type, message) = output;
 		log::log!(target: "tui", self.output_type.as_log_level(), "{}", message.as_ref().to_string());
+		if let Some(deferred_output) = self.deferred_output.as_mut() {
+			deferred_output.push((output_type, message.as_ref().to_string()));
+			return;
+		}
 		self.output_type = output_type;
 		self.text = message.as_ref().to_string();
 		self.clock = Some(Instant::now());
 	}
 
+	/// Defers the output messages until [`flush_output`] is called.
+	///
+	/// [`flush_output`]: Prompt::flush_output
+	pub fn defer_output(&mut self) {
+		self.deferred_output.get_or_insert_with(Vec::new);
+	}
+
+	/// Shows the deferred output messages.
+	///
+	/// The last message is shown along with the number of the others
+	/// since all of them are already written to the logs.
+	pub fn flush_output(&mut self) {
+		let mut deferred_output = match self.deferred_output.take() {
+			Some(deferred_output) => deferred_output,
+			None => return,
+		};
+		if let Some((output_type, message)) = deferred_output.pop() {
+			self.output_type = output_type;
+			self.text = if deferred_output.is_empty() {
+				message
+			} else {
+				format!(
+					"{message} (+{} more in the logs)",
+					deferred_output.len()
+				)
+			};
+			self.clock = Some(Instant::now());
+		}
+	}
+
 	/// Sets the command that will be asked to confirm.
 	pub fn set_command(&mut self, command: Command) {
 		self.text = format!("press 'y' to {command}");
 		self.output_type = OutputType::Action;
 		self.command = Some(command);
 		self.clock = Some(Instant::now());
+		self.confirm = false;
 	}
 
 	/// Select the next command.
@@ -180,6 +471,163 @@ impl Prompt {
 		}
 	}
 
+	/// Returns the default location of the history file.
+	pub fn get_history_location() -> Option<PathBuf> {
+		dirs_next::data_dir().map(|data_dir| {
+			data_dir.join(env!("CARGO_PKG_NAME")).join(HISTORY_FILE)
+		})
+	}
+
+	/// Loads the command/search history from the given file.
+	///
+	/// Nothing is persisted if the path is not given.
+	pub fn load_history(&mut self, path: Option<PathBuf>) {
+		self.history = path
+			.as_ref()
+			.and_then(|path| fs::read_to_string(path).ok())
+			.map(|contents| {
+				contents
+					.lines()
+					.filter(|line| !line.is_empty())
+					.map(String::from)
+					.collect()
+			})
+			.unwrap_or_default();
+		self.history_path = path;
+	}
+
+	/// Appends the given entry to the history and saves it.
+	///
+	/// Consecutive duplicates are skipped and only the
+	/// last [`MAX_HISTORY_SIZE`] entries are kept.
+	pub fn push_history(&mut self, entry: &str) -> Result<()> {
+		let entry = entry.replace('\n', " ");
+		if self.history.last() == Some(&entry) {
+			return Ok(());
+		}
+		self.history.push(entry);
+		if self.history.len() > MAX_HISTORY_SIZE {
+			self.history.drain(..self.history.len() - MAX_HISTORY_SIZE);
+		}
+		if let Some(path) = &self.history_path {
+			if let Some(parent) = path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			fs::write(
+				path,
+				self.history
+					.iter()
+					.map(|entry| format!("{entry}\n"))
+					.collect::<String>(),
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Starts the reverse incremental search in history.
+	///
+	/// If the search is already started, the next (older) match is selected.
+	pub fn search_history(&mut self) {
+		if self.history_search.is_some() {
+			self.find_in_history(self.history_index);
+		} else {
+			self.history_search = Some(String::new());
+			self.history_index = 0;
+		}
+	}
+
+	/// Appends the given character to the search query.
+	pub fn push_history_search(&mut self, c: char) {
+		if let Some(query) = self.history_search.as_mut() {
+			query.push(c);
+			self.find_in_history(self.history_index.saturating_sub(1));
+		}
+	}
+
+	/// Removes the last character of the search query.
+	pub fn pop_history_search(&mut self) {
+		if let Some(query) = self.history_search.as_mut() {
+			query.pop();
+			self.find_in_history(0);
+		}
+	}
+
+	/// Finishes the search and keeps the found entry for editing.
+	pub fn finish_history_search(&mut self) {
+		self.history_search = None;
+	}
+
+	/// Selects the most recent entry that contains the search query.
+	///
+	/// The given number of recent entries are skipped.
+	fn find_in_history(&mut self, skip: usize) {
+		let query = self.history_search.as_deref().unwrap_or_default();
+		if let Some((index, entry)) = self
+			.history
+			.iter()
+			.rev()
+			.enumerate()
+			.skip(skip)
+			.find(|(_, entry)| entry.contains(query))
+		{
+			self.text = entry.to_string();
+			self.history_index = index + 1;
+		}
+	}
+
+	/// Completes the last word of the command input.
+	///
+	/// Command names, `:set`/`:get` options, file paths and the
+	/// given key IDs/user IDs are completed depending on the command.
+	/// If the matches don't share a longer prefix, repeated calls
+	/// cycle through them.
+	pub fn complete(&mut self, keys: &[String]) {
+		if let Some((input, candidates, index)) = self.completion.as_mut() {
+			if self.text == format!("{input}{}", candidates[*index]) {
+				*index = (*index + 1) % candidates.len();
+				self.text = format!("{input}{}", candidates[*index]);
+				return;
+			}
+		}
+		self.completion = None;
+		let words = self.text[1..].split(' ').collect::<Vec<&str>>();
+		let word = words.last().copied().unwrap_or_default();
+		let input = self.text[..self.text.len() - word.len()].to_string();
+		let mut matches = get_candidates(&words, keys)
+			.into_iter()
+			.filter(|candidate| {
+				candidate.to_lowercase().starts_with(&word.to_lowercase())
+			})
+			.collect::<Vec<String>>();
+		matches.sort();
+		matches.dedup();
+		match matches.as_slice() {
+			[] => {}
+			[candidate] => {
+				self.text = format!(
+					"{input}{candidate}{}",
+					if candidate.ends_with('/') { "" } else { " " }
+				);
+			}
+			[first, ..] => {
+				let common_prefix = first
+					.chars()
+					.enumerate()
+					.take_while(|(i, c)| {
+						matches.iter().all(|v| v.chars().nth(*i) == Some(*c))
+					})
+					.map(|(_, c)| c)
+					.collect::<String>();
+				if common_prefix.len() > word.len() {
+					self.text = format!("{input}{common_prefix}");
+				} else {
+					self.text = format!("{input}{first}");
+					self.completion = Some((input, matches, 0));
+				}
+			}
+		}
+	}
+
 	/// Clears the prompt.
 	pub fn clear(&mut self) {
 		self.text.clear();
@@ -187,6 +635,9 @@ impl Prompt {
 		self.clock = None;
 		self.command = None;
 		self.history_index = 0;
+		self.history_search = None;
+		self.passphrase_input = None;
+		self.confirm = false;
 	}
 }
 
@@ -195,7 +646,7 @@ mod tests {
 	use super::*;
 	use pretty_assertions::{assert_eq, assert_ne};
 	#[test]
-	fn test_app_prompt() {
+	fn test_app_prompt() -> Result<()> {
 		let mut prompt = Prompt::default();
 		prompt.enable_command_input();
 		assert!(prompt.is_command_input_enabled());
@@ -217,6 +668,26 @@ mod tests {
 		prompt.clear();
 		assert_eq!(String::new(), prompt.text);
 		assert_eq!(None, prompt.clock);
+		prompt.enable_passphrase_input(
+			"passphrase for 0x0",
+			Command::PresetPassphrase(String::from("0x0"), None),
+		);
+		assert!(prompt.is_enabled());
+		assert!(prompt.is_passphrase_input_enabled());
+		prompt.push_passphrase('a');
+		prompt.push_passphrase('b');
+		prompt.pop_passphrase();
+		prompt.push_passphrase('c');
+		assert_eq!("passphrase for 0x0: **", prompt.text);
+		assert_eq!(
+			Some((
+				Command::PresetPassphrase(String::from("0x0"), None),
+				SecretInput::from("ac")
+			)),
+			prompt.passphrase_input
+		);
+		prompt.clear();
+		assert!(!prompt.is_passphrase_input_enabled());
 		prompt.history =
 			vec![String::from("0"), String::from("1"), String::from("2")];
 		for i in 0..prompt.history.len() {
@@ -227,6 +698,69 @@ mod tests {
 			prompt.next();
 			assert_eq!(i.to_string(), prompt.text);
 		}
+		prompt.clear();
+		prompt.history = vec![
+			String::from(":list pub"),
+			String::from(":export pub"),
+			String::from(":list sec"),
+		];
+		prompt.enable_command_input();
+		prompt.search_history();
+		"list".chars().for_each(|c| prompt.push_history_search(c));
+		assert_eq!(":list sec", prompt.text);
+		prompt.search_history();
+		assert_eq!(":list pub", prompt.text);
+		prompt.search_history();
+		assert_eq!(":list pub", prompt.text);
+		(0..4).for_each(|_| prompt.pop_history_search());
+		"ex".chars().for_each(|c| prompt.push_history_search(c));
+		assert_eq!(":export pub", prompt.text);
+		prompt.finish_history_search();
+		assert_eq!(None, prompt.history_search);
+		assert_eq!(":export pub", prompt.text);
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("history");
+		prompt.load_history(Some(path.to_path_buf()));
+		assert!(prompt.history.is_empty());
+		prompt.push_history(":list pub")?;
+		prompt.push_history(":list pub")?;
+		prompt.push_history("/test")?;
+		prompt.load_history(Some(path.to_path_buf()));
+		assert_eq!(vec![":list pub", "/test"], prompt.history);
+		prompt.clear();
+		prompt.defer_output();
+		prompt.set_output((OutputType::Success, "first"));
+		prompt.set_output((OutputType::Failure, "second"));
+		assert!(prompt.text.is_empty());
+		prompt.flush_output();
+		assert_eq!(OutputType::Failure, prompt.output_type);
+		assert_eq!("second (+1 more in the logs)", prompt.text);
+		prompt.set_output((OutputType::Success, "third"));
+		assert_eq!("third", prompt.text);
+		prompt.clear();
+		let keys = vec![
+			String::from("0xABCD1234"),
+			String::from("0xABEF5678"),
+			String::from("test@example.org"),
+		];
+		for (input, output) in [
+			(":sync-c", ":sync-config "),
+			(":set rel", ":set relative_dates "),
+			(":sign 0xab", ":sign 0xABCD1234"),
+			(":sign te", ":sign test@example.org "),
+			(":import src/ap", ":import src/app/"),
+			(":import src/app/pro", ":import src/app/prompt.rs "),
+			(":quit now", ":quit now"),
+		] {
+			prompt.text = input.to_string();
+			prompt.complete(&keys);
+			assert_eq!(output, prompt.text);
+		}
+		prompt.text = String::from(":exp");
+		prompt.complete(&keys);
+		assert_eq!(":expire", prompt.text);
+		prompt.complete(&keys);
+		assert_eq!(":expiring", prompt.text);
 		for output_type in [
 			OutputType::from(String::from("warning")),
 			OutputType::from(String::from("failure")),
@@ -242,5 +776,6 @@ mod tests {
 				&output_type.to_string()
 			);
 		}
+		Ok(())
 	}
 }
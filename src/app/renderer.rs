@@ -1,16 +1,25 @@
 use crate::app::banner::Banner;
+use crate::app::command::Command;
 use crate::app::launcher::App;
+use crate::app::mode::Mode;
 use crate::app::prompt::OutputType;
 use crate::app::style;
 use crate::app::tab::Tab;
+use crate::app::wizard::WizardStep;
+use crate::config::ThemeElement;
+use crate::term::graphics::{GraphicsProtocol, ImagePlacement};
+use crate::widget::list::ListEntry;
+use crate::widget::photo::Photo;
 use crate::widget::row::RowItem;
+use crate::widget::secret::Strength;
 use crate::widget::table::TableSize;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::terminal::Frame;
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
-	Block, Borders, Clear, List, ListItem, Paragraph, Row, Table, Wrap,
+	Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table,
+	TableState, Wrap,
 };
 use std::cmp;
 use std::convert::{TryFrom, TryInto};
@@ -20,17 +29,36 @@ use unicode_width::UnicodeWidthStr;
 /// Lengths of keys row in minimized/normal mode.
 const KEYS_ROW_LENGTH: (u16, u16) = (31, 55);
 
+/// Length of the checkbox for recipients and marked keys.
+const CHECKBOX_LENGTH: u16 = 4;
+
+/// Length of the computed key validity flag.
+const VALIDITY_FLAG_LENGTH: u16 = 4;
+
+/// Length of the relative expiration column.
+const COUNTDOWN_LENGTH: u16 = 17;
+
 /// Renders all the widgets thus the user interface.
 pub fn render(app: &mut App, frame: &mut Frame) {
 	let rect = frame.size();
+	app.image_placement = None;
 	if app.keys_table.state.minimize_threshold != 0 {
+		let size = app.keys_table.state.size;
 		app.keys_table.state.size.set_minimized(
 			rect.width < app.keys_table.state.minimize_threshold,
 		);
+		if app.keys_table.state.size != size {
+			app.keys_table_rows = None;
+		}
 	}
-	if app.state.show_splash {
+	if app.state.locked {
+		render_lock_screen(app, frame, rect);
+	} else if app.state.show_splash {
 		frame.render_widget(&mut app.splash_screen, rect);
 		app.state.show_splash = !app.splash_screen.is_rendered();
+		if let Some(photo) = app.splash_photo.clone() {
+			render_photo(app, frame, photo, rect);
+		}
 	} else {
 		let mut main_rect = rect;
 		if app.state.show_logs {
@@ -53,30 +81,73 @@ pub fn render(app: &mut App, frame: &mut Frame) {
 			.split(main_rect);
 		render_command_prompt(app, frame, chunks[1]);
 		match app.tab {
-			Tab::Keys(_) => render_keys_table(app, frame, chunks[0]),
+			Tab::Keys(_) | Tab::KeyserverResults => {
+				render_keys_table(app, frame, chunks[0])
+			}
 			Tab::Help => render_help_tab(app, frame, chunks[0]),
+			Tab::Card => render_card_tab(app, frame, chunks[0]),
+			Tab::KeyDetail(_) => render_key_detail_tab(app, frame, chunks[0]),
 		}
 		if app.state.show_options {
 			render_options_menu(app, frame, main_rect);
 		}
+		if app.prompt.command.is_some() {
+			render_confirmation_popup(app, frame, main_rect);
+		} else if app.file_browser.is_some() {
+			render_file_browser_popup(app, frame, main_rect);
+		} else if app.wizard.is_some() {
+			render_wizard_popup(app, frame, main_rect);
+		} else if app.verifications.is_some() {
+			render_verification_popup(app, frame, main_rect);
+		} else if app.receive_results.is_some() {
+			render_receive_popup(app, frame, main_rect);
+		} else if app.expiring_keys.is_some() {
+			render_expiring_popup(app, frame, main_rect);
+		} else if app.fingerprint_comparison.is_some() {
+			render_comparison_popup(app, frame, main_rect);
+		} else if app.key_history.is_some() {
+			render_history_popup(app, frame, main_rect);
+		} else if app.key_preferences.is_some() {
+			render_preferences_popup(app, frame, main_rect);
+		} else if app.qr_code.is_some() {
+			render_qr_code_popup(app, frame, rect);
+		}
 	}
 }
 
+/// Renders the lock screen which hides the key listing.
+fn render_lock_screen(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let mut lines = vec![Line::from(""); (rect.height / 2) as usize];
+	lines.push(Line::from(Span::styled(
+		"locked",
+		Style::default().add_modifier(Modifier::BOLD),
+	)));
+	lines.push(Line::from(if app.state.lock_phrase.is_some() {
+		format!(
+			"enter the phrase to resume: {}",
+			"*".repeat(app.state.unlock_input.chars().count())
+		)
+	} else {
+		String::from("press any key to resume")
+	}));
+	frame.render_widget(Clear, rect);
+	frame.render_widget(
+		Paragraph::new(lines)
+			.style(app.state.style.get_text_style(app.state.color))
+			.alignment(Alignment::Center),
+		rect,
+	);
+}
+
 /// Renders the log view.
 fn render_log_view(app: &mut App, frame: &mut Frame, rect: Rect) {
 	let logger_widget = TuiLoggerSmartWidget::default()
-		.style_trace(Style::default().fg(Color::DarkGray))
+		.style_trace(Style::default().fg(app.state.style.get_dim_color()))
 		.style_debug(Style::default().fg(Color::Blue))
 		.style_warn(Style::default().fg(Color::Yellow))
 		.style_error(Style::default().fg(Color::Red))
 		.style_info(Style::default().fg(Color::Green))
-		.highlight_style(if app.state.style.is_colored() {
-			Style::default().add_modifier(Modifier::BOLD)
-		} else {
-			Style::default()
-				.fg(Color::Reset)
-				.add_modifier(Modifier::BOLD)
-		})
+		.highlight_style(get_highlight_style(app))
 		.output_separator(':')
 		.output_timestamp(Some("%H:%M:%S".to_string()))
 		.output_level(Some(TuiLoggerLevelOutput::Long))
@@ -89,24 +160,57 @@ fn render_log_view(app: &mut App, frame: &mut Frame, rect: Rect) {
 
 /// Renders the command prompt.
 fn render_command_prompt(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let text = match &app.prompt.history_search {
+		Some(query) => {
+			format!("(reverse-i-search)`{query}': {}", app.prompt.text)
+		}
+		None => format!("{}{}", app.prompt.output_type, app.prompt.text),
+	};
 	frame.render_widget(
 		Paragraph::new(Line::from(if !app.prompt.text.is_empty() {
-			vec![Span::raw(format!(
-				"{}{}",
-				app.prompt.output_type, app.prompt.text
-			))]
+			vec![Span::raw(text.clone())]
 		} else {
 			let arrow_color = if app.state.style.is_colored() {
 				Color::LightBlue
 			} else {
-				Color::DarkGray
+				app.state.style.get_dim_color()
 			};
 			vec![
 				Span::styled("< ", Style::default().fg(arrow_color)),
 				match app.tab {
 					Tab::Keys(key_type) => Span::raw(format!(
-						"list {}{}",
+						"list {}{}{}{}",
 						key_type,
+						if app.gpgme.is_sandboxed() {
+							" (sandbox)"
+						} else {
+							""
+						},
+						match app.state.key_filter {
+							Some(filter) => format!(" [{filter}]"),
+							None => String::new(),
+						},
+						if app.loading_keys {
+							format!(
+								" (loading... {})",
+								app.keys_table.items.len()
+							)
+						} else if !app.keys_table.items.is_empty() {
+							format!(
+								" ({}/{})",
+								app.keys_table
+									.state
+									.tui
+									.selected()
+									.unwrap_or_default() + 1,
+								app.keys_table.items.len()
+							)
+						} else {
+							String::new()
+						}
+					)),
+					Tab::KeyserverResults => Span::raw(format!(
+						"keyserver results{}",
 						if !app.keys_table.items.is_empty() {
 							format!(
 								" ({}/{})",
@@ -122,37 +226,20 @@ fn render_command_prompt(app: &mut App, frame: &mut Frame, rect: Rect) {
 						}
 					)),
 					Tab::Help => Span::raw("help"),
+					Tab::Card => Span::raw(match &app.card {
+						Some(card) => format!("card {}", card.serial),
+						None => String::from("card"),
+					}),
+					Tab::KeyDetail(_) => Span::raw(format!(
+						"key details ({}/{})",
+						app.key_detail.state.selected().unwrap_or_default() + 1,
+						app.key_detail.items.len()
+					)),
 				},
 				Span::styled(" >", Style::default().fg(arrow_color)),
 			]
 		}))
-		.style(if app.state.style.is_colored() {
-			match app.prompt.output_type {
-				OutputType::Success => Style::default()
-					.fg(Color::LightGreen)
-					.add_modifier(Modifier::BOLD),
-				OutputType::Warning => Style::default()
-					.fg(Color::LightYellow)
-					.add_modifier(Modifier::BOLD),
-				OutputType::Failure => Style::default()
-					.fg(Color::LightRed)
-					.add_modifier(Modifier::BOLD),
-				OutputType::Action => {
-					if app.state.style.is_colored() {
-						Style::default()
-							.fg(Color::LightBlue)
-							.add_modifier(Modifier::BOLD)
-					} else {
-						Style::default().add_modifier(Modifier::BOLD)
-					}
-				}
-				OutputType::None => Style::default(),
-			}
-		} else if app.prompt.output_type != OutputType::None {
-			Style::default().add_modifier(Modifier::BOLD)
-		} else {
-			Style::default()
-		})
+		.style(get_prompt_style(app))
 		.alignment(if !app.prompt.text.is_empty() {
 			Alignment::Left
 		} else {
@@ -162,16 +249,16 @@ fn render_command_prompt(app: &mut App, frame: &mut Frame, rect: Rect) {
 		rect,
 	);
 	if app.prompt.is_enabled() {
-		frame.set_cursor(rect.x + app.prompt.text.width() as u16, rect.y + 1);
+		frame.set_cursor(rect.x + text.width() as u16, rect.y + 1);
 	}
 }
 
 /// Renders the help tab.
 fn render_help_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
 	frame.render_widget(
-		Block::default()
+		get_block(app)
 			.borders(Borders::ALL)
-			.border_style(Style::default().fg(Color::DarkGray)),
+			.border_style(get_border_style(app)),
 		rect,
 	);
 	let chunks = Layout::default()
@@ -187,9 +274,12 @@ fn render_help_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
 			.selected()
 			.map(|v| {
 				v.get_description_text(
-					Style::default()
-						.fg(Color::DarkGray)
-						.add_modifier(Modifier::ITALIC),
+					app.state.theme.get_style(
+						ThemeElement::HelpCommand,
+						Style::default()
+							.fg(app.state.style.get_dim_color())
+							.add_modifier(Modifier::ITALIC),
+					),
 				)
 			})
 			.unwrap_or_default();
@@ -227,35 +317,30 @@ fn render_help_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
 						v.as_list_item(
 							app.state.style.is_colored(),
 							app.key_bindings.state.selected() == Some(i),
+							app.state.ascii,
 						)
 					})
 					.collect::<Vec<ListItem>>(),
 			)
 			.block(
-				Block::default()
+				get_block(app)
 					.borders(Borders::RIGHT)
-					.border_style(Style::default().fg(Color::DarkGray)),
+					.border_style(get_border_style(app)),
 			)
-			.style(Style::default().fg(app.state.color))
-			.highlight_style(if app.state.style.is_colored() {
-				Style::default().add_modifier(Modifier::BOLD)
-			} else {
-				Style::default()
-					.fg(Color::Reset)
-					.add_modifier(Modifier::BOLD)
-			})
-			.highlight_symbol("> "),
+			.style(app.state.style.get_text_style(app.state.color))
+			.highlight_style(get_highlight_style(app))
+			.highlight_symbol(app.state.theme.get_highlight_symbol()),
 			chunks[0],
 			&mut app.key_bindings.state,
 		);
 		frame.render_widget(
 			Paragraph::new(description)
 				.block(
-					Block::default()
+					get_block(app)
 						.borders(Borders::RIGHT)
-						.border_style(Style::default().fg(Color::DarkGray)),
+						.border_style(get_border_style(app)),
 				)
-				.style(Style::default().fg(app.state.color))
+				.style(app.state.style.get_text_style(app.state.color))
 				.alignment(Alignment::Left)
 				.wrap(Wrap { trim: true }),
 			chunks[1],
@@ -292,11 +377,11 @@ fn render_help_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
 				Text::raw(banner)
 			})
 			.block(
-				Block::default()
+				get_block(app)
 					.borders(Borders::BOTTOM)
-					.border_style(Style::default().fg(Color::DarkGray)),
+					.border_style(get_border_style(app)),
 			)
-			.style(Style::default().fg(app.state.color))
+			.style(app.state.style.get_text_style(app.state.color))
 			.alignment(Alignment::Left)
 			.wrap(Wrap { trim: false }),
 			chunks[0],
@@ -308,11 +393,11 @@ fn render_help_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
 				Text::raw(information)
 			})
 			.block(
-				Block::default()
+				get_block(app)
 					.borders(Borders::NONE)
-					.border_style(Style::default().fg(Color::DarkGray)),
+					.border_style(get_border_style(app)),
 			)
-			.style(Style::default().fg(app.state.color))
+			.style(app.state.style.get_text_style(app.state.color))
 			.alignment(Alignment::Left)
 			.wrap(Wrap { trim: true }),
 			chunks[1],
@@ -320,15 +405,210 @@ fn render_help_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
 	}
 }
 
+/// Renders the details of the key.
+///
+/// Photo ID is shown on the right side if the key has one.
+fn render_key_detail_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let rect = match app.key_photo.clone() {
+		Some(photo) => {
+			let (width, _) = photo.get_size(Rect::new(
+				0,
+				0,
+				rect.width / 3,
+				rect.height.saturating_sub(2),
+			));
+			let chunks = Layout::default()
+				.direction(Direction::Horizontal)
+				.constraints([
+					Constraint::Min(0),
+					Constraint::Length(width + 2),
+				])
+				.split(rect);
+			let block = get_block(app)
+				.title("Photo ID")
+				.borders(Borders::ALL)
+				.border_style(get_border_style(app));
+			render_photo(app, frame, photo, block.inner(chunks[1]));
+			frame.render_widget(block, chunks[1]);
+			chunks[0]
+		}
+		None => rect,
+	};
+	let rows = app
+		.key_detail
+		.items
+		.iter()
+		.map(|entry| {
+			Row::new(vec![
+				Cell::from(format!(
+					"{}{}",
+					"  ".repeat(entry.depth),
+					entry.name
+				))
+				.style(if app.state.style.is_colored() {
+					Style::default().fg(Color::Cyan)
+				} else {
+					Style::default()
+				}),
+				Cell::from(entry.value.to_string()),
+			])
+		})
+		.collect::<Vec<Row>>();
+	let mut table_state =
+		TableState::default().with_selected(app.key_detail.state.selected());
+	frame.render_stateful_widget(
+		Table::new(rows, [Constraint::Length(18), Constraint::Percentage(100)])
+			.block(
+				get_block(app)
+					.borders(Borders::ALL)
+					.border_style(get_border_style(app)),
+			)
+			.style(app.state.style.get_text_style(app.state.color))
+			.highlight_style(get_highlight_style(app))
+			.highlight_symbol(app.state.theme.get_highlight_symbol())
+			.column_spacing(1),
+		rect,
+		&mut table_state,
+	);
+}
+
+/// Renders the photo via the graphics protocol if it is in use.
+///
+/// Otherwise, the photo is drawn with colored cells.
+fn render_photo(app: &mut App, frame: &mut Frame, photo: Photo, rect: Rect) {
+	if app.state.graphics == GraphicsProtocol::None {
+		frame.render_widget(&photo, rect);
+		return;
+	}
+	if rect.is_empty() {
+		return;
+	}
+	let (width, height) = photo.get_size(rect);
+	let area =
+		Rect::new(rect.x + (rect.width - width) / 2, rect.y, width, height);
+	for y in area.top()..area.bottom() {
+		for x in area.left()..area.right() {
+			frame.buffer_mut().get_mut(x, y).set_skip(true);
+		}
+	}
+	app.image_placement = Some(ImagePlacement { photo, area });
+}
+
+/// Renders the smartcard status.
+fn render_card_tab(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let block = get_block(app)
+		.borders(Borders::ALL)
+		.border_style(get_border_style(app));
+	let information = match &app.card {
+		Some(card) => card.get_info().join("\n"),
+		None => String::from("no card found (press 'r' to refresh)"),
+	};
+	let information_height =
+		u16::try_from(information.lines().count()).unwrap_or(1) + 1;
+	let chunks = Layout::default()
+		.direction(Direction::Vertical)
+		.margin(1)
+		.constraints(
+			[
+				Constraint::Length(information_height),
+				Constraint::Min(
+					rect.height
+						.checked_sub(information_height + 2)
+						.unwrap_or_default(),
+				),
+			]
+			.as_ref(),
+		)
+		.split(rect);
+	frame.render_widget(block, rect);
+	frame.render_widget(
+		Paragraph::new(if app.state.style.is_colored() {
+			style::get_colored_info(&information, Color::Cyan)
+		} else {
+			Text::raw(information)
+		})
+		.block(
+			get_block(app)
+				.borders(Borders::BOTTOM)
+				.border_style(get_border_style(app)),
+		)
+		.style(app.state.style.get_text_style(app.state.color))
+		.alignment(Alignment::Left),
+		chunks[0],
+	);
+	let rows = app
+		.card_slots
+		.items
+		.iter()
+		.map(|slot| {
+			let user_id = slot.fingerprint.as_ref().and_then(|fingerprint| {
+				app.keys
+					.values()
+					.flatten()
+					.find(|key| key.has_subkey(fingerprint))
+					.map(|key| key.get_user_id())
+			});
+			Row::new(vec![
+				slot.name.to_string(),
+				slot.fingerprint
+					.clone()
+					.unwrap_or_else(|| String::from("[none]")),
+				user_id.unwrap_or_else(|| String::from("[not in keyring]")),
+			])
+		})
+		.collect::<Vec<Row>>();
+	let mut table_state =
+		TableState::default().with_selected(app.card_slots.state.selected());
+	frame.render_stateful_widget(
+		Table::new(
+			rows,
+			[
+				Constraint::Length(16),
+				Constraint::Length(42),
+				Constraint::Percentage(100),
+			],
+		)
+		.style(app.state.style.get_text_style(app.state.color))
+		.highlight_style(get_highlight_style(app))
+		.highlight_symbol(app.state.theme.get_highlight_symbol())
+		.column_spacing(1),
+		chunks[1],
+		&mut table_state,
+	);
+}
+
 /// Renders the options menu.
 fn render_options_menu(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let (length_x, mut percent_y) = (38, 60);
 	let items = app
 		.options
 		.items
 		.iter()
-		.map(|v| ListItem::new(Span::raw(v.to_string())))
+		.map(|v| match v {
+			ListEntry::Item(command) => {
+				ListItem::new(Span::raw(command.to_string()))
+			}
+			ListEntry::Header(title) => ListItem::new(Span::styled(
+				title.to_string(),
+				if app.state.style.is_colored() {
+					Style::default()
+						.fg(Color::LightBlue)
+						.add_modifier(Modifier::BOLD)
+				} else {
+					Style::default().add_modifier(Modifier::BOLD)
+				},
+			)),
+			ListEntry::Separator => ListItem::new(Span::styled(
+				(if app.state.ascii { "-" } else { "─" })
+					.repeat(usize::from(length_x)),
+				if app.state.style.is_colored() {
+					Style::default().fg(Color::DarkGray)
+				} else {
+					Style::default()
+				},
+			)),
+		})
 		.collect::<Vec<ListItem>>();
-	let (length_x, mut percent_y) = (38, 60);
 	let text_height = items.iter().map(|v| v.height() as f32).sum::<f32>() + 3.;
 	if rect.height.checked_sub(5).unwrap_or(rect.height) as f32 > text_height {
 		percent_y = ((text_height / rect.height as f32) * 100.) as u16;
@@ -365,9 +645,140 @@ fn render_options_menu(app: &mut App, frame: &mut Frame, rect: Rect) {
 	frame.render_stateful_widget(
 		List::new(items)
 			.block(
-				Block::default()
+				get_block(app)
 					.title("Options")
 					.title_alignment(Alignment::Center)
+					.style(app.state.theme.get_style(
+						ThemeElement::Options,
+						if app.state.style.is_colored() {
+							Style::default().fg(Color::LightBlue)
+						} else {
+							Style::default()
+						},
+					))
+					.borders(Borders::ALL),
+			)
+			.style(app.state.style.get_text_style(app.state.color))
+			.highlight_style(app.state.theme.get_style(
+				ThemeElement::Selected,
+				app.state.style.get_highlight_style().fg(Color::Reset),
+			))
+			.highlight_symbol(app.state.theme.get_highlight_symbol()),
+		area,
+		&mut app.options.state,
+	);
+}
+
+/// Returns the style of the command prompt.
+///
+/// Styles of the success, warning and error outputs can be set via theme.
+fn get_prompt_style(app: &App) -> Style {
+	let style = if app.state.style.is_colored() {
+		match app.prompt.output_type {
+			OutputType::Success => Style::default()
+				.fg(Color::LightGreen)
+				.add_modifier(Modifier::BOLD),
+			OutputType::Warning => Style::default()
+				.fg(Color::LightYellow)
+				.add_modifier(Modifier::BOLD),
+			OutputType::Failure => Style::default()
+				.fg(Color::LightRed)
+				.add_modifier(Modifier::BOLD),
+			OutputType::Action => {
+				if app.state.style.is_colored() {
+					Style::default()
+						.fg(Color::LightBlue)
+						.add_modifier(Modifier::BOLD)
+				} else {
+					Style::default().add_modifier(Modifier::BOLD)
+				}
+			}
+			OutputType::None => Style::default(),
+		}
+	} else if app.prompt.output_type != OutputType::None {
+		Style::default().add_modifier(Modifier::BOLD)
+	} else {
+		Style::default()
+	};
+	match app.prompt.output_type {
+		OutputType::Success => {
+			app.state.theme.get_style(ThemeElement::Success, style)
+		}
+		OutputType::Warning => {
+			app.state.theme.get_style(ThemeElement::Warning, style)
+		}
+		OutputType::Failure => {
+			app.state.theme.get_style(ThemeElement::Failure, style)
+		}
+		_ => style,
+	}
+}
+
+/// Returns the style of the borders.
+fn get_border_style(app: &App) -> Style {
+	app.state.theme.get_style(
+		ThemeElement::Border,
+		Style::default().fg(app.state.style.get_dim_color()),
+	)
+}
+
+/// Returns the style of the selected items.
+fn get_highlight_style(app: &App) -> Style {
+	app.state.theme.get_style(
+		ThemeElement::Selected,
+		app.state.style.get_highlight_style(),
+	)
+}
+
+/// Returns a block with the border symbols of the current mode.
+fn get_block<'a>(app: &App) -> Block<'a> {
+	if app.state.ascii {
+		Block::default().border_set(style::ASCII_BORDER)
+	} else {
+		Block::default()
+	}
+}
+
+/// Returns the centered area for showing the given lines in a popup.
+fn get_popup_area(lines: &[Line], rect: Rect) -> Rect {
+	let width = lines
+		.iter()
+		.map(|line| line.width() as u16)
+		.max()
+		.unwrap_or_default()
+		.saturating_add(4)
+		.min(rect.width);
+	let height = (lines.len() as u16 + 3).min(rect.height);
+	Rect::new(
+		rect.x + (rect.width - width) / 2,
+		rect.y + (rect.height - height) / 2,
+		width,
+		height,
+	)
+}
+
+/// Renders a popup with the given lines that is closed with any key.
+fn render_list_popup(
+	app: &App,
+	frame: &mut Frame,
+	rect: Rect,
+	title: &str,
+	mut lines: Vec<Line>,
+	alignment: Alignment,
+) {
+	lines.push(Line::from(""));
+	lines.push(Line::from(Span::styled(
+		"press any key to close",
+		Style::default().fg(app.state.style.get_dim_color()),
+	)));
+	let area = get_popup_area(&lines, rect);
+	frame.render_widget(Clear, area);
+	frame.render_widget(
+		Paragraph::new(lines)
+			.block(
+				get_block(app)
+					.title(title)
+					.title_alignment(Alignment::Center)
 					.style(if app.state.style.is_colored() {
 						Style::default().fg(Color::LightBlue)
 					} else {
@@ -375,137 +786,749 @@ fn render_options_menu(app: &mut App, frame: &mut Frame, rect: Rect) {
 					})
 					.borders(Borders::ALL),
 			)
-			.style(Style::default().fg(app.state.color))
-			.highlight_style(
-				Style::default()
-					.fg(Color::Reset)
-					.add_modifier(Modifier::BOLD),
-			)
-			.highlight_symbol("> "),
+			.style(app.state.style.get_text_style(app.state.color))
+			.alignment(alignment),
 		area,
-		&mut app.options.state,
 	);
 }
 
-/// Renders the table of keys.
-fn render_keys_table(app: &mut App, frame: &mut Frame, rect: Rect) {
-	let keys_row_length = if app.keys_table.state.size != TableSize::Normal {
-		KEYS_ROW_LENGTH.0
+/// Renders the results of the signature verification.
+fn render_verification_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let verifications = match &app.verifications {
+		Some(verifications) => verifications,
+		None => return,
+	};
+	let mut lines = Vec::new();
+	for verification in verifications {
+		lines.push(Line::from(""));
+		for (i, info) in verification
+			.get_info(app.state.get_date_format())
+			.into_iter()
+			.enumerate()
+		{
+			lines.push(Line::from(Span::styled(
+				info,
+				if i == 0 && app.state.style.is_colored() {
+					Style::default()
+						.fg(if verification.is_good() {
+							Color::Green
+						} else {
+							Color::Red
+						})
+						.add_modifier(Modifier::BOLD)
+				} else if i == 0 {
+					Style::default().add_modifier(Modifier::BOLD)
+				} else {
+					Style::default()
+				},
+			)));
+		}
+	}
+	render_list_popup(
+		app,
+		frame,
+		rect,
+		"Verification",
+		lines,
+		Alignment::Center,
+	);
+}
+
+/// Renders the per-key results of receiving keys.
+fn render_receive_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let results = match &app.receive_results {
+		Some(results) => results,
+		None => return,
+	};
+	let key_width = results
+		.iter()
+		.map(|(key_id, _)| key_id.len())
+		.max()
+		.unwrap_or_default();
+	let mut lines = vec![Line::from("")];
+	for (key_id, result) in results {
+		let (status, color) = match result {
+			Ok(()) => (String::from("received"), Color::Green),
+			Err(e) => (format!("failed ({e})"), Color::Red),
+		};
+		lines.push(Line::from(vec![
+			Span::raw(format!("{key_id:<key_width$}  ")),
+			Span::styled(
+				status,
+				if app.state.style.is_colored() {
+					Style::default().fg(color)
+				} else {
+					Style::default()
+				},
+			),
+		]));
+	}
+	render_list_popup(app, frame, rect, "Receive", lines, Alignment::Left);
+}
+
+/// Renders the keys that expire soon.
+fn render_expiring_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let (days, keys) = match &app.expiring_keys {
+		Some(expiring_keys) => expiring_keys,
+		None => return,
+	};
+	let key_width = keys
+		.iter()
+		.map(|(key_id, _, _)| key_id.len())
+		.max()
+		.unwrap_or_default();
+	let mut lines = vec![Line::from("")];
+	for (key_id, user_id, remaining) in keys {
+		lines.push(Line::from(vec![
+			Span::raw(format!("{key_id:<key_width$}  ")),
+			Span::styled(
+				format!("{remaining:>4}d"),
+				if app.state.style.is_colored() {
+					Style::default().fg(if *remaining < 7 {
+						Color::Red
+					} else {
+						Color::Yellow
+					})
+				} else {
+					Style::default()
+				},
+			),
+			Span::raw(format!("  {user_id}")),
+		]));
+	}
+	render_list_popup(
+		app,
+		frame,
+		rect,
+		&format!("Expiring within {days} days"),
+		lines,
+		Alignment::Left,
+	);
+}
+
+/// Renders the recorded actions of the selected key.
+fn render_history_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let (key_id, entries) = match &app.key_history {
+		Some(key_history) => key_history,
+		None => return,
+	};
+	let mut lines = vec![Line::from("")];
+	for entry in entries {
+		lines.push(Line::from(vec![
+			Span::styled(
+				format!("{}  ", entry.time),
+				Style::default().fg(app.state.style.get_dim_color()),
+			),
+			Span::raw(entry.action.to_string()),
+		]));
+	}
+	render_list_popup(
+		app,
+		frame,
+		rect,
+		&format!("History of {key_id}"),
+		lines,
+		Alignment::Left,
+	);
+}
+
+/// Renders the algorithm preferences of each user ID.
+fn render_preferences_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let (key_id, preferences) = match &app.key_preferences {
+		Some(key_preferences) => key_preferences,
+		None => return,
+	};
+	let mut lines = Vec::new();
+	for (user_id, values) in preferences {
+		lines.push(Line::from(""));
+		lines.push(Line::from(Span::styled(
+			user_id.to_string(),
+			Style::default().add_modifier(Modifier::BOLD),
+		)));
+		lines.extend(values.iter().map(|v| Line::from(format!("  {v}"))));
+	}
+	lines.push(Line::from(""));
+	lines.push(Line::from(Span::styled(
+		format!("run :prefs {key_id} update to use the modern defaults"),
+		Style::default().fg(app.state.style.get_dim_color()),
+	)));
+	render_list_popup(
+		app,
+		frame,
+		rect,
+		&format!("Preferences of {key_id}"),
+		lines,
+		Alignment::Left,
+	);
+}
+
+/// Renders the QR code of the selection on the whole screen.
+///
+/// Colors are fixed (dark on light) so that the code can be scanned
+/// regardless of the terminal theme.
+fn render_qr_code_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let (title, qr_code) = match &app.qr_code {
+		Some(qr_code) => qr_code,
+		None => return,
+	};
+	let lines = qr_code.lines().collect::<Vec<&str>>();
+	let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+	frame.render_widget(Clear, rect);
+	let block = get_block(app)
+		.title(format!("QR code of the {title} (press any key to close)"))
+		.title_alignment(Alignment::Center)
+		.borders(Borders::ALL);
+	let area = block.inner(rect);
+	frame.render_widget(block, rect);
+	if lines.len() > usize::from(area.height) || width > usize::from(area.width)
+	{
+		frame.render_widget(
+			Paragraph::new(format!(
+				"terminal is too small for the QR code ({width}x{})",
+				lines.len()
+			))
+			.alignment(Alignment::Center),
+			area,
+		);
+		return;
+	}
+	let area = Rect::new(
+		area.x + (area.width - width as u16) / 2,
+		area.y + (area.height - lines.len() as u16) / 2,
+		width as u16,
+		lines.len() as u16,
+	);
+	frame.render_widget(
+		Paragraph::new(qr_code.as_str())
+			.style(Style::default().fg(Color::Black).bg(Color::White)),
+		area,
+	);
+}
+
+/// Renders the compared fingerprints with the differing groups highlighted.
+fn render_comparison_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let groups = match &app.fingerprint_comparison {
+		Some(groups) => groups,
+		None => return,
+	};
+	let highlight_style = if app.state.style.is_colored() {
+		Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
 	} else {
-		KEYS_ROW_LENGTH.1
+		Style::default().add_modifier(Modifier::REVERSED)
 	};
-	frame.render_stateful_widget(
-		Table::new(
-			get_keys_table_rows(
-				app,
-				rect.width
-					.checked_sub(keys_row_length + 7)
-					.unwrap_or(rect.width),
-				rect.height.checked_sub(2).unwrap_or(rect.height),
+	let mut lines = vec![Line::from("")];
+	for (title, is_given) in [("key:   ", false), ("given: ", true)] {
+		let mut spans = vec![Span::raw(title)];
+		for (expected, given) in groups {
+			spans.push(Span::styled(
+				format!("{:_<4}", if is_given { given } else { expected }),
+				if expected == given {
+					Style::default()
+				} else {
+					highlight_style
+				},
+			));
+			spans.push(Span::raw(" "));
+		}
+		lines.push(Line::from(spans));
+	}
+	render_list_popup(
+		app,
+		frame,
+		rect,
+		"Fingerprint mismatch",
+		lines,
+		Alignment::Left,
+	);
+}
+
+/// Renders the current step of the key generation wizard.
+fn render_wizard_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let wizard = match &app.wizard {
+		Some(wizard) => wizard,
+		None => return,
+	};
+	let mut lines = vec![
+		Line::from(""),
+		Line::from(Span::styled(
+			wizard.step.to_string(),
+			Style::default().add_modifier(Modifier::BOLD),
+		)),
+		Line::from(""),
+	];
+	for (i, (item, enabled)) in wizard.get_items().into_iter().enumerate() {
+		let marker = match (wizard.step, enabled) {
+			(WizardStep::Usage, true) => "[x]",
+			(WizardStep::Usage, false) => "[ ]",
+			(_, true) => "(*)",
+			(_, false) => "( )",
+		};
+		lines.push(Line::from(Span::styled(
+			format!(
+				"{}{marker} {item}",
+				if i == wizard.selected { "> " } else { "  " }
 			),
-			&[
-				Constraint::Min(keys_row_length),
-				Constraint::Percentage(100),
-			],
-		)
-		.style(Style::default().fg(app.state.color))
-		.highlight_style(if app.state.style.is_colored() {
-			Style::default().add_modifier(Modifier::BOLD)
+			if i == wizard.selected {
+				Style::default().add_modifier(Modifier::BOLD)
+			} else {
+				Style::default()
+			},
+		)));
+	}
+	if let Some(input) = wizard.get_input() {
+		lines.push(Line::from(format!(
+			"> {}_",
+			if wizard.step == WizardStep::Passphrase {
+				wizard.passphrase.get_masked()
+			} else {
+				input.to_string()
+			}
+		)));
+	}
+	if wizard.step == WizardStep::Passphrase
+		&& wizard.check_passphrase
+		&& !wizard.passphrase.is_empty()
+	{
+		let estimate = wizard.passphrase.get_strength();
+		lines.push(Line::from(""));
+		lines.push(Line::from(Span::styled(
+			format!("strength: {}", estimate.strength),
+			if app.state.style.is_colored() {
+				Style::default().fg(match estimate.strength {
+					Strength::Weak => Color::Red,
+					Strength::Fair => Color::Yellow,
+					Strength::Good | Strength::Strong => Color::Green,
+				})
+			} else {
+				Style::default()
+			},
+		)));
+		lines.extend(estimate.warnings.into_iter().map(|warning| {
+			Line::from(Span::styled(
+				format!("- {warning}"),
+				Style::default().fg(app.state.style.get_dim_color()),
+			))
+		}));
+	}
+	if let Some(error) = &wizard.error {
+		lines.push(Line::from(""));
+		lines.push(Line::from(Span::styled(
+			error.to_string(),
+			if app.state.style.is_colored() {
+				Style::default().fg(Color::Red)
+			} else {
+				Style::default().add_modifier(Modifier::BOLD)
+			},
+		)));
+	}
+	lines.push(Line::from(""));
+	lines.push(Line::from(Span::styled(
+		if wizard.step.is_input() {
+			"enter: next, shift-tab: back, esc: cancel"
+		} else {
+			"space: select, enter: next, shift-tab: back, esc: cancel"
+		},
+		Style::default().fg(app.state.style.get_dim_color()),
+	)));
+	let area = get_popup_area(&lines, rect);
+	frame.render_widget(Clear, area);
+	frame.render_widget(
+		Paragraph::new(lines)
+			.block(
+				get_block(app)
+					.title(format!(
+						"{} ({}/{})",
+						if wizard.key_id.is_some() {
+							"Add subkey"
+						} else {
+							"Generate key"
+						},
+						wizard.get_step_index() + 1,
+						wizard.get_steps().len()
+					))
+					.title_alignment(Alignment::Center)
+					.style(if app.state.style.is_colored() {
+						Style::default().fg(Color::LightBlue)
+					} else {
+						Style::default()
+					})
+					.borders(Borders::ALL),
+			)
+			.style(app.state.style.get_text_style(app.state.color))
+			.alignment(Alignment::Left),
+		area,
+	);
+}
+
+/// Renders the built-in file browser.
+fn render_file_browser_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let block = get_block(app)
+		.style(if app.state.style.is_colored() {
+			Style::default().fg(Color::LightBlue)
 		} else {
 			Style::default()
-				.fg(Color::Reset)
-				.add_modifier(Modifier::BOLD)
 		})
-		.highlight_symbol("> ")
-		.block(
-			Block::default()
-				.borders(Borders::ALL)
-				.border_style(Style::default().fg(Color::DarkGray)),
-		)
-		.column_spacing(1),
+		.borders(Borders::ALL);
+	let text_style = app.state.style.get_text_style(app.state.color);
+	let highlight_style = get_highlight_style(app);
+	let hint_style = Style::default().fg(app.state.style.get_dim_color());
+	let browser = match app.file_browser.as_mut() {
+		Some(browser) => browser,
+		None => return,
+	};
+	let mut items = browser
+		.entries
+		.items
+		.iter()
+		.map(|entry| {
+			let marker = if entry.is_dir {
+				"    "
+			} else if browser.is_selected(&entry.path) {
+				"[x] "
+			} else {
+				"[ ] "
+			};
+			ListItem::new(format!("{marker}{}", entry.get_name()))
+		})
+		.collect::<Vec<ListItem>>();
+	if items.is_empty() {
+		items.push(ListItem::new(
+			browser
+				.error
+				.clone()
+				.unwrap_or_else(|| String::from("(empty)")),
+		));
+	}
+	let width = rect.width.min(70);
+	let height = rect.height.min(items.len() as u16 + 5).max(rect.height / 2);
+	let area = Rect::new(
+		rect.x + (rect.width - width) / 2,
+		rect.y + (rect.height - height) / 2,
+		width,
+		height,
+	);
+	let chunks = Layout::default()
+		.direction(Direction::Vertical)
+		.margin(1)
+		.constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+		.split(area);
+	frame.render_widget(Clear, area);
+	frame.render_widget(
+		block
+			.title(format!(
+				"{} ({})",
+				if browser.encrypt { "Encrypt" } else { "Import" },
+				browser.dir.to_string_lossy()
+			))
+			.title_alignment(Alignment::Center),
+		area,
+	);
+	frame.render_stateful_widget(
+		List::new(items)
+			.style(text_style)
+			.highlight_style(highlight_style)
+			.highlight_symbol(app.state.theme.get_highlight_symbol()),
+		chunks[0],
+		&mut browser.entries.state,
+	);
+	frame.render_widget(
+		Paragraph::new(Span::styled(
+			"space: select, enter: open, backspace: up, esc: cancel",
+			hint_style,
+		))
+		.alignment(Alignment::Center),
+		chunks[1],
+	);
+}
+
+/// Renders the confirmation popup for the pending command.
+fn render_confirmation_popup(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let command = match &app.prompt.command {
+		Some(command) => command,
+		None => return,
+	};
+	let mut lines = vec![
+		Line::from(""),
+		Line::from(Span::styled(
+			format!("{command}?"),
+			Style::default().add_modifier(Modifier::BOLD),
+		)),
+	];
+	let key_id = match command {
+		Command::DeleteKey(_, key_id)
+		| Command::SendKey(key_id, _)
+		| Command::RevokeKey(key_id, _)
+		| Command::ApplyRevocation(key_id, _) => Some(key_id),
+		_ => None,
+	};
+	if let Some(key) = key_id.and_then(|key_id| {
+		app.keys.values().flatten().find(|key| {
+			key.get_id().eq_ignore_ascii_case(key_id)
+				|| key.get_fingerprint().eq_ignore_ascii_case(key_id)
+		})
+	}) {
+		lines.push(Line::from(""));
+		lines.push(Line::from(key.get_user_id()));
+		lines.push(Line::from(key.get_fingerprint()));
+		if let Command::SendKey(_, _) = command {
+			let warnings = key.get_publish_warnings();
+			if !warnings.is_empty() {
+				lines.push(Line::from(""));
+				lines.push(Line::from(Span::styled(
+					"warning: the key contains private information",
+					Style::default()
+						.fg(if app.state.style.is_colored() {
+							Color::Yellow
+						} else {
+							Color::Reset
+						})
+						.add_modifier(Modifier::BOLD),
+				)));
+				lines.extend(warnings.into_iter().map(Line::from));
+			}
+		}
+	}
+	if let Command::ForceQuit = command {
+		lines.push(Line::from(""));
+		lines.extend(app.get_background_operations().into_iter().map(
+			|operation| Line::from(format!("{operation} is in progress")),
+		));
+	}
+	if let Command::ImportFetched(_, fingerprints, _) = command {
+		lines.push(Line::from(""));
+		lines.extend(fingerprints.iter().map(|v| Line::from(v.to_string())));
+	}
+	let button_style = |selected: bool| {
+		if selected {
+			Style::default()
+				.fg(if app.state.style.is_colored() {
+					Color::LightBlue
+				} else {
+					Color::Reset
+				})
+				.add_modifier(Modifier::BOLD | Modifier::REVERSED)
+		} else {
+			Style::default()
+		}
+	};
+	lines.push(Line::from(""));
+	lines.push(Line::from(vec![
+		Span::styled("[ Yes ]", button_style(app.prompt.confirm)),
+		Span::raw("  "),
+		Span::styled("[ No ]", button_style(!app.prompt.confirm)),
+	]));
+	let area = get_popup_area(&lines, rect);
+	frame.render_widget(Clear, area);
+	frame.render_widget(
+		Paragraph::new(lines)
+			.block(
+				get_block(app)
+					.title("Confirm")
+					.title_alignment(Alignment::Center)
+					.style(if app.state.style.is_colored() {
+						Style::default().fg(Color::LightBlue)
+					} else {
+						Style::default()
+					})
+					.borders(Borders::ALL),
+			)
+			.style(app.state.style.get_text_style(app.state.color))
+			.alignment(Alignment::Center),
+		area,
+	);
+}
+
+/// Renders the table of keys.
+fn render_keys_table(app: &mut App, frame: &mut Frame, rect: Rect) {
+	let mut keys_row_length = match app.keys_table.state.size {
+		TableSize::Normal => KEYS_ROW_LENGTH.1,
+		_ => KEYS_ROW_LENGTH.0,
+	};
+	if show_checkbox(app) {
+		keys_row_length += CHECKBOX_LENGTH;
+	}
+	if app.gpgme.config.validate {
+		keys_row_length += VALIDITY_FLAG_LENGTH;
+	}
+	let mut constraints = vec![
+		Constraint::Min(keys_row_length),
+		Constraint::Percentage(100),
+	];
+	let mut reserved_length = keys_row_length + 7;
+	if app.state.relative_dates {
+		constraints.push(Constraint::Length(COUNTDOWN_LENGTH));
+		reserved_length += COUNTDOWN_LENGTH + 1;
+	}
+	let max_height = rect.height.checked_sub(2).unwrap_or(rect.height);
+	let (mut rows, row_height) = get_keys_table_rows(
+		app,
+		rect.width
+			.checked_sub(reserved_length)
+			.unwrap_or(rect.width),
+		max_height,
+	);
+	let mut block = get_block(app)
+		.borders(Borders::ALL)
+		.border_style(get_border_style(app));
+	let mut table_state = app.keys_table.state.tui.clone();
+	if app.keys_table.state.paging {
+		let page_size =
+			cmp::max(max_height / (row_height + app.keys_table_margin), 1)
+				.into();
+		app.keys_table.state.page_size = page_size;
+		let (page, page_count) = app.keys_table.get_page();
+		rows = rows.into_iter().skip((page - 1) * page_size).collect();
+		rows.truncate(page_size);
+		table_state = table_state
+			.with_offset(0)
+			.with_selected(table_state.selected().map(|i| i % page_size));
+		block = block
+			.title(format!("page {page}/{page_count}"))
+			.title_alignment(Alignment::Right)
+			.title_style(app.state.style.get_text_style(app.state.color));
+	}
+	frame.render_stateful_widget(
+		Table::new(rows, &constraints)
+			.style(app.state.style.get_text_style(app.state.color))
+			.highlight_style(get_highlight_style(app))
+			.highlight_symbol(app.state.theme.get_highlight_symbol())
+			.block(block)
+			.column_spacing(1),
 		rect,
-		&mut app.keys_table.state.tui,
+		if app.keys_table.state.paging {
+			&mut table_state
+		} else {
+			&mut app.keys_table.state.tui
+		},
 	);
 }
 
-/// Returns the rows for keys table.
+/// Checks if the checkbox is shown for the keys.
+///
+/// It is shown for the recipients in pick mode and
+/// for the marked keys in visual mode.
+fn show_checkbox(app: &App) -> bool {
+	app.mode == Mode::Pick
+		|| (app.mode == Mode::Visual && !app.keys_table.marked.is_empty())
+}
+
+/// Returns the rows for keys table along with the maximum row height.
+///
+/// Rows are built from the prepared (filtered) rows of the keys table.
 fn get_keys_table_rows<'a>(
 	app: &mut App,
 	max_width: u16,
 	max_height: u16,
-) -> Vec<Row<'a>> {
+) -> (Vec<Row<'a>>, u16) {
 	let mut rows = Vec::new();
-	app.keys_table.items = app
-		.keys_table
-		.items
-		.clone()
-		.into_iter()
-		.enumerate()
-		.filter(|(i, key)| {
-			let subkey_info = key.get_subkey_info(
-				app.gpgme.config.default_key.as_deref(),
-				app.keys_table.state.size != TableSize::Normal,
-			);
-			let user_info = key.get_user_info(
-				app.keys_table.state.size == TableSize::Minimized,
-			);
-			if app.prompt.is_search_enabled() {
-				let search_term =
-					app.prompt.text.replacen('/', "", 1).to_lowercase();
-				if !subkey_info.join("\n").to_lowercase().contains(&search_term)
-					&& !user_info
-						.join("\n")
-						.to_lowercase()
-						.contains(&search_term)
-				{
-					return false;
-				}
+	let mut row_height = 1;
+	app.update_keys_table_rows();
+	let key_rows = app.keys_table_rows.as_deref().unwrap_or_default();
+	for (i, (key, key_row)) in
+		app.keys_table.items.iter().zip(key_rows).enumerate()
+	{
+		let collapsed = key_row.collapsed;
+		let subkey_info = if show_checkbox(app) {
+			let checked = if app.mode == Mode::Pick {
+				app.recipients.contains(&key.get_id())
+			} else {
+				app.keys_table.is_marked(&key.get_id())
+			};
+			let checkbox = if checked { "[x] " } else { "[ ] " };
+			key_row
+				.subkey_info
+				.iter()
+				.enumerate()
+				.map(|(i, line)| {
+					if i == 0 {
+						format!("{checkbox}{line}")
+					} else {
+						format!("    {line}")
+					}
+				})
+				.collect()
+		} else {
+			key_row.subkey_info.clone()
+		};
+		let keys_row = RowItem::new(
+			subkey_info,
+			None,
+			max_height,
+			app.keys_table.state.scroll,
+		);
+		let users_row = RowItem::new(
+			key_row.user_info.clone(),
+			Some(max_width),
+			max_height,
+			app.keys_table.state.scroll,
+		);
+		let mut cells = if app.state.style.is_colored() {
+			let highlighted = app.keys_table.state.tui.selected() == Some(i);
+			vec![
+				style::get_colored_table_row(&keys_row.data, highlighted),
+				style::get_colored_table_row(&users_row.data, highlighted),
+			]
+		} else {
+			vec![
+				Text::from(keys_row.data.join("\n")),
+				Text::from(users_row.data.join("\n")),
+			]
+		};
+		if app.state.ascii {
+			cells = cells.into_iter().map(style::get_ascii_text).collect();
+		}
+		if app.state.relative_dates {
+			let mut expiration_info = key.get_expiration_info();
+			if collapsed {
+				expiration_info.truncate(2);
 			}
-			let keys_row = RowItem::new(
-				subkey_info,
+			let expiration_row = RowItem::new(
+				expiration_info,
 				None,
 				max_height,
 				app.keys_table.state.scroll,
 			);
-			let users_row = RowItem::new(
-				user_info,
-				Some(max_width),
-				max_height,
-				app.keys_table.state.scroll,
-			);
-			rows.push(
-				Row::new(if app.state.style.is_colored() {
-					let highlighted =
-						app.keys_table.state.tui.selected() == Some(*i);
-					vec![
-						style::get_colored_table_row(
-							&keys_row.data,
-							highlighted,
-						),
-						style::get_colored_table_row(
-							&users_row.data,
-							highlighted,
-						),
-					]
-				} else {
-					vec![
-						Text::from(keys_row.data.join("\n")),
-						Text::from(users_row.data.join("\n")),
-					]
-				})
-				.height(
-					cmp::max(keys_row.data.len(), users_row.data.len())
-						.try_into()
-						.unwrap_or(1),
-				)
-				.bottom_margin(app.keys_table_margin)
-				.style(Style::default()),
-			);
-			true
-		})
-		.map(|(_, v)| v)
-		.collect();
-	rows
+			cells.push(if app.state.style.is_colored() {
+				style::get_colored_expiration_row(&expiration_row.data)
+			} else {
+				Text::from(expiration_row.data.join("\n"))
+			});
+		}
+		let height: u16 = cmp::max(keys_row.data.len(), users_row.data.len())
+			.try_into()
+			.unwrap_or(1);
+		row_height = cmp::max(row_height, height);
+		let row = match app
+			.state
+			.theme
+			.row_separator
+			.as_ref()
+			.filter(|_| app.keys_table_margin != 0)
+		{
+			Some(separator) => {
+				// draw the separator in the first line of the margin
+				let separator = Line::styled(
+					separator.repeat(usize::from(max_width.saturating_add(
+						KEYS_ROW_LENGTH.1
+							+ CHECKBOX_LENGTH + VALIDITY_FLAG_LENGTH,
+					))),
+					Style::default().fg(app.state.style.get_dim_color()),
+				);
+				for cell in cells.iter_mut() {
+					cell.lines.resize(usize::from(height), Line::default());
+					cell.lines.push(separator.clone());
+				}
+				Row::new(cells)
+					.height(height + 1)
+					.bottom_margin(app.keys_table_margin - 1)
+			}
+			None => Row::new(cells)
+				.height(height)
+				.bottom_margin(app.keys_table_margin),
+		};
+		rows.push(row.style(Style::default()));
+	}
+	(rows, row_height)
 }
 
 #[cfg(feature = "gpg-tests")]
@@ -1,5 +1,7 @@
 use clap::ValueEnum;
+use serde_json::Value;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
 
 /// Application property to copy to clipboard.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
@@ -21,6 +23,166 @@ pub enum Selection {
 	/// User ID of the selected key.
 	#[clap(aliases = ["user", "user_id", "userid", "user-id", "key_user_id", "keyuserid"])]
 	UserId,
+	/// Authentication subkey of the selected key in SSH format.
+	#[clap(aliases = ["ssh", "ssh_key", "sshkey"])]
+	SshKey,
+	/// Contact card (vCard) of the selected key.
+	#[clap(aliases = ["vcf", "contact"])]
+	Vcard,
+	/// Fingerprints of the visible (or picked) keys.
+	#[clap(aliases = ["fprs", "all_fingerprints"])]
+	Fingerprints,
+	/// Creation time of the selected key. (ISO 8601)
+	#[clap(aliases = ["creation", "creation_time", "created_at"])]
+	Created,
+	/// Expiration time of the selected key. (ISO 8601)
+	#[clap(aliases = ["expiry", "expiration", "expiration_time", "expires_at"])]
+	Expires,
+	/// Selected line of the key detail view.
+	#[clap(aliases = ["line", "detail", "detail_line"])]
+	DetailLine,
+}
+
+/// Output format of the selection mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectFormat {
+	/// Raw value of the selection.
+	Plain,
+	/// JSON object of the selected key's fields.
+	Json,
+	/// Template with the placeholders of the selected key's fields.
+	///
+	/// e.g. `{id}\t{user_id}\t{fingerprint}`
+	Template(String),
+}
+
+impl FromStr for SelectFormat {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"plain" => Ok(Self::Plain),
+			"json" => Ok(Self::Json),
+			_ if s.contains('{') => {
+				Ok(Self::Template(s.replace("\\t", "\t").replace("\\n", "\n")))
+			}
+			_ => Err(format!(
+				"invalid format: {s} (expected plain, json or a template)"
+			)),
+		}
+	}
+}
+
+impl SelectFormat {
+	/// Renders the given template with the fields of a key.
+	///
+	/// Placeholders (e.g. `{id}`) are replaced with the values
+	/// of the fields and arrays are joined with commas.
+	pub fn render(template: &str, fields: &Value) -> String {
+		let mut output = template.to_string();
+		if let Some(fields) = fields.as_object() {
+			for (name, value) in fields {
+				let value = match value {
+					Value::Null => String::new(),
+					Value::String(v) => v.to_string(),
+					Value::Array(values) => values
+						.iter()
+						.map(|v| {
+							v.as_str().map(String::from).unwrap_or_default()
+						})
+						.collect::<Vec<String>>()
+						.join(","),
+					v => v.to_string(),
+				};
+				output = output.replace(&format!("{{{name}}}"), &value);
+			}
+		}
+		output
+	}
+}
+
+impl Default for SelectFormat {
+	fn default() -> Self {
+		Self::Plain
+	}
+}
+
+/// Format of the copied fingerprints.
+///
+/// It is parsed from a comma-separated list of options,
+/// e.g. `spaced,lowercase` or `colon,0x`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FingerprintFormat {
+	/// Separator and size of the character groups.
+	pub grouping: Option<(char, usize)>,
+	/// Whether if the `0x` prefix is added.
+	pub prefix: bool,
+	/// Whether if lowercase letters are used.
+	pub lowercase: bool,
+}
+
+impl FromStr for FingerprintFormat {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut format = Self::default();
+		for option in s.split(',').map(|v| v.trim().to_lowercase()) {
+			match option.as_str() {
+				"plain" => {}
+				"spaced" => format.grouping = Some((' ', 4)),
+				"colon" => format.grouping = Some((':', 2)),
+				"0x" | "prefix" => format.prefix = true,
+				"lowercase" | "lower" => format.lowercase = true,
+				"uppercase" | "upper" => format.lowercase = false,
+				_ => return Err(format!("invalid option: {option}")),
+			}
+		}
+		Ok(format)
+	}
+}
+
+impl Display for FingerprintFormat {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		let mut options = Vec::new();
+		match self.grouping {
+			Some((':', _)) => options.push("colon"),
+			Some(_) => options.push("spaced"),
+			None => {}
+		}
+		if self.prefix {
+			options.push("0x");
+		}
+		if self.lowercase {
+			options.push("lowercase");
+		}
+		if options.is_empty() {
+			options.push("plain");
+		}
+		write!(f, "{}", options.join(","))
+	}
+}
+
+impl FingerprintFormat {
+	/// Formats the given fingerprint.
+	pub fn format(&self, fingerprint: &str) -> String {
+		let mut fingerprint = if self.lowercase {
+			fingerprint.to_lowercase()
+		} else {
+			fingerprint.to_uppercase()
+		};
+		if let Some((separator, size)) = self.grouping {
+			fingerprint = fingerprint
+				.chars()
+				.collect::<Vec<char>>()
+				.chunks(size)
+				.map(|group| group.iter().collect::<String>())
+				.collect::<Vec<String>>()
+				.join(&separator.to_string());
+		}
+		if self.prefix {
+			format!("0x{fingerprint}")
+		} else {
+			fingerprint
+		}
+	}
 }
 
 impl Display for Selection {
@@ -35,6 +197,12 @@ impl Display for Selection {
 				Self::KeyId => String::from("key ID"),
 				Self::KeyFingerprint => String::from("key fingerprint"),
 				Self::UserId => String::from("user ID"),
+				Self::SshKey => String::from("SSH key"),
+				Self::Vcard => String::from("vCard"),
+				Self::Fingerprints => String::from("key fingerprints"),
+				Self::Created => String::from("creation time"),
+				Self::Expires => String::from("expiration time"),
+				Self::DetailLine => String::from("detail line"),
 			}
 		)
 	}
@@ -64,6 +232,56 @@ mod tests {
 		let copy_type = Selection::from_str("key_user_id", true)?;
 		assert_eq!(Selection::UserId, copy_type);
 		assert_eq!(String::from("user ID"), copy_type.to_string());
+		let copy_type = Selection::from_str("ssh", true)?;
+		assert_eq!(Selection::SshKey, copy_type);
+		assert_eq!(String::from("SSH key"), copy_type.to_string());
+		let copy_type = Selection::from_str("vcf", true)?;
+		assert_eq!(Selection::Vcard, copy_type);
+		assert_eq!(String::from("vCard"), copy_type.to_string());
+		let copy_type = Selection::from_str("fingerprints", true)?;
+		assert_eq!(Selection::Fingerprints, copy_type);
+		assert_eq!(String::from("key fingerprints"), copy_type.to_string());
+		let copy_type = Selection::from_str("created", true)?;
+		assert_eq!(Selection::Created, copy_type);
+		assert_eq!(String::from("creation time"), copy_type.to_string());
+		let copy_type = Selection::from_str("expiry", true)?;
+		assert_eq!(Selection::Expires, copy_type);
+		assert_eq!(String::from("expiration time"), copy_type.to_string());
+		let copy_type = Selection::from_str("line", true)?;
+		assert_eq!(Selection::DetailLine, copy_type);
+		assert_eq!(String::from("detail line"), copy_type.to_string());
+		assert_eq!(SelectFormat::Json, SelectFormat::from_str("json")?);
+		assert_eq!(SelectFormat::Plain, SelectFormat::default());
+		let format = SelectFormat::from_str("{id}\\t{user_ids}")?;
+		assert_eq!(
+			SelectFormat::Template(String::from("{id}\t{user_ids}")),
+			format
+		);
+		assert_eq!(
+			"0x00\ta,b {expires} false",
+			SelectFormat::render(
+				"{id}\t{user_ids} {expires} {revoked}",
+				&serde_json::json!({
+					"id": "0x00",
+					"user_ids": ["a", "b"],
+					"revoked": false,
+				})
+			)
+		);
+		assert!(SelectFormat::from_str("xml").is_err());
+		let fingerprint = "1234567890abcdef";
+		let format = FingerprintFormat::from_str("spaced,0x")?;
+		assert_eq!("0x1234 5678 90AB CDEF", format.format(fingerprint));
+		assert_eq!("spaced,0x", format.to_string());
+		let format = FingerprintFormat::from_str("colon, lowercase")?;
+		assert_eq!("12:34:56:78:90:ab:cd:ef", format.format(fingerprint));
+		assert_eq!("colon,lowercase", format.to_string());
+		assert_eq!(
+			FingerprintFormat::default(),
+			FingerprintFormat::from_str("plain")?
+		);
+		assert_eq!("plain", FingerprintFormat::default().to_string());
+		assert!(FingerprintFormat::from_str("dashed").is_err());
 		Ok(())
 	}
 }
@@ -0,0 +1,180 @@
+use crate::gpg::http;
+use anyhow::{anyhow, Result};
+use std::io::{ErrorKind, Read, Result as IoResult, Write};
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream, UdpSocket};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default duration of serving a key. (in seconds)
+pub const SERVE_TIMEOUT: u64 = 120;
+
+/// Default address for serving keys.
+pub const DEFAULT_SERVE_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// Interval of checking for the incoming connections.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Timeout of reading the request and writing the response.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves the given content over HTTP until the timeout is reached.
+///
+/// The server listens on the given address (on a random port) in a
+/// separate thread and each connection is handled in its own thread.
+/// Unspecified address (`0.0.0.0`) can be used for fetching the content
+/// from another device on the local network. Returns the URL of the content.
+pub fn serve(
+	content: String,
+	file_name: String,
+	address: IpAddr,
+	timeout: Duration,
+) -> Result<String> {
+	let listener = TcpListener::bind((address, 0))?;
+	listener.set_nonblocking(true)?;
+	let url = format!(
+		"http://{}:{}/{file_name}",
+		if address.is_unspecified() {
+			get_local_address()
+		} else {
+			address
+		},
+		listener.local_addr()?.port()
+	);
+	let content = Arc::new(content);
+	thread::spawn(move || {
+		let deadline = Instant::now() + timeout;
+		while Instant::now() < deadline {
+			match listener.accept() {
+				Ok((stream, address)) => {
+					log::info!(target: "serve", "request from {address}");
+					let content = Arc::clone(&content);
+					let file_name = file_name.clone();
+					thread::spawn(move || {
+						if let Err(e) = respond(stream, &content, &file_name) {
+							log::warn!(
+								target: "serve",
+								"failed to respond: {e}"
+							);
+						}
+					});
+				}
+				Err(e) if e.kind() == ErrorKind::WouldBlock => {
+					thread::sleep(POLL_INTERVAL);
+				}
+				Err(e) => {
+					log::error!(target: "serve", "{e}");
+					break;
+				}
+			}
+		}
+		log::info!(target: "serve", "stopped serving {file_name}");
+	});
+	Ok(url)
+}
+
+/// Responds to the HTTP request with the given content.
+///
+/// Only the path of the served file is available.
+fn respond(
+	mut stream: TcpStream,
+	content: &str,
+	file_name: &str,
+) -> IoResult<()> {
+	stream.set_nonblocking(false)?;
+	stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+	stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+	let mut buffer = [0; 1024];
+	let length = stream.read(&mut buffer)?;
+	let request = String::from_utf8_lossy(&buffer[..length]);
+	let mut request_line =
+		request.lines().next().unwrap_or_default().split(' ');
+	let response = match (request_line.next(), request_line.next()) {
+		(Some("GET"), Some(path))
+			if path.strip_prefix('/') == Some(file_name) =>
+		{
+			format!(
+				"HTTP/1.1 200 OK\r\n\
+				Content-Type: application/pgp-keys\r\n\
+				Content-Disposition: inline; filename=\"{file_name}\"\r\n\
+				Content-Length: {}\r\n\
+				Connection: close\r\n\r\n{content}",
+				content.len()
+			)
+		}
+		(Some("GET"), _) => String::from(
+			"HTTP/1.1 404 Not Found\r\n\
+			Content-Length: 0\r\n\
+			Connection: close\r\n\r\n",
+		),
+		_ => String::from(
+			"HTTP/1.1 405 Method Not Allowed\r\n\
+			Content-Length: 0\r\n\
+			Connection: close\r\n\r\n",
+		),
+	};
+	stream.write_all(response.as_bytes())
+}
+
+/// Fetches the content of the given URL.
+///
+/// It is meant for fetching the keys that are served on the
+/// local network (via `:serve`) but HTTPS is also supported.
+pub fn fetch(url: &str) -> Result<Vec<u8>> {
+	if url.starts_with("http://") || url.starts_with("https://") {
+		http::send_request(url, None)
+	} else {
+		Err(anyhow!("unsupported URL (expected http:// or https://)"))
+	}
+}
+
+/// Returns the address of the machine on the local network.
+///
+/// Address is determined by the route of a UDP socket
+/// (no packets are sent) and falls back to localhost.
+fn get_local_address() -> IpAddr {
+	UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+		.and_then(|socket| {
+			socket.connect((Ipv4Addr::new(10, 254, 254, 254), 1))?;
+			socket.local_addr()
+		})
+		.map(|address| address.ip())
+		.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_app_server() -> Result<()> {
+		let url = serve(
+			String::from("test"),
+			String::from("0x0.asc"),
+			DEFAULT_SERVE_ADDRESS,
+			Duration::from_secs(5),
+		)?;
+		assert!(url.starts_with("http://127.0.0.1:"));
+		let port = url
+			.trim_end_matches("/0x0.asc")
+			.rsplit_once(':')
+			.map(|(_, port)| port.to_string())
+			.unwrap_or_default();
+		assert_eq!(
+			b"test".to_vec(),
+			fetch(&format!("http://127.0.0.1:{port}/0x0.asc"))?
+		);
+		let mut stream = TcpStream::connect(format!("127.0.0.1:{port}"))?;
+		stream.write_all(b"POST / HTTP/1.1\r\n\r\n")?;
+		let mut response = String::new();
+		stream.read_to_string(&mut response)?;
+		assert!(response.starts_with("HTTP/1.1 405"));
+		let mut stream = TcpStream::connect(format!("127.0.0.1:{port}"))?;
+		stream.write_all(b"GET /etc/passwd HTTP/1.1\r\n\r\n")?;
+		let mut response = String::new();
+		stream.read_to_string(&mut response)?;
+		assert!(response.starts_with("HTTP/1.1 404"));
+		assert!(fetch("ftp://example.org/key.asc").is_err());
+		Ok(())
+	}
+}
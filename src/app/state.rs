@@ -1,10 +1,20 @@
-use crate::app::selection::Selection;
+use crate::app::clipboard::{DEFAULT_COPY_COMMAND, DEFAULT_PASTE_COMMAND};
+use crate::app::selection::{FingerprintFormat, SelectFormat, Selection};
+use crate::app::server::DEFAULT_SERVE_ADDRESS;
 use crate::app::style::Style;
+use crate::app::util::DEFAULT_FILE_EXPLORER;
 use crate::args::Args;
-use crate::gpg::key::KeyDetail;
+use crate::config::{CustomKeyBinding, KeyGenProfile, ThemeConfig};
+use crate::gpg::handler::RELATIVE_DATE_FORMAT;
+use crate::gpg::key::{KeyDetail, KeyFilter};
+use crate::term::graphics::GraphicsProtocol;
 use crate::widget::style::Color;
+use crate::widget::table::{TableSize, TableState};
 use log::LevelFilter;
 use ratatui::style::Color as TuiColor;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Instant;
 use tui_logger::TuiWidgetState;
 
 /// Application states (flags) for managing the launcher.
@@ -15,12 +25,16 @@ pub struct State {
 	pub style: Style,
 	/// Accent color of the app.
 	pub color: TuiColor,
+	/// Whether if only ASCII characters are used for drawing.
+	pub ascii: bool,
 	/// Is the options menu (popup) showing?
 	pub show_options: bool,
 	/// Is the splash screen showing?
 	pub show_splash: bool,
 	/// Is the selection mode enabled?
 	pub select: Option<Selection>,
+	/// Output format of the selection mode.
+	pub select_format: SelectFormat,
 	/// File explorer to run.
 	pub file_explorer: String,
 	/// Detail level for the keys table.
@@ -31,6 +45,56 @@ pub struct State {
 	pub show_logs: bool,
 	/// Logger state.
 	pub logger_state: TuiWidgetState,
+	/// Commands that are allowed to run. (restricted mode)
+	pub allowed_commands: Option<Vec<String>>,
+	/// Whether if the relative dates are shown.
+	pub relative_dates: bool,
+	/// Format of the dates.
+	pub date_format: String,
+	/// Format of the copied fingerprints.
+	pub fingerprint_format: FingerprintFormat,
+	/// Initial state of the keys table.
+	pub table_state: TableState,
+	/// Filter of the listed keys.
+	pub key_filter: Option<KeyFilter>,
+	/// Whether if the output file name is edited before exporting.
+	pub rename_output: bool,
+	/// Whether if the key details are shown on Enter.
+	pub detail_view: bool,
+	/// Whether if the strength of the entered passphrases is checked.
+	pub passphrase_check: bool,
+	/// Protocol for showing images.
+	pub graphics: GraphicsProtocol,
+	/// Command for copying to the clipboard.
+	pub copy_command: String,
+	/// Command for pasting from the clipboard.
+	pub paste_command: String,
+	/// Idle timeout (in seconds) for locking the screen.
+	pub lock_timeout: u64,
+	/// Interval (in seconds) for syncing the GnuPG configuration.
+	pub sync_interval: u64,
+	/// Time of the last configuration sync.
+	pub last_sync: Instant,
+	/// Address for serving keys.
+	pub serve_address: IpAddr,
+	/// Phrase that is required for unlocking the screen.
+	pub lock_phrase: Option<String>,
+	/// Profiles for generating keys.
+	pub keygen_profiles: HashMap<String, KeyGenProfile>,
+	/// Theme configuration.
+	pub theme: ThemeConfig,
+	/// Custom key bindings.
+	pub custom_key_bindings: Vec<CustomKeyBinding>,
+	/// Path of the configuration file.
+	pub config_file: Option<String>,
+	/// Is the screen locked?
+	pub locked: bool,
+	/// Input of the unlock phrase.
+	pub unlock_input: String,
+	/// Time of the last user activity.
+	pub last_activity: Instant,
+	/// Is the terminal focused?
+	pub focused: bool,
 }
 
 impl Default for State {
@@ -39,15 +103,42 @@ impl Default for State {
 			running: true,
 			style: Style::default(),
 			color: Color::default().get(),
+			ascii: false,
 			show_options: false,
 			show_splash: false,
 			select: None,
-			file_explorer: String::from("xplr"),
+			select_format: SelectFormat::default(),
+			file_explorer: String::from(DEFAULT_FILE_EXPLORER),
 			detail_level: KeyDetail::default(),
 			exit_message: None,
 			show_logs: false,
 			logger_state: TuiWidgetState::new()
 				.set_default_display_level(LevelFilter::Trace),
+			allowed_commands: None,
+			relative_dates: false,
+			date_format: String::from("%F"),
+			fingerprint_format: FingerprintFormat::default(),
+			table_state: TableState::default(),
+			key_filter: None,
+			rename_output: false,
+			detail_view: false,
+			passphrase_check: true,
+			graphics: GraphicsProtocol::None,
+			copy_command: String::from(DEFAULT_COPY_COMMAND),
+			paste_command: String::from(DEFAULT_PASTE_COMMAND),
+			lock_timeout: 0,
+			sync_interval: 0,
+			last_sync: Instant::now(),
+			serve_address: DEFAULT_SERVE_ADDRESS,
+			lock_phrase: None,
+			keygen_profiles: HashMap::new(),
+			theme: ThemeConfig::default(),
+			custom_key_bindings: Vec::new(),
+			config_file: None,
+			locked: false,
+			unlock_input: String::new(),
+			last_activity: Instant::now(),
+			focused: true,
 		}
 	}
 }
@@ -55,12 +146,45 @@ impl Default for State {
 impl<'a> From<&'a Args> for State {
 	fn from(args: &'a Args) -> Self {
 		State {
-			style: args.style,
-			color: args.color.get(),
+			style: if args.is_color_disabled() {
+				Style::Plain
+			} else {
+				args.style
+			},
+			color: if args.is_color_disabled() {
+				TuiColor::Reset
+			} else {
+				args.color.get()
+			},
+			ascii: args.ascii,
 			show_splash: args.splash,
 			select: args.select,
+			select_format: args.select_format.clone(),
 			file_explorer: args.file_explorer.to_string(),
 			detail_level: args.detail_level,
+			lock_timeout: args.lock_timeout,
+			sync_interval: args.sync_interval,
+			serve_address: args.serve_address.unwrap_or(DEFAULT_SERVE_ADDRESS),
+			date_format: args.date_format.to_string(),
+			fingerprint_format: args.fingerprint_format,
+			table_state: TableState {
+				size: args.table_size.unwrap_or(TableSize::Normal),
+				minimize_threshold: args.minimize_threshold.unwrap_or(
+					// keep the given table size
+					if args.table_size.is_some() {
+						0
+					} else {
+						TableState::default().minimize_threshold
+					},
+				),
+				..TableState::default()
+			},
+			rename_output: args.rename_output,
+			detail_view: args.detail_view,
+			passphrase_check: !args.no_passphrase_check,
+			graphics: args.graphics.resolve(),
+			copy_command: args.copy_command.to_string(),
+			paste_command: args.paste_command.to_string(),
 			..Self::default()
 		}
 	}
@@ -72,10 +196,88 @@ impl State {
 		let style = self.style;
 		let detail_level = self.detail_level;
 		let color = self.color;
+		let allowed_commands = self.allowed_commands.take();
+		let relative_dates = self.relative_dates;
+		let date_format = std::mem::take(&mut self.date_format);
+		let fingerprint_format = self.fingerprint_format;
+		let table_state = self.table_state.clone();
+		let rename_output = self.rename_output;
+		let detail_view = self.detail_view;
+		let passphrase_check = self.passphrase_check;
+		let graphics = self.graphics;
+		let copy_command = std::mem::take(&mut self.copy_command);
+		let paste_command = std::mem::take(&mut self.paste_command);
+		let lock_timeout = self.lock_timeout;
+		let sync_interval = self.sync_interval;
+		let serve_address = self.serve_address;
+		let lock_phrase = self.lock_phrase.take();
+		let keygen_profiles = std::mem::take(&mut self.keygen_profiles);
+		let theme = std::mem::take(&mut self.theme);
+		let custom_key_bindings = std::mem::take(&mut self.custom_key_bindings);
+		let config_file = self.config_file.take();
+		let locked = self.locked;
+		let focused = self.focused;
 		*self = Self::default();
 		self.style = style;
 		self.detail_level = detail_level;
 		self.color = color;
+		self.allowed_commands = allowed_commands;
+		self.relative_dates = relative_dates;
+		self.date_format = date_format;
+		self.fingerprint_format = fingerprint_format;
+		self.table_state = table_state;
+		self.rename_output = rename_output;
+		self.detail_view = detail_view;
+		self.passphrase_check = passphrase_check;
+		self.graphics = graphics;
+		self.copy_command = copy_command;
+		self.paste_command = paste_command;
+		self.lock_timeout = lock_timeout;
+		self.sync_interval = sync_interval;
+		self.serve_address = serve_address;
+		self.lock_phrase = lock_phrase;
+		self.keygen_profiles = keygen_profiles;
+		self.theme = theme;
+		self.custom_key_bindings = custom_key_bindings;
+		self.config_file = config_file;
+		self.locked = locked;
+		self.focused = focused;
+	}
+
+	/// Returns the format of the dates to show.
+	pub fn get_date_format(&self) -> &str {
+		if self.relative_dates {
+			RELATIVE_DATE_FORMAT
+		} else {
+			&self.date_format
+		}
+	}
+
+	/// Checks if the screen should be locked due to inactivity.
+	pub fn is_idle(&self) -> bool {
+		self.lock_timeout != 0
+			&& self.last_activity.elapsed().as_secs() >= self.lock_timeout
+	}
+
+	/// Locks the screen.
+	pub fn lock(&mut self) {
+		self.locked = true;
+		self.unlock_input.clear();
+	}
+
+	/// Unlocks the screen if the unlock phrase is correct.
+	///
+	/// Returns `true` if the screen is unlocked.
+	pub fn unlock(&mut self) -> bool {
+		let unlocked = match &self.lock_phrase {
+			Some(lock_phrase) => lock_phrase == &self.unlock_input,
+			None => true,
+		};
+		if unlocked {
+			self.locked = false;
+		}
+		self.unlock_input.clear();
+		unlocked
 	}
 }
 
@@ -93,8 +295,39 @@ mod tests {
 		assert_eq!(false, state.show_options);
 		assert_eq!(false, state.show_splash);
 		assert_eq!(None, state.select);
+		assert_eq!(SelectFormat::Plain, state.select_format);
 		assert_eq!(KeyDetail::default(), state.detail_level);
-		assert_eq!("xplr", state.file_explorer);
+		assert_eq!(DEFAULT_FILE_EXPLORER, state.file_explorer);
 		assert_eq!(None, state.exit_message);
+		state.allowed_commands = Some(vec![String::from("list")]);
+		state.refresh();
+		assert_eq!(Some(vec![String::from("list")]), state.allowed_commands);
+		assert!(!state.is_idle());
+		assert_eq!("%F", state.get_date_format());
+		state.relative_dates = true;
+		assert_eq!(RELATIVE_DATE_FORMAT, state.get_date_format());
+		state.lock_phrase = Some(String::from("test"));
+		state.locked = true;
+		state.unlock_input = String::from("tset");
+		assert!(!state.unlock());
+		assert!(state.locked);
+		state.unlock_input = String::from("test");
+		assert!(state.unlock());
+		assert!(!state.locked);
+		let state = State::from(&Args {
+			table_size: Some(TableSize::Minimized),
+			..Args::default()
+		});
+		assert_eq!(TableSize::Minimized, state.table_state.size);
+		assert_eq!(0, state.table_state.minimize_threshold);
+		let state = State::from(&Args {
+			style: Style::Colored,
+			no_color: true,
+			ascii: true,
+			..Args::default()
+		});
+		assert_eq!(Style::Plain, state.style);
+		assert_eq!(TuiColor::Reset, state.color);
+		assert!(state.ascii);
 	}
 }
@@ -1,8 +1,31 @@
+use crate::gpg::handler::EXPIRATION_WARNING_DAYS;
 use clap::ValueEnum;
-use ratatui::style::{Color, Style as TuiStyle};
+use ratatui::style::{Color, Modifier, Style as TuiStyle};
+use ratatui::symbols::border;
 use ratatui::text::{Line, Span, Text};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
+/// Box-drawing characters and their ASCII replacements.
+const ASCII_SYMBOLS: [(&str, &str); 5] = [
+	("└─", "`-"),
+	("├─", "|-"),
+	("─>", "->"),
+	("│", "|"),
+	("─", "-"),
+];
+
+/// Border symbols that only consist of ASCII characters.
+pub const ASCII_BORDER: border::Set = border::Set {
+	top_left: "+",
+	top_right: "+",
+	bottom_left: "+",
+	bottom_right: "+",
+	vertical_left: "|",
+	vertical_right: "|",
+	horizontal_top: "-",
+	horizontal_bottom: "-",
+};
+
 /// Application style.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 pub enum Style {
@@ -10,6 +33,9 @@ pub enum Style {
 	Plain,
 	/// More rich style with highlighted widgets and more colors.
 	Colored,
+	/// Bold text without dim colors for better readability.
+	#[clap(aliases = ["high_contrast", "highcontrast"])]
+	HighContrast,
 }
 
 impl Default for Style {
@@ -20,7 +46,15 @@ impl Default for Style {
 
 impl Display for Style {
 	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-		write!(f, "{}", format!("{self:?}").to_lowercase())
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Plain => "plain",
+				Self::Colored => "colored",
+				Self::HighContrast => "high-contrast",
+			}
+		)
 	}
 }
 
@@ -39,6 +73,61 @@ impl Style {
 			_ => Self::Plain,
 		}
 	}
+
+	/// Returns the color for the secondary elements. (e.g. borders)
+	///
+	/// Dark gray is not used in the [`HighContrast`] style.
+	///
+	/// [`HighContrast`]: Self::HighContrast
+	pub fn get_dim_color(&self) -> Color {
+		match self {
+			Self::HighContrast => Color::Reset,
+			_ => Color::DarkGray,
+		}
+	}
+
+	/// Returns the style of the text with the given color.
+	pub fn get_text_style(&self, color: Color) -> TuiStyle {
+		match self {
+			Self::HighContrast => {
+				TuiStyle::default().fg(color).add_modifier(Modifier::BOLD)
+			}
+			_ => TuiStyle::default().fg(color),
+		}
+	}
+
+	/// Returns the style of the selected items.
+	///
+	/// Selected items are reversed in the [`HighContrast`] style
+	/// for being distinguishable without colors.
+	///
+	/// [`HighContrast`]: Self::HighContrast
+	pub fn get_highlight_style(&self) -> TuiStyle {
+		match self {
+			Self::Plain => TuiStyle::default()
+				.fg(Color::Reset)
+				.add_modifier(Modifier::BOLD),
+			Self::Colored => TuiStyle::default().add_modifier(Modifier::BOLD),
+			Self::HighContrast => TuiStyle::default()
+				.fg(Color::Reset)
+				.add_modifier(Modifier::BOLD | Modifier::REVERSED),
+		}
+	}
+}
+
+/// Replaces the box-drawing characters in the given text with ASCII.
+pub fn to_ascii(text: &str) -> String {
+	ASCII_SYMBOLS
+		.iter()
+		.fold(text.to_string(), |text, (from, to)| text.replace(from, to))
+}
+
+/// Replaces the box-drawing characters in the given [`Text`] widget.
+pub fn get_ascii_text(mut text: Text<'_>) -> Text<'_> {
+	for span in text.lines.iter_mut().flat_map(|line| line.spans.iter_mut()) {
+		span.content = to_ascii(&span.content).into();
+	}
+	text
 }
 
 /// Converts the given multi-line row value to colored [`Text`] widget.
@@ -189,6 +278,38 @@ pub fn get_colored_table_row<'a>(
 	Text::from(row)
 }
 
+/// Converts the given relative expiration times to colored [`Text`] widget.
+///
+/// It colors the lines depending on how close the expiration date is.
+pub fn get_colored_expiration_row<'a>(row_data: &[String]) -> Text<'a> {
+	Text::from(
+		row_data
+			.iter()
+			.map(|line| {
+				let color =
+					if line.starts_with("expired") || line == "expires today" {
+						Color::LightRed
+					} else if let Some(days) =
+						line.strip_prefix("expires in ").and_then(|v| {
+							v.trim_end_matches('d').parse::<i64>().ok()
+						}) {
+						if days <= EXPIRATION_WARNING_DAYS {
+							Color::LightYellow
+						} else {
+							Color::LightGreen
+						}
+					} else {
+						Color::DarkGray
+					};
+				Line::from(Span::styled(
+					line.to_string(),
+					TuiStyle::default().fg(color),
+				))
+			})
+			.collect::<Vec<Line>>(),
+	)
+}
+
 /// Converts the given information text to colored [`Text`] widget.
 ///
 /// It adds colors to:
@@ -562,6 +683,56 @@ mod tests {
 				reset",
 				Color::LightRed
 			)
-		)
+		);
+		assert_eq!(
+			Text::from(vec![
+				Line::from(Span::styled(
+					"expired 3d ago",
+					TuiStyle::default().fg(Color::LightRed),
+				)),
+				Line::from(Span::styled(
+					"expires in 7d",
+					TuiStyle::default().fg(Color::LightYellow),
+				)),
+				Line::from(Span::styled(
+					"expires in 365d",
+					TuiStyle::default().fg(Color::LightGreen),
+				)),
+				Line::from(Span::styled(
+					"-",
+					TuiStyle::default().fg(Color::DarkGray),
+				)),
+			]),
+			get_colored_expiration_row(&[
+				String::from("expired 3d ago"),
+				String::from("expires in 7d"),
+				String::from("expires in 365d"),
+				String::from("-"),
+			])
+		);
+		let style = Style::from_str("high-contrast", true).expect("invalid");
+		assert_eq!("high-contrast", style.to_string());
+		assert_eq!(Color::Reset, style.get_dim_color());
+		assert_eq!(Color::DarkGray, Style::Plain.get_dim_color());
+		assert!(style
+			.get_highlight_style()
+			.add_modifier
+			.contains(Modifier::REVERSED));
+		assert!(style
+			.get_text_style(Color::Gray)
+			.add_modifier
+			.contains(Modifier::BOLD));
+		assert_eq!(Style::Plain, style.next());
+		assert_eq!(
+			"`-[13] selfsig (2020-07-29) -> (2022-07-29)",
+			to_ascii("└─[13] selfsig (2020-07-29) ─> (2022-07-29)")
+		);
+		assert_eq!(
+			Text::from(vec![Line::from("|-[10]"), Line::from("|   x")]),
+			get_ascii_text(Text::from(vec![
+				Line::from("├─[10]"),
+				Line::from("│   x")
+			]))
+		);
 	}
 }
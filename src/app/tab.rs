@@ -8,14 +8,27 @@ pub enum Tab {
 	Help,
 	/// Show keys in the GPG keyring.
 	Keys(KeyType),
+	/// Show the results of the keyserver search.
+	KeyserverResults,
+	/// Show the smartcard status.
+	Card,
+	/// Show the details of a key from the given keyring.
+	KeyDetail(KeyType),
 }
 
 impl Tab {
 	/// Returns the corresponding application command.
 	pub fn get_command(&self) -> Command {
 		match self {
-			Self::Keys(key_type) => Command::ListKeys(*key_type),
+			Self::Keys(key_type) | Self::KeyDetail(key_type) => {
+				Command::ListKeys(*key_type)
+			}
 			Self::Help => Command::ShowHelp,
+			Self::Card => Command::ShowCard,
+			Self::KeyserverResults => Command::Set(
+				String::from("prompt"),
+				String::from(":search-keyserver "),
+			),
 		}
 	}
 
@@ -51,5 +64,12 @@ mod tests {
 		let tab = tab.previous();
 		assert_eq!(Tab::Keys(KeyType::Public), tab);
 		assert_ne!(Tab::Keys(KeyType::Secret), tab);
+		let tab = Tab::KeyserverResults;
+		assert_eq!(Tab::Keys(KeyType::Public), tab.next());
+		assert_eq!(Tab::Keys(KeyType::Secret), tab.previous());
+		assert_eq!(Command::ShowCard, Tab::Card.get_command());
+		assert_eq!(Tab::Keys(KeyType::Public), Tab::Card.next());
+		let tab = Tab::KeyDetail(KeyType::Secret);
+		assert_eq!(Command::ListKeys(KeyType::Secret), tab.get_command());
 	}
 }
@@ -1,20 +1,64 @@
+use crate::gpg::context::GpgContext;
 use anyhow::{anyhow, Result};
+use std::env;
+use std::fs;
+use std::path::{self, Path};
 use std::process::{Command, Stdio};
 
-/// Runs the given OS command and returns the output lines.
-pub fn run_os_command(cmd: &str) -> Result<Vec<String>> {
-	log::trace!(target: "cmd", "running OS command: {cmd:?}");
-	let child = if cfg!(target_os = "windows") {
+/// Default utility for file selection.
+#[cfg(not(target_os = "windows"))]
+pub const DEFAULT_FILE_EXPLORER: &str = "xplr";
+
+/// Default utility for file selection.
+///
+/// Shows the file dialog and prints the selected files.
+#[cfg(target_os = "windows")]
+pub const DEFAULT_FILE_EXPLORER: &str = "powershell -NoProfile -Command \
+	Add-Type -AssemblyName System.Windows.Forms; \
+	$d = New-Object System.Windows.Forms.OpenFileDialog; \
+	$d.Multiselect = $true; \
+	if ($d.ShowDialog() -eq 'OK') { $d.FileNames }";
+
+/// Value of the file explorer for using the built-in file browser.
+pub const BUILTIN_FILE_EXPLORER: &str = "builtin";
+
+/// Placeholder for the file that the selected files are written to.
+const SELECTION_FILE_PLACEHOLDER: &str = "{file}";
+
+/// Presets of the known file explorers.
+///
+/// Explorers that draw on stdout write the selected files
+/// to a temporary file (`{file}`) instead of printing them.
+const FILE_EXPLORER_PRESETS: [(&str, &str); 5] = [
+	("xplr", "xplr"),
+	("nnn", "nnn -p {file}"),
+	("fzf", "fzf --multi"),
+	("ranger", "ranger --choosefiles={file}"),
+	("yazi", "yazi --chooser-file={file}"),
+];
+
+/// Returns the shell command for running the given command.
+fn get_shell_command(cmd: &str) -> Command {
+	let mut command = if cfg!(target_os = "windows") {
 		Command::new("cmd")
-			.args(["/C", cmd])
-			.stdout(Stdio::piped())
-			.spawn()
 	} else {
 		Command::new("sh")
-			.args(["-c", cmd])
-			.stdout(Stdio::piped())
-			.spawn()
 	};
+	command.args([
+		if cfg!(target_os = "windows") {
+			"/C"
+		} else {
+			"-c"
+		},
+		cmd,
+	]);
+	command
+}
+
+/// Runs the given OS command and returns the output lines.
+pub fn run_os_command(cmd: &str) -> Result<Vec<String>> {
+	log::trace!(target: "cmd", "running OS command: {cmd:?}");
+	let child = get_shell_command(cmd).stdout(Stdio::piped()).spawn();
 	match child {
 		Ok(child) => {
 			let output = child.wait_with_output()?;
@@ -33,3 +77,107 @@ pub fn run_os_command(cmd: &str) -> Result<Vec<String>> {
 		Err(e) => Err(anyhow!("cannot run command: {:?}", e)),
 	}
 }
+
+/// Checks if the binary of the given command is installed.
+fn is_installed(cmd: &str) -> bool {
+	let binary = match cmd.split_whitespace().next() {
+		Some(binary) => binary,
+		None => return false,
+	};
+	if binary.contains(path::MAIN_SEPARATOR) {
+		return Path::new(binary).is_file();
+	}
+	env::var_os("PATH")
+		.map(|paths| {
+			env::split_paths(&paths).any(|dir| {
+				dir.join(binary).is_file()
+					|| dir.join(format!("{binary}.exe")).is_file()
+			})
+		})
+		.unwrap_or_default()
+}
+
+/// Returns the command for running the given file explorer.
+///
+/// Known file explorers can be given by their names for using
+/// the presets. Returns `None` if the explorer is not installed
+/// or the built-in file browser is requested.
+pub fn get_file_explorer(explorer: &str) -> Option<String> {
+	let explorer = explorer.trim();
+	let cmd = FILE_EXPLORER_PRESETS
+		.iter()
+		.find(|(name, _)| *name == explorer)
+		.map(|(_, cmd)| cmd.to_string())
+		.unwrap_or_else(|| explorer.to_string());
+	if explorer != BUILTIN_FILE_EXPLORER && is_installed(&cmd) {
+		Some(cmd)
+	} else {
+		None
+	}
+}
+
+/// Parses the selected files from the output of the file explorer.
+///
+/// Files can be separated by newline or NUL characters.
+fn parse_selection(output: &str) -> Vec<String> {
+	output
+		.split(['\n', '\0'])
+		.map(str::trim)
+		.filter(|v| !v.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+/// Runs the file explorer and returns the selected files.
+///
+/// If the command contains the `{file}` placeholder, the selected
+/// files are read from that file instead of stdout.
+pub fn run_file_explorer(cmd: &str) -> Result<Vec<String>> {
+	if !cmd.contains(SELECTION_FILE_PLACEHOLDER) {
+		return Ok(parse_selection(&run_os_command(cmd)?.join("\n")));
+	}
+	// the file is created exclusively (with 0600) in a private directory
+	let temp_dir = GpgContext::create_temp_dir()?;
+	let file = tempfile::Builder::new()
+		.suffix(".sel")
+		.tempfile_in(temp_dir.path())?;
+	let cmd =
+		cmd.replace(SELECTION_FILE_PLACEHOLDER, &file.path().to_string_lossy());
+	log::trace!(target: "cmd", "running OS command: {cmd:?}");
+	let status = get_shell_command(&cmd)
+		.status()
+		.map_err(|e| anyhow!("cannot run command: {:?}", e))?;
+	let output = fs::read_to_string(file.path()).unwrap_or_default();
+	if status.success() {
+		Ok(parse_selection(&output))
+	} else {
+		Err(anyhow!("command exited with {:?}", status))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_app_util() -> Result<()> {
+		assert_eq!(None, get_file_explorer(BUILTIN_FILE_EXPLORER));
+		assert_eq!(None, get_file_explorer("gpg-tui-nonexistent-explorer"));
+		assert_eq!(
+			vec!["a.asc", "b c.asc"],
+			parse_selection("a.asc\0b c.asc\n\n")
+		);
+		if !cfg!(target_os = "windows") {
+			assert_eq!(
+				Some(String::from("sh -c true")),
+				get_file_explorer("sh -c true")
+			);
+			assert_eq!(
+				vec!["test.asc"],
+				run_file_explorer("echo test.asc > {file}")?
+			);
+			assert_eq!(vec!["test.asc"], run_file_explorer("echo test.asc")?);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,515 @@
+use crate::config::KeyGenProfile;
+use crate::gpg::keygen::{self, KeyAlgorithm, KeyGenParams};
+use crate::widget::secret::{SecretInput, Strength};
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Usage flags that can be toggled in the wizard.
+const USAGE_FLAGS: [&str; 3] = ["sign", "encrypt", "authenticate"];
+
+/// Steps of the key generation wizard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WizardStep {
+	/// Select the algorithm.
+	Algorithm,
+	/// Select the key size or curve.
+	Size,
+	/// Toggle the usage flags.
+	Usage,
+	/// Enter the user ID.
+	UserId,
+	/// Enter the expiration time.
+	Expiry,
+	/// Enter the passphrase.
+	Passphrase,
+}
+
+impl Display for WizardStep {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Algorithm => "Algorithm",
+				Self::Size => "Key size/curve",
+				Self::Usage => "Usage",
+				Self::UserId => "User ID (Name (Comment) <email>)",
+				Self::Expiry => "Expiration (0, 10d, 2w, 6m, 2y)",
+				Self::Passphrase => "Passphrase (empty for no protection)",
+			}
+		)
+	}
+}
+
+impl WizardStep {
+	/// All steps in order.
+	pub const ALL: [Self; 6] = [
+		Self::Algorithm,
+		Self::Size,
+		Self::Usage,
+		Self::UserId,
+		Self::Expiry,
+		Self::Passphrase,
+	];
+
+	/// Steps for adding a subkey.
+	pub const SUBKEY: [Self; 4] =
+		[Self::Algorithm, Self::Size, Self::Usage, Self::Expiry];
+
+	/// Checks if the step expects text input.
+	pub fn is_input(&self) -> bool {
+		matches!(self, Self::UserId | Self::Expiry | Self::Passphrase)
+	}
+}
+
+/// Multi-step form for generating a new key pair or subkey.
+#[derive(Clone)]
+pub struct KeyGenWizard {
+	/// Key to add the subkey to. (`None` for a new key pair)
+	pub key_id: Option<String>,
+	/// Current step.
+	pub step: WizardStep,
+	/// Index of the highlighted item in the current step.
+	pub selected: usize,
+	/// Selected algorithm.
+	pub algorithm: KeyAlgorithm,
+	/// Selected key size or curve.
+	pub size: String,
+	/// Usage flags. (sign, encrypt, authenticate)
+	pub usage: [bool; 3],
+	/// Entered user ID.
+	pub user_id: String,
+	/// Entered expiration time.
+	pub expiry: String,
+	/// Entered passphrase.
+	pub passphrase: SecretInput,
+	/// Whether if the strength of the passphrase is checked.
+	pub check_passphrase: bool,
+	/// Validation error of the current step.
+	pub error: Option<String>,
+	/// Profile that the wizard is prefilled from.
+	pub profile: Option<KeyGenProfile>,
+}
+
+impl Default for KeyGenWizard {
+	fn default() -> Self {
+		let algorithm = KeyAlgorithm::default();
+		Self {
+			key_id: None,
+			step: WizardStep::Algorithm,
+			selected: 0,
+			algorithm,
+			size: algorithm.get_sizes()[0].to_string(),
+			usage: [true, true, false],
+			user_id: String::new(),
+			expiry: String::from("2y"),
+			passphrase: SecretInput::default(),
+			check_passphrase: true,
+			error: None,
+			profile: None,
+		}
+	}
+}
+
+impl KeyGenWizard {
+	/// Constructs a new instance for adding a subkey to the given key.
+	pub fn for_subkey(key_id: String) -> Self {
+		Self {
+			key_id: Some(key_id),
+			usage: [false, true, false],
+			..Self::default()
+		}
+	}
+
+	/// Constructs a new instance from the parameters of the given profile.
+	///
+	/// The wizard starts from the user ID step and the comment policy of
+	/// the profile is applied to the entered user ID.
+	pub fn from_profile(profile: &KeyGenProfile) -> Result<Self> {
+		let mut wizard = Self::default();
+		if let Some(algo) = profile
+			.algo
+			.as_deref()
+			.map(str::to_lowercase)
+			.filter(|v| v != "default")
+		{
+			let (algorithm, size) = match algo.as_str() {
+				"ed25519" | "cv25519" => (KeyAlgorithm::Ecc, "25519"),
+				v if v.starts_with("nistp") => (KeyAlgorithm::Ecc, v),
+				v if v.starts_with("rsa") => (KeyAlgorithm::Rsa, &v[3..]),
+				v if v.starts_with("dsa") => (KeyAlgorithm::Dsa, &v[3..]),
+				_ => return Err(anyhow!("unsupported algorithm: {algo}")),
+			};
+			if !algorithm.get_sizes().contains(&size) {
+				return Err(anyhow!("unsupported algorithm: {algo}"));
+			}
+			wizard.size = size.to_string();
+			wizard.algorithm = algorithm;
+		}
+		if let Some(usage) = profile.usage.as_deref().map(str::to_lowercase) {
+			if usage != "default" {
+				wizard.usage = [false; 3];
+				for flag in usage.split([',', ' ']).filter(|v| !v.is_empty()) {
+					match flag {
+						"sign" => wizard.usage[0] = true,
+						"encr" | "encrypt" => wizard.usage[1] = true,
+						"auth" => wizard.usage[2] = true,
+						// primary key is always capable of certification
+						"cert" => {}
+						_ => return Err(anyhow!("unsupported usage: {flag}")),
+					}
+				}
+			}
+		}
+		match profile.expiry.as_deref().map(str::trim) {
+			None | Some("-") => {}
+			Some(expiry) => {
+				keygen::parse_expiry(expiry)?;
+				wizard.expiry = expiry.to_string();
+			}
+		}
+		wizard.profile = Some(profile.clone());
+		wizard.set_step(WizardStep::UserId);
+		Ok(wizard)
+	}
+
+	/// Returns the steps of the wizard.
+	pub fn get_steps(&self) -> &'static [WizardStep] {
+		if self.key_id.is_some() {
+			&WizardStep::SUBKEY
+		} else {
+			&WizardStep::ALL
+		}
+	}
+
+	/// Returns the index of the current step.
+	pub fn get_step_index(&self) -> usize {
+		self.get_steps()
+			.iter()
+			.position(|step| step == &self.step)
+			.unwrap_or_default()
+	}
+
+	/// Returns the items of the current step with their states.
+	///
+	/// Steps that expect text input do not have any items.
+	pub fn get_items(&self) -> Vec<(String, bool)> {
+		match self.step {
+			WizardStep::Algorithm => KeyAlgorithm::ALL
+				.iter()
+				.map(|v| (v.to_string(), v == &self.algorithm))
+				.collect(),
+			WizardStep::Size => self
+				.algorithm
+				.get_sizes()
+				.iter()
+				.map(|v| (v.to_string(), v == &self.size))
+				.collect(),
+			WizardStep::Usage => USAGE_FLAGS
+				.iter()
+				.zip(self.usage)
+				.map(|(v, enabled)| (v.to_string(), enabled))
+				.collect(),
+			_ => Vec::new(),
+		}
+	}
+
+	/// Returns the input of the current step.
+	pub fn get_input(&self) -> Option<&str> {
+		match self.step {
+			WizardStep::UserId => Some(&self.user_id),
+			WizardStep::Expiry => Some(&self.expiry),
+			WizardStep::Passphrase => Some(self.passphrase.as_str()),
+			_ => None,
+		}
+	}
+
+	/// Highlights the next item.
+	pub fn select_next(&mut self) {
+		let len = self.get_items().len();
+		if len != 0 {
+			self.selected = (self.selected + 1) % len;
+		}
+	}
+
+	/// Highlights the previous item.
+	pub fn select_previous(&mut self) {
+		let len = self.get_items().len();
+		if len != 0 {
+			self.selected = (self.selected + len - 1) % len;
+		}
+	}
+
+	/// Selects (or toggles) the highlighted item.
+	pub fn select(&mut self) {
+		match self.step {
+			WizardStep::Algorithm => {
+				let algorithm = KeyAlgorithm::ALL[self.selected];
+				if algorithm != self.algorithm {
+					self.algorithm = algorithm;
+					self.size = algorithm.get_sizes()[0].to_string();
+				}
+			}
+			WizardStep::Size => {
+				self.size =
+					self.algorithm.get_sizes()[self.selected].to_string()
+			}
+			WizardStep::Usage => {
+				self.usage[self.selected] = !self.usage[self.selected]
+			}
+			_ => {}
+		}
+	}
+
+	/// Appends a character to the input of the current step.
+	pub fn push(&mut self, c: char) {
+		self.error = None;
+		match self.step {
+			WizardStep::UserId => self.user_id.push(c),
+			WizardStep::Expiry => self.expiry.push(c),
+			WizardStep::Passphrase => {
+				self.passphrase.push(c);
+			}
+			_ => {}
+		}
+	}
+
+	/// Removes the last character from the input of the current step.
+	pub fn pop(&mut self) {
+		self.error = None;
+		match self.step {
+			WizardStep::UserId => {
+				self.user_id.pop();
+			}
+			WizardStep::Expiry => {
+				self.expiry.pop();
+			}
+			WizardStep::Passphrase => {
+				self.passphrase.pop();
+			}
+			_ => {}
+		}
+	}
+
+	/// Switches to the given step and highlights the current choice.
+	fn set_step(&mut self, step: WizardStep) {
+		self.step = step;
+		self.error = None;
+		self.selected = self
+			.get_items()
+			.iter()
+			.position(|(_, selected)| *selected)
+			.filter(|_| step != WizardStep::Usage)
+			.unwrap_or_default();
+	}
+
+	/// Validates the current step and switches to the next one.
+	///
+	/// Returns the key generation parameters after the last step.
+	pub fn next_step(&mut self) -> Option<KeyGenParams> {
+		match self.step {
+			WizardStep::Algorithm | WizardStep::Size => self.select(),
+			WizardStep::Usage if self.key_id.is_some() => {
+				if !self.usage.contains(&true) {
+					self.error =
+						Some(String::from("select at least one capability"));
+					return None;
+				} else if self.usage[1]
+					&& (self.usage[0] || self.usage[2])
+					&& self.algorithm != KeyAlgorithm::Rsa
+				{
+					self.error = Some(String::from(
+						"only RSA subkeys can combine encryption with others",
+					));
+					return None;
+				}
+			}
+			WizardStep::UserId if self.user_id.trim().is_empty() => {
+				self.error = Some(String::from("user ID cannot be empty"));
+				return None;
+			}
+			WizardStep::UserId => {
+				if let Some(Err(e)) = self
+					.profile
+					.as_ref()
+					.map(|profile| profile.check_user_id(&self.user_id))
+				{
+					self.error = Some(e.to_string());
+					return None;
+				}
+			}
+			WizardStep::Expiry => {
+				if let Err(e) = keygen::parse_expiry(&self.expiry) {
+					self.error = Some(e.to_string());
+					return None;
+				}
+			}
+			// weak passphrases are accepted after a second confirmation
+			WizardStep::Passphrase
+				if self.check_passphrase
+					&& !self.passphrase.is_empty()
+					&& self.error.is_none() =>
+			{
+				let estimate = self.passphrase.get_strength();
+				if estimate.strength == Strength::Weak {
+					self.error = Some(format!(
+						"weak passphrase ({}), press enter again to use it",
+						estimate.warnings.join(", ")
+					));
+					return None;
+				}
+			}
+			_ => {}
+		}
+		match self.get_steps().get(self.get_step_index() + 1) {
+			Some(step) => {
+				self.set_step(*step);
+				None
+			}
+			None => Some(KeyGenParams {
+				algorithm: self.algorithm,
+				size: self.size.to_string(),
+				sign: self.usage[0],
+				encrypt: self.usage[1],
+				auth: self.usage[2],
+				user_id: self.user_id.trim().to_string(),
+				expiry: keygen::parse_expiry(&self.expiry).ok().flatten(),
+				passphrase: self.passphrase.clone(),
+			}),
+		}
+	}
+
+	/// Switches to the previous step.
+	pub fn previous_step(&mut self) {
+		if let Some(index) = self.get_step_index().checked_sub(1) {
+			self.set_step(self.get_steps()[index]);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::CommentPolicy;
+	use pretty_assertions::assert_eq;
+	use std::time::Duration;
+	#[test]
+	fn test_app_wizard() {
+		let mut wizard = KeyGenWizard::default();
+		assert_eq!(WizardStep::Algorithm, wizard.step);
+		assert_eq!(3, wizard.get_items().len());
+		wizard.select_previous();
+		assert_eq!(None, wizard.next_step());
+		assert_eq!(KeyAlgorithm::Dsa, wizard.algorithm);
+		assert_eq!(WizardStep::Size, wizard.step);
+		assert_eq!(
+			vec![(String::from("3072"), true), (String::from("2048"), false)],
+			wizard.get_items()
+		);
+		wizard.select_next();
+		wizard.next_step();
+		assert_eq!("2048", wizard.size);
+		assert_eq!(WizardStep::Usage, wizard.step);
+		wizard.select_next();
+		wizard.select();
+		assert_eq!([true, false, false], wizard.usage);
+		wizard.next_step();
+		assert_eq!(WizardStep::UserId, wizard.step);
+		assert_eq!(None, wizard.next_step());
+		assert!(wizard.error.is_some());
+		"Test <test@example.org>"
+			.chars()
+			.for_each(|c| wizard.push(c));
+		assert!(wizard.error.is_none());
+		wizard.next_step();
+		assert_eq!(Some("2y"), wizard.get_input());
+		wizard.pop();
+		wizard.push('x');
+		assert_eq!(None, wizard.next_step());
+		assert!(wizard.error.is_some());
+		wizard.pop();
+		wizard.push('w');
+		wizard.next_step();
+		assert!(wizard.step.is_input());
+		wizard.previous_step();
+		assert_eq!(WizardStep::Expiry, wizard.step);
+		wizard.next_step();
+		wizard.push('x');
+		assert_eq!(None, wizard.next_step());
+		assert!(wizard.error.as_deref().unwrap_or_default().contains("weak"));
+		let params = wizard.next_step().expect("no parameters");
+		assert_eq!("dsa2048", params.get_primary_algo());
+		assert!(params.sign && !params.encrypt && !params.auth);
+		assert_eq!("Test <test@example.org>", params.user_id);
+		assert_eq!(Some(Duration::from_secs(86_400 * 14)), params.expiry);
+		assert_eq!("x", params.passphrase.as_str());
+
+		let mut wizard = KeyGenWizard::for_subkey(String::from("0x0"));
+		assert_eq!(4, wizard.get_steps().len());
+		wizard.next_step();
+		wizard.next_step();
+		assert_eq!(2, wizard.get_step_index());
+		wizard.select();
+		assert_eq!(None, wizard.next_step());
+		assert!(wizard.error.is_some());
+		wizard.select_next();
+		wizard.select();
+		wizard.select_next();
+		wizard.select();
+		assert_eq!(None, wizard.next_step());
+		assert_eq!(WizardStep::Expiry, wizard.step);
+		let params = wizard.next_step().expect("no parameters");
+		assert!(params.sign && !params.encrypt && params.auth);
+		assert_eq!("ed25519", params.get_added_subkey_algo());
+		assert!(params.user_id.is_empty());
+	}
+
+	#[test]
+	fn test_app_wizard_profile() -> Result<()> {
+		let mut profile = KeyGenProfile {
+			algo: Some(String::from("rsa3072")),
+			usage: Some(String::from("sign,cert")),
+			expiry: Some(String::from("-")),
+			comment: Some(CommentPolicy::Require),
+		};
+		let mut wizard = KeyGenWizard::from_profile(&profile)?;
+		assert_eq!(WizardStep::UserId, wizard.step);
+		assert_eq!(KeyAlgorithm::Rsa, wizard.algorithm);
+		assert_eq!("3072", wizard.size);
+		assert_eq!([true, false, false], wizard.usage);
+		assert_eq!("2y", wizard.expiry);
+		"Test <test@example.org>"
+			.chars()
+			.for_each(|c| wizard.push(c));
+		assert_eq!(None, wizard.next_step());
+		assert_eq!(WizardStep::UserId, wizard.step);
+		assert!(wizard.error.is_some());
+		wizard.user_id = String::from("Test (work) <test@example.org>");
+		wizard.next_step();
+		assert_eq!(WizardStep::Expiry, wizard.step);
+		wizard.next_step();
+		let params = wizard.next_step().expect("no parameters");
+		assert_eq!("rsa3072", params.get_primary_algo());
+		assert!(params.sign && !params.encrypt && !params.auth);
+		profile.algo = Some(String::from("ed25519"));
+		profile.usage = None;
+		profile.expiry = Some(String::from("never"));
+		let wizard = KeyGenWizard::from_profile(&profile)?;
+		assert_eq!(KeyAlgorithm::Ecc, wizard.algorithm);
+		assert_eq!("25519", wizard.size);
+		assert_eq!([true, true, false], wizard.usage);
+		assert_eq!("never", wizard.expiry);
+		for (algo, usage, expiry) in [
+			("rsa1024", "sign", "2y"),
+			("ed448", "sign", "2y"),
+			("rsa4096", "sign,fly", "2y"),
+			("rsa4096", "sign", "2x"),
+		] {
+			profile.algo = Some(algo.to_string());
+			profile.usage = Some(usage.to_string());
+			profile.expiry = Some(expiry.to_string());
+			assert!(KeyGenWizard::from_profile(&profile).is_err());
+		}
+		Ok(())
+	}
+}
@@ -1,19 +1,31 @@
 //! Command-line argument parser.
 
 use crate::app::banner::BANNERS;
-use crate::app::selection::Selection;
+use crate::app::clipboard::{
+	ClipboardType, DEFAULT_COPY_COMMAND, DEFAULT_PASTE_COMMAND,
+};
+use crate::app::selection::{FingerprintFormat, SelectFormat, Selection};
 use crate::app::style::Style;
-use crate::gpg::key::KeyDetail;
+use crate::app::util::DEFAULT_FILE_EXPLORER;
+use crate::gpg::handler;
+use crate::gpg::key::{KeyDetail, OutputFormat};
+use crate::gpg::keyserver::BackendType;
+use crate::term::graphics::GraphicsProtocol;
 use crate::widget::style::Color;
-use clap::Parser;
+use crate::widget::table::TableSize;
+use clap::{ArgAction, Parser};
+use std::env;
+use std::net::IpAddr;
+use std::str::FromStr;
 
 /// Argument parser powered by [`clap`].
-#[derive(Debug, Default, Parser)]
+#[derive(Clone, Debug, Default, Parser)]
 #[clap(
     version,
     author = clap::crate_authors!("\n"),
     about,
 	rename_all_env = "screaming-snake",
+	disable_version_flag = true,
 	before_help = format!("\u{2800} {}", BANNERS[2]),
 	help_template = "\
 {before-help}{name} {version}
@@ -28,9 +40,21 @@ pub struct Args {
 	/// Enables ASCII armored output.
 	#[clap(short, long)]
 	pub armor: bool,
+	/// Enables the validity computation of the keys.
+	#[clap(long)]
+	pub validate: bool,
 	/// Shows the splash screen on startup.
 	#[clap(long)]
 	pub splash: bool,
+	/// Enables editing the output file name before exporting.
+	#[clap(long)]
+	pub rename_output: bool,
+	/// Shows the details of the selected key on Enter.
+	#[clap(long)]
+	pub detail_view: bool,
+	/// Disables the strength check of the entered passphrases.
+	#[clap(long, env)]
+	pub no_passphrase_check: bool,
 	/// Sets the configuration file.
 	#[clap(
 		long,
@@ -68,6 +92,15 @@ pub struct Args {
 	/// Sets the default key to sign with.
 	#[clap(short, long, value_name = "key", env)]
 	pub default_key: Option<String>,
+	/// Adds a keyserver to choose from while sending keys.
+	#[clap(long = "keyserver", value_name = "url")]
+	pub keyservers: Vec<String>,
+	/// Sets the keyserver for sending and receiving keys.
+	#[clap(long, value_name = "url", env)]
+	pub default_keyserver: Option<String>,
+	/// Sets the backend for sending and receiving keys.
+	#[clap(long, value_name = "backend", default_value = "gnupg", env)]
+	pub keyserver_backend: BackendType,
 	/// Sets the tick rate of the terminal.
 	#[clap(short, long, value_name = "ms", default_value = "250", env)]
 	pub tick_rate: u64,
@@ -77,21 +110,126 @@ pub struct Args {
 	/// Sets the style of the terminal.
 	#[clap(short, long, value_name = "style", default_value = "colored", env)]
 	pub style: Style,
+	/// Disables the colors.
+	#[clap(long)]
+	pub no_color: bool,
+	/// Uses ASCII characters instead of the box-drawing characters.
+	#[clap(long, env)]
+	pub ascii: bool,
 	/// Sets the utility for file selection.
-	#[clap(short, long, value_name = "app", default_value = "xplr", env)]
+	#[clap(
+		short,
+		long,
+		value_name = "app",
+		default_value = DEFAULT_FILE_EXPLORER,
+		env
+	)]
 	pub file_explorer: String,
 	/// Sets the detail level for the keys.
 	#[clap(long, value_name = "level", default_value = "minimum", env)]
 	pub detail_level: KeyDetail,
+	/// Sets the initial size of the keys table.
+	#[clap(long, value_name = "size", env)]
+	pub table_size: Option<TableSize>,
+	/// Sets the width threshold for minimizing the keys table.
+	#[clap(long, value_name = "width", env)]
+	pub minimize_threshold: Option<u16>,
 	/// Sets the file to save the logs.
 	#[clap(long, value_name = "path", env)]
 	pub log_file: Option<String>,
+	/// Writes the events as JSON lines to the given file. ("-" for stdout)
+	#[clap(long, value_name = "path", env)]
+	pub json_events: Option<String>,
 	/// Enables the selection mode.
 	#[clap(long, value_name = "option", env)]
 	pub select: Option<Selection>,
+	/// Focuses on the keys that match the given pattern.
+	#[clap(long, value_name = "pattern")]
+	pub focus: Option<String>,
+	/// Sets the output format of the selection mode (plain, json or template).
+	#[clap(
+		long,
+		value_name = "format",
+		default_value = "plain",
+		value_parser = SelectFormat::from_str,
+		env
+	)]
+	pub select_format: SelectFormat,
+	/// Prints the public keys in the given format and exits.
+	#[clap(long, value_name = "format")]
+	pub format: Option<OutputFormat>,
+	/// Runs the given command without starting the interface.
+	#[clap(long, value_name = "command")]
+	pub batch: Option<String>,
+	/// Runs the commands that ask for confirmation in batch mode.
+	#[clap(long, requires = "batch")]
+	pub yes: bool,
+	/// Sets the format of the dates.
+	#[clap(
+		long,
+		value_name = "format",
+		default_value = "%F",
+		env,
+		value_parser = Args::parse_date_format
+	)]
+	pub date_format: String,
+	/// Sets the format of the copied fingerprints.
+	#[clap(
+		long,
+		value_name = "format",
+		default_value = "plain",
+		env,
+		value_parser = FingerprintFormat::from_str
+	)]
+	pub fingerprint_format: FingerprintFormat,
+	/// Sets the idle timeout for locking the screen.
+	#[clap(long, value_name = "sec", default_value = "0", env)]
+	pub lock_timeout: u64,
+	/// Sets the interval for syncing the GnuPG configuration.
+	#[clap(long, value_name = "sec", default_value = "0", env)]
+	pub sync_interval: u64,
+	/// Sets the address for serving keys. [default: 127.0.0.1]
+	#[clap(long, value_name = "address", env)]
+	pub serve_address: Option<IpAddr>,
+	/// Sets the clipboard backend.
+	#[clap(long, value_name = "backend", default_value = "auto", env)]
+	pub clipboard: ClipboardType,
+	/// Sets the protocol for showing images.
+	#[clap(long, value_name = "protocol", default_value = "auto", env)]
+	pub graphics: GraphicsProtocol,
+	/// Sets the command for copying to the clipboard.
+	#[clap(
+		long,
+		value_name = "cmd",
+		default_value = DEFAULT_COPY_COMMAND,
+		env
+	)]
+	pub copy_command: String,
+	/// Sets the command for pasting from the clipboard.
+	#[clap(
+		long,
+		value_name = "cmd",
+		default_value = DEFAULT_PASTE_COMMAND,
+		env
+	)]
+	pub paste_command: String,
+	/// Prints version information (use twice for more details).
+	#[clap(short = 'V', long, action = ArgAction::Count)]
+	pub version: u8,
+	/// Prints the GPGME and GnuPG details along with the version.
+	#[clap(long, requires = "version")]
+	pub verbose: bool,
 }
 
 impl Args {
+	/// Checks if the colors are disabled.
+	///
+	/// Colors are disabled via `--no-color` or setting the `NO_COLOR`
+	/// environment variable to a non-empty value. (see <https://no-color.org>)
+	pub fn is_color_disabled(&self) -> bool {
+		self.no_color || env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+	}
+
 	/// Custom string parser for directories.
 	///
 	/// Expands the tilde (`~`) character in the beginning of the
@@ -101,6 +239,17 @@ impl Args {
 	fn parse_dir(dir: &str) -> Result<String, String> {
 		Ok(shellexpand::tilde(dir).to_string())
 	}
+
+	/// Custom string parser for date formats.
+	///
+	/// Accepts `strftime` specifiers or `relative`.
+	fn parse_date_format(format: &str) -> Result<String, String> {
+		if handler::is_valid_date_format(format) {
+			Ok(format.to_string())
+		} else {
+			Err(String::from("invalid date format"))
+		}
+	}
 }
 
 #[cfg(test)]
@@ -1,28 +1,42 @@
 //! Configuration file parser.
 
+use crate::app::clipboard::ClipboardType;
 use crate::app::command::Command;
+use crate::app::selection::FingerprintFormat;
 use crate::app::style::Style;
+use crate::app::util::DEFAULT_FILE_EXPLORER;
 use crate::args::Args;
+use crate::gpg::handler;
 use crate::gpg::key::KeyDetail;
+use crate::gpg::keyserver::BackendType;
+use crate::term::graphics::GraphicsProtocol;
 use crate::widget::style::Color;
-use anyhow::Result;
+use crate::widget::table::TableSize;
+use anyhow::{anyhow, Result};
 use clap::ValueEnum;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use serde::{de, Deserialize, Deserializer, Serialize};
+use ratatui::style::{Modifier, Style as TuiStyle};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::str::FromStr;
 use toml::value::Value;
+use toml_edit::{Document, Item, Table};
 
 /// Default color, style, and settings.
 const DEFAULT_COLOR: &str = "gray";
 const DEFAULT_STYLE: &str = "plain";
-const DEFAULT_FILE_EXPLORER: &str = "xplr";
 const DEFAULT_TICK_RATE: u64 = 250_u64;
 const DEFAULT_SPLASH: bool = false;
 const DEFAULT_ARMOR: bool = false;
+const DEFAULT_VALIDATE: bool = false;
 const DEFAULT_DETAIL_LEVEL: &str = "minimum";
 const DEFAULT_HOMEDIR: &str = "~/.gnupg";
 const DEFAULT_OUTDIR: &str = "~/.gnupg";
+const DEFAULT_HIGHLIGHT_SYMBOL: &str = "> ";
+const DEFAULT_TABLE_MARGIN: u16 = 1;
 
 /// Application configuration.
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -31,6 +45,10 @@ pub struct Config {
 	pub general: Option<GeneralConfig>,
 	/// GnuPG configuration.
 	pub gpg: Option<GpgConfig>,
+	/// Key generation profiles.
+	pub keygen: Option<HashMap<String, KeyGenProfile>>,
+	/// Theme configuration.
+	pub theme: Option<ThemeConfig>,
 }
 
 /// General configuration.
@@ -48,22 +66,95 @@ pub struct GeneralConfig {
 	pub file_explorer: Option<String>,
 	/// [`Args::detail_level`]
 	pub detail_level: Option<KeyDetail>,
+	/// [`Args::table_size`]
+	pub table_size: Option<TableSize>,
+	/// [`Args::minimize_threshold`]
+	pub minimize_threshold: Option<u16>,
+	/// [`Args::rename_output`]
+	pub rename_output: Option<bool>,
+	/// [`Args::detail_view`]
+	pub detail_view: Option<bool>,
+	/// [`Args::no_passphrase_check`] (inverted)
+	pub passphrase_check: Option<bool>,
 	/// Custom key bindings.
-	#[serde(skip_serializing)]
 	pub key_bindings: Option<Vec<CustomKeyBinding>>,
 	/// File to save the logs.
 	pub log_file: Option<String>,
+	/// File to write the events as JSON lines.
+	pub json_events: Option<String>,
+	/// Commands that are allowed to run. (restricted mode)
+	pub allowed_commands: Option<Vec<String>>,
+	/// [`Args::lock_timeout`]
+	pub lock_timeout: Option<u64>,
+	/// [`Args::sync_interval`]
+	pub sync_interval: Option<u64>,
+	/// [`Args::serve_address`]
+	pub serve_address: Option<IpAddr>,
+	/// [`Args::date_format`]
+	#[serde(default, deserialize_with = "deserialize_date_format")]
+	pub date_format: Option<String>,
+	/// [`Args::fingerprint_format`]
+	pub fingerprint_format: Option<String>,
+	/// [`Args::clipboard`]
+	pub clipboard: Option<String>,
+	/// [`Args::graphics`]
+	pub graphics: Option<String>,
+	/// [`Args::copy_command`]
+	pub copy_command: Option<String>,
+	/// [`Args::paste_command`]
+	pub paste_command: Option<String>,
+	/// Phrase that is required for unlocking the screen.
+	pub lock_phrase: Option<String>,
+}
+
+/// Context that a custom key binding is active in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyBindingContext {
+	/// Keys tab.
+	Keys,
+	/// Help tab.
+	Help,
+	/// Smartcard tab.
+	Card,
+	/// Command/search prompt.
+	Prompt,
 }
 
 /// Representation of custom key bindings.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CustomKeyBinding {
 	/// Key events to check.
-	#[serde(deserialize_with = "deserialize_keys")]
+	#[serde(
+		deserialize_with = "deserialize_keys",
+		serialize_with = "serialize_keys"
+	)]
 	pub keys: Vec<KeyEvent>,
 	/// Command to run.
+	///
+	/// Placeholders are expanded before parsing.
 	#[serde(deserialize_with = "deserialize_command")]
-	pub command: Command,
+	pub command: String,
+	/// Context of the key binding.
+	///
+	/// Key bindings without a context are active in all tabs.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub context: Option<KeyBindingContext>,
+}
+
+impl CustomKeyBinding {
+	/// Checks if the key binding matches the key event in the given context.
+	pub fn matches(
+		&self,
+		key_event: &KeyEvent,
+		context: KeyBindingContext,
+	) -> bool {
+		self.keys.contains(key_event)
+			&& match self.context {
+				Some(binding_context) => binding_context == context,
+				None => context != KeyBindingContext::Prompt,
+			}
+	}
 }
 
 /// Custom deserializer for parsing a vector of [`KeyEvent`]s
@@ -118,14 +209,96 @@ where
 	Ok(key_bindings)
 }
 
-/// Custom deserializer for parsing [`Command`]s
-fn deserialize_command<'de, D>(deserializer: D) -> Result<Command, D::Error>
+/// Custom serializer for writing [`KeyEvent`]s in the parsable form.
+fn serialize_keys<S>(
+	keys: &[KeyEvent],
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.collect_seq(keys.iter().map(|key| match key.code {
+		KeyCode::Char(c) if key.modifiers == KeyModifiers::CONTROL => {
+			format!("C-{c}")
+		}
+		KeyCode::Char(c) if key.modifiers == KeyModifiers::ALT => {
+			format!("A-{c}")
+		}
+		KeyCode::Char(c) => c.to_string(),
+		KeyCode::F(num) => format!("F{num}"),
+		key_code => format!("{key_code:?}"),
+	}))
+}
+
+/// Custom deserializer for validating [`Command`]s
+fn deserialize_command<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let s = String::deserialize(deserializer)?;
+	match Command::from_str(&s) {
+		Ok(_) => Ok(s),
+		Err(_) => Err(de::Error::custom(format!("invalid command ({s})"))),
+	}
+}
+
+/// Custom deserializer for validating the date format.
+fn deserialize_date_format<'de, D>(
+	deserializer: D,
+) -> Result<Option<String>, D::Error>
 where
 	D: Deserializer<'de>,
 {
 	let s = String::deserialize(deserializer)?;
-	Command::from_str(&s)
-		.map_err(|_| de::Error::custom(format!("invalid command ({s})")))
+	if handler::is_valid_date_format(&s) {
+		Ok(Some(s))
+	} else {
+		Err(de::Error::custom(format!("invalid date format ({s})")))
+	}
+}
+
+/// Policy for the comment part of the user IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentPolicy {
+	/// Comment is optional.
+	Allow,
+	/// Comment is required.
+	Require,
+	/// Comment is not allowed.
+	Forbid,
+}
+
+/// Profile for generating keys with the predefined parameters.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KeyGenProfile {
+	/// Algorithm of the primary key. (e.g. `ed25519`, `rsa4096`)
+	pub algo: Option<String>,
+	/// Usage of the primary key. (e.g. `sign`, `cert`)
+	pub usage: Option<String>,
+	/// Expiration time of the key. (e.g. `2y`, `never`)
+	pub expiry: Option<String>,
+	/// Policy for the comment in the user ID.
+	pub comment: Option<CommentPolicy>,
+}
+
+impl KeyGenProfile {
+	/// Checks if the user ID conforms to the comment policy.
+	pub fn check_user_id(&self, user_id: &str) -> Result<()> {
+		let has_comment = user_id
+			.split_once('(')
+			.map(|(_, v)| v.contains(')'))
+			.unwrap_or(false);
+		match self.comment {
+			Some(CommentPolicy::Require) if !has_comment => {
+				Err(anyhow!("user ID must contain a comment"))
+			}
+			Some(CommentPolicy::Forbid) if has_comment => {
+				Err(anyhow!("user ID must not contain a comment"))
+			}
+			_ => Ok(()),
+		}
+	}
 }
 
 /// GnuPG configuration.
@@ -133,6 +306,8 @@ where
 pub struct GpgConfig {
 	/// [`Args::armor`]
 	pub armor: Option<bool>,
+	/// [`Args::validate`]
+	pub validate: Option<bool>,
 	/// [`Args::homedir`]
 	pub homedir: Option<String>,
 	/// [`Args::outdir`]
@@ -141,6 +316,134 @@ pub struct GpgConfig {
 	pub outfile: Option<String>,
 	/// [`Args::default_key`]
 	pub default_key: Option<String>,
+	/// [`Args::keyservers`]
+	pub keyservers: Option<Vec<String>>,
+	/// [`Args::default_keyserver`]
+	pub keyserver: Option<String>,
+	/// [`Args::keyserver_backend`]
+	pub keyserver_backend: Option<String>,
+}
+
+/// Theme configuration.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ThemeConfig {
+	/// Symbol that is shown before the selected item.
+	pub highlight_symbol: Option<String>,
+	/// Number of lines between the rows of the keys table.
+	pub table_margin: Option<u16>,
+	/// Symbol for drawing a separator line in the table margin.
+	pub row_separator: Option<String>,
+	/// Style of the borders.
+	pub border: Option<ElementStyle>,
+	/// Style of the selected items.
+	pub selected: Option<ElementStyle>,
+	/// Style of the successful command output.
+	pub success: Option<ElementStyle>,
+	/// Style of the warnings.
+	pub warning: Option<ElementStyle>,
+	/// Style of the errors.
+	pub failure: Option<ElementStyle>,
+	/// Style of the options popup.
+	pub options: Option<ElementStyle>,
+	/// Style of the commands in the help tab.
+	pub help_command: Option<ElementStyle>,
+}
+
+/// Element of the user interface that can be styled via theme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeElement {
+	/// Borders of the widgets.
+	Border,
+	/// Selected item of the tables and lists.
+	Selected,
+	/// Successful command output.
+	Success,
+	/// Warning output.
+	Warning,
+	/// Error output.
+	Failure,
+	/// Options popup.
+	Options,
+	/// Commands in the help tab.
+	HelpCommand,
+}
+
+/// Style of an element in the user interface.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ElementStyle {
+	/// Foreground color. (e.g. `red`, `FF0000`)
+	pub fg: Option<String>,
+	/// Background color.
+	pub bg: Option<String>,
+	/// Text modifiers. (e.g. `bold`, `italic`)
+	pub modifiers: Option<Vec<String>>,
+}
+
+impl ElementStyle {
+	/// Applies the element style on top of the given style.
+	///
+	/// Unknown modifiers are ignored.
+	pub fn apply(&self, mut style: TuiStyle) -> TuiStyle {
+		if let Some(fg) = &self.fg {
+			style = style.fg(Color::from(fg.as_str()).get());
+		}
+		if let Some(bg) = &self.bg {
+			style = style.bg(Color::from(bg.as_str()).get());
+		}
+		for modifier in self.modifiers.iter().flatten() {
+			style = style.add_modifier(
+				match modifier.to_lowercase().replace('-', "_").as_str() {
+					"bold" => Modifier::BOLD,
+					"dim" => Modifier::DIM,
+					"italic" => Modifier::ITALIC,
+					"underlined" => Modifier::UNDERLINED,
+					"slow_blink" => Modifier::SLOW_BLINK,
+					"rapid_blink" => Modifier::RAPID_BLINK,
+					"reversed" => Modifier::REVERSED,
+					"hidden" => Modifier::HIDDEN,
+					"crossed_out" => Modifier::CROSSED_OUT,
+					_ => Modifier::empty(),
+				},
+			);
+		}
+		style
+	}
+}
+
+impl ThemeConfig {
+	/// Returns the style of the given element.
+	///
+	/// The configured style is applied on top of the default style.
+	pub fn get_style(
+		&self,
+		element: ThemeElement,
+		default_style: TuiStyle,
+	) -> TuiStyle {
+		match element {
+			ThemeElement::Border => &self.border,
+			ThemeElement::Selected => &self.selected,
+			ThemeElement::Success => &self.success,
+			ThemeElement::Warning => &self.warning,
+			ThemeElement::Failure => &self.failure,
+			ThemeElement::Options => &self.options,
+			ThemeElement::HelpCommand => &self.help_command,
+		}
+		.as_ref()
+		.map(|style| style.apply(default_style))
+		.unwrap_or(default_style)
+	}
+
+	/// Returns the highlight symbol.
+	pub fn get_highlight_symbol(&self) -> &str {
+		self.highlight_symbol
+			.as_deref()
+			.unwrap_or(DEFAULT_HIGHLIGHT_SYMBOL)
+	}
+
+	/// Returns the default margin of the keys table.
+	pub fn get_table_margin(&self) -> u16 {
+		self.table_margin.unwrap_or(DEFAULT_TABLE_MARGIN)
+	}
 }
 
 impl Config {
@@ -167,6 +470,19 @@ impl Config {
 		None
 	}
 
+	/// Returns the path for writing a new configuration file.
+	///
+	/// `<config_dir>/gpg-tui/gpg-tui.toml`
+	pub fn get_write_location() -> Option<String> {
+		dirs_next::config_dir().and_then(|config_dir| {
+			config_dir
+				.join(env!("CARGO_PKG_NAME"))
+				.join(concat!(env!("CARGO_PKG_NAME"), ".toml"))
+				.to_str()
+				.map(String::from)
+		})
+	}
+
 	/// Parses the configuration file.
 	pub fn parse_config(file: &str) -> Result<Config> {
 		let contents = fs::read_to_string(file)?;
@@ -174,6 +490,58 @@ impl Config {
 		Ok(config)
 	}
 
+	/// Writes the configuration to the given file.
+	///
+	/// Only the changed entries of an existing file are updated
+	/// so that its comments and ordering are preserved.
+	/// Parent directories are created if they don't exist.
+	pub fn write_config(&self, file: &str) -> Result<()> {
+		let path = PathBuf::from(file);
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let mut document = if path.exists() {
+			fs::read_to_string(&path)?.parse::<Document>()?
+		} else {
+			Document::new()
+		};
+		let config = toml::to_string(self)?.parse::<Document>()?;
+		Self::merge_table(document.as_table_mut(), config.as_table());
+		fs::write(path, document.to_string())?;
+		Ok(())
+	}
+
+	/// Updates the entries of the table from the given table.
+	///
+	/// Decoration (e.g. trailing comments) of the values is kept.
+	fn merge_table(table: &mut Table, source: &Table) {
+		let removed_keys = table
+			.iter()
+			.map(|(key, _)| key.to_string())
+			.filter(|key| !source.contains_key(key))
+			.collect::<Vec<String>>();
+		for key in removed_keys {
+			table.remove(&key);
+		}
+		for (key, item) in source.iter() {
+			match (table.entry(key).or_insert(Item::None), item) {
+				(Item::Table(entry), Item::Table(item)) => {
+					Self::merge_table(entry, item)
+				}
+				(entry, item) => {
+					let decor =
+						entry.as_value().map(|value| value.decor().clone());
+					*entry = item.clone();
+					if let (Some(value), Some(decor)) =
+						(entry.as_value_mut(), decor)
+					{
+						*value.decor_mut() = decor;
+					}
+				}
+			}
+		}
+	}
+
 	/// Update the command-line arguments based on configuration.
 	pub fn update_args(&self, mut args: Args) -> Args {
 		let default_color: Color = Color::from(DEFAULT_COLOR);
@@ -183,6 +551,7 @@ impl Config {
 		match self.gpg.as_ref() {
 			Some(gpg) => {
 				args.armor = gpg.armor.unwrap_or_default();
+				args.validate = gpg.validate.unwrap_or_default();
 				args.homedir.clone_from(&gpg.homedir);
 				args.outdir.clone_from(&gpg.outdir);
 				if let Some(outfile) = &gpg.outfile {
@@ -191,9 +560,22 @@ impl Config {
 				if let Some(default_key) = &gpg.default_key {
 					args.default_key = Some(default_key.clone());
 				}
+				if let Some(keyservers) = &gpg.keyservers {
+					args.keyservers.clone_from(keyservers);
+				}
+				if let Some(keyserver) = &gpg.keyserver {
+					args.default_keyserver = Some(keyserver.to_string());
+				}
+				if let Some(backend) =
+					gpg.keyserver_backend.as_ref().and_then(|backend| {
+						BackendType::from_str(backend, true).ok()
+					}) {
+					args.keyserver_backend = backend;
+				}
 			}
 			None => {
 				args.armor = DEFAULT_ARMOR;
+				args.validate = DEFAULT_VALIDATE;
 				args.homedir = Some(String::from(DEFAULT_HOMEDIR));
 				args.outdir = Some(String::from(DEFAULT_OUTDIR));
 			}
@@ -224,9 +606,64 @@ impl Config {
 					KeyDetail::from_str(DEFAULT_DETAIL_LEVEL, true)
 						.unwrap_or_default(),
 				);
+				if general.table_size.is_some() {
+					args.table_size = general.table_size;
+				}
+				if general.minimize_threshold.is_some() {
+					args.minimize_threshold = general.minimize_threshold;
+				}
+				if let Some(rename_output) = general.rename_output {
+					args.rename_output = rename_output;
+				}
+				if let Some(detail_view) = general.detail_view {
+					args.detail_view = detail_view;
+				}
+				if let Some(passphrase_check) = general.passphrase_check {
+					args.no_passphrase_check = !passphrase_check;
+				}
 				if general.log_file.is_some() {
 					args.log_file.clone_from(&general.log_file);
 				}
+				if general.json_events.is_some() {
+					args.json_events.clone_from(&general.json_events);
+				}
+				if let Some(lock_timeout) = general.lock_timeout {
+					args.lock_timeout = lock_timeout;
+				}
+				if let Some(sync_interval) = general.sync_interval {
+					args.sync_interval = sync_interval;
+				}
+				if general.serve_address.is_some() {
+					args.serve_address = general.serve_address;
+				}
+				if let Some(date_format) = general.date_format.clone() {
+					args.date_format = date_format;
+				}
+				if let Some(fingerprint_format) = general
+					.fingerprint_format
+					.as_ref()
+					.and_then(|format| FingerprintFormat::from_str(format).ok())
+				{
+					args.fingerprint_format = fingerprint_format;
+				}
+				if let Some(clipboard) =
+					general.clipboard.as_ref().and_then(|clipboard| {
+						ClipboardType::from_str(clipboard, true).ok()
+					}) {
+					args.clipboard = clipboard;
+				}
+				if let Some(graphics) =
+					general.graphics.as_ref().and_then(|graphics| {
+						GraphicsProtocol::from_str(graphics, true).ok()
+					}) {
+					args.graphics = graphics;
+				}
+				if let Some(copy_command) = &general.copy_command {
+					args.copy_command.clone_from(copy_command);
+				}
+				if let Some(paste_command) = &general.paste_command {
+					args.paste_command.clone_from(paste_command);
+				}
 			}
 			None => {
 				args.splash = DEFAULT_SPLASH;
@@ -260,6 +697,31 @@ mod tests {
 		}
 		let args = config.update_args(Args::default());
 		assert_eq!(Some(String::from("test_key")), args.default_key);
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir
+			.path()
+			.join("config")
+			.join(concat!(env!("CARGO_PKG_NAME"), ".toml"))
+			.to_string_lossy()
+			.into_owned();
+		config.write_config(&path)?;
+		assert_eq!(config.gpg, Config::parse_config(&path)?.gpg);
+		fs::write(
+			&path,
+			"# comment\n[general]\nsplash = true # trailing\n\n[gpg]\n\
+			 armor = false\nunknown = 1\n",
+		)?;
+		let mut config = Config::parse_config(&path)?;
+		config.gpg.get_or_insert_with(GpgConfig::default).armor = Some(true);
+		config.write_config(&path)?;
+		assert_eq!(
+			"# comment\n[general]\nsplash = true # trailing\n\n[gpg]\n\
+			 armor = true\n",
+			fs::read_to_string(&path)?
+		);
+		let theme = config.theme.unwrap_or_default();
+		assert_eq!("> ", theme.get_highlight_symbol());
+		assert_eq!(1, theme.get_table_margin());
 		Ok(())
 	}
 
@@ -293,12 +755,92 @@ mod tests {
 			assert_eq!(args.tick_rate, 250_u64);
 			// [gpg]
 			assert_eq!(args.armor, true); // supplied
+			assert_eq!(args.validate, false);
 			assert_eq!(args.default_key, None);
 		}
 		fs::remove_file(tmp_path)?;
 		Ok(())
 	}
 
+	#[test]
+	fn test_parse_date_format() -> Result<()> {
+		let config =
+			toml::from_str::<Config>("[general]\ndate_format = \"%d.%m.%Y\"")?;
+		let args = config.update_args(Args::default());
+		assert_eq!("%d.%m.%Y", args.date_format);
+		assert!(toml::from_str::<Config>("[general]\ndate_format = \"%Q\"")
+			.unwrap_err()
+			.to_string()
+			.contains("invalid date format (%Q)"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_theme() -> Result<()> {
+		let theme = toml::from_str::<Config>(
+			"[theme]\n   highlight_symbol = \"▶ \"\n   table_margin = 0\n   \
+			 row_separator = \"─\"\n",
+		)?
+		.theme
+		.unwrap_or_default();
+		assert_eq!("▶ ", theme.get_highlight_symbol());
+		assert_eq!(0, theme.get_table_margin());
+		assert_eq!(Some(String::from("─")), theme.row_separator);
+		let theme = toml::from_str::<Config>(
+			"[theme]\n   border = { fg = \"blue\" }\n   \
+			 failure = { fg = \"FF0000\", modifiers = [\"bold\", \"x\"] }\n",
+		)?
+		.theme
+		.unwrap_or_default();
+		assert_eq!(
+			TuiStyle::default().fg(ratatui::style::Color::Blue),
+			theme.get_style(ThemeElement::Border, TuiStyle::default())
+		);
+		assert_eq!(
+			TuiStyle::default()
+				.fg(ratatui::style::Color::Rgb(255, 0, 0))
+				.bg(ratatui::style::Color::Black)
+				.add_modifier(Modifier::BOLD),
+			theme.get_style(
+				ThemeElement::Failure,
+				TuiStyle::default().bg(ratatui::style::Color::Black)
+			)
+		);
+		assert_eq!(
+			TuiStyle::default(),
+			theme.get_style(ThemeElement::Options, TuiStyle::default())
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_keygen_profiles() -> Result<()> {
+		let mut temp_file = File::create("config/temp3.toml")?;
+		temp_file.write_all(
+			"[keygen.work]\n   algo = \"ed25519\"\n   expiry = \"2y\"\n   \
+			 comment = \"require\"\n"
+				.as_bytes(),
+		)?;
+		let tmp_path = PathBuf::from("config/temp3.toml");
+		let config = Config::parse_config(&tmp_path.to_string_lossy());
+		fs::remove_file(tmp_path)?;
+		let profiles = config?.keygen.unwrap_or_default();
+		let profile = profiles.get("work").cloned().unwrap_or_default();
+		assert_eq!(Some(CommentPolicy::Require), profile.comment);
+		assert_eq!(Some(String::from("ed25519")), profile.algo);
+		assert!(profile.check_user_id("Test <test@example.org>").is_err());
+		profile.check_user_id("Test (work) <test@example.org>")?;
+		let profile = KeyGenProfile {
+			comment: Some(CommentPolicy::Forbid),
+			..KeyGenProfile::default()
+		};
+		assert!(profile
+			.check_user_id("Test (work) <test@example.org>")
+			.is_err());
+		profile.check_user_id("Test")?;
+		Ok(())
+	}
+
 	#[test]
 	fn test_parse_key_bindings() -> Result<()> {
 		for (keys, cmd, config) in [
@@ -378,12 +920,30 @@ mod tests {
 			assert_eq!(
 				CustomKeyBinding {
 					keys,
-					command: Command::from_str(cmd).expect("invalid command"),
+					command: cmd.to_string(),
+					context: None,
 				},
 				toml::from_str(&format!("{config}\ncommand = '{cmd}'"))?
 			);
 		}
 
+		let key_binding = CustomKeyBinding {
+			keys: vec![
+				KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+				KeyEvent::new(KeyCode::Char('1'), KeyModifiers::ALT),
+				KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE),
+				KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE),
+			],
+			command: String::from(":refresh"),
+			context: Some(KeyBindingContext::Keys),
+		};
+		let serialized = toml::to_string(&key_binding)?;
+		assert_eq!(
+			Some(&Value::from(vec!["C-c", "A-1", "F5", "PageUp"])),
+			toml::from_str::<toml::Table>(&serialized)?.get("keys")
+		);
+		assert_eq!(key_binding, toml::from_str(&serialized)?);
+
 		for config in &[
 			"keys = [ 'x' ] \n command = ':x'",
 			"keys = [ 'test' ] \n command = ':help'",
@@ -394,4 +954,33 @@ mod tests {
 		}
 		Ok(())
 	}
+
+	#[test]
+	fn test_key_binding_context() -> Result<()> {
+		let key_event = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+		let key_binding = toml::from_str::<CustomKeyBinding>(
+			"keys = [ 'x' ] \n command = ':help' \n context = 'help'",
+		)?;
+		assert_eq!(Some(KeyBindingContext::Help), key_binding.context);
+		assert!(key_binding.matches(&key_event, KeyBindingContext::Help));
+		assert!(!key_binding.matches(&key_event, KeyBindingContext::Keys));
+		assert!(!key_binding.matches(&key_event, KeyBindingContext::Card));
+		let key_binding = toml::from_str::<CustomKeyBinding>(
+			"keys = [ 'x' ] \n command = ':help' \n context = 'card'",
+		)?;
+		assert!(key_binding.matches(&key_event, KeyBindingContext::Card));
+		assert!(!key_binding.matches(&key_event, KeyBindingContext::Keys));
+		let key_binding = toml::from_str::<CustomKeyBinding>(
+			"keys = [ 'x' ] \n command = ':help'",
+		)?;
+		assert!(key_binding.matches(&key_event, KeyBindingContext::Keys));
+		assert!(key_binding.matches(&key_event, KeyBindingContext::Help));
+		assert!(key_binding.matches(&key_event, KeyBindingContext::Card));
+		assert!(!key_binding.matches(&key_event, KeyBindingContext::Prompt));
+		assert!(toml::from_str::<CustomKeyBinding>(
+			"keys = [ 'x' ] \n command = ':help' \n context = 'x'",
+		)
+		.is_err());
+		Ok(())
+	}
 }
@@ -0,0 +1,155 @@
+use crate::gpg::paper;
+use anyhow::{anyhow, Result};
+
+/// Characters of the base64 encoding.
+const BASE64_CHARS: &[u8] =
+	b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Number of base64 characters per line in the armored output.
+const LINE_LENGTH: usize = 64;
+
+/// Encodes the given data as base64.
+pub fn encode_base64(data: &[u8]) -> String {
+	let mut output = String::with_capacity((data.len() + 2) / 3 * 4);
+	for chunk in data.chunks(3) {
+		let bytes = [
+			chunk[0],
+			chunk.get(1).copied().unwrap_or_default(),
+			chunk.get(2).copied().unwrap_or_default(),
+		];
+		let value = (u32::from(bytes[0]) << 16)
+			| (u32::from(bytes[1]) << 8)
+			| u32::from(bytes[2]);
+		for i in 0..4 {
+			if i <= chunk.len() {
+				let index = (value >> (18 - i * 6)) & 0x3F;
+				output.push(char::from(BASE64_CHARS[index as usize]));
+			} else {
+				output.push('=');
+			}
+		}
+	}
+	output
+}
+
+/// Decodes the given base64 data.
+pub fn decode_base64(data: &str) -> Result<Vec<u8>> {
+	let data = data.trim_end_matches('=');
+	let mut output = Vec::with_capacity(data.len() * 3 / 4);
+	let (mut value, mut bits) = (0_u32, 0);
+	for c in data.bytes() {
+		let index =
+			BASE64_CHARS.iter().position(|v| *v == c).ok_or_else(|| {
+				anyhow!("invalid base64 character: {}", c as char)
+			})?;
+		value = (value << 6) | index as u32;
+		bits += 6;
+		if bits >= 8 {
+			bits -= 8;
+			output.push((value >> bits) as u8);
+			value &= (1 << bits) - 1;
+		}
+	}
+	Ok(output)
+}
+
+/// Returns the armor label of the given (binary) OpenPGP data.
+///
+/// Only the tag of the first packet is checked since the messages
+/// might use indeterminate or partial packet lengths.
+fn get_label(data: &[u8]) -> Result<&'static str> {
+	let header = data.first().ok_or_else(|| anyhow!("no data to armor"))?;
+	Ok(match paper::get_packet_tag(*header)? {
+		paper::SECRET_KEY_TAG => "PRIVATE KEY BLOCK",
+		6 => "PUBLIC KEY BLOCK",
+		2 => "SIGNATURE",
+		_ => "MESSAGE",
+	})
+}
+
+/// Converts the given OpenPGP data to the ASCII armored form.
+///
+/// Armor header is chosen from the first packet and the
+/// output ends with a CRC-24 checksum of the data.
+pub fn armor(data: &[u8]) -> Result<String> {
+	let label = get_label(data)?;
+	let mut output = format!("-----BEGIN PGP {label}-----\n\n");
+	let encoded = encode_base64(data);
+	for line in encoded.as_bytes().chunks(LINE_LENGTH) {
+		output.push_str(&String::from_utf8_lossy(line));
+		output.push('\n');
+	}
+	output.push_str(&format!(
+		"={}\n-----END PGP {label}-----\n",
+		encode_base64(&paper::crc24(data).to_be_bytes()[1..])
+	));
+	Ok(output)
+}
+
+/// Converts the given ASCII armored data to the binary form.
+///
+/// The checksum is verified if it exists.
+pub fn dearmor(data: &str) -> Result<Vec<u8>> {
+	let mut lines = data
+		.lines()
+		.map(str::trim)
+		.skip_while(|line| !line.starts_with("-----BEGIN PGP "));
+	match lines.next() {
+		Some("-----BEGIN PGP SIGNED MESSAGE-----") => {
+			return Err(anyhow!("cleartext signatures cannot be dearmored"))
+		}
+		Some(_) => {}
+		None => return Err(anyhow!("no armored data found")),
+	}
+	let (mut body, mut checksum) = (String::new(), None);
+	for line in lines.skip_while(|line| !line.is_empty()).skip(1) {
+		if line.starts_with("-----END PGP ") {
+			let output = decode_base64(&body)?;
+			return match checksum {
+				Some(checksum)
+					if decode_base64(checksum)?
+						!= paper::crc24(&output).to_be_bytes()[1..] =>
+				{
+					Err(anyhow!("checksum mismatch"))
+				}
+				_ => Ok(output),
+			};
+		} else if let Some(value) = line.strip_prefix('=') {
+			checksum = Some(value);
+		} else {
+			body.push_str(line);
+		}
+	}
+	Err(anyhow!("unexpected end of armored data"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_armor() -> Result<()> {
+		assert_eq!("", encode_base64(b""));
+		assert_eq!("Zg==", encode_base64(b"f"));
+		assert_eq!("Zm9vYg==", encode_base64(b"foob"));
+		assert_eq!("Zm9vYmFy", encode_base64(b"foobar"));
+		assert_eq!(b"foob".to_vec(), decode_base64("Zm9vYg==")?);
+		assert!(decode_base64("Zm9v!").is_err());
+		let packet = [0xC6, 0x03, 0x04, 0x00, 0x00];
+		let output = armor(&packet)?;
+		assert!(output.starts_with("-----BEGIN PGP PUBLIC KEY BLOCK-----\n"));
+		assert!(output.ends_with("-----END PGP PUBLIC KEY BLOCK-----\n"));
+		assert_eq!(packet.to_vec(), dearmor(&output)?);
+		assert!(dearmor(&output.replace("\nxgME", "\nxgMF")).is_err());
+		assert!(dearmor("-----BEGIN PGP SIGNED MESSAGE-----").is_err());
+		assert!(dearmor("test").is_err());
+		assert!(armor(b"test").is_err());
+		assert!(armor(b"").is_err());
+		for packet in [[0xA3, 0x01, 0x78, 0x9C], [0xCB, 0xE1, 0x62, 0x00]] {
+			let output = armor(&packet)?;
+			assert!(output.starts_with("-----BEGIN PGP MESSAGE-----\n"));
+			assert_eq!(packet.to_vec(), dearmor(&output)?);
+		}
+		Ok(())
+	}
+}
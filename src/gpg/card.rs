@@ -0,0 +1,177 @@
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Names of the key slots on the card.
+const SLOT_NAMES: [&str; 3] = ["signature", "encryption", "authentication"];
+
+/// Key slot of the smartcard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardSlot {
+	/// Name of the slot.
+	pub name: String,
+	/// Fingerprint of the key in the slot.
+	pub fingerprint: Option<String>,
+}
+
+impl Display for CardSlot {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}: {}",
+			self.name,
+			self.fingerprint.as_deref().unwrap_or("[none]")
+		)
+	}
+}
+
+/// Status of the smartcard (e.g. YubiKey).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CardStatus {
+	/// Name of the card reader.
+	pub reader: String,
+	/// Application ID of the card.
+	pub application_id: String,
+	/// Manufacturer of the card.
+	pub vendor: String,
+	/// Serial number of the card.
+	pub serial: String,
+	/// Name of the cardholder.
+	pub cardholder: String,
+	/// Remaining attempts for the user PIN, reset code and admin PIN.
+	pub pin_retries: [String; 3],
+	/// Number of the created signatures.
+	pub signature_count: String,
+	/// Key slots of the card.
+	pub slots: Vec<CardSlot>,
+}
+
+impl CardStatus {
+	/// Parses the output of `gpg --card-status --with-colons`.
+	pub fn from_colons(output: &str) -> Result<Self> {
+		let mut status = Self {
+			slots: SLOT_NAMES
+				.iter()
+				.map(|name| CardSlot {
+					name: name.to_string(),
+					fingerprint: None,
+				})
+				.collect(),
+			..Self::default()
+		};
+		for line in output.lines() {
+			let fields = line.split(':').collect::<Vec<&str>>();
+			let field = |i: usize| fields.get(i).cloned().unwrap_or_default();
+			match field(0) {
+				"Reader" => {
+					status.reader = field(1).to_string();
+					status.application_id = field(3).to_string();
+				}
+				"vendor" => status.vendor = field(2).to_string(),
+				"serial" => status.serial = field(1).to_string(),
+				"name" => {
+					status.cardholder =
+						format!("{} {}", field(1), field(2)).trim().to_string()
+				}
+				"pinretry" => {
+					status.pin_retries = [
+						field(1).to_string(),
+						field(2).to_string(),
+						field(3).to_string(),
+					]
+				}
+				"sigcount" => status.signature_count = field(1).to_string(),
+				"fpr" => {
+					for (i, slot) in status.slots.iter_mut().enumerate() {
+						slot.fingerprint = Some(field(i + 1))
+							.filter(|v| !v.is_empty())
+							.map(|v| v.to_uppercase());
+					}
+				}
+				_ => {}
+			}
+		}
+		if status.reader.is_empty() && status.serial.is_empty() {
+			Err(anyhow!("no card found"))
+		} else {
+			Ok(status)
+		}
+	}
+
+	/// Returns information about the card.
+	///
+	/// * reader
+	/// * serial number
+	/// * cardholder
+	/// * PIN retry counters
+	/// * signature counter
+	pub fn get_info(&self) -> Vec<String> {
+		vec![
+			format!("reader: {}", self.reader),
+			format!(
+				"serial: {}{}",
+				self.serial,
+				if self.vendor.is_empty() {
+					String::new()
+				} else {
+					format!(" ({})", self.vendor)
+				}
+			),
+			format!(
+				"cardholder: {}",
+				if self.cardholder.is_empty() {
+					"[not set]"
+				} else {
+					&self.cardholder
+				}
+			),
+			format!(
+				"PIN retries: {} (user) {} (reset) {} (admin)",
+				self.pin_retries[0], self.pin_retries[1], self.pin_retries[2]
+			),
+			format!("signatures: {}", self.signature_count),
+		]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_card() -> Result<()> {
+		let status = CardStatus::from_colons(
+			"Reader:Yubico YubiKey OTP FIDO CCID 00 00:AID:\
+			D2760001240103040006123456780000:openpgp-card:
+version:0304:
+vendor:0006:Yubico:
+serial:12345678:
+name:Test:User:
+lang::
+pinretry:3:0:3:
+sigcount:42:::
+fpr:0123456789abcdef0123456789abcdef01234567::\
+			89ABCDEF0123456789ABCDEF0123456789ABCDEF:
+",
+		)?;
+		assert_eq!("12345678", status.serial);
+		assert_eq!("Test User", status.cardholder);
+		assert_eq!(
+			Some("0123456789ABCDEF0123456789ABCDEF01234567"),
+			status.slots[0].fingerprint.as_deref()
+		);
+		assert_eq!(None, status.slots[1].fingerprint);
+		assert_eq!("encryption: [none]", status.slots[1].to_string());
+		assert_eq!(
+			vec![
+				"reader: Yubico YubiKey OTP FIDO CCID 00 00",
+				"serial: 12345678 (Yubico)",
+				"cardholder: Test User",
+				"PIN retries: 3 (user) 0 (reset) 3 (admin)",
+				"signatures: 42",
+			],
+			status.get_info()
+		);
+		assert!(CardStatus::from_colons("").is_err());
+		Ok(())
+	}
+}
@@ -1,7 +1,111 @@
 use crate::args::Args;
+use crate::gpg::keyserver::BackendType;
 use anyhow::{anyhow, Result};
 use gpgme::{Gpgme, Protocol};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as OsCommand;
+
+/// Trust models that are supported by GnuPG.
+pub const TRUST_MODELS: &[&str] = &[
+	"auto", "pgp", "classic", "tofu", "tofu+pgp", "direct", "always",
+];
+
+/// Default trust model of GnuPG.
+const DEFAULT_TRUST_MODEL: &str = "auto";
+
+/// Returns the options in the given GnuPG configuration file contents.
+///
+/// Each option is returned as a name and (possibly empty) value pair.
+fn parse_conf(contents: &str) -> Vec<(&str, &str)> {
+	contents
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| match line.split_once(char::is_whitespace) {
+			Some((name, value)) => (name, value.trim()),
+			None => (line, ""),
+		})
+		.collect()
+}
+
+/// Sets the option in the given GnuPG configuration file contents.
+///
+/// Previous occurrences of the option are removed.
+fn set_conf_option(contents: &str, name: &str, value: &str) -> String {
+	let mut lines = contents
+		.lines()
+		.filter(|line| line.split_whitespace().next() != Some(name))
+		.map(String::from)
+		.collect::<Vec<String>>();
+	lines.push(format!("{name} {value}"));
+	lines.join("\n") + "\n"
+}
+
+/// Returns the options in the output of `gpgconf --list-options`.
+///
+/// Only the options that are set are returned. Values are unquoted and
+/// percent-decoded, and only the first value is kept for the lists.
+fn parse_gpgconf_options(output: &str) -> Vec<(String, String)> {
+	output
+		.lines()
+		.filter_map(|line| {
+			let fields = line.split(':').collect::<Vec<&str>>();
+			let value = fields.get(9)?.split(',').next()?;
+			if value.is_empty() {
+				return None;
+			}
+			Some((fields[0].to_string(), decode_gpgconf_value(value)))
+		})
+		.collect()
+}
+
+/// Decodes the given (percent-encoded) option value of `gpgconf`.
+fn decode_gpgconf_value(value: &str) -> String {
+	let value = value.strip_prefix('"').unwrap_or(value);
+	let mut bytes = Vec::new();
+	let mut index = 0;
+	while index < value.len() {
+		if let Some(byte) = value
+			.get(index..index + 3)
+			.and_then(|v| v.strip_prefix('%'))
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+		{
+			bytes.push(byte);
+			index += 3;
+		} else {
+			bytes.push(value.as_bytes()[index]);
+			index += 1;
+		}
+	}
+	String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Returns the value of the given option.
+fn find_option(options: &[(String, String)], name: &str) -> Option<String> {
+	options
+		.iter()
+		.find(|(option, _)| option == name)
+		.map(|(_, value)| value.to_string())
+}
+
+/// Returns the trust model in the given options.
+fn get_trust_model(options: &[(&str, &str)]) -> String {
+	options
+		.iter()
+		.filter(|(name, value)| name == &"trust-model" && !value.is_empty())
+		.map(|(_, value)| value.to_string())
+		.last()
+		.unwrap_or_else(|| String::from(DEFAULT_TRUST_MODEL))
+}
+
+/// Returns the default key that is set in the given options.
+fn get_default_key(options: &[(&str, &str)]) -> Option<String> {
+	options
+		.iter()
+		.find(|(name, value)| name == &"default-key" && !value.is_empty())
+		.map(|(_, value)| value.to_string())
+}
 
 /// Configuration manager for GPGME.
 #[derive(Clone, Debug)]
@@ -10,14 +114,31 @@ pub struct GpgConfig {
 	inner: Gpgme,
 	/// Flag for using ASCII armored output.
 	pub armor: bool,
+	/// Flag for computing the validity of the keys.
+	pub validate: bool,
 	/// Default key for signing operations.
 	pub default_key: Option<String>,
+	/// Keyservers to choose from while sending keys.
+	pub keyservers: Vec<String>,
+	/// Keyserver for sending and receiving keys.
+	///
+	/// The keyserver in `dirmngr.conf` is used if it is not specified.
+	pub keyserver: Option<String>,
+	/// Backend for sending and receiving keys.
+	pub keyserver_backend: BackendType,
 	/// Home directory.
 	pub home_dir: PathBuf,
 	/// Template for the output file name.
 	pub output_file: String,
 	/// Output directory.
 	pub output_dir: PathBuf,
+	/// Trust model that is set in `gpg.conf`.
+	pub trust_model: String,
+	/// Default key that is set in `gpg.conf`.
+	///
+	/// It is used for detecting the changes so that the default key
+	/// which is set otherwise (e.g. via `:set signer`) is not overwritten.
+	conf_default_key: Option<String>,
 }
 
 impl GpgConfig {
@@ -36,16 +157,68 @@ impl GpgConfig {
 		if let Some(output) = &args.outdir {
 			output_dir = PathBuf::from(output);
 		}
+		let gpg_conf = Self::read_conf(&home_dir, "gpg.conf");
+		let trust_model = get_trust_model(&parse_conf(&gpg_conf));
+		let conf_default_key = get_default_key(&parse_conf(&gpg_conf));
+		let gpg_options = Self::get_gpgconf_options(&home_dir, "gpg");
+		let default_key = args
+			.default_key
+			.clone()
+			.or_else(|| find_option(&gpg_options, "default-key"));
+		let keyserver = args
+			.default_keyserver
+			.clone()
+			.or_else(|| {
+				find_option(
+					&Self::get_gpgconf_options(&home_dir, "dirmngr"),
+					"keyserver",
+				)
+			})
+			.or_else(|| find_option(&gpg_options, "keyserver"));
 		Ok(Self {
 			inner: gpgme,
 			armor: args.armor,
-			default_key: args.default_key.as_ref().cloned(),
+			validate: args.validate,
+			default_key,
+			keyservers: args.keyservers.clone(),
+			keyserver,
+			keyserver_backend: args.keyserver_backend,
 			home_dir,
 			output_file: args.outfile.to_string(),
 			output_dir,
+			trust_model,
+			conf_default_key,
 		})
 	}
 
+	/// Returns the contents of the given configuration file
+	/// in the home directory.
+	fn read_conf(home_dir: &Path, file: &str) -> String {
+		fs::read_to_string(home_dir.join(file)).unwrap_or_default()
+	}
+
+	/// Returns the effective options of the given GnuPG component.
+	///
+	/// Options are read via `gpgconf` so that the global configuration
+	/// files are also taken into account, as in plain `gpg`.
+	fn get_gpgconf_options(
+		home_dir: &Path,
+		component: &str,
+	) -> Vec<(String, String)> {
+		OsCommand::new("gpgconf")
+			.arg("--homedir")
+			.arg(home_dir)
+			.arg("--list-options")
+			.arg(component)
+			.output()
+			.ok()
+			.filter(|output| output.status.success())
+			.map(|output| {
+				parse_gpgconf_options(&String::from_utf8_lossy(&output.stdout))
+			})
+			.unwrap_or_default()
+	}
+
 	/// Returns general information about the library configuration.
 	pub fn get_info(&mut self) -> Result<String> {
 		let engine_info = self.inner.engine_info()?;
@@ -62,6 +235,11 @@ impl GpgConfig {
 				Output directory: {:?}
 				Default signing key: {}
 				Armored output: {}
+				Key validation: {}
+				Trust model: {}
+				Keyserver: {}
+				Keyserver backend: {}
+				Keyservers: {}
 				"#,
 				self.inner.version(),
 				engine.protocol(),
@@ -76,11 +254,144 @@ impl GpgConfig {
 					.cloned()
 					.unwrap_or_else(|| String::from("not specified")),
 				self.armor,
+				self.validate,
+				self.trust_model,
+				self.keyserver.as_deref().unwrap_or("default"),
+				self.keyserver_backend,
+				if self.keyservers.is_empty() {
+					String::from("default")
+				} else {
+					self.keyservers.join(", ")
+				},
 			)),
 			None => Err(anyhow!("failed to get engine information")),
 		}
 	}
 
+	/// Returns the version information of the library and engines.
+	///
+	/// It extends [`get_info`] with the version of `dirmngr`.
+	///
+	/// [`get_info`]: Self::get_info
+	pub fn get_version_info(&mut self) -> Result<String> {
+		let dirmngr_version = OsCommand::new("dirmngr")
+			.arg("--version")
+			.output()
+			.ok()
+			.and_then(|output| {
+				String::from_utf8(output.stdout).ok()?.lines().next().map(
+					|line| {
+						line.split_whitespace()
+							.last()
+							.unwrap_or("?")
+							.to_string()
+					},
+				)
+			})
+			.unwrap_or_else(|| String::from("?"));
+		let mut info = self
+			.get_info()?
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty())
+			.map(String::from)
+			.collect::<Vec<String>>();
+		info.push(format!("dirmngr version: {dirmngr_version}"));
+		Ok(info.join("\n"))
+	}
+
+	/// Re-reads the GnuPG configuration files and updates the
+	/// default key, armor, trust model and keyserver settings.
+	///
+	/// Default key is only updated if it is changed in `gpg.conf`.
+	///
+	/// Returns the list of changes.
+	pub fn sync(&mut self) -> Result<Vec<String>> {
+		// make sure that the engine is still available
+		self.inner.engine_info()?;
+		let gpg_conf = Self::read_conf(&self.home_dir, "gpg.conf");
+		let dirmngr_conf = Self::read_conf(&self.home_dir, "dirmngr.conf");
+		let (gpg_options, dirmngr_options) =
+			(parse_conf(&gpg_conf), parse_conf(&dirmngr_conf));
+		let mut changes = Vec::new();
+		let conf_default_key = get_default_key(&gpg_options);
+		if self.conf_default_key != conf_default_key {
+			if let Some(default_key) = &conf_default_key {
+				if self.default_key.as_ref() != Some(default_key) {
+					changes.push(format!(
+						"default key: {} -> {}",
+						self.default_key.as_deref().unwrap_or("none"),
+						default_key
+					));
+					self.default_key = Some(default_key.to_string());
+				}
+			}
+			self.conf_default_key = conf_default_key;
+		}
+		if let Some(armor) = gpg_options
+			.iter()
+			.filter_map(|(name, _)| match *name {
+				"armor" => Some(true),
+				"no-armor" => Some(false),
+				_ => None,
+			})
+			.last()
+		{
+			if self.armor != armor {
+				changes.push(format!("armor: {} -> {}", self.armor, armor));
+				self.armor = armor;
+			}
+		}
+		let trust_model = get_trust_model(&gpg_options);
+		if self.trust_model != trust_model {
+			changes.push(format!(
+				"trust model: {} -> {}",
+				self.trust_model, trust_model
+			));
+			self.trust_model = trust_model;
+		}
+		let keyservers = [dirmngr_options, gpg_options]
+			.iter()
+			.map(|options| {
+				options
+					.iter()
+					.filter(|(name, value)| {
+						name == &"keyserver" && !value.is_empty()
+					})
+					.map(|(_, value)| value.to_string())
+					.collect::<Vec<String>>()
+			})
+			.find(|keyservers| !keyservers.is_empty());
+		if let Some(keyservers) = keyservers {
+			if self.keyservers != keyservers {
+				changes.push(format!(
+					"keyservers: [{}] -> [{}]",
+					self.keyservers.join(", "),
+					keyservers.join(", ")
+				));
+				self.keyservers = keyservers;
+			}
+		}
+		Ok(changes)
+	}
+
+	/// Sets the trust model in `gpg.conf`.
+	pub fn set_trust_model(&mut self, trust_model: &str) -> Result<()> {
+		if !TRUST_MODELS.contains(&trust_model) {
+			return Err(anyhow!("invalid trust model: {trust_model}"));
+		}
+		fs::write(
+			self.home_dir.join("gpg.conf"),
+			set_conf_option(
+				&Self::read_conf(&self.home_dir, "gpg.conf"),
+				"trust-model",
+				trust_model,
+			),
+		)?;
+		self.trust_model = trust_model.to_string();
+		Ok(())
+	}
+
 	/// Returns the directory information for the given value.
 	pub fn get_dir_info(&self, dir: &str) -> Result<&str> {
 		self.inner.get_dir_info(dir).map_err(|e| anyhow!("{:?}", e))
@@ -100,8 +411,55 @@ mod tests {
 	#[test]
 	fn test_gpg_config() -> Result<()> {
 		let args = Args::default();
-		let config = GpgConfig::new(&args)?;
+		let mut config = GpgConfig::new(&args)?;
 		config.check_gpgme_version(GPGME_REQUIRED_VERSION);
+		assert!(config.get_version_info()?.contains("dirmngr version: "));
+		assert_eq!(
+			vec![
+				("default-key", "0xABC"),
+				("armor", ""),
+				("keyserver", "hkps://keys.openpgp.org"),
+			],
+			parse_conf(
+				"# comment\ndefault-key 0xABC\n\n  armor\n\
+				 keyserver  hkps://keys.openpgp.org  \n"
+			)
+		);
+		assert_eq!(
+			"armor\n# trust-model pgp\ntrust-model tofu+pgp\n",
+			set_conf_option(
+				"trust-model always\narmor\n# trust-model pgp\n",
+				"trust-model",
+				"tofu+pgp"
+			)
+		);
+		assert_eq!(
+			"tofu",
+			get_trust_model(&parse_conf("trust-model pgp\ntrust-model tofu"))
+		);
+		assert_eq!("auto", get_trust_model(&[]));
+		assert_eq!(
+			Some(String::from("0xABC")),
+			get_default_key(&parse_conf("default-key\ndefault-key 0xABC"))
+		);
+		let options = parse_gpgconf_options(
+			"verbose:16:0:verbose:0:0::::\n\
+			default-key:16:1:key:2:2:NAME:::\"0xABC\n\
+			keyserver:24:1:url:1:1:URL:::\"hkps%3a//a.org,\"hkp%3a//b\n",
+		);
+		assert_eq!(
+			vec![
+				(String::from("default-key"), String::from("0xABC")),
+				(String::from("keyserver"), String::from("hkps://a.org")),
+			],
+			options
+		);
+		assert_eq!(
+			Some(String::from("0xABC")),
+			find_option(&options, "default-key")
+		);
+		assert_eq!(None, find_option(&options, "verbose"));
+		assert!(config.set_trust_model("invalid").is_err());
 		Ok(())
 	}
 }
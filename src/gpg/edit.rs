@@ -0,0 +1,361 @@
+use crate::gpg::keygen;
+use gpgme::{Error, InteractionStatus, Interactor};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Write;
+use std::str::FromStr;
+
+/// Available commands of the key editor along with their descriptions.
+pub const EDIT_COMMANDS: [(&str, &str); 7] = [
+	("adduid", "add a user ID"),
+	("revuid", "revoke a user ID"),
+	("primary", "set the primary user ID"),
+	("expire", "change the expiration time"),
+	("trust", "change the owner trust"),
+	("passwd", "change the passphrase"),
+	("keytocard", "move a subkey to the smartcard"),
+];
+
+/// Owner trust levels in the order of the `trust` menu.
+pub const TRUST_LEVELS: [&str; 5] =
+	["unknown", "never", "marginal", "full", "ultimate"];
+
+/// Prompt of the key editor menu.
+const EDIT_PROMPT: &str = "keyedit.prompt";
+
+/// Key edit operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyEdit {
+	/// Add a new user ID.
+	AddUid(String),
+	/// Revoke the user ID at the given index.
+	RevokeUid(usize),
+	/// Set the user ID at the given index as primary.
+	PrimaryUid(usize),
+	/// Change the expiration time of the primary key.
+	Expire(String),
+	/// Set the owner trust. (1-5)
+	Trust(usize),
+	/// Change the passphrase.
+	Passwd,
+	/// Move the (sub)key at the given index to the given card slot.
+	///
+	/// Existing key in the slot is replaced only if `true`.
+	KeyToCard(usize, usize, bool),
+}
+
+impl Display for KeyEdit {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		match self {
+			Self::AddUid(user_id) => write!(f, "add user ID: {user_id}"),
+			Self::RevokeUid(index) => write!(f, "revoke user ID #{index}"),
+			Self::PrimaryUid(index) => {
+				write!(f, "set user ID #{index} as primary")
+			}
+			Self::Expire(value) => write!(f, "set expiration time to {value}"),
+			Self::Trust(level) => write!(
+				f,
+				"set owner trust to {}",
+				level
+					.checked_sub(1)
+					.and_then(|i| TRUST_LEVELS.get(i))
+					.unwrap_or(&"[?]")
+			),
+			Self::Passwd => write!(f, "change the passphrase"),
+			Self::KeyToCard(index, slot, replace) => write!(
+				f,
+				"move key #{index} to card slot {slot}{}",
+				if *replace {
+					" (replace the existing key)"
+				} else {
+					""
+				}
+			),
+		}
+	}
+}
+
+impl FromStr for KeyEdit {
+	type Err = ();
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		let (command, value) = s.split_once(' ').unwrap_or((s, ""));
+		let value = value.trim();
+		let parse_index = |v: &str| -> Result<usize, ()> {
+			v.parse().ok().filter(|v| *v != 0).ok_or(())
+		};
+		match command.to_lowercase().as_str() {
+			"passwd" => Ok(Self::Passwd),
+			_ if value.is_empty() => Err(()),
+			"adduid" => Ok(Self::AddUid(value.to_string())),
+			"revuid" => Ok(Self::RevokeUid(parse_index(value)?)),
+			"primary" => Ok(Self::PrimaryUid(parse_index(value)?)),
+			"expire" => match keygen::parse_expiry(value) {
+				Ok(_) => Ok(Self::Expire(value.to_lowercase())),
+				Err(_) => Err(()),
+			},
+			"trust" => parse_trust_level(value).map(Self::Trust).ok_or(()),
+			"keytocard" => {
+				let values = value.split_whitespace().collect::<Vec<&str>>();
+				match values.as_slice() {
+					[index, slot] => Ok(Self::KeyToCard(
+						index.parse().map_err(|_| ())?,
+						parse_index(slot)?,
+						false,
+					)),
+					_ => Err(()),
+				}
+			}
+			_ => Err(()),
+		}
+	}
+}
+
+impl KeyEdit {
+	/// Checks if the operation might ask for the passphrase.
+	///
+	/// Passphrase is changed via the masked prompt instead.
+	pub fn needs_passphrase(&self) -> bool {
+		!matches!(self, Self::Trust(_) | Self::Passwd)
+	}
+}
+
+/// Parses the owner trust level from its name or number. (1-5)
+pub fn parse_trust_level(value: &str) -> Option<usize> {
+	let value = value.trim().to_lowercase();
+	TRUST_LEVELS
+		.iter()
+		.position(|v| *v == value)
+		.map(|i| i + 1)
+		.or_else(|| value.parse().ok())
+		.filter(|v| (1..=TRUST_LEVELS.len()).contains(v))
+}
+
+/// Splits the user ID into name, comment and email.
+fn split_user_id(user_id: &str) -> (String, String, String) {
+	let mut name = user_id.to_string();
+	let mut take = |start: char, end: char| -> String {
+		match (name.find(start), name.rfind(end)) {
+			(Some(i), Some(j)) if i < j => {
+				let value = name[i + 1..j].trim().to_string();
+				name.replace_range(i..=j, "");
+				value
+			}
+			_ => String::new(),
+		}
+	};
+	let email = take('<', '>');
+	let comment = take('(', ')');
+	(name.trim().to_string(), comment, email)
+}
+
+/// Formats the user ID as `Name (Comment) <email>`.
+///
+/// Parts of the user ID can be given in any order.
+pub fn format_user_id(user_id: &str) -> String {
+	let (name, comment, email) = split_user_id(user_id);
+	let mut parts = vec![name];
+	if !comment.is_empty() {
+		parts.push(format!("({comment})"));
+	}
+	if !email.is_empty() {
+		parts.push(format!("<{email}>"));
+	}
+	parts
+		.into_iter()
+		.filter(|v| !v.is_empty())
+		.collect::<Vec<String>>()
+		.join(" ")
+}
+
+/// Interactor that answers the prompts of `gpg --edit-key`.
+#[derive(Debug)]
+pub struct KeyEditor {
+	/// Commands to run on the editor menu.
+	commands: VecDeque<String>,
+	/// Answers of the prompts.
+	answers: Vec<(&'static str, String)>,
+}
+
+impl KeyEditor {
+	/// Constructs a new instance of `KeyEditor`.
+	///
+	/// Returns `None` for the operations that GPGME supports directly.
+	/// (e.g. adding a user ID)
+	pub fn new(edit: &KeyEdit) -> Option<Self> {
+		let yes = || String::from("y");
+		let no = || String::from("n");
+		let (commands, answers) = match edit {
+			KeyEdit::AddUid(_)
+			| KeyEdit::RevokeUid(_)
+			| KeyEdit::PrimaryUid(_)
+			| KeyEdit::Expire(_) => return None,
+			KeyEdit::Trust(level) => (
+				vec![String::from("trust")],
+				vec![
+					("edit_ownertrust.value", level.to_string()),
+					("edit_ownertrust.set_ultimate.okay", yes()),
+				],
+			),
+			KeyEdit::Passwd => (vec![String::from("passwd")], Vec::new()),
+			KeyEdit::KeyToCard(index, slot, replace) => {
+				let mut commands = Vec::new();
+				if *index != 0 {
+					commands.push(format!("key {index}"));
+				}
+				commands.push(String::from("keytocard"));
+				(
+					commands,
+					vec![
+						("cardedit.genkeys.storekeytype", slot.to_string()),
+						("keyedit.keytocard.use_primary", yes()),
+						(
+							"cardedit.genkeys.replace_key",
+							if *replace { yes() } else { no() },
+						),
+					],
+				)
+			}
+		};
+		Some(Self {
+			commands: commands.into(),
+			answers,
+		})
+	}
+
+	/// Returns the response for the given status.
+	///
+	/// Menu prompts are answered with the next command and
+	/// `save` is sent after all the commands are run.
+	pub fn get_response(
+		&mut self,
+		keyword: &str,
+		args: &str,
+	) -> Result<Option<String>, String> {
+		if !matches!(keyword, "GET_LINE" | "GET_BOOL" | "GET_HIDDEN") {
+			Ok(None)
+		} else if args == EDIT_PROMPT {
+			Ok(Some(
+				self.commands
+					.pop_front()
+					.unwrap_or_else(|| String::from("save")),
+			))
+		} else {
+			self.answers
+				.iter()
+				.find(|(prompt, _)| *prompt == args)
+				.map(|(_, answer)| Some(answer.to_string()))
+				.ok_or_else(|| format!("unexpected prompt: {args}"))
+		}
+	}
+}
+
+impl Interactor for KeyEditor {
+	fn interact<W: Write>(
+		&mut self,
+		status: InteractionStatus<'_>,
+		response: Option<W>,
+	) -> Result<(), Error> {
+		let keyword = status.keyword().unwrap_or_default();
+		let args = status.args().unwrap_or_default();
+		log::trace!(target: "edit", "{keyword} {args}");
+		match self.get_response(keyword, args) {
+			Ok(Some(answer)) => {
+				if let Some(mut response) = response {
+					writeln!(response, "{answer}").map_err(Error::from)?;
+				}
+				Ok(())
+			}
+			Ok(None) => Ok(()),
+			Err(e) => {
+				log::error!(target: "edit", "{e}");
+				Err(Error::CANCELED)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_edit() {
+		assert_eq!(
+			Ok(KeyEdit::AddUid(String::from("Test <test@example.org>"))),
+			KeyEdit::from_str("adduid Test <test@example.org>")
+		);
+		assert_eq!(Ok(KeyEdit::RevokeUid(2)), KeyEdit::from_str("revuid 2"));
+		assert!(KeyEdit::from_str("primary 0").is_err());
+		assert_eq!(
+			Ok(KeyEdit::Expire(String::from("2y"))),
+			KeyEdit::from_str("expire 2Y")
+		);
+		assert!(KeyEdit::from_str("expire x").is_err());
+		assert_eq!(Ok(KeyEdit::Trust(5)), KeyEdit::from_str("trust ultimate"));
+		assert_eq!(Ok(KeyEdit::Trust(3)), KeyEdit::from_str("trust 3"));
+		assert!(KeyEdit::from_str("trust 6").is_err());
+		assert_eq!(Some(4), parse_trust_level("Full"));
+		assert_eq!(None, parse_trust_level("0"));
+		assert_eq!(Ok(KeyEdit::Passwd), KeyEdit::from_str("passwd"));
+		assert_eq!(
+			Ok(KeyEdit::KeyToCard(1, 2, false)),
+			KeyEdit::from_str("keytocard 1 2")
+		);
+		for (replace, answer) in [(false, "n"), (true, "y")] {
+			assert_eq!(
+				Ok(Some(String::from(answer))),
+				KeyEditor::new(&KeyEdit::KeyToCard(1, 2, replace))
+					.map(|mut editor| editor.get_response(
+						"GET_BOOL",
+						"cardedit.genkeys.replace_key"
+					))
+					.unwrap_or(Ok(None))
+			);
+		}
+		assert_eq!(
+			"move key #1 to card slot 2 (replace the existing key)",
+			KeyEdit::KeyToCard(1, 2, true).to_string()
+		);
+		assert!(KeyEdit::from_str("keytocard 1").is_err());
+		assert!(KeyEdit::from_str("adduid").is_err());
+		assert!(KeyEdit::from_str("xyz 1").is_err());
+		assert_eq!("set owner trust to full", KeyEdit::Trust(4).to_string());
+		assert!(!KeyEdit::Trust(4).needs_passphrase());
+		assert!(!KeyEdit::Passwd.needs_passphrase());
+		assert_eq!(
+			(
+				String::from("Test"),
+				String::from("Work"),
+				String::from("test@example.org")
+			),
+			split_user_id("Test (Work) <test@example.org>")
+		);
+		assert_eq!(
+			"Test (Work) <test@example.org>",
+			format_user_id("Test <test@example.org> (Work)")
+		);
+		assert_eq!("<test@example.org>", format_user_id(" <test@example.org>"));
+
+		assert!(KeyEditor::new(&KeyEdit::RevokeUid(2)).is_none());
+		assert!(KeyEditor::new(&KeyEdit::Expire(String::from("2y"))).is_none());
+		let mut editor = KeyEditor::new(&KeyEdit::KeyToCard(2, 1, false))
+			.expect("failed to create the key editor");
+		assert_eq!(Ok(None), editor.get_response("GOT_IT", ""));
+		for command in ["key 2", "keytocard"] {
+			assert_eq!(
+				Ok(Some(command.to_string())),
+				editor.get_response("GET_LINE", EDIT_PROMPT)
+			);
+		}
+		assert_eq!(
+			Ok(Some(String::from("1"))),
+			editor.get_response("GET_LINE", "cardedit.genkeys.storekeytype")
+		);
+		assert_eq!(
+			Ok(Some(String::from("save"))),
+			editor.get_response("GET_LINE", EDIT_PROMPT)
+		);
+		assert!(editor.get_response("GET_LINE", "keygen.name").is_err());
+	}
+}
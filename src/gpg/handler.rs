@@ -1,5 +1,17 @@
-use chrono::{DateTime, Utc};
-use gpgme::{Subkey, UserIdSignature};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, SecondsFormat, Utc};
+use gpgme::{Key, Subkey, UserIdSignature};
+use std::time::{Duration, SystemTime};
+
+/// Date format for showing the dates relative to the current time.
+pub const RELATIVE_DATE_FORMAT: &str = "relative";
+
+/// Number of days to warn about before the expiration.
+pub const EXPIRATION_WARNING_DAYS: i64 = 30;
+
+/// Modern defaults for the cipher/digest/compression preferences.
+pub const MODERN_PREFERENCES: &str =
+	"AES256 AES192 AES SHA512 SHA384 SHA256 SHA224 ZLIB BZIP2 ZIP";
 
 /// Returns the flags of the given subkey.
 ///
@@ -17,6 +29,48 @@ pub fn get_subkey_flags(subkey: Subkey) -> String {
 	)
 }
 
+/// Checks if the given date format is valid.
+pub fn is_valid_date_format(format: &str) -> bool {
+	format == RELATIVE_DATE_FORMAT
+		|| !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+/// Returns the given date relative to the current time.
+///
+/// e.g. `3y ago`, `in 42d`, `today`
+pub fn get_relative_time(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+	let days = (date - now).num_days();
+	let value = if days.abs() >= 365 {
+		format!("{}y", days.abs() / 365)
+	} else {
+		format!("{}d", days.abs())
+	};
+	match days {
+		0 => String::from("today"),
+		_ if days > 0 => format!("in {value}"),
+		_ => format!("{value} ago"),
+	}
+}
+
+/// Returns the given time as ISO 8601 timestamp.
+///
+/// e.g. `2020-09-13T12:26:40Z`
+pub fn get_iso_timestamp(time: SystemTime) -> String {
+	DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Formats the given date.
+///
+/// Dates are shown relatively for [`RELATIVE_DATE_FORMAT`].
+pub fn format_date(date: SystemTime, format: &str) -> String {
+	let date = DateTime::<Utc>::from(date);
+	if format == RELATIVE_DATE_FORMAT {
+		get_relative_time(date, Utc::now())
+	} else {
+		date.format(format).to_string()
+	}
+}
+
 /// Returns time information about the given subkey.
 ///
 /// * creation time
@@ -26,14 +80,12 @@ pub fn get_subkey_time(subkey: Subkey, format: &str) -> String {
 	format!(
 		"({}){}{}{}{}{}{}",
 		if let Some(date) = subkey.creation_time() {
-			DateTime::<Utc>::from(date).format(format).to_string()
+			format_date(date, format)
 		} else {
 			String::from("[?]")
 		},
 		if let Some(date) = subkey.expiration_time() {
-			DateTime::<Utc>::from(date)
-				.format(&format!(" ─> ({format})"))
-				.to_string()
+			format!(" ─> ({})", format_date(date, format))
 		} else {
 			String::new()
 		},
@@ -45,6 +97,61 @@ pub fn get_subkey_time(subkey: Subkey, format: &str) -> String {
 	)
 }
 
+/// Returns the relative time until the given expiration date.
+///
+/// e.g. `expires in 42d`, `expires today`, `expired 3d ago`
+pub fn get_relative_expiration(
+	expiration: DateTime<Utc>,
+	now: DateTime<Utc>,
+) -> String {
+	let days = (expiration - now).num_days();
+	if expiration < now {
+		format!("expired {}d ago", -days)
+	} else if days == 0 {
+		String::from("expires today")
+	} else {
+		format!("expires in {days}d")
+	}
+}
+
+/// Returns the relative expiration time of the given subkey.
+pub fn get_subkey_countdown(subkey: Subkey) -> String {
+	match subkey.expiration_time() {
+		Some(date) => {
+			get_relative_expiration(DateTime::<Utc>::from(date), Utc::now())
+		}
+		None => String::from("-"),
+	}
+}
+
+/// Escapes the special characters of a vCard property value.
+fn escape_vcard_value(value: &str) -> String {
+	value
+		.replace('\\', "\\\\")
+		.replace(',', "\\,")
+		.replace(';', "\\;")
+		.replace('\n', "\\n")
+}
+
+/// Returns a vCard that contains the given contact information.
+///
+/// The fingerprint is stored in the `KEY;PGP` property.
+pub fn get_vcard(name: &str, emails: &[&str], fingerprint: &str) -> String {
+	let mut lines = vec![
+		String::from("BEGIN:VCARD"),
+		String::from("VERSION:3.0"),
+		format!("FN:{}", escape_vcard_value(name)),
+		format!("N:{};;;;", escape_vcard_value(name)),
+	];
+	for email in emails {
+		lines
+			.push(format!("EMAIL;TYPE=INTERNET:{}", escape_vcard_value(email)));
+	}
+	lines.push(format!("KEY;PGP:{fingerprint}"));
+	lines.push(String::from("END:VCARD"));
+	lines.join("\r\n") + "\r\n"
+}
+
 /// Returns time information about the given signature.
 ///
 /// * creation time
@@ -54,14 +161,12 @@ pub fn get_signature_time(signature: UserIdSignature, format: &str) -> String {
 	format!(
 		"({}){}{}{}{}{}",
 		if let Some(date) = signature.creation_time() {
-			DateTime::<Utc>::from(date).format(format).to_string()
+			format_date(date, format)
 		} else {
 			String::from("[?]")
 		},
 		if let Some(date) = signature.expiration_time() {
-			DateTime::<Utc>::from(date)
-				.format(&format!(" ─> ({format})"))
-				.to_string()
+			format!(" ─> ({})", format_date(date, format))
 		} else {
 			String::new()
 		},
@@ -79,3 +184,376 @@ pub fn get_signature_time(signature: UserIdSignature, format: &str) -> String {
 		},
 	)
 }
+
+/// Revocation of a key, subkey or user ID.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Revocation {
+	/// Revoked user ID or subkey ID.
+	///
+	/// It is `None` for the revocation of the primary key.
+	pub target: Option<String>,
+	/// Signature class. (`0x20`, `0x28` or `0x30`)
+	pub class: u8,
+	/// Creation time of the revocation signature.
+	pub date: Option<SystemTime>,
+	/// Code of the revocation reason.
+	pub reason: Option<u8>,
+	/// Comment of the revocation reason.
+	pub comment: String,
+}
+
+impl Revocation {
+	/// Returns the description of the revocation reason.
+	pub fn get_reason(&self) -> &str {
+		match self.reason {
+			Some(0x00) => "no reason specified",
+			Some(0x01) => "key is superseded",
+			Some(0x02) => "key material has been compromised",
+			Some(0x03) => "key is retired and no longer used",
+			Some(0x20) => "user ID information is no longer valid",
+			Some(_) => "unknown reason",
+			None => "no reason given",
+		}
+	}
+
+	/// Returns the revocation information.
+	///
+	/// e.g. `revoked: key is superseded (2021-01-01) "new key"`
+	pub fn get_info(&self, format: &str) -> String {
+		format!(
+			"revoked: {} ({}){}",
+			self.get_reason(),
+			self.date
+				.map(|date| format_date(date, format))
+				.unwrap_or_else(|| String::from("[?]")),
+			if self.comment.is_empty() {
+				String::new()
+			} else {
+				format!(" \"{}\"", self.comment)
+			}
+		)
+	}
+}
+
+/// Parses the revocations from the output of `gpg --list-packets`.
+pub fn parse_revocations(packets: &str) -> Vec<Revocation> {
+	let mut revocations = Vec::new();
+	let mut target = None;
+	let mut signature: Option<Revocation> = None;
+	let mut is_subkey = false;
+	for line in packets.lines().map(str::trim) {
+		if line.starts_with(':') || line.starts_with("# off=") {
+			revocations.extend(
+				signature
+					.take()
+					.filter(|v| [0x20, 0x28, 0x30].contains(&v.class)),
+			);
+		}
+		if let Some(user_id) = line.strip_prefix(":user ID packet:") {
+			target = Some(user_id.trim().trim_matches('"').to_string());
+		} else if line.starts_with(":public key packet:") {
+			target = None;
+			is_subkey = false;
+		} else if line.starts_with(":public sub key packet:") {
+			is_subkey = true;
+		} else if line.starts_with(":signature packet:") {
+			signature = Some(Revocation {
+				target: target.clone(),
+				..Revocation::default()
+			});
+		} else if let Some(key_id) = line.strip_prefix("keyid: ") {
+			if is_subkey {
+				target = Some(format!("0x{}", key_id.trim()));
+			}
+		} else if let Some(signature) = signature.as_mut() {
+			for field in line.split(", ") {
+				if let Some(class) = field.strip_prefix("sigclass 0x") {
+					signature.class =
+						u8::from_str_radix(class, 16).unwrap_or_default();
+				} else if let Some(created) = field.strip_prefix("created ") {
+					signature.date = created.parse().ok().map(|secs| {
+						SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+					});
+				}
+			}
+			if let Some((_, reason)) = line.split_once("revocation reason 0x") {
+				signature.reason = reason
+					.get(..2)
+					.and_then(|code| u8::from_str_radix(code, 16).ok());
+				signature.comment = reason
+					.get(2..)
+					.unwrap_or_default()
+					.trim()
+					.trim_start_matches('(')
+					.trim_end_matches(')')
+					.to_string();
+			}
+		}
+	}
+	revocations
+		.extend(signature.filter(|v| [0x20, 0x28, 0x30].contains(&v.class)));
+	revocations
+}
+
+/// Parses the preferences of each user ID from the output of
+/// `gpg --edit-key <key> showpref`.
+pub fn parse_preferences(output: &str) -> Vec<(String, Vec<String>)> {
+	let mut preferences: Vec<(String, Vec<String>)> = Vec::new();
+	for line in output.lines() {
+		let trimmed = line.trim();
+		// e.g. `[ultimate] (1). Name <email>` (`.` marks the primary)
+		if let Some((_, user_id)) = trimmed
+			.strip_prefix('[')
+			.and_then(|v| v.split_once("] ("))
+			.and_then(|(_, v)| v.split_once(')'))
+		{
+			preferences.push((
+				user_id.trim_start_matches('.').trim().to_string(),
+				Vec::new(),
+			));
+		} else if line.starts_with(char::is_whitespace)
+			&& trimmed.contains(": ")
+		{
+			if let Some((_, values)) = preferences.last_mut() {
+				values.push(trimmed.to_string());
+			}
+		}
+	}
+	preferences
+}
+
+/// Returns the comparable entries (subkeys, user IDs and signatures)
+/// of the given key.
+fn get_key_entries(key: &Key) -> Vec<String> {
+	let mut entries = Vec::new();
+	if key.is_revoked() {
+		entries.push(String::from("key revocation"));
+	}
+	for subkey in key.subkeys() {
+		entries.push(format!(
+			"subkey {}{}",
+			subkey.fingerprint().unwrap_or("[?]"),
+			if subkey.is_revoked() { " [rev]" } else { "" }
+		));
+	}
+	for user in key.user_ids() {
+		let user_id = user.id().unwrap_or("[?]");
+		entries.push(format!(
+			"uid {}{}",
+			user_id,
+			if user.is_revoked() { " [rev]" } else { "" }
+		));
+		for signature in user.signatures() {
+			entries.push(format!(
+				"sig [{:x}] {} on {} {}",
+				signature.cert_class(),
+				signature.signer_key_id().unwrap_or("[?]"),
+				user_id,
+				get_signature_time(signature, "%F")
+			));
+		}
+	}
+	entries
+}
+
+/// Returns the differences between the local and remote copy of a key.
+///
+/// * `+`: only available in the remote copy
+/// * `-`: only available in the local copy
+pub fn get_key_diff(local_key: &Key, remote_key: &Key) -> Vec<String> {
+	let local_entries = get_key_entries(local_key);
+	let remote_entries = get_key_entries(remote_key);
+	remote_entries
+		.iter()
+		.filter(|entry| !local_entries.contains(entry))
+		.map(|entry| format!("+ {entry}"))
+		.chain(
+			local_entries
+				.iter()
+				.filter(|entry| !remote_entries.contains(entry))
+				.map(|entry| format!("- {entry}")),
+		)
+		.collect()
+}
+
+/// Compares the given fingerprints in groups of 4 characters.
+///
+/// Fingerprints are normalized by removing the `0x` prefix,
+/// whitespace and separators before the comparison.
+pub fn compare_fingerprints(
+	expected: &str,
+	given: &str,
+) -> Vec<(String, String)> {
+	let normalize = |fingerprint: &str| {
+		fingerprint
+			.trim()
+			.to_uppercase()
+			.trim_start_matches("0X")
+			.chars()
+			.filter(|c| c.is_ascii_alphanumeric())
+			.collect::<Vec<char>>()
+	};
+	let (expected, given) = (normalize(expected), normalize(given));
+	let get_group = |chars: &[char], index: usize| {
+		chars
+			.chunks(4)
+			.nth(index)
+			.map(|group| group.iter().collect())
+			.unwrap_or_default()
+	};
+	(0..(expected.len().max(given.len()) + 3) / 4)
+		.map(|i| (get_group(&expected, i), get_group(&given, i)))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Duration;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_handler() {
+		assert_eq!(
+			"2020-09-13T12:26:40Z",
+			get_iso_timestamp(
+				SystemTime::UNIX_EPOCH
+					+ std::time::Duration::from_secs(1_600_000_000)
+			)
+		);
+		assert_eq!(
+			"BEGIN:VCARD\r\n\
+			VERSION:3.0\r\n\
+			FN:Test\\, Example\r\n\
+			N:Test\\, Example;;;;\r\n\
+			EMAIL;TYPE=INTERNET:test@example.org\r\n\
+			KEY;PGP:0123456789ABCDEF\r\n\
+			END:VCARD\r\n",
+			get_vcard(
+				"Test, Example",
+				&["test@example.org"],
+				"0123456789ABCDEF"
+			)
+		);
+		let revocations = parse_revocations(
+			r#"
+# off=0 ctb=99 tag=6 hlen=3 plen=525
+:public key packet:
+	version 4, algo 1, created 1600000000, expires 0
+	keyid: 1234567890ABCDEF
+# off=528 ctb=89 tag=2 hlen=3 plen=566
+:signature packet: algo 1, keyid 1234567890ABCDEF
+	version 4, created 1600000100, md5len 0, sigclass 0x20
+	hashed subpkt 29 len 8 (revocation reason 0x01 (new key))
+# off=1097 ctb=b4 tag=13 hlen=2 plen=25
+:user ID packet: "Test <test@example.org>"
+# off=1124 ctb=89 tag=2 hlen=3 plen=590
+:signature packet: algo 1, keyid 1234567890ABCDEF
+	version 4, created 1600000000, md5len 0, sigclass 0x13
+# off=1717 ctb=89 tag=2 hlen=3 plen=566
+:signature packet: algo 1, keyid 1234567890ABCDEF
+	version 4, created 1600000200, md5len 0, sigclass 0x30
+	hashed subpkt 29 len 1 (revocation reason 0x20 ())
+# off=2286 ctb=b9 tag=14 hlen=3 plen=525
+:public sub key packet:
+	version 4, algo 1, created 1600000000, expires 0
+	keyid: ABCDEF1234567890
+# off=2814 ctb=89 tag=2 hlen=3 plen=566
+:signature packet: algo 1, keyid 1234567890ABCDEF
+	version 4, created 1600000300, md5len 0, sigclass 0x28
+"#,
+		);
+		assert_eq!(3, revocations.len());
+		assert_eq!(None, revocations[0].target);
+		assert_eq!(
+			"revoked: key is superseded (2020-09-13) \"new key\"",
+			revocations[0].get_info("%F")
+		);
+		assert_eq!(
+			Some(String::from("Test <test@example.org>")),
+			revocations[1].target
+		);
+		assert_eq!(
+			"user ID information is no longer valid",
+			revocations[1].get_reason()
+		);
+		assert_eq!(
+			Some(String::from("0xABCDEF1234567890")),
+			revocations[2].target
+		);
+		assert_eq!("no reason given", revocations[2].get_reason());
+		let now = Utc::now();
+		assert_eq!("in 42d", get_relative_time(now + Duration::days(42), now));
+		assert_eq!(
+			"3y ago",
+			get_relative_time(now - Duration::days(1100), now)
+		);
+		assert_eq!("today", get_relative_time(now, now));
+		assert!(is_valid_date_format("%d/%m/%Y"));
+		assert!(is_valid_date_format(RELATIVE_DATE_FORMAT));
+		assert!(!is_valid_date_format("%Q"));
+		assert_eq!(
+			"expires in 42d",
+			get_relative_expiration(now + Duration::days(42), now)
+		);
+		assert_eq!(
+			"expires today",
+			get_relative_expiration(now + Duration::hours(3), now)
+		);
+		assert_eq!(
+			"expired 3d ago",
+			get_relative_expiration(now - Duration::days(3), now)
+		);
+	}
+
+	#[test]
+	fn test_gpg_compare_fingerprints() {
+		let groups = compare_fingerprints(
+			"0x1234567890ABCDEF",
+			"1234 5678 90AB cdeg 0000",
+		);
+		assert_eq!(5, groups.len());
+		assert_eq!(
+			vec![3, 4],
+			groups
+				.iter()
+				.enumerate()
+				.filter(|(_, (expected, given))| expected != given)
+				.map(|(i, _)| i)
+				.collect::<Vec<usize>>()
+		);
+		assert!(compare_fingerprints("AB:CD", " abcd ")
+			.iter()
+			.all(|(expected, given)| expected == given));
+	}
+
+	#[test]
+	fn test_gpg_parse_preferences() {
+		let output = "sec  ed25519/0x1234567890ABCDEF\n\
+		              \x20    created: 2024-01-01  usage: SC\n\
+		              [ultimate] (1). Test <test@example.org>\n\
+		              \x20    Cipher: AES256, AES192, AES, 3DES\n\
+		              \x20    Digest: SHA512, SHA384, SHA256, SHA1\n\
+		              [ revoked] (2)  Old <old@example.org>\n\
+		              [ultimate] (3)  Other <other@example.org>\n\
+		              \x20    Compression: ZLIB, BZIP2, ZIP, Uncompressed\n";
+		assert_eq!(
+			vec![
+				(
+					String::from("Test <test@example.org>"),
+					vec![
+						String::from("Cipher: AES256, AES192, AES, 3DES"),
+						String::from("Digest: SHA512, SHA384, SHA256, SHA1"),
+					]
+				),
+				(String::from("Old <old@example.org>"), Vec::new()),
+				(
+					String::from("Other <other@example.org>"),
+					vec![String::from(
+						"Compression: ZLIB, BZIP2, ZIP, Uncompressed"
+					)]
+				),
+			],
+			parse_preferences(output)
+		);
+	}
+}
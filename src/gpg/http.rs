@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use std::io::Read;
+use std::time::Duration;
+
+/// Maximum size of the response body. (in bytes)
+pub const MAX_RESPONSE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Timeout of the requests.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sends an HTTP(S) request and returns the response body.
+///
+/// The body is posted with its content type if it is given.
+/// Responses that are larger than [`MAX_RESPONSE_SIZE`] are rejected.
+pub fn send_request(url: &str, body: Option<(&str, &[u8])>) -> Result<Vec<u8>> {
+	let agent = ureq::AgentBuilder::new()
+		.timeout(TIMEOUT)
+		.user_agent(concat!(
+			env!("CARGO_PKG_NAME"),
+			"/",
+			env!("CARGO_PKG_VERSION")
+		))
+		.build();
+	let response = match body {
+		Some((content_type, data)) => agent
+			.post(url)
+			.set("Content-Type", content_type)
+			.send_bytes(data),
+		None => agent.get(url).call(),
+	}
+	.map_err(|e| anyhow!("request failed: {e}"))?;
+	let mut data = Vec::new();
+	response
+		.into_reader()
+		.take(MAX_RESPONSE_SIZE + 1)
+		.read_to_end(&mut data)?;
+	if data.len() as u64 > MAX_RESPONSE_SIZE {
+		return Err(anyhow!(
+			"response is too large (limit: {MAX_RESPONSE_SIZE} bytes)"
+		));
+	}
+	Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_gpg_http() {
+		assert!(send_request("ftp://example.org/key.asc", None).is_err());
+		assert!(send_request("not a url", None).is_err());
+	}
+}
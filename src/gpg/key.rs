@@ -1,10 +1,26 @@
-use crate::gpg::handler;
+use crate::gpg::handler::{self, Revocation};
+use chrono::{DateTime, Utc};
 use clap::ValueEnum;
-use gpgme::{Key, SignatureNotation, Subkey, UserId, UserIdSignature};
+use gpgme::{
+	Key, SignatureNotation, Subkey, UserId, UserIdSignature, Validity,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::str::FromStr;
 
+/// Keywords that indicate a user ID comment is not meant to be published.
+const PRIVATE_COMMENT_KEYWORDS: &[&str] = &[
+	"private",
+	"personal",
+	"internal",
+	"confidential",
+	"secret",
+	"local",
+	"unpublished",
+	"do not publish",
+];
+
 /// Type of the key.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum KeyType {
@@ -41,7 +57,7 @@ impl FromStr for KeyType {
 
 /// Level of detail to show for key.
 #[derive(
-	Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum,
+	Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum,
 )]
 #[serde(rename_all = "snake_case")]
 #[clap(rename_all = "snake_case")]
@@ -80,6 +96,128 @@ impl KeyDetail {
 	}
 }
 
+/// Output format of the key list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+	/// Fingerprint and user ID of each key per line.
+	Plain,
+	/// JSON array of the keys with their subkeys.
+	Json,
+}
+
+/// Capability of a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeyCapability {
+	/// Encryption capability.
+	#[clap(name = "can-encrypt", alias = "encrypt")]
+	Encrypt,
+	/// Signing capability.
+	#[clap(name = "can-sign", alias = "sign")]
+	Sign,
+	/// Certification capability.
+	#[clap(name = "can-certify", alias = "certify")]
+	Certify,
+	/// Authentication capability.
+	#[clap(name = "can-auth", aliases = ["auth", "authenticate"])]
+	Auth,
+}
+
+impl Display for KeyCapability {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Encrypt => "can-encrypt",
+				Self::Sign => "can-sign",
+				Self::Certify => "can-certify",
+				Self::Auth => "can-auth",
+			}
+		)
+	}
+}
+
+/// State of a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeyState {
+	/// Expired key.
+	Expired,
+	/// Revoked key.
+	Revoked,
+	/// Disabled key.
+	Disabled,
+	/// Key that is not expired, revoked, disabled or invalid.
+	Valid,
+	/// Key with an available secret key.
+	#[clap(name = "has-secret", alias = "secret")]
+	HasSecret,
+}
+
+impl Display for KeyState {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Expired => "expired",
+				Self::Revoked => "revoked",
+				Self::Disabled => "disabled",
+				Self::Valid => "valid",
+				Self::HasSecret => "has-secret",
+			}
+		)
+	}
+}
+
+/// Filter for the listed keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFilter {
+	/// Keys with the given capability.
+	Capability(KeyCapability),
+	/// Keys in the given state.
+	State(KeyState),
+}
+
+impl Display for KeyFilter {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		match self {
+			Self::Capability(capability) => write!(f, "{capability}"),
+			Self::State(state) => write!(f, "{state}"),
+		}
+	}
+}
+
+impl FromStr for KeyFilter {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		KeyCapability::from_str(s, true)
+			.map(Self::Capability)
+			.or_else(|_| KeyState::from_str(s, true).map(Self::State))
+	}
+}
+
+/// Entry of the key detail view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyDetailEntry {
+	/// Name of the field.
+	pub name: String,
+	/// Value of the field.
+	pub value: String,
+	/// Level of indentation.
+	pub depth: usize,
+}
+
+impl KeyDetailEntry {
+	/// Constructs a new instance of `KeyDetailEntry`.
+	fn new(depth: usize, name: &str, value: impl ToString) -> Self {
+		Self {
+			name: name.to_string(),
+			value: value.to_string(),
+			depth,
+		}
+	}
+}
+
 /// Representation of a key.
 #[derive(Clone, Debug)]
 pub struct GpgKey {
@@ -87,12 +225,149 @@ pub struct GpgKey {
 	inner: Key,
 	/// Level of detail to show about key information.
 	pub detail: KeyDetail,
+	/// Revocations of the key, subkeys and user IDs.
+	pub revocations: Vec<Revocation>,
 }
 
 impl GpgKey {
 	/// Constructs a new instance of `GpgKey`.
 	pub fn new(key: Key, detail: KeyDetail) -> Self {
-		Self { inner: key, detail }
+		Self {
+			inner: key,
+			detail,
+			revocations: Vec::new(),
+		}
+	}
+
+	/// Checks if the key or any of its subkeys/user IDs is revoked.
+	pub fn has_revocations(&self) -> bool {
+		self.inner.is_revoked()
+			|| self.inner.subkeys().any(|subkey| subkey.is_revoked())
+			|| self.inner.user_ids().any(|user| user.is_revoked())
+	}
+
+	/// Checks if the key has a usable subkey with the given capability.
+	///
+	/// Revoked, expired, disabled and invalid subkeys are not considered.
+	pub fn has_capability(&self, capability: KeyCapability) -> bool {
+		!self.inner.is_revoked()
+			&& !self.inner.is_expired()
+			&& !self.inner.is_disabled()
+			&& !self.inner.is_invalid()
+			&& self.inner.subkeys().any(|subkey| {
+				!subkey.is_revoked()
+					&& !subkey.is_expired()
+					&& !subkey.is_disabled()
+					&& !subkey.is_invalid()
+					&& match capability {
+						KeyCapability::Encrypt => subkey.can_encrypt(),
+						KeyCapability::Sign => subkey.can_sign(),
+						KeyCapability::Certify => subkey.can_certify(),
+						KeyCapability::Auth => subkey.can_authenticate(),
+					}
+			})
+	}
+
+	/// Checks if the key is in the given state.
+	pub fn has_state(&self, state: KeyState) -> bool {
+		match state {
+			KeyState::Expired => self.inner.is_expired(),
+			KeyState::Revoked => self.inner.is_revoked(),
+			KeyState::Disabled => self.inner.is_disabled(),
+			KeyState::Valid => {
+				!self.inner.is_expired()
+					&& !self.inner.is_revoked()
+					&& !self.inner.is_disabled()
+					&& !self.inner.is_invalid()
+			}
+			KeyState::HasSecret => self.inner.has_secret(),
+		}
+	}
+
+	/// Checks if the key matches the given filter.
+	pub fn matches_filter(&self, filter: KeyFilter) -> bool {
+		match filter {
+			KeyFilter::Capability(capability) => {
+				self.has_capability(capability)
+			}
+			KeyFilter::State(state) => self.has_state(state),
+		}
+	}
+
+	/// Checks if the key has a (sub)key with the given ID or fingerprint.
+	pub fn has_subkey(&self, key_id: &str) -> bool {
+		let key_id = key_id.trim_start_matches("0x");
+		self.inner.subkeys().any(|subkey| {
+			[subkey.fingerprint(), subkey.id()]
+				.iter()
+				.any(|v| v.map_or(false, |v| v.eq_ignore_ascii_case(key_id)))
+		})
+	}
+
+	/// Checks if one of the user IDs has the given email address.
+	pub fn has_email(&self, email: &str) -> bool {
+		self.inner.user_ids().any(|user| {
+			user.email()
+				.map_or(false, |v| v.eq_ignore_ascii_case(email))
+		})
+	}
+
+	/// Returns the IDs of the signer keys that are not in the keyring.
+	pub fn get_unknown_signers(&self) -> Vec<String> {
+		let mut signers = Vec::new();
+		for signature in
+			self.inner.user_ids().flat_map(|user| user.signatures())
+		{
+			let key_id = match signature.signer_key_id() {
+				Ok(key_id) if Ok(key_id) != self.inner.id() => key_id,
+				_ => continue,
+			};
+			if signature.signer_user_id().unwrap_or_default().is_empty()
+				&& !signers.contains(&format!("0x{key_id}"))
+			{
+				signers.push(format!("0x{key_id}"));
+			}
+		}
+		signers
+	}
+
+	/// Checks if the given user ID comment looks private.
+	pub fn is_private_comment(comment: &str) -> bool {
+		let comment = comment.to_lowercase();
+		PRIVATE_COMMENT_KEYWORDS
+			.iter()
+			.any(|keyword| comment.contains(keyword))
+	}
+
+	/// Returns warnings about the information that should not be
+	/// published to a keyserver.
+	///
+	/// * non-exportable (local) signatures
+	/// * user IDs with private comments
+	pub fn get_publish_warnings(&self) -> Vec<String> {
+		let mut warnings = Vec::new();
+		for user in self.inner.user_ids() {
+			let user_id = user.id().unwrap_or("[?]");
+			if Self::is_private_comment(user.comment().unwrap_or_default()) {
+				warnings.push(format!("private comment: {user_id}"));
+			}
+			warnings.extend(
+				user.signatures()
+					.filter(|signature| !signature.is_exportable())
+					.map(|signature| {
+						format!(
+							"local signature by 0x{}: {user_id}",
+							signature.signer_key_id().unwrap_or("[?]")
+						)
+					}),
+			);
+		}
+		warnings
+	}
+
+	/// Returns a reference to the underlying GPGME key.
+	pub fn get_raw(&self) -> &Key {
+		&self.inner
 	}
 
 	/// Returns the key ID with '0x' prefix.
@@ -119,21 +394,162 @@ impl GpgKey {
 		}
 	}
 
+	/// Returns the email addresses of the user IDs.
+	pub fn get_emails(&self) -> Vec<String> {
+		self.inner
+			.user_ids()
+			.filter_map(|user| user.email().ok().map(String::from))
+			.filter(|email| !email.is_empty())
+			.collect()
+	}
+
+	/// Returns the contact card (vCard) of the key holder.
+	///
+	/// Name is taken from the primary user ID while all the
+	/// email addresses of the user IDs are included.
+	pub fn get_vcard(&self) -> String {
+		let name = self
+			.inner
+			.user_ids()
+			.next()
+			.and_then(|user| user.name().ok().map(String::from))
+			.unwrap_or_default();
+		let emails = self.get_emails();
+		handler::get_vcard(
+			&name,
+			&emails.iter().map(String::as_str).collect::<Vec<&str>>(),
+			&self.get_fingerprint(),
+		)
+	}
+
+	/// Returns the fields of the key as a JSON object.
+	pub fn get_json(&self) -> Value {
+		json!({
+			"id": self.get_id(),
+			"fingerprint": self.get_fingerprint(),
+			"user_id": self.get_user_id(),
+			"user_ids": self
+				.inner
+				.user_ids()
+				.filter_map(|user| user.id().ok())
+				.collect::<Vec<&str>>(),
+			"created": self.get_creation_time(),
+			"expires": self.get_expiration_time(),
+			"owner_trust": self.get_owner_trust(),
+			"revoked": self.inner.is_revoked(),
+			"expired": self.inner.is_expired(),
+		})
+	}
+
+	/// Returns the fields of the key along with the
+	/// validity and subkeys as a JSON object.
+	pub fn get_detailed_json(&self) -> Value {
+		let mut json = self.get_json();
+		json["validity"] = Value::from(self.get_validity().to_string());
+		json["has_secret"] = Value::from(self.inner.has_secret());
+		json["subkeys"] = self
+			.inner
+			.subkeys()
+			.skip(1)
+			.map(|subkey| {
+				json!({
+					"id": subkey.id().ok(),
+					"fingerprint": subkey.fingerprint().ok(),
+					"algorithm": subkey.algorithm_name().ok(),
+					"flags": handler::get_subkey_flags(subkey),
+					"created": subkey
+						.creation_time()
+						.map(handler::get_iso_timestamp),
+					"expires": subkey
+						.expiration_time()
+						.map(handler::get_iso_timestamp),
+					"revoked": subkey.is_revoked(),
+					"expired": subkey.is_expired(),
+				})
+			})
+			.collect::<Vec<Value>>()
+			.into();
+		json
+	}
+
+	/// Returns the creation time of the primary key as ISO 8601 timestamp.
+	pub fn get_creation_time(&self) -> Option<String> {
+		self.inner
+			.subkeys()
+			.next()
+			.and_then(|key| key.creation_time())
+			.map(handler::get_iso_timestamp)
+	}
+
+	/// Returns the expiration time of the primary key as ISO 8601 timestamp.
+	pub fn get_expiration_time(&self) -> Option<String> {
+		self.inner
+			.subkeys()
+			.next()
+			.and_then(|key| key.expiration_time())
+			.map(handler::get_iso_timestamp)
+	}
+
+	/// Returns the number of days until the primary key expires.
+	///
+	/// It is negative if the key is already expired.
+	pub fn get_days_until_expiry(&self) -> Option<i64> {
+		self.inner
+			.subkeys()
+			.next()
+			.and_then(|key| key.expiration_time())
+			.map(|date| {
+				DateTime::<Utc>::from(date)
+					.signed_duration_since(Utc::now())
+					.num_days()
+			})
+	}
+
+	/// Returns the computed validity of the key.
+	///
+	/// It is the validity of the primary user ID as GnuPG does.
+	pub fn get_validity(&self) -> Validity {
+		self.inner
+			.user_ids()
+			.next()
+			.map(|user| user.validity())
+			.unwrap_or(Validity::Unknown)
+	}
+
+	/// Returns the owner trust of the key.
+	///
+	/// `None` is returned if the owner trust is not set.
+	pub fn get_owner_trust(&self) -> Option<&str> {
+		match self.inner.owner_trust() {
+			Validity::Never => Some("never"),
+			Validity::Marginal => Some("marginal"),
+			Validity::Full => Some("full"),
+			Validity::Ultimate => Some("ultimate"),
+			_ => None,
+		}
+	}
+
 	/// Returns information about the subkeys.
 	pub fn get_subkey_info(
 		&self,
 		default_key: Option<&str>,
 		truncate: bool,
+		date_format: &str,
 	) -> Vec<String> {
+		let date_format = Self::get_date_format(date_format, truncate);
+		// default key can be given as key ID or fingerprint (e.g. in gpg.conf)
+		let default_key =
+			default_key.map(|v| v.trim_start_matches("0x").to_uppercase());
 		let mut key_info = Vec::new();
 		let subkeys = self.inner.subkeys().collect::<Vec<Subkey>>();
 		for (i, subkey) in subkeys.iter().enumerate() {
 			key_info.push(format!(
 				"[{}]{}{}/{}",
 				handler::get_subkey_flags(*subkey),
-				if default_key.map(|v| v.trim_start_matches("0x"))
-					== subkey.id().ok()
-				{
+				if default_key.as_deref().map_or(false, |v| {
+					subkey.id().ok() == Some(v)
+						|| subkey.fingerprint().ok() == Some(v)
+				}) {
 					"*"
 				} else {
 					" "
@@ -163,17 +579,46 @@ impl GpgKey {
 			key_info.push(format!(
 				"{}      └─{}",
 				if i != subkeys.len() - 1 { "|" } else { " " },
-				handler::get_subkey_time(
-					*subkey,
-					if truncate { "%Y" } else { "%F" }
-				)
+				handler::get_subkey_time(*subkey, date_format)
 			));
 		}
 		key_info
 	}
 
+	/// Returns the relative expiration times of the subkeys.
+	///
+	/// Lines are aligned with the output of [`get_subkey_info`].
+	///
+	/// [`get_subkey_info`]: GpgKey::get_subkey_info
+	pub fn get_expiration_info(&self) -> Vec<String> {
+		let mut expiration_info = Vec::new();
+		for subkey in self.inner.subkeys() {
+			expiration_info.push(handler::get_subkey_countdown(subkey));
+			if self.detail == KeyDetail::Minimum {
+				break;
+			}
+			expiration_info.push(String::new());
+		}
+		expiration_info
+	}
+
+	/// Returns the date format depending on the truncation.
+	///
+	/// Only the year is shown for truncated absolute dates.
+	fn get_date_format(date_format: &str, truncate: bool) -> &str {
+		if truncate && date_format != handler::RELATIVE_DATE_FORMAT {
+			"%Y"
+		} else {
+			date_format
+		}
+	}
+
 	/// Returns information about the users of the key.
-	pub fn get_user_info(&self, truncate: bool) -> Vec<String> {
+	pub fn get_user_info(
+		&self,
+		truncate: bool,
+		date_format: &str,
+	) -> Vec<String> {
 		let mut user_info = Vec::new();
 		let user_ids = self.inner.user_ids().collect::<Vec<UserId>>();
 		for (i, user) in user_ids.iter().enumerate() {
@@ -194,17 +639,165 @@ impl GpgKey {
 				break;
 			}
 			if self.detail == KeyDetail::Full {
+				user_info.extend(
+					self.revocations
+						.iter()
+						.filter(|revocation| {
+							revocation.class == 0x30
+								&& revocation.target.as_deref()
+									== user.id().ok()
+						})
+						.map(|revocation| {
+							format!(
+								" {}  {}",
+								Self::get_user_padding(user_ids.len(), i),
+								revocation.get_info(Self::get_date_format(
+									date_format,
+									truncate
+								))
+							)
+						}),
+				);
 				user_info.extend(self.get_user_signatures(
 					user,
 					user_ids.len(),
 					i,
 					truncate,
+					date_format,
 				));
 			}
 		}
+		if self.detail == KeyDetail::Full {
+			user_info.extend(
+				self.revocations
+					.iter()
+					.filter(|revocation| revocation.class != 0x30)
+					.map(|revocation| {
+						format!(
+							"{}{}",
+							revocation
+								.target
+								.as_ref()
+								.map(|key_id| format!("{key_id} "))
+								.unwrap_or_default(),
+							revocation.get_info(Self::get_date_format(
+								date_format,
+								truncate
+							))
+						)
+					}),
+			);
+		}
 		user_info
 	}
 
+	/// Returns all the information about the key for the detail view.
+	///
+	/// Subkeys, user IDs, signatures and notations are listed
+	/// as separate entries so that each value can be copied.
+	pub fn get_detail_entries(&self, date_format: &str) -> Vec<KeyDetailEntry> {
+		let mut entries = vec![
+			KeyDetailEntry::new(0, "key ID", self.get_id()),
+			KeyDetailEntry::new(0, "fingerprint", self.get_fingerprint()),
+			KeyDetailEntry::new(0, "validity", self.get_validity()),
+		];
+		if let Some(owner_trust) = self.get_owner_trust() {
+			entries.push(KeyDetailEntry::new(0, "owner trust", owner_trust));
+		}
+		for (i, subkey) in self.inner.subkeys().enumerate() {
+			entries.push(KeyDetailEntry::new(
+				0,
+				if i == 0 { "primary key" } else { "subkey" },
+				subkey.fingerprint().unwrap_or("[?]"),
+			));
+			entries.push(KeyDetailEntry::new(
+				1,
+				"algorithm",
+				subkey
+					.algorithm_name()
+					.unwrap_or_else(|_| String::from("[?]")),
+			));
+			entries.push(KeyDetailEntry::new(
+				1,
+				"flags",
+				handler::get_subkey_flags(subkey),
+			));
+			if let Ok(keygrip) = subkey.keygrip() {
+				entries.push(KeyDetailEntry::new(1, "keygrip", keygrip));
+			}
+			entries.push(KeyDetailEntry::new(
+				1,
+				"time",
+				handler::get_subkey_time(subkey, date_format),
+			));
+		}
+		for user in self.inner.user_ids() {
+			entries.push(KeyDetailEntry::new(
+				0,
+				"user ID",
+				user.id().unwrap_or("[?]"),
+			));
+			entries.push(KeyDetailEntry::new(1, "validity", user.validity()));
+			for sig in user.signatures() {
+				entries.push(KeyDetailEntry::new(
+					1,
+					"signature",
+					sig.signer_key_id().unwrap_or("[?]"),
+				));
+				entries.push(KeyDetailEntry::new(
+					2,
+					"signer",
+					match sig.signer_user_id() {
+						Ok(user_id) if !user_id.is_empty() => user_id,
+						_ => "[?]",
+					},
+				));
+				entries.push(KeyDetailEntry::new(
+					2,
+					"class",
+					format!("{:x}", sig.cert_class()),
+				));
+				entries.push(KeyDetailEntry::new(
+					2,
+					"time",
+					handler::get_signature_time(sig, date_format),
+				));
+				for notation in sig.notations() {
+					entries.push(KeyDetailEntry::new(
+						2,
+						"notation",
+						format!(
+							"{}={}",
+							notation.name().unwrap_or("?"),
+							notation.value().unwrap_or("?")
+						),
+					));
+				}
+			}
+		}
+		entries.extend(self.revocations.iter().map(|revocation| {
+			KeyDetailEntry::new(
+				0,
+				"revocation",
+				revocation.get_info(date_format),
+			)
+		}));
+		entries
+	}
+
+	/// Returns the padding for the lines under the given user.
+	fn get_user_padding(user_count: usize, user_index: usize) -> &'static str {
+		if user_count == 1 {
+			" "
+		} else if user_index == user_count - 1 {
+			"    "
+		} else if user_index == 0 {
+			"│"
+		} else {
+			"│   "
+		}
+	}
+
 	/// Returns the signature information of an user.
 	fn get_user_signatures(
 		&self,
@@ -212,20 +805,13 @@ impl GpgKey {
 		user_count: usize,
 		user_index: usize,
 		truncate: bool,
+		date_format: &str,
 	) -> Vec<String> {
 		let mut user_signatures = Vec::new();
 		let signatures = user.signatures().collect::<Vec<UserIdSignature>>();
 		for (i, sig) in signatures.iter().enumerate() {
 			let notations = sig.notations().collect::<Vec<SignatureNotation>>();
-			let padding = if user_count == 1 {
-				" "
-			} else if user_index == user_count - 1 {
-				"    "
-			} else if user_index == 0 {
-				"│"
-			} else {
-				"│   "
-			};
+			let padding = Self::get_user_padding(user_count, user_index);
 			user_signatures.push(format!(
 				" {}  {}[{:x}] {} {}",
 				padding,
@@ -249,7 +835,7 @@ impl GpgKey {
 				},
 				handler::get_signature_time(
 					*sig,
-					if truncate { "%Y" } else { "%F" }
+					Self::get_date_format(date_format, truncate)
 				)
 			));
 			if !notations.is_empty() {
@@ -328,17 +914,33 @@ mod tests {
 		assert_eq!(KeyDetail::Full, key.detail);
 		assert_eq!("full", key.detail.to_string());
 		assert!(key
-			.get_subkey_info(Some(""), true)
+			.get_subkey_info(Some(""), true, "%F")
 			.join("\n")
 			.contains(&key.get_id().replace("0x", "")));
 		assert!(key
-			.get_subkey_info(Some(""), false)
+			.get_subkey_info(Some(""), false, "%F")
 			.join("\n")
 			.contains(&key.get_fingerprint()));
 		assert!(key
-			.get_user_info(false)
+			.get_user_info(false, "%F")
 			.join("\n")
 			.contains(&key.get_user_id()));
+		let json = key.get_json();
+		assert_eq!(Some(key.get_id().as_str()), json["id"].as_str());
+		assert_eq!(
+			Some(key.get_fingerprint().as_str()),
+			json["fingerprint"].as_str()
+		);
+		let entries = key.get_detail_entries("%F");
+		assert_eq!(key.get_id(), entries[0].value);
+		assert!(entries.iter().any(|entry| entry.name == "keygrip"));
+		assert!(entries.iter().any(|entry| entry.value == key.get_user_id()));
+		assert!(key.has_subkey(&key.get_id()));
+		assert!(key.has_subkey(&key.get_fingerprint().to_lowercase()));
+		assert!(!key.has_subkey("0x0"));
+		assert!(GpgKey::is_private_comment("Private"));
+		assert!(GpgKey::is_private_comment("do not publish"));
+		assert!(!GpgKey::is_private_comment("work"));
 		Ok(())
 	}
 }
@@ -0,0 +1,210 @@
+use crate::widget::secret::SecretInput;
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, Utc};
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::time::Duration;
+
+/// Number of seconds in a day.
+const SECONDS_IN_DAY: u64 = 86_400;
+
+/// Public key algorithm of the generated key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyAlgorithm {
+	/// EdDSA/ECDSA for signing and ECDH for encryption.
+	Ecc,
+	/// RSA for signing and encryption.
+	Rsa,
+	/// DSA for signing and Elgamal for encryption.
+	Dsa,
+}
+
+impl Default for KeyAlgorithm {
+	fn default() -> Self {
+		Self::Ecc
+	}
+}
+
+impl Display for KeyAlgorithm {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Ecc => "ECC (EdDSA/ECDH)",
+				Self::Rsa => "RSA",
+				Self::Dsa => "DSA (Elgamal)",
+			}
+		)
+	}
+}
+
+impl KeyAlgorithm {
+	/// Available algorithms.
+	pub const ALL: [Self; 3] = [Self::Ecc, Self::Rsa, Self::Dsa];
+
+	/// Returns the available key sizes or curves.
+	///
+	/// The first one is the recommended default.
+	pub fn get_sizes(&self) -> &'static [&'static str] {
+		match self {
+			Self::Ecc => &["25519", "nistp256", "nistp384", "nistp521"],
+			Self::Rsa => &["4096", "3072", "2048"],
+			Self::Dsa => &["3072", "2048"],
+		}
+	}
+}
+
+/// Parses the expiration time in the format of GnuPG.
+///
+/// Supported values are `0`/`never` (no expiration), a date in
+/// `YYYY-MM-DD` format or a number with an optional `d`, `w`, `m`
+/// or `y` suffix.
+pub fn parse_expiry(value: &str) -> Result<Option<Duration>> {
+	let value = value.trim().to_lowercase();
+	if value.is_empty() || value == "0" || value == "never" {
+		return Ok(None);
+	}
+	if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+		let days = date
+			.signed_duration_since(Utc::now().date_naive())
+			.num_days();
+		return match u64::try_from(days) {
+			Ok(days) if days != 0 => days
+				.checked_mul(SECONDS_IN_DAY)
+				.map(|seconds| Some(Duration::from_secs(seconds)))
+				.ok_or_else(|| anyhow!("expiration date is too far: {value}")),
+			_ => Err(anyhow!("expiration date is not in the future: {value}")),
+		};
+	}
+	let (number, days) = match value.chars().last() {
+		Some('d') => (&value[..value.len() - 1], 1),
+		Some('w') => (&value[..value.len() - 1], 7),
+		Some('m') => (&value[..value.len() - 1], 30),
+		Some('y') => (&value[..value.len() - 1], 365),
+		_ => (value.as_str(), 1),
+	};
+	match number.parse::<u64>() {
+		Ok(0) => Ok(None),
+		Ok(number) => number
+			.checked_mul(days * SECONDS_IN_DAY)
+			.map(|seconds| Some(Duration::from_secs(seconds)))
+			.ok_or_else(|| anyhow!("expiration time is too long: {value}")),
+		Err(_) => Err(anyhow!("invalid expiration time: {value}")),
+	}
+}
+
+/// Parameters for generating a new key pair.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyGenParams {
+	/// Public key algorithm.
+	pub algorithm: KeyAlgorithm,
+	/// Key size or curve.
+	pub size: String,
+	/// Whether if the primary key can sign.
+	pub sign: bool,
+	/// Whether if an encryption subkey is generated.
+	pub encrypt: bool,
+	/// Whether if the primary key can authenticate.
+	pub auth: bool,
+	/// User ID of the key.
+	pub user_id: String,
+	/// Expiration time. (`None` for no expiration)
+	pub expiry: Option<Duration>,
+	/// Passphrase of the key. (empty for no protection)
+	pub passphrase: SecretInput,
+}
+
+impl Debug for KeyGenParams {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		f.debug_struct("KeyGenParams")
+			.field("algorithm", &self.algorithm)
+			.field("size", &self.size)
+			.field("sign", &self.sign)
+			.field("encrypt", &self.encrypt)
+			.field("auth", &self.auth)
+			.field("user_id", &self.user_id)
+			.field("expiry", &self.expiry)
+			.field("passphrase", &"[hidden]")
+			.finish()
+	}
+}
+
+impl KeyGenParams {
+	/// Returns the algorithm of the primary key.
+	pub fn get_primary_algo(&self) -> String {
+		match self.algorithm {
+			KeyAlgorithm::Ecc if self.size == "25519" => {
+				String::from("ed25519")
+			}
+			KeyAlgorithm::Ecc => self.size.to_string(),
+			KeyAlgorithm::Rsa => format!("rsa{}", self.size),
+			KeyAlgorithm::Dsa => format!("dsa{}", self.size),
+		}
+	}
+
+	/// Returns the algorithm of the encryption subkey.
+	pub fn get_subkey_algo(&self) -> String {
+		match self.algorithm {
+			KeyAlgorithm::Ecc if self.size == "25519" => {
+				String::from("cv25519")
+			}
+			KeyAlgorithm::Ecc => self.size.to_string(),
+			KeyAlgorithm::Rsa => format!("rsa{}", self.size),
+			KeyAlgorithm::Dsa => format!("elg{}", self.size),
+		}
+	}
+
+	/// Returns the algorithm of a subkey that is added to an existing key.
+	///
+	/// Encryption-only subkeys use the algorithm of the encryption subkey.
+	pub fn get_added_subkey_algo(&self) -> String {
+		if self.encrypt && !self.sign && !self.auth {
+			self.get_subkey_algo()
+		} else {
+			self.get_primary_algo()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_keygen() -> Result<()> {
+		assert_eq!(None, parse_expiry("0")?);
+		assert_eq!(None, parse_expiry("never")?);
+		assert_eq!(Some(Duration::from_secs(86_400 * 10)), parse_expiry("10")?);
+		assert_eq!(Some(Duration::from_secs(86_400 * 14)), parse_expiry("2w")?);
+		assert_eq!(
+			Some(Duration::from_secs(86_400 * 730)),
+			parse_expiry("2Y")?
+		);
+		assert!(parse_expiry("2x").is_err());
+		assert!(parse_expiry(&format!("{}y", u64::MAX / 365)).is_err());
+		assert!(parse_expiry("2000-01-01").is_err());
+		assert!(parse_expiry("2999-01-01")?.is_some());
+		assert_eq!("25519", KeyAlgorithm::default().get_sizes()[0]);
+		let mut params = KeyGenParams {
+			algorithm: KeyAlgorithm::Ecc,
+			size: String::from("25519"),
+			sign: true,
+			encrypt: true,
+			auth: false,
+			user_id: String::from("Test <test@example.org>"),
+			expiry: None,
+			passphrase: SecretInput::from("secret"),
+		};
+		assert_eq!("ed25519", params.get_primary_algo());
+		assert_eq!("cv25519", params.get_subkey_algo());
+		assert_eq!("ed25519", params.get_added_subkey_algo());
+		params.sign = false;
+		assert_eq!("cv25519", params.get_added_subkey_algo());
+		assert!(!format!("{params:?}").contains("secret"));
+		params.algorithm = KeyAlgorithm::Dsa;
+		params.size = String::from("3072");
+		assert_eq!("dsa3072", params.get_primary_algo());
+		assert_eq!("elg3072", params.get_subkey_algo());
+		Ok(())
+	}
+}
@@ -0,0 +1,344 @@
+use crate::gpg::context::GpgContext;
+use crate::gpg::http::send_request;
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use gpgme::{Context, ExportMode, Key, KeyListMode, Protocol};
+use serde_json::json;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use tempfile::TempDir;
+
+/// Default port of the HKP keyservers.
+const HKP_PORT: u16 = 11371;
+
+/// Type of the keyserver backend.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BackendType {
+	/// GnuPG (via `dirmngr`).
+	#[default]
+	Gnupg,
+	/// HTTP Keyserver Protocol. (e.g. `hkps://keyserver.ubuntu.com`)
+	Hkp,
+	/// Verifying Keyserver API. (e.g. `https://keys.openpgp.org`)
+	Vks,
+}
+
+impl Display for BackendType {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Gnupg => "gnupg",
+				Self::Hkp => "hkp",
+				Self::Vks => "vks",
+			}
+		)
+	}
+}
+
+/// Transport for sending and receiving keys.
+pub trait KeyserverBackend {
+	/// Sends the given (armored) key to the keyserver.
+	fn send_key(&self, key_id: &str, key: &str) -> Result<()>;
+
+	/// Returns the keys that match the given query.
+	///
+	/// Keys are returned in their exported form
+	/// so that they can be imported afterwards.
+	fn receive_keys(&self, query: &str) -> Result<Vec<u8>>;
+
+	/// Returns the keys that match the given query without importing them.
+	fn search_keys(&self, query: &str) -> Result<Vec<Key>>;
+}
+
+/// Returns the backend of the given type.
+///
+/// Default keyserver of `dirmngr` is only available for the GnuPG backend.
+pub fn get_backend(
+	backend_type: BackendType,
+	home_dir: &Path,
+	keyserver: Option<&str>,
+) -> Result<Box<dyn KeyserverBackend>> {
+	let get_url = || {
+		keyserver
+			.map(get_http_url)
+			.ok_or_else(|| anyhow!("keyserver is not specified"))
+	};
+	Ok(match backend_type {
+		BackendType::Gnupg => Box::new(GnupgBackend {
+			home_dir: home_dir.to_path_buf(),
+			keyserver: keyserver.map(String::from),
+		}),
+		BackendType::Hkp => Box::new(HkpBackend { url: get_url()? }),
+		BackendType::Vks => Box::new(VksBackend { url: get_url()? }),
+	})
+}
+
+/// Returns the GnuPG backend for searching the keyserver at the given URL.
+///
+/// Keys are listed via HKP which is also supported by the VKS servers.
+fn get_search_backend(url: &str) -> GnupgBackend {
+	GnupgBackend {
+		home_dir: PathBuf::new(),
+		keyserver: Some(if let Some(host) = url.strip_prefix("https://") {
+			format!("hkps://{host}")
+		} else if let Some(host) = url.strip_prefix("http://") {
+			format!("hkp://{host}")
+		} else {
+			url.to_string()
+		}),
+	}
+}
+
+/// Converts the given keyserver address to an HTTP URL.
+///
+/// e.g. `hkp://example.org` -> `http://example.org:11371`
+fn get_http_url(keyserver: &str) -> String {
+	let keyserver = keyserver.trim_end_matches('/');
+	if let Some(host) = keyserver.strip_prefix("hkps://") {
+		format!("https://{host}")
+	} else if let Some(host) = keyserver.strip_prefix("hkp://") {
+		if host.contains(':') {
+			format!("http://{host}")
+		} else {
+			format!("http://{host}:{HKP_PORT}")
+		}
+	} else {
+		keyserver.to_string()
+	}
+}
+
+/// Percent-encodes the given value for using in a URL.
+fn encode_url_component(value: &str) -> String {
+	value
+		.bytes()
+		.map(|byte| match byte {
+			b'A'..=b'Z'
+			| b'a'..=b'z'
+			| b'0'..=b'9'
+			| b'-'
+			| b'.'
+			| b'_'
+			| b'~' => char::from(byte).to_string(),
+			_ => format!("%{byte:02X}"),
+		})
+		.collect()
+}
+
+/// Backend that uses GnuPG and the configuration of `dirmngr`.
+#[derive(Debug)]
+pub struct GnupgBackend {
+	/// Home directory of GnuPG.
+	home_dir: PathBuf,
+	/// Keyserver to use instead of the default one.
+	keyserver: Option<String>,
+}
+
+impl GnupgBackend {
+	/// Creates a temporary home directory that is configured
+	/// for using the keyserver.
+	fn create_home_dir(&self) -> Result<TempDir> {
+		let temp_dir = GpgContext::create_temp_dir()?;
+		let config_file = temp_dir.path().join("dirmngr.conf");
+		match &self.keyserver {
+			Some(keyserver) => {
+				fs::write(config_file, format!("keyserver {keyserver}\n"))?
+			}
+			None => {
+				// the file might not exist for using the default keyserver
+				let _ =
+					fs::copy(self.home_dir.join("dirmngr.conf"), config_file);
+			}
+		}
+		Ok(temp_dir)
+	}
+
+	/// Returns the keys on the keyserver that match the given query
+	/// by using the specified home directory.
+	fn find_keys_at(home_dir: &Path, query: &str) -> Result<Vec<Key>> {
+		let mut context = Context::from_protocol(Protocol::OpenPgp)?;
+		context.set_engine_home_dir(home_dir.to_string_lossy().to_string())?;
+		context.set_key_list_mode(KeyListMode::EXTERN)?;
+		let keys = context
+			.find_keys(vec![query.to_string()])?
+			.filter_map(|key| key.ok())
+			.collect::<Vec<Key>>();
+		if keys.is_empty() {
+			Err(anyhow!("no keys found on the keyserver"))
+		} else {
+			Ok(keys)
+		}
+	}
+
+	/// Receives the matching keys into the keyring
+	/// at the specified home directory and exports them.
+	fn receive_keys_into(home_dir: &Path, query: &str) -> Result<Vec<u8>> {
+		let keys = Self::find_keys_at(home_dir, query)?;
+		let mut context = Context::from_protocol(Protocol::OpenPgp)?;
+		context.set_engine_home_dir(home_dir.to_string_lossy().to_string())?;
+		context.set_armor(true);
+		context.import_keys(&keys)?;
+		context.set_key_list_mode(KeyListMode::LOCAL)?;
+		let keys = context
+			.keys()?
+			.filter_map(|key| key.ok())
+			.collect::<Vec<Key>>();
+		let mut output = Vec::new();
+		context.export_keys(&keys, ExportMode::empty(), &mut output)?;
+		Ok(output)
+	}
+}
+
+impl KeyserverBackend for GnupgBackend {
+	fn send_key(&self, key_id: &str, _: &str) -> Result<()> {
+		let mut command = process::Command::new("gpg");
+		command
+			.arg("--homedir")
+			.arg(self.home_dir.as_os_str())
+			.arg("--batch");
+		if let Some(keyserver) = &self.keyserver {
+			command.arg("--keyserver").arg(keyserver);
+		}
+		let output = command.arg("--send-keys").arg(key_id).output()?;
+		if output.status.success() {
+			Ok(())
+		} else {
+			Err(anyhow!(
+				"failed to send key(s): {}",
+				String::from_utf8_lossy(&output.stderr).trim()
+			))
+		}
+	}
+
+	fn receive_keys(&self, query: &str) -> Result<Vec<u8>> {
+		let temp_dir = self.create_home_dir()?;
+		let keys = Self::receive_keys_into(temp_dir.path(), query);
+		GpgContext::kill_agents(temp_dir.path());
+		keys
+	}
+
+	fn search_keys(&self, query: &str) -> Result<Vec<Key>> {
+		let temp_dir = self.create_home_dir()?;
+		let keys = Self::find_keys_at(temp_dir.path(), query);
+		GpgContext::kill_agents(temp_dir.path());
+		keys
+	}
+}
+
+/// Backend for the keyservers that implement HKP.
+#[derive(Debug)]
+pub struct HkpBackend {
+	/// URL of the keyserver.
+	url: String,
+}
+
+impl KeyserverBackend for HkpBackend {
+	fn send_key(&self, _: &str, key: &str) -> Result<()> {
+		send_request(
+			&format!("{}/pks/add", self.url),
+			Some((
+				"application/x-www-form-urlencoded",
+				format!("keytext={}", encode_url_component(key)).as_bytes(),
+			)),
+		)?;
+		Ok(())
+	}
+
+	fn receive_keys(&self, query: &str) -> Result<Vec<u8>> {
+		send_request(
+			&format!(
+				"{}/pks/lookup?op=get&options=mr&search={}",
+				self.url,
+				encode_url_component(query)
+			),
+			None,
+		)
+	}
+
+	fn search_keys(&self, query: &str) -> Result<Vec<Key>> {
+		get_search_backend(&self.url).search_keys(query)
+	}
+}
+
+/// Backend for the keyservers that implement VKS. (e.g. Hagrid)
+#[derive(Debug)]
+pub struct VksBackend {
+	/// URL of the keyserver.
+	url: String,
+}
+
+impl KeyserverBackend for VksBackend {
+	fn send_key(&self, _: &str, key: &str) -> Result<()> {
+		send_request(
+			&format!("{}/vks/v1/upload", self.url),
+			Some((
+				"application/json",
+				json!({ "keytext": key }).to_string().as_bytes(),
+			)),
+		)?;
+		Ok(())
+	}
+
+	fn receive_keys(&self, query: &str) -> Result<Vec<u8>> {
+		let id = query.trim_start_matches("0x").to_uppercase();
+		let path = if query.contains('@') {
+			format!("by-email/{}", encode_url_component(query))
+		} else if id.len() == 40 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+			format!("by-fingerprint/{id}")
+		} else if id.len() == 16 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+			format!("by-keyid/{id}")
+		} else {
+			return Err(anyhow!(
+				"only email, key ID or fingerprint can be searched via VKS"
+			));
+		};
+		send_request(&format!("{}/vks/v1/{path}", self.url), None)
+	}
+
+	fn search_keys(&self, query: &str) -> Result<Vec<Key>> {
+		get_search_backend(&self.url).search_keys(query)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_keyserver() -> Result<()> {
+		assert_eq!(
+			"http://example.org:11371",
+			get_http_url("hkp://example.org/")
+		);
+		assert_eq!(
+			"http://example.org:80",
+			get_http_url("hkp://example.org:80")
+		);
+		assert_eq!("https://example.org", get_http_url("hkps://example.org"));
+		assert_eq!("https://example.org", get_http_url("https://example.org"));
+		assert_eq!("a%20b%2Bc%40d.org", encode_url_component("a b+c@d.org"));
+		assert_eq!(
+			BackendType::Vks,
+			BackendType::from_str("vks", true).map_err(|e| anyhow!(e))?
+		);
+		assert!(get_backend(BackendType::Hkp, Path::new("/"), None).is_err());
+		assert!(get_backend(BackendType::Gnupg, Path::new("/"), None).is_ok());
+		assert_eq!(
+			Some(String::from("hkps://example.org")),
+			get_search_backend("https://example.org").keyserver
+		);
+		assert_eq!(
+			Some(String::from("hkp://example.org:11371")),
+			get_search_backend("http://example.org:11371").keyserver
+		);
+		assert!(VksBackend {
+			url: String::from("https://example.org")
+		}
+		.receive_keys("test")
+		.is_err());
+		Ok(())
+	}
+}
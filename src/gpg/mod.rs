@@ -17,3 +17,33 @@ pub mod key;
 
 /// Handler methods.
 pub mod handler;
+
+/// Keyring report generator.
+pub mod report;
+
+/// File signatures.
+pub mod signature;
+
+/// Key generation parameters.
+pub mod keygen;
+
+/// Smartcard status.
+pub mod card;
+
+/// Key editor.
+pub mod edit;
+
+/// Paper backup of the secret keys.
+pub mod paper;
+
+/// ASCII armor.
+pub mod armor;
+
+/// Keyserver backends.
+pub mod keyserver;
+
+/// HTTP client.
+pub mod http;
+
+/// Photo IDs.
+pub mod photo;
@@ -0,0 +1,244 @@
+use crate::gpg::handler;
+use anyhow::{anyhow, Result};
+use std::time::SystemTime;
+
+/// Version of the paperkey format.
+const PAPERKEY_VERSION: u8 = 0;
+
+/// Packet tag of the secret keys.
+pub(crate) const SECRET_KEY_TAG: u8 = 5;
+
+/// Packet tag of the secret subkeys.
+const SECRET_SUBKEY_TAG: u8 = 7;
+
+/// Number of bytes per line in the base16 output.
+const LINE_ITEMS: usize = 22;
+
+/// Returns the CRC-24 checksum of the given data. (RFC 4880)
+pub fn crc24(data: &[u8]) -> u32 {
+	data.iter().fold(0xB7_04CE, |mut crc, byte| {
+		crc ^= u32::from(*byte) << 16;
+		for _ in 0..8 {
+			crc <<= 1;
+			if crc & 0x100_0000 != 0 {
+				crc ^= 0x186_4CFB;
+			}
+		}
+		crc
+	}) & 0xFF_FFFF
+}
+
+/// Reads a big-endian number of the given size and advances the offset.
+pub(crate) fn read_number(
+	data: &[u8],
+	offset: &mut usize,
+	size: usize,
+) -> Result<usize> {
+	let bytes = data
+		.get(*offset..*offset + size)
+		.ok_or_else(|| anyhow!("unexpected end of data"))?;
+	*offset += size;
+	Ok(bytes
+		.iter()
+		.fold(0, |number, byte| (number << 8) | usize::from(*byte)))
+}
+
+/// Returns the tag of an OpenPGP packet from its header byte.
+///
+/// Both the old and the new packet formats are supported.
+pub(crate) fn get_packet_tag(header: u8) -> Result<u8> {
+	if header & 0x80 == 0 {
+		Err(anyhow!("invalid packet header"))
+	} else if header & 0x40 == 0 {
+		Ok((header >> 2) & 0x0F)
+	} else {
+		Ok(header & 0x3F)
+	}
+}
+
+/// Reads an OpenPGP packet and returns its tag and body.
+pub(crate) fn read_packet<'a>(
+	data: &'a [u8],
+	offset: &mut usize,
+) -> Result<(u8, &'a [u8])> {
+	let header = read_number(data, offset, 1)? as u8;
+	let tag = get_packet_tag(header)?;
+	let length = if header & 0x40 == 0 {
+		match header & 0x03 {
+			0 => read_number(data, offset, 1)?,
+			1 => read_number(data, offset, 2)?,
+			2 => read_number(data, offset, 4)?,
+			_ => return Err(anyhow!("indeterminate packet length")),
+		}
+	} else {
+		match read_number(data, offset, 1)? {
+			length @ 0..=191 => length,
+			length @ 192..=223 => {
+				((length - 192) << 8) + read_number(data, offset, 1)? + 192
+			}
+			255 => read_number(data, offset, 4)?,
+			_ => return Err(anyhow!("partial packet length")),
+		}
+	};
+	let body = data
+		.get(*offset..*offset + length)
+		.ok_or_else(|| anyhow!("unexpected end of packet"))?;
+	*offset += length;
+	Ok((tag, body))
+}
+
+/// Returns the offset of the secret data in a secret key packet.
+///
+/// Public key material is skipped according to the algorithm
+/// and the secret data starts with the string-to-key usage octet.
+fn get_secret_offset(body: &[u8]) -> Result<usize> {
+	if body.first() != Some(&4) {
+		return Err(anyhow!("unsupported key version"));
+	}
+	let (has_oid, mpi_count, has_kdf) = match body.get(5).copied() {
+		Some(1..=3) => (false, 2, false),
+		Some(16) | Some(20) => (false, 3, false),
+		Some(17) => (false, 4, false),
+		Some(18) => (true, 1, true),
+		Some(19) | Some(22) => (true, 1, false),
+		_ => return Err(anyhow!("unsupported key algorithm")),
+	};
+	let mut offset = 6;
+	if has_oid {
+		let length = read_number(body, &mut offset, 1)?;
+		offset += length;
+	}
+	for _ in 0..mpi_count {
+		let bits = read_number(body, &mut offset, 2)?;
+		offset += (bits + 7) / 8;
+	}
+	if has_kdf {
+		let length = read_number(body, &mut offset, 1)?;
+		offset += length;
+	}
+	if offset >= body.len() {
+		Err(anyhow!("no secret data"))
+	} else {
+		Ok(offset)
+	}
+}
+
+/// Extracts the secret portions of the exported (binary) secret keys.
+///
+/// The output is in paperkey format which contains the version,
+/// fingerprint and secret data of the key and each subkey.
+/// Fingerprints are expected in the same order as the key packets.
+pub fn extract_secrets(
+	data: &[u8],
+	fingerprints: &[String],
+) -> Result<Vec<u8>> {
+	let mut output = vec![PAPERKEY_VERSION];
+	let mut fingerprints = fingerprints.iter();
+	let mut offset = 0;
+	while offset < data.len() {
+		let (tag, body) = read_packet(data, &mut offset)?;
+		if tag != SECRET_KEY_TAG && tag != SECRET_SUBKEY_TAG {
+			continue;
+		}
+		let fingerprint = fingerprints
+			.next()
+			.ok_or_else(|| anyhow!("missing fingerprint"))?;
+		let secret = &body[get_secret_offset(body)?..];
+		output.push(body[0]);
+		for i in (0..fingerprint.len()).step_by(2) {
+			output.push(u8::from_str_radix(
+				fingerprint.get(i..i + 2).unwrap_or_default(),
+				16,
+			)?);
+		}
+		output.extend(u16::try_from(secret.len())?.to_be_bytes());
+		output.extend(secret);
+	}
+	if output.len() == 1 {
+		Err(anyhow!("no secret key found"))
+	} else {
+		Ok(output)
+	}
+}
+
+/// Checks if the given data is a paper backup in text or raw format.
+pub fn is_paper_key(data: &[u8]) -> bool {
+	if data.starts_with(b"# Secret portions of key") {
+		return true;
+	}
+	if data.len() < 2 || data[0] != PAPERKEY_VERSION {
+		return false;
+	}
+	let mut offset = 1;
+	while offset < data.len() {
+		offset += match data[offset] {
+			4 => 21,
+			5 => 33,
+			_ => return false,
+		};
+		match read_number(data, &mut offset, 2) {
+			Ok(length) => offset += length,
+			Err(_) => return false,
+		}
+	}
+	offset == data.len()
+}
+
+/// Returns the base16 (text) output of the extracted secrets.
+///
+/// Each line ends with a CRC-24 of that line and the last line
+/// contains the CRC-24 of the entire data, as in paperkey.
+pub fn get_base16_output(secrets: &[u8], fingerprint: &str) -> String {
+	let mut output = format!(
+		"# Secret portions of key {fingerprint}\n\
+		# Base16 data extracted {}\n\
+		# Created with {} {}\n\
+		#\n\
+		# Restore with: paperkey --pubring <key> --secrets <file>\n\
+		# Each base16 line ends with a CRC-24 of that line.\n\
+		# The entire block of data ends with a CRC-24 of the data.\n\n",
+		handler::get_iso_timestamp(SystemTime::now()),
+		env!("CARGO_PKG_NAME"),
+		env!("CARGO_PKG_VERSION"),
+	);
+	let mut line_count = 0;
+	for (i, line) in secrets.chunks(LINE_ITEMS).enumerate() {
+		output.push_str(&format!("{:3}: ", i + 1));
+		for byte in line {
+			output.push_str(&format!("{byte:02X} "));
+		}
+		output.push_str(&format!("{:06X}\n", crc24(line)));
+		line_count = i + 1;
+	}
+	output.push_str(&format!("{:3}: {:06X}\n", line_count + 1, crc24(secrets)));
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_paper() -> Result<()> {
+		assert_eq!(0xB7_04CE, crc24(&[]));
+		let packet = [
+			0xC5, 0x0E, 0x04, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0xFF,
+			0x00, 0x02, 0x03, 0x00, 0xAA,
+		];
+		let fingerprint = "AB".repeat(20);
+		let secrets = extract_secrets(&packet, &[fingerprint.to_string()])?;
+		assert_eq!(26, secrets.len());
+		assert_eq!([0x00, 0x04, 0xAB], secrets[..3]);
+		assert_eq!([0x00, 0x02, 0x00, 0xAA], secrets[22..]);
+		let output = get_base16_output(&secrets, &fingerprint);
+		assert!(output.contains(&format!("  1: 00 04 {}", "AB ".repeat(20))));
+		assert!(output.ends_with(&format!("  3: {:06X}\n", crc24(&secrets))));
+		assert!(is_paper_key(&secrets));
+		assert!(is_paper_key(output.as_bytes()));
+		assert!(!is_paper_key(&secrets[..25]));
+		assert!(!is_paper_key(&packet));
+		assert!(extract_secrets(&packet[..10], &[fingerprint]).is_err());
+		assert!(extract_secrets(&packet, &[]).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,94 @@
+use crate::gpg::paper;
+use anyhow::{anyhow, Result};
+
+/// Packet tag of the user attributes.
+const USER_ATTRIBUTE_TAG: u8 = 17;
+
+/// Subpacket type of the images.
+const IMAGE_SUBPACKET_TYPE: u8 = 1;
+
+/// Version of the image header.
+const IMAGE_HEADER_VERSION: u8 = 1;
+
+/// Encoding of the JPEG images.
+const JPEG_ENCODING: u8 = 1;
+
+/// Reads a user attribute subpacket and returns its type and body.
+fn read_subpacket<'a>(
+	data: &'a [u8],
+	offset: &mut usize,
+) -> Result<(u8, &'a [u8])> {
+	let length = match paper::read_number(data, offset, 1)? {
+		length @ 0..=191 => length,
+		length @ 192..=254 => {
+			((length - 192) << 8) + paper::read_number(data, offset, 1)? + 192
+		}
+		_ => paper::read_number(data, offset, 4)?,
+	};
+	let subpacket = data
+		.get(*offset..*offset + length)
+		.filter(|subpacket| !subpacket.is_empty())
+		.ok_or_else(|| anyhow!("invalid subpacket length"))?;
+	*offset += length;
+	Ok((subpacket[0], &subpacket[1..]))
+}
+
+/// Returns the JPEG image of the given image subpacket.
+///
+/// Image header starts with its length (little-endian) and
+/// the version, followed by the encoding of the image. (RFC 4880)
+fn get_jpeg(body: &[u8]) -> Option<&[u8]> {
+	let header_length =
+		usize::from(u16::from_le_bytes([*body.first()?, *body.get(1)?]));
+	if body.get(2) == Some(&IMAGE_HEADER_VERSION)
+		&& body.get(3) == Some(&JPEG_ENCODING)
+	{
+		body.get(header_length..).filter(|image| !image.is_empty())
+	} else {
+		None
+	}
+}
+
+/// Extracts the JPEG photos from the exported (binary) keys.
+pub fn extract_photos(data: &[u8]) -> Result<Vec<Vec<u8>>> {
+	let mut photos = Vec::new();
+	let mut offset = 0;
+	while offset < data.len() {
+		let (tag, body) = paper::read_packet(data, &mut offset)?;
+		if tag != USER_ATTRIBUTE_TAG {
+			continue;
+		}
+		let mut subpacket_offset = 0;
+		while subpacket_offset < body.len() {
+			let (subpacket_type, subpacket) =
+				read_subpacket(body, &mut subpacket_offset)?;
+			if subpacket_type == IMAGE_SUBPACKET_TYPE {
+				if let Some(image) = get_jpeg(subpacket) {
+					photos.push(image.to_vec());
+				}
+			}
+		}
+	}
+	Ok(photos)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_photo() -> Result<()> {
+		let mut image = vec![IMAGE_SUBPACKET_TYPE, 16, 0, 1, JPEG_ENCODING];
+		image.extend_from_slice(&[0; 12]);
+		image.extend_from_slice(&[0xFF, 0xD8, 0xFF, 0xD9]);
+		let mut data = vec![0xC0 | USER_ATTRIBUTE_TAG, 22, 21];
+		data.extend_from_slice(&image);
+		data.extend_from_slice(&[0xCD, 2, 0xAB, 0xCD]);
+		assert_eq!(vec![vec![0xFF, 0xD8, 0xFF, 0xD9]], extract_photos(&data)?);
+		data[3] = 2;
+		assert!(extract_photos(&data)?.is_empty());
+		data[2] = 30;
+		assert!(extract_photos(&data).is_err());
+		Ok(())
+	}
+}
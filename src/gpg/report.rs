@@ -0,0 +1,189 @@
+use crate::gpg::key::GpgKey;
+use chrono::{DateTime, Utc};
+use gpgme::Subkey;
+use std::time::SystemTime;
+
+/// Minimum key length (in bits) that is not reported as weak.
+const MIN_KEY_LENGTH: u32 = 2048;
+
+/// Style sheet of the HTML report.
+const REPORT_STYLE: &str = r#"
+      body { font-family: monospace; background: #1d1d1d; color: #d0d0d0; margin: 2em; }
+      h1 { color: #ffffff; }
+      table { border-collapse: collapse; width: 100%; }
+      th, td { border: 1px solid #424242; padding: 0.4em 0.6em; text-align: left; vertical-align: top; }
+      th { background: #2d2d2d; color: #ffffff; }
+      tr:nth-child(even) { background: #242424; }
+      .ok { color: #87d787; }
+      .warn { color: #ffd75f; }
+      .fail { color: #ff5f5f; }
+"#;
+
+/// Escapes the special HTML characters in the given text.
+pub fn escape_html(text: &str) -> String {
+	text.chars().fold(String::new(), |mut escaped, c| {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&#39;"),
+			_ => escaped.push(c),
+		}
+		escaped
+	})
+}
+
+/// Formats the given time as date.
+fn format_date(time: Option<SystemTime>) -> String {
+	time.map(|date| DateTime::<Utc>::from(date).format("%F").to_string())
+		.unwrap_or_else(|| String::from("-"))
+}
+
+/// Returns the audit findings of the given key.
+///
+/// * expired/revoked/disabled/invalid key
+/// * primary key without an expiration date
+/// * weak key length
+pub fn get_audit_findings(key: &GpgKey) -> Vec<String> {
+	let raw_key = key.get_raw();
+	let mut findings = Vec::new();
+	if raw_key.is_expired() {
+		findings.push(String::from("expired"));
+	}
+	if raw_key.is_revoked() {
+		findings.push(String::from("revoked"));
+	}
+	if raw_key.is_disabled() {
+		findings.push(String::from("disabled"));
+	}
+	if raw_key.is_invalid() {
+		findings.push(String::from("invalid"));
+	}
+	let subkeys = raw_key.subkeys().collect::<Vec<Subkey>>();
+	if let Some(primary_key) = subkeys.first() {
+		if primary_key.expiration_time().is_none() {
+			findings.push(String::from("no expiration date"));
+		}
+	}
+	for subkey in subkeys {
+		let algorithm_name = subkey.algorithm_name().unwrap_or_default();
+		if ["rsa", "dsa", "elg"]
+			.iter()
+			.any(|prefix| algorithm_name.starts_with(prefix))
+			&& subkey.length() < MIN_KEY_LENGTH
+		{
+			findings.push(format!(
+				"weak key length ({}/{})",
+				algorithm_name,
+				subkey.id().unwrap_or("[?]")
+			));
+		}
+	}
+	findings
+}
+
+/// Returns the HTML report of the given keys.
+pub fn get_html_report(keys: &[GpgKey]) -> String {
+	let mut rows = String::new();
+	for key in keys {
+		let raw_key = key.get_raw();
+		let primary_key = raw_key.subkeys().next();
+		let creation_time =
+			primary_key.as_ref().and_then(|v| v.creation_time());
+		let expiration_time =
+			primary_key.as_ref().and_then(|v| v.expiration_time());
+		let user_ids = raw_key
+			.user_ids()
+			.map(|user| {
+				format!(
+					"[{}] {}",
+					user.validity(),
+					escape_html(user.id().unwrap_or("[?]"))
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("<br>");
+		let findings = get_audit_findings(key);
+		rows.push_str(&format!(
+			r#"
+      <tr>
+        <td>{}<br><small>{}</small></td>
+        <td>{}</td>
+        <td>{}</td>
+        <td>{}</td>
+        <td>{}</td>
+        <td>{}</td>
+        <td class="{}">{}</td>
+      </tr>"#,
+			escape_html(&key.get_id()),
+			escape_html(&key.get_fingerprint()),
+			user_ids,
+			format_date(creation_time),
+			format_date(expiration_time),
+			format!("{:?}", raw_key.owner_trust()).to_lowercase(),
+			if raw_key.has_secret() { "yes" } else { "no" },
+			if findings.is_empty() {
+				"ok"
+			} else if raw_key.is_expired() || raw_key.is_revoked() {
+				"fail"
+			} else {
+				"warn"
+			},
+			if findings.is_empty() {
+				String::from("-")
+			} else {
+				escape_html(&findings.join(", "))
+			}
+		));
+	}
+	format!(
+		r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>{} keyring report</title>
+    <style>{}</style>
+  </head>
+  <body>
+    <h1>Keyring report</h1>
+    <p>Generated by {} {} at {} ({} keys)</p>
+    <table>
+      <tr>
+        <th>Key</th>
+        <th>User IDs</th>
+        <th>Created</th>
+        <th>Expires</th>
+        <th>Owner trust</th>
+        <th>Secret</th>
+        <th>Findings</th>
+      </tr>{}
+    </table>
+  </body>
+</html>
+"#,
+		env!("CARGO_PKG_NAME"),
+		REPORT_STYLE,
+		env!("CARGO_PKG_NAME"),
+		env!("CARGO_PKG_VERSION"),
+		Utc::now().format("%F %T UTC"),
+		keys.len(),
+		rows
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_gpg_report() {
+		assert_eq!(
+			"Test &lt;test@example.org&gt; &amp; &quot;x&quot; &#39;y&#39;",
+			escape_html(r#"Test <test@example.org> & "x" 'y'"#)
+		);
+		let report = get_html_report(&[]);
+		assert!(report.starts_with("<!DOCTYPE html>"));
+		assert!(report.contains("(0 keys)"));
+	}
+}
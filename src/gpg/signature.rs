@@ -0,0 +1,186 @@
+use crate::gpg::handler;
+use gpgme::SignMode;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// Type of the file signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureType {
+	/// Signature that contains the signed data.
+	Normal,
+	/// Detached signature.
+	Detached,
+	/// Cleartext signature.
+	Clear,
+}
+
+impl Default for SignatureType {
+	fn default() -> Self {
+		Self::Normal
+	}
+}
+
+impl Display for SignatureType {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(f, "{}", format!("{self:?}").to_lowercase())
+	}
+}
+
+impl FromStr for SignatureType {
+	type Err = ();
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"normal" => Ok(Self::Normal),
+			"detached" | "detach" => Ok(Self::Detached),
+			"clear" | "clearsign" => Ok(Self::Clear),
+			_ => Err(()),
+		}
+	}
+}
+
+impl From<SignatureType> for SignMode {
+	fn from(signature_type: SignatureType) -> Self {
+		match signature_type {
+			SignatureType::Normal => SignMode::Normal,
+			SignatureType::Detached => SignMode::Detached,
+			SignatureType::Clear => SignMode::Clear,
+		}
+	}
+}
+
+impl SignatureType {
+	/// Returns the extension of the signature file.
+	pub fn get_extension(&self, armor: bool) -> &str {
+		match self {
+			Self::Clear => "asc",
+			_ if armor => "asc",
+			Self::Detached => "sig",
+			Self::Normal => "gpg",
+		}
+	}
+}
+
+/// Result of a signature verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Verification {
+	/// User ID of the signer.
+	pub signer: Option<String>,
+	/// Fingerprint of the signing key.
+	pub fingerprint: String,
+	/// Error message if the signature is not good.
+	pub error: Option<String>,
+	/// Validity of the signature.
+	pub validity: String,
+	/// Creation time of the signature.
+	pub time: Option<SystemTime>,
+}
+
+impl Verification {
+	/// Checks if the signature is good.
+	pub fn is_good(&self) -> bool {
+		self.error.is_none()
+	}
+
+	/// Checks if the signature is good and the signing key is certified.
+	pub fn is_trusted(&self) -> bool {
+		self.is_good() && matches!(self.validity.as_str(), "full" | "ultimate")
+	}
+
+	/// Returns a short verdict about the verification.
+	pub fn get_verdict(&self) -> String {
+		let signer = self.signer.as_deref().unwrap_or(&self.fingerprint);
+		match &self.error {
+			Some(e) => format!("bad signature: {e}"),
+			None if self.is_trusted() => {
+				format!("good signature from {signer}")
+			}
+			None => format!(
+				"good signature from {signer} but the key is not certified \
+				 (validity: {})",
+				self.validity
+			),
+		}
+	}
+
+	/// Returns information about the verification.
+	///
+	/// * status
+	/// * signer
+	/// * fingerprint
+	/// * validity
+	/// * creation time
+	pub fn get_info(&self, format: &str) -> Vec<String> {
+		vec![
+			format!(
+				"status: {}",
+				self.error.as_deref().unwrap_or("good signature")
+			),
+			format!("signer: {}", self.signer.as_deref().unwrap_or("[?]")),
+			format!("fingerprint: {}", self.fingerprint),
+			format!("validity: {}", self.validity),
+			format!(
+				"created: {}",
+				self.time
+					.map(|time| handler::format_date(time, format))
+					.unwrap_or_else(|| String::from("[?]"))
+			),
+		]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::time::Duration;
+	#[test]
+	fn test_gpg_signature() {
+		assert_eq!(
+			SignatureType::Detached,
+			SignatureType::from_str("detached").unwrap()
+		);
+		assert_eq!(
+			SignatureType::Clear,
+			SignatureType::from_str("clear").unwrap()
+		);
+		assert!(SignatureType::from_str("x").is_err());
+		assert_eq!("detached", SignatureType::Detached.to_string());
+		assert_eq!("sig", SignatureType::Detached.get_extension(false));
+		assert_eq!("asc", SignatureType::Detached.get_extension(true));
+		assert_eq!("asc", SignatureType::Clear.get_extension(false));
+		assert_eq!("gpg", SignatureType::Normal.get_extension(false));
+		let mut verification = Verification {
+			signer: Some(String::from("Test <test@example.org>")),
+			fingerprint: String::from("0123456789ABCDEF"),
+			error: None,
+			validity: String::from("full"),
+			time: Some(
+				SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+			),
+		};
+		assert!(verification.is_good());
+		assert!(verification.is_trusted());
+		assert_eq!(
+			"good signature from Test <test@example.org>",
+			verification.get_verdict()
+		);
+		assert_eq!(
+			vec![
+				"status: good signature",
+				"signer: Test <test@example.org>",
+				"fingerprint: 0123456789ABCDEF",
+				"validity: full",
+				"created: 2020-09-13",
+			],
+			verification.get_info("%F")
+		);
+		verification.error = Some(String::from("Bad signature"));
+		assert!(!verification.is_good());
+		assert_eq!("status: Bad signature", verification.get_info("%F")[0]);
+		verification.error = None;
+		verification.validity = String::from("unknown");
+		assert!(!verification.is_trusted());
+		assert!(verification.get_verdict().ends_with("(validity: unknown)"));
+	}
+}
@@ -1,7 +1,9 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Parser;
+use gpg_tui::app::command::Command;
 use gpg_tui::app::handler;
 use gpg_tui::app::launcher::App;
+use gpg_tui::app::prompt::Prompt;
 use gpg_tui::args::Args;
 use gpg_tui::config::Config;
 use gpg_tui::gpg::config::GpgConfig;
@@ -19,16 +21,53 @@ use std::str::FromStr;
 fn main() -> Result<()> {
 	// Parse command-line arguments.
 	let mut args = Args::parse();
+	let cli_args = args.clone();
 	// Parse configuration file.
-	let config = if let Some(config_file) =
-		args.config.to_owned().or_else(Config::get_default_location)
-	{
-		let config = Config::parse_config(&config_file)?;
+	let config_file =
+		args.config.to_owned().or_else(Config::get_default_location);
+	let config = if let Some(config_file) = &config_file {
+		let config = Config::parse_config(config_file)?;
 		args = config.update_args(args);
 		config
 	} else {
 		Config::default()
 	};
+	// Print the version information.
+	if args.version != 0 {
+		writeln!(
+			io::stdout(),
+			"{} {}",
+			env!("CARGO_PKG_NAME"),
+			env!("CARGO_PKG_VERSION")
+		)?;
+		if args.version > 1 || args.verbose {
+			let mut gpg_config = GpgConfig::new(&args)?;
+			writeln!(io::stdout(), "{}", gpg_config.get_version_info()?)?;
+		}
+		return Ok(());
+	}
+	// Print the key list.
+	if let Some(format) = args.format {
+		let mut gpgme = GpgContext::new(GpgConfig::new(&args)?)?;
+		writeln!(io::stdout(), "{}", gpgme.get_key_list(format)?)?;
+		return Ok(());
+	}
+	// Run the command without starting the interface.
+	if let Some(ref command) = args.batch {
+		let mut gpgme = GpgContext::new(GpgConfig::new(&args)?)?;
+		let mut app = App::new(&mut gpgme, &args)?;
+		app.state.allowed_commands = config
+			.general
+			.as_ref()
+			.and_then(|general| general.allowed_commands.clone());
+		let output = app.run_batch_command(
+			Command::from_str(command)
+				.map_err(|_| anyhow!("invalid command: {command}"))?,
+			args.yes,
+		)?;
+		writeln!(io::stdout(), "{output}")?;
+		return Ok(());
+	}
 	// Initialize logger.
 	tui_logger::init_logger(if let Ok(log_level) = env::var("RUST_LOG") {
 		LevelFilter::from_str(&log_level)?
@@ -41,38 +80,76 @@ fn main() -> Result<()> {
 	}
 	log::debug!(target: "args", "{:?}", args);
 	log::debug!(target: "config", "{:?}", config);
-	// Set custom key bindings.
-	let custom_key_bindings = config
-		.general
-		.unwrap_or_default()
-		.key_bindings
-		.unwrap_or_default();
+	let general_config = config.general.unwrap_or_default();
 	// Initialize GPGME library.
 	let gpg_config = GpgConfig::new(&args)?;
 	log::warn!(target: "gpg", "checking gpgme version: {:?}", GPGME_REQUIRED_VERSION);
 	gpg_config.check_gpgme_version(GPGME_REQUIRED_VERSION);
 	let mut gpgme = GpgContext::new(gpg_config)?;
-	// Create an application for rendering.
-	let mut app = App::new(&mut gpgme, &args)?;
+	// Create an application for rendering. (keys are loaded in the background)
+	let events = EventHandler::new(args.tick_rate);
+	let mut app = App::with_key_loader(&mut gpgme, &args, events.sender())?;
+	// Set custom key bindings.
+	app.state.custom_key_bindings =
+		general_config.key_bindings.unwrap_or_default();
+	// Set the configuration file for reloading/writing.
+	app.state.config_file = config_file;
+	app.cli_args = cli_args;
+	// Load the command/search history.
+	app.prompt.load_history(Prompt::get_history_location());
+	// Restrict the available commands.
+	app.state.allowed_commands = general_config.allowed_commands;
+	// Set the phrase for unlocking the screen.
+	app.state.lock_phrase = general_config.lock_phrase;
+	// Set the profiles for generating keys.
+	app.state.keygen_profiles = config.keygen.unwrap_or_default();
+	// Set the theme of the user interface.
+	app.state.theme = config.theme.unwrap_or_default();
+	app.keys_table_margin = app.state.theme.get_table_margin();
 	// Initialize the text-based user interface.
 	let backend = CrosstermBackend::new(io::stderr());
 	let terminal = Terminal::new(backend)?;
-	let events = EventHandler::new(args.tick_rate);
 	let mut tui = Tui::new(terminal, events);
 	tui.init()?;
 	// Start the main loop.
 	while app.state.running {
-		// Render the user interface.
-		tui.draw(&mut app)?;
+		// Render the user interface. (skipped while unfocused)
+		if app.state.focused {
+			tui.draw(&mut app)?;
+		}
 		// Handle events.
 		match tui.events.next()? {
-			Event::Key(key_event) => handler::handle_events(
-				key_event,
-				&custom_key_bindings,
-				&mut tui,
-				&mut app,
-			)?,
+			Event::Key(key_event) => {
+				app.set_focused(true);
+				let custom_key_bindings = app.state.custom_key_bindings.clone();
+				handler::handle_events(
+					key_event,
+					&custom_key_bindings,
+					&mut tui,
+					&mut app,
+				)?
+			}
+			Event::Mouse(mouse_event) => {
+				handler::handle_mouse_events(mouse_event, &mut tui, &mut app)?
+			}
 			Event::Tick => app.tick(),
+			Event::Keys(load_id, key_type, keys) => {
+				app.append_keys(load_id, key_type, keys)
+			}
+			Event::KeysLoaded(load_id, error) => {
+				app.finish_loading_keys(load_id, error)
+			}
+			Event::KeyLocated(email, result) => {
+				app.finish_locating_key(email, result)
+			}
+			Event::KeyReceived(key_id, result) => {
+				app.finish_receiving_key(key_id, result)?
+			}
+			Event::FocusGained => {
+				app.set_focused(true);
+				tui.force_redraw()?;
+			}
+			Event::FocusLost => app.set_focused(false),
 			_ => {}
 		}
 	}
@@ -1,3 +1,5 @@
+use crate::app::command::Command;
+use crate::gpg::key::{GpgKey, KeyType};
 use anyhow::Result;
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
 use std::sync::mpsc;
@@ -9,11 +11,12 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 /// Representation of terminal events
-/// ([`Crossterm events`] + [`Tick`]).
+/// ([`Crossterm events`] + [`Tick`]) and
+/// the events of the background tasks.
 ///
 /// [`Crossterm events`]: crossterm::event::Event
 /// [`Tick`]: Event::Tick
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
 	/// Key press.
 	Key(KeyEvent),
@@ -21,8 +24,24 @@ pub enum Event {
 	Mouse(MouseEvent),
 	/// Terminal resize.
 	Resize(u16, u16),
+	/// Terminal gained focus.
+	FocusGained,
+	/// Terminal lost focus.
+	FocusLost,
 	/// Terminal tick.
 	Tick,
+	/// Batch of keys that are loaded in the background.
+	/// (load ID, key type, keys)
+	Keys(usize, KeyType, Vec<GpgKey>),
+	/// Loading the keys in the background is finished.
+	/// (load ID, error)
+	KeysLoaded(usize, Option<String>),
+	/// Looking up the key of an email address in the background is finished.
+	/// (email, command for importing the key and its source)
+	KeyLocated(String, Option<(Command, String)>),
+	/// Receiving a key from the keyserver in the background is finished.
+	/// (key ID, result)
+	KeyReceived(String, Result<(), String>),
 }
 
 /// Basic event handler for terminal [`events`].
@@ -74,6 +93,12 @@ impl EventHandler {
 							CrosstermEvent::Resize(w, h) => {
 								sender.send(Event::Resize(w, h))
 							}
+							CrosstermEvent::FocusGained => {
+								sender.send(Event::FocusGained)
+							}
+							CrosstermEvent::FocusLost => {
+								sender.send(Event::FocusLost)
+							}
 							_ => Ok(()),
 						}
 						.expect("failed to send terminal event")
@@ -95,6 +120,11 @@ impl EventHandler {
 		}
 	}
 
+	/// Returns a sender for the events of the background tasks.
+	pub fn sender(&self) -> mpsc::Sender<Event> {
+		self.sender.clone()
+	}
+
 	/// Receive the next event from handler.
 	///
 	/// > This function will always block the current thread if
@@ -0,0 +1,277 @@
+use crate::gpg::armor::encode_base64;
+use crate::widget::photo::Photo;
+use anyhow::Result;
+use clap::ValueEnum;
+use crossterm::terminal;
+use image::imageops::FilterType;
+use image::{ImageFormat, RgbImage};
+use ratatui::layout::Rect;
+use std::env;
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+use std::io::Cursor;
+
+/// Default size of a terminal cell in pixels. (width, height)
+const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+/// Maximum size of the data in a Kitty graphics escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Escape sequence for deleting the images that are shown via Kitty.
+pub const KITTY_DELETE_SEQUENCE: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+/// Protocol for showing images in the terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum GraphicsProtocol {
+	/// Select the protocol depending on the terminal.
+	#[default]
+	Auto,
+	/// Kitty graphics protocol.
+	Kitty,
+	/// Inline images protocol of iTerm2.
+	Iterm2,
+	/// Sixel graphics.
+	Sixel,
+	/// Draw the images with colored cells.
+	None,
+}
+
+impl Display for GraphicsProtocol {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(f, "{}", format!("{self:?}").to_lowercase())
+	}
+}
+
+impl GraphicsProtocol {
+	/// Returns the protocol to use.
+	///
+	/// Protocol is detected from the environment if it is set to auto.
+	pub fn resolve(self) -> Self {
+		if self == Self::Auto {
+			Self::detect(
+				&env::var("TERM").unwrap_or_default(),
+				&env::var("TERM_PROGRAM").unwrap_or_default(),
+				env::var("KITTY_WINDOW_ID").is_ok(),
+			)
+		} else {
+			self
+		}
+	}
+
+	/// Detects the supported protocol from the terminal name/program.
+	fn detect(term: &str, term_program: &str, kitty_window: bool) -> Self {
+		if kitty_window
+			|| term.contains("kitty")
+			|| term.contains("ghostty")
+			|| ["WezTerm", "ghostty"].contains(&term_program)
+		{
+			Self::Kitty
+		} else if term_program == "iTerm.app" {
+			Self::Iterm2
+		} else if term.contains("sixel")
+			|| ["foot", "mlterm", "contour"]
+				.iter()
+				.any(|v| term.starts_with(v))
+		{
+			Self::Sixel
+		} else {
+			Self::None
+		}
+	}
+
+	/// Returns the escape sequence for showing the image in the given area.
+	///
+	/// Image is downscaled to fit the area while preserving the aspect
+	/// ratio. `None` is returned if a protocol is not in use.
+	pub fn get_sequence(
+		&self,
+		image: &RgbImage,
+		area: Rect,
+	) -> Result<Option<String>> {
+		if matches!(self, Self::Auto | Self::None) {
+			return Ok(None);
+		}
+		let (cell_width, cell_height) = get_cell_size();
+		let scale = (f64::from(area.width) * f64::from(cell_width)
+			/ f64::from(image.width().max(1)))
+		.min(
+			f64::from(area.height) * f64::from(cell_height)
+				/ f64::from(image.height().max(1)),
+		);
+		let image = image::imageops::resize(
+			image,
+			((f64::from(image.width()) * scale) as u32).max(1),
+			((f64::from(image.height()) * scale) as u32).max(1),
+			FilterType::Triangle,
+		);
+		Ok(Some(match self {
+			Self::Kitty => encode_kitty(&image),
+			Self::Iterm2 => encode_iterm2(&image)?,
+			_ => encode_sixel(&image),
+		}))
+	}
+}
+
+/// Image that is shown via a graphics protocol.
+///
+/// Cells of the area are skipped while drawing the
+/// interface so that the image is not overwritten.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImagePlacement {
+	/// Image to show.
+	pub photo: Photo,
+	/// Area of the image.
+	pub area: Rect,
+}
+
+/// Returns the size of a terminal cell in pixels.
+fn get_cell_size() -> (u16, u16) {
+	match terminal::window_size() {
+		Ok(size) if size.columns != 0 && size.rows != 0 && size.width != 0 => {
+			(size.width / size.columns, size.height / size.rows)
+		}
+		_ => DEFAULT_CELL_SIZE,
+	}
+}
+
+/// Encodes the image for the Kitty graphics protocol.
+///
+/// Raw RGB data is transmitted in chunks.
+fn encode_kitty(image: &RgbImage) -> String {
+	let data = encode_base64(image.as_raw());
+	let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+	let mut output = String::new();
+	for (i, chunk) in chunks.iter().enumerate() {
+		let more = u8::from(i + 1 != chunks.len());
+		if i == 0 {
+			let _ = write!(
+				output,
+				"\x1b_Ga=T,f=24,s={},v={},C=1,q=2,m={more};",
+				image.width(),
+				image.height()
+			);
+		} else {
+			let _ = write!(output, "\x1b_Gm={more};");
+		}
+		output.push_str(&String::from_utf8_lossy(chunk));
+		output.push_str("\x1b\\");
+	}
+	output
+}
+
+/// Encodes the image for the inline images protocol of iTerm2.
+fn encode_iterm2(image: &RgbImage) -> Result<String> {
+	let mut data = Vec::new();
+	image.write_to(&mut Cursor::new(&mut data), ImageFormat::Jpeg)?;
+	Ok(format!(
+		"\x1b]1337;File=inline=1;size={};width={}px;height={}px:{}\x07",
+		data.len(),
+		image.width(),
+		image.height(),
+		encode_base64(&data)
+	))
+}
+
+/// Encodes the image as sixels.
+///
+/// Colors are reduced to a 6x6x6 color cube and each
+/// band of 6 rows is drawn once per color in the band.
+fn encode_sixel(image: &RgbImage) -> String {
+	let get_index = |pixel: &image::Rgb<u8>| {
+		let level = |v: u8| (u16::from(v) * 5 + 127) / 255;
+		(level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2])) as usize
+	};
+	let (width, height) = (image.width(), image.height());
+	let mut output = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+	let mut used_colors = [false; 216];
+	image
+		.pixels()
+		.for_each(|pixel| used_colors[get_index(pixel)] = true);
+	for (index, _) in used_colors.iter().enumerate().filter(|(_, v)| **v) {
+		let percent = |level: usize| level * 100 / 5;
+		let _ = write!(
+			output,
+			"#{index};2;{};{};{}",
+			percent(index / 36),
+			percent(index / 6 % 6),
+			percent(index % 6)
+		);
+	}
+	for band in (0..height).step_by(6) {
+		let mut colors = vec![vec![0u8; width as usize]; 216];
+		let mut band_colors = Vec::new();
+		for y in band..(band + 6).min(height) {
+			for x in 0..width {
+				let index = get_index(image.get_pixel(x, y));
+				if !band_colors.contains(&index) {
+					band_colors.push(index);
+				}
+				colors[index][x as usize] |= 1 << (y - band);
+			}
+		}
+		for (i, index) in band_colors.iter().enumerate() {
+			if i != 0 {
+				output.push('$');
+			}
+			let _ = write!(output, "#{index}");
+			let sixels = &colors[*index];
+			let mut x = 0;
+			while x < sixels.len() {
+				let count = sixels[x..]
+					.iter()
+					.take_while(|sixel| **sixel == sixels[x])
+					.count();
+				let sixel = char::from(63 + sixels[x]);
+				if count > 3 {
+					let _ = write!(output, "!{count}{sixel}");
+				} else {
+					(0..count).for_each(|_| output.push(sixel));
+				}
+				x += count;
+			}
+		}
+		output.push('-');
+	}
+	output.push_str("\x1b\\");
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgb;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_term_graphics() -> Result<()> {
+		assert_eq!(
+			GraphicsProtocol::Kitty,
+			GraphicsProtocol::detect("xterm-kitty", "", false)
+		);
+		assert_eq!(
+			GraphicsProtocol::Iterm2,
+			GraphicsProtocol::detect("xterm-256color", "iTerm.app", false)
+		);
+		assert_eq!(
+			GraphicsProtocol::Sixel,
+			GraphicsProtocol::detect("foot", "", false)
+		);
+		assert_eq!(
+			GraphicsProtocol::None,
+			GraphicsProtocol::detect("xterm-256color", "", false)
+		);
+		assert_eq!(GraphicsProtocol::Sixel, GraphicsProtocol::Sixel.resolve());
+		assert_eq!("iterm2", GraphicsProtocol::Iterm2.to_string());
+		let image = RgbImage::from_pixel(4, 6, Rgb([255, 0, 0]));
+		assert_eq!(
+			"\x1bP0;1;0q\"1;1;4;6#180;2;100;0;0#180!4~-\x1b\\",
+			encode_sixel(&image)
+		);
+		assert!(encode_kitty(&image).starts_with("\x1b_Ga=T,f=24,s=4,v=6"));
+		assert!(encode_iterm2(&image)?.starts_with("\x1b]1337;File=inline=1"));
+		let area = Rect::new(0, 0, 10, 10);
+		assert_eq!(None, GraphicsProtocol::None.get_sequence(&image, area)?);
+		assert!(GraphicsProtocol::Sixel
+			.get_sequence(&image, area)?
+			.is_some());
+		Ok(())
+	}
+}
@@ -5,3 +5,6 @@ pub mod event;
 
 /// Terminal user interface.
 pub mod tui;
+
+/// Graphics protocols for showing images.
+pub mod graphics;
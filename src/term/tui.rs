@@ -1,8 +1,16 @@
 use crate::app::launcher::App;
 use crate::app::renderer;
 use crate::term::event::EventHandler;
+use crate::term::graphics::{
+	GraphicsProtocol, ImagePlacement, KITTY_DELETE_SEQUENCE,
+};
 use anyhow::{Context, Result};
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::cursor::MoveTo;
+use crossterm::event::{
+	DisableFocusChange, DisableMouseCapture, EnableFocusChange,
+	EnableMouseCapture,
+};
+use crossterm::style::Print;
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::Terminal;
@@ -25,6 +33,8 @@ pub struct Tui<B: Backend> {
 	pub events: EventHandler,
 	/// Is the interface paused?
 	pub paused: bool,
+	/// Image that is shown via the graphics protocol.
+	image: Option<ImagePlacement>,
 }
 
 impl<B: Backend> Tui<B> {
@@ -34,6 +44,7 @@ impl<B: Backend> Tui<B> {
 			terminal,
 			events,
 			paused: false,
+			image: None,
 		}
 	}
 
@@ -45,7 +56,8 @@ impl<B: Backend> Tui<B> {
 		crossterm::execute!(
 			io::stderr(),
 			EnterAlternateScreen,
-			EnableMouseCapture
+			EnableMouseCapture,
+			EnableFocusChange
 		)?;
 		panic::set_hook(Box::new(move |panic| {
 			Self::reset().expect("failed to reset the terminal");
@@ -94,12 +106,83 @@ impl<B: Backend> Tui<B> {
 	/// [`Draw`]: tui::Terminal::draw
 	/// [`rendering`]: crate::app::renderer::render
 	pub fn draw(&mut self, app: &mut App) -> Result<()> {
-		self.terminal
+		self.render(app)?;
+		self.draw_image(app)
+	}
+
+	/// Renders the widgets and checks if the image can be shown.
+	///
+	/// Image is skipped if it is covered by another widget. (e.g. popup)
+	fn render(&mut self, app: &mut App) -> Result<()> {
+		let frame = self
+			.terminal
 			.draw(|frame| renderer::render(app, frame))
 			.context("failed to draw TUI")?;
+		if let Some(placement) = &app.image_placement {
+			let area = placement.area.intersection(frame.area);
+			if (area.top()..area.bottom()).any(|y| {
+				(area.left()..area.right())
+					.any(|x| !frame.buffer.get(x, y).skip)
+			}) {
+				app.image_placement = None;
+			}
+		}
 		Ok(())
 	}
 
+	/// Shows the image of the application via the graphics protocol.
+	///
+	/// The image is only sent to the terminal when it is changed and
+	/// the previous one is cleared by redrawing the interface.
+	fn draw_image(&mut self, app: &mut App) -> Result<()> {
+		if app.image_placement == self.image {
+			return Ok(());
+		}
+		if self.image.is_some() {
+			self.clear_image(app.state.graphics)?;
+			self.render(app)?;
+		}
+		if let Some(placement) = &app.image_placement {
+			match app
+				.state
+				.graphics
+				.get_sequence(placement.photo.get_image(), placement.area)
+			{
+				Ok(Some(sequence)) => crossterm::execute!(
+					io::stderr(),
+					MoveTo(placement.area.x, placement.area.y),
+					Print(sequence)
+				)?,
+				Ok(None) => {}
+				Err(e) => {
+					log::error!(target: "graphics", "failed to encode: {e}")
+				}
+			}
+			self.image = Some(placement.clone());
+		}
+		Ok(())
+	}
+
+	/// Clears the image that is shown via the graphics protocol.
+	fn clear_image(&mut self, protocol: GraphicsProtocol) -> Result<()> {
+		if self.image.take().is_some() {
+			if protocol == GraphicsProtocol::Kitty {
+				crossterm::execute!(
+					io::stderr(),
+					Print(KITTY_DELETE_SEQUENCE)
+				)?;
+			}
+			self.terminal.clear()?;
+		}
+		Ok(())
+	}
+
+	/// Clears the terminal for forcing a full redraw on the next draw.
+	pub fn force_redraw(&mut self) -> Result<()> {
+		self.image = None;
+		Ok(self.terminal.clear()?)
+	}
+
 	/// Reset the terminal interface.
 	///
 	/// It disables the raw mode and reverts back the terminal properties.
@@ -108,7 +191,8 @@ impl<B: Backend> Tui<B> {
 		crossterm::execute!(
 			io::stderr(),
 			LeaveAlternateScreen,
-			DisableMouseCapture
+			DisableMouseCapture,
+			DisableFocusChange
 		)?;
 		Terminal::new(CrosstermBackend::new(io::stderr()))?.show_cursor()?;
 		Ok(())
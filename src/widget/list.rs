@@ -1,5 +1,44 @@
 use ratatui::widgets::ListState;
 
+/// Entry of a list that can be grouped into sections.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListEntry<T> {
+	/// Selectable item.
+	Item(T),
+	/// Header of a section.
+	Header(String),
+	/// Separator between the sections.
+	Separator,
+}
+
+impl<T> ListEntry<T> {
+	/// Returns the entries of the given sections.
+	///
+	/// Sections are separated from each other and empty ones are skipped.
+	pub fn from_sections(sections: Vec<(&str, Vec<T>)>) -> Vec<Self> {
+		let mut entries = Vec::new();
+		for (title, items) in sections {
+			if items.is_empty() {
+				continue;
+			}
+			if !entries.is_empty() {
+				entries.push(Self::Separator);
+			}
+			entries.push(Self::Header(title.to_string()));
+			entries.extend(items.into_iter().map(Self::Item));
+		}
+		entries
+	}
+
+	/// Returns the item if the entry is selectable.
+	pub fn item(&self) -> Option<&T> {
+		match self {
+			Self::Item(item) => Some(item),
+			_ => None,
+		}
+	}
+}
+
 /// List widget with TUI controlled states.
 #[derive(Debug)]
 pub struct StatefulList<T> {
@@ -56,6 +95,35 @@ impl<T> StatefulList<T> {
 	}
 }
 
+impl<T> StatefulList<ListEntry<T>> {
+	/// Returns the selected item.
+	///
+	/// Headers and separators are not considered as items.
+	pub fn selected_item(&self) -> Option<&T> {
+		self.selected().and_then(ListEntry::item)
+	}
+
+	/// Selects the next item by skipping the non-selectable entries.
+	pub fn next_item(&mut self) {
+		for _ in 0..self.items.len() {
+			self.next();
+			if self.selected_item().is_some() {
+				break;
+			}
+		}
+	}
+
+	/// Selects the previous item by skipping the non-selectable entries.
+	pub fn previous_item(&mut self) {
+		for _ in 0..self.items.len() {
+			self.previous();
+			if self.selected_item().is_some() {
+				break;
+			}
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -70,5 +138,31 @@ mod tests {
 		assert_eq!(Some(2), list.state.selected());
 		list.previous();
 		assert_eq!(Some(1), list.state.selected());
+		let mut list =
+			StatefulList::with_items(ListEntry::from_sections(vec![
+				("first", vec!["data1"]),
+				("empty", Vec::new()),
+				("second", vec!["data2", "data3"]),
+			]));
+		assert_eq!(
+			vec![
+				ListEntry::Header(String::from("first")),
+				ListEntry::Item("data1"),
+				ListEntry::Separator,
+				ListEntry::Header(String::from("second")),
+				ListEntry::Item("data2"),
+				ListEntry::Item("data3"),
+			],
+			list.items
+		);
+		list.state.select(Some(1));
+		list.next_item();
+		assert_eq!(Some(&"data2"), list.selected_item());
+		list.previous_item();
+		assert_eq!(Some(&"data1"), list.selected_item());
+		list.previous_item();
+		assert_eq!(Some(&"data3"), list.selected_item());
+		list.state.select(Some(0));
+		assert_eq!(None, list.selected_item());
 	}
 }
@@ -11,3 +11,9 @@ pub mod row;
 
 /// Style helper.
 pub mod style;
+
+/// Masked input for secrets.
+pub mod secret;
+
+/// Image widget for photo IDs.
+pub mod photo;
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use image::imageops::FilterType;
+use image::RgbImage;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+use std::rc::Rc;
+
+/// Character for rendering two vertical pixels in a cell.
+const HALF_BLOCK: &str = "▀";
+
+/// Photo widget that renders an image with colored cells.
+///
+/// Each cell contains two pixels: the upper one as the
+/// foreground color and the lower one as the background color.
+#[derive(Clone, Debug)]
+pub struct Photo {
+	/// Decoded image. (shared between the clones)
+	image: Rc<RgbImage>,
+}
+
+impl PartialEq for Photo {
+	fn eq(&self, other: &Self) -> bool {
+		Rc::ptr_eq(&self.image, &other.image)
+	}
+}
+
+impl Photo {
+	/// Constructs a new instance of `Photo` from the encoded image.
+	pub fn new(data: &[u8]) -> Result<Self> {
+		Ok(Self {
+			image: Rc::new(image::load_from_memory(data)?.to_rgb8()),
+		})
+	}
+
+	/// Returns the decoded image.
+	pub fn get_image(&self) -> &RgbImage {
+		&self.image
+	}
+
+	/// Returns the size (in cells) that fits the given area.
+	///
+	/// Aspect ratio of the image is preserved.
+	pub fn get_size(&self, area: Rect) -> (u16, u16) {
+		let (width, height) = (
+			f64::from(self.image.width().max(1)),
+			f64::from(self.image.height().max(1)),
+		);
+		let scale = (f64::from(area.width) / width)
+			.min(f64::from(area.height) * 2. / height);
+		(
+			((width * scale) as u16).clamp(1, area.width.max(1)),
+			((height * scale / 2.) as u16).clamp(1, area.height.max(1)),
+		)
+	}
+}
+
+impl Widget for &Photo {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let area = area.intersection(buf.area);
+		if area.is_empty() {
+			return;
+		}
+		let (width, height) = self.get_size(area);
+		let image = image::imageops::resize(
+			self.image.as_ref(),
+			u32::from(width),
+			u32::from(height) * 2,
+			FilterType::Triangle,
+		);
+		let x_offset = area.x + (area.width - width) / 2;
+		for (x, y, pixel) in image.enumerate_pixels() {
+			let color = Color::Rgb(pixel[0], pixel[1], pixel[2]);
+			let cell = buf.get_mut(x_offset + x as u16, area.y + y as u16 / 2);
+			cell.set_symbol(HALF_BLOCK);
+			if y % 2 == 0 {
+				cell.set_fg(color);
+			} else {
+				cell.set_bg(color);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::{ImageFormat, Rgb};
+	use pretty_assertions::assert_eq;
+	use std::io::Cursor;
+	#[test]
+	fn test_widget_photo() -> Result<()> {
+		let mut data = Vec::new();
+		RgbImage::from_pixel(40, 20, Rgb([255, 0, 0]))
+			.write_to(&mut Cursor::new(&mut data), ImageFormat::Jpeg)?;
+		let photo = Photo::new(&data)?;
+		assert_eq!((20, 5), photo.get_size(Rect::new(0, 0, 20, 10)));
+		assert_eq!((20, 5), photo.get_size(Rect::new(0, 0, 40, 5)));
+		let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 10));
+		photo.render(buffer.area, &mut buffer);
+		let cell = buffer.get(0, 0);
+		assert_eq!(HALF_BLOCK, cell.symbol());
+		assert!(matches!(cell.fg, Color::Rgb(r, _, _) if r > 200));
+		assert_eq!(" ", buffer.get(0, 5).symbol());
+		assert_eq!(photo, photo.clone());
+		assert_ne!(photo, Photo::new(&data)?);
+		assert!(Photo::new(&[0xFF, 0xD8]).is_err());
+		Ok(())
+	}
+}
@@ -16,6 +16,10 @@ pub enum ScrollDirection {
 	Top,
 	/// Scroll to bottom.
 	Bottom,
+	/// Scroll to the previous page.
+	PageUp,
+	/// Scroll to the next page.
+	PageDown,
 }
 
 impl FromStr for ScrollDirection {
@@ -30,6 +34,8 @@ impl FromStr for ScrollDirection {
 			Some("left") | Some("l") => Ok(Self::Left(value)),
 			Some("top") | Some("t") => Ok(Self::Top),
 			Some("bottom") | Some("b") => Ok(Self::Bottom),
+			Some("pageup") | Some("pu") => Ok(Self::PageUp),
+			Some("pagedown") | Some("pd") => Ok(Self::PageDown),
 			_ => Err(()),
 		}
 	}
@@ -217,6 +223,11 @@ mod tests {
 			ScrollDirection::Bottom,
 			ScrollDirection::from_str("bottom")?
 		);
+		assert_eq!(ScrollDirection::PageUp, ScrollDirection::from_str("pu")?);
+		assert_eq!(
+			ScrollDirection::PageDown,
+			ScrollDirection::from_str("pagedown")?
+		);
 		assert!(ScrollDirection::from_str("xyz").is_err());
 		Ok(())
 	}
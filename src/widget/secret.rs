@@ -0,0 +1,263 @@
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::str;
+use zeroize::Zeroize;
+
+/// Maximum length of the input. (in bytes)
+///
+/// Buffer is allocated once with this capacity so that it
+/// is never reallocated (and copied) while typing.
+pub const MAX_LENGTH: usize = 256;
+
+/// Character for masking the input.
+const MASK_CHAR: char = '*';
+
+/// Minimum recommended length of a passphrase.
+const MIN_PASSPHRASE_LENGTH: usize = 12;
+
+/// Commonly used passwords and words that are easy to guess.
+const COMMON_PASSWORDS: &[&str] = &[
+	"password", "passwort", "123456", "qwerty", "azerty", "letmein", "admin",
+	"welcome", "iloveyou", "monkey", "dragon", "secret", "master", "login",
+	"abc123", "111111", "sunshine", "football", "princess", "trustno1",
+];
+
+/// Strength of a passphrase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strength {
+	/// Easy to guess.
+	Weak,
+	/// Might resist online attacks.
+	Fair,
+	/// Might resist offline attacks.
+	Good,
+	/// Hard to guess.
+	Strong,
+}
+
+impl Display for Strength {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Weak => "weak",
+				Self::Fair => "fair",
+				Self::Good => "good",
+				Self::Strong => "strong",
+			}
+		)
+	}
+}
+
+/// Estimated strength of a passphrase along with the warnings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StrengthEstimate {
+	/// Estimated strength.
+	pub strength: Strength,
+	/// Reasons that weaken the passphrase.
+	pub warnings: Vec<String>,
+}
+
+/// Input widget for secrets such as passphrases.
+///
+/// Characters are masked while rendering and the memory
+/// is overwritten with zeros when the input is dropped.
+#[derive(PartialEq, Eq)]
+pub struct SecretInput {
+	/// Bytes of the input. (UTF-8)
+	bytes: Vec<u8>,
+}
+
+impl Default for SecretInput {
+	fn default() -> Self {
+		Self {
+			bytes: Vec::with_capacity(MAX_LENGTH),
+		}
+	}
+}
+
+impl Clone for SecretInput {
+	fn clone(&self) -> Self {
+		let mut input = Self::default();
+		input.bytes.extend_from_slice(&self.bytes);
+		input
+	}
+}
+
+impl Debug for SecretInput {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		f.debug_tuple("SecretInput").field(&"[hidden]").finish()
+	}
+}
+
+impl From<&str> for SecretInput {
+	fn from(s: &str) -> Self {
+		let mut input = Self::default();
+		s.chars().for_each(|c| input.push(c));
+		input
+	}
+}
+
+impl Drop for SecretInput {
+	fn drop(&mut self) {
+		self.clear();
+	}
+}
+
+impl SecretInput {
+	/// Appends the given character to the input.
+	///
+	/// Returns `false` if the maximum length is exceeded.
+	pub fn push(&mut self, c: char) -> bool {
+		let mut buffer = [0; 4];
+		let encoded = c.encode_utf8(&mut buffer).as_bytes();
+		let pushed = self.bytes.len() + encoded.len() <= MAX_LENGTH;
+		if pushed {
+			self.bytes.extend_from_slice(encoded);
+		}
+		buffer.zeroize();
+		pushed
+	}
+
+	/// Removes the last character of the input.
+	///
+	/// Returns `false` if the input is empty.
+	pub fn pop(&mut self) -> bool {
+		match self.as_str().chars().next_back() {
+			Some(c) => {
+				let len = self.bytes.len() - c.len_utf8();
+				self.bytes[len..].zeroize();
+				self.bytes.truncate(len);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Overwrites the input with zeros and clears it.
+	pub fn clear(&mut self) {
+		self.bytes.zeroize();
+	}
+
+	/// Checks if the input is empty.
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+
+	/// Returns the input as string.
+	pub fn as_str(&self) -> &str {
+		str::from_utf8(&self.bytes).unwrap_or_default()
+	}
+
+	/// Returns the input as bytes.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Returns the masked input for rendering.
+	pub fn get_masked(&self) -> String {
+		MASK_CHAR.to_string().repeat(self.as_str().chars().count())
+	}
+
+	/// Estimates the strength of the input as a passphrase.
+	///
+	/// Entropy is approximated from the length and the character
+	/// classes, and each detected weakness lowers the strength.
+	pub fn get_strength(&self) -> StrengthEstimate {
+		let input = self.as_str();
+		let length = input.chars().count();
+		let pool_size = [
+			(input.chars().any(|c| c.is_ascii_lowercase()), 26),
+			(input.chars().any(|c| c.is_ascii_uppercase()), 26),
+			(input.chars().any(|c| c.is_ascii_digit()), 10),
+			(
+				input.chars().any(|c| c.is_ascii_punctuation() || c == ' '),
+				33,
+			),
+			(input.chars().any(|c| !c.is_ascii()), 100),
+		]
+		.iter()
+		.filter(|(present, _)| *present)
+		.map(|(_, size)| size)
+		.sum::<u32>();
+		let entropy = length as f64 * f64::from(pool_size.max(1)).log2();
+		let mut warnings = Vec::new();
+		if length < MIN_PASSPHRASE_LENGTH {
+			warnings.push(format!(
+				"shorter than {MIN_PASSPHRASE_LENGTH} characters"
+			));
+		}
+		let lowercase = input.to_lowercase();
+		if COMMON_PASSWORDS
+			.iter()
+			.any(|password| lowercase.contains(password))
+		{
+			warnings.push(String::from("contains a common password"));
+		}
+		let chars = lowercase.chars().collect::<Vec<char>>();
+		if chars.windows(3).any(|v| v[0] == v[1] && v[1] == v[2]) {
+			warnings.push(String::from("contains repeated characters"));
+		}
+		if chars.windows(4).any(|v| {
+			v.windows(2)
+				.all(|pair| pair[1] as u32 == pair[0] as u32 + 1)
+		}) {
+			warnings.push(String::from("contains a sequence"));
+		}
+		if length > 0 && chars.iter().all(char::is_ascii_digit) {
+			warnings.push(String::from("contains only digits"));
+		}
+		let strength = match entropy {
+			v if v < 40.0 => Strength::Weak,
+			v if v < 60.0 => Strength::Fair,
+			v if v < 80.0 => Strength::Good,
+			_ => Strength::Strong,
+		};
+		let strength = match (strength as usize).saturating_sub(warnings.len())
+		{
+			0 => Strength::Weak,
+			1 => Strength::Fair,
+			2 => Strength::Good,
+			_ => Strength::Strong,
+		};
+		StrengthEstimate { strength, warnings }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_widget_secret() {
+		let mut input = SecretInput::from("pässword");
+		assert_eq!("pässword", input.as_str());
+		assert_eq!("********", input.get_masked());
+		assert!(input.pop());
+		assert!(input.push('d'));
+		assert_eq!("pässword", input.clone().as_str());
+		assert_eq!("SecretInput(\"[hidden]\")", format!("{input:?}"));
+		input.clear();
+		assert!(input.is_empty());
+		assert!(!input.pop());
+		let estimate = SecretInput::from("Password1234").get_strength();
+		assert_eq!(Strength::Weak, estimate.strength);
+		assert_eq!(
+			vec![
+				String::from("contains a common password"),
+				String::from("contains a sequence")
+			],
+			estimate.warnings
+		);
+		let estimate = SecretInput::from("111").get_strength();
+		assert_eq!(3, estimate.warnings.len());
+		let estimate =
+			SecretInput::from("correct horse Battery staple!").get_strength();
+		assert_eq!(Strength::Strong, estimate.strength);
+		assert!(estimate.warnings.is_empty());
+		assert_eq!("strong", estimate.strength.to_string());
+		let mut input = SecretInput::from("x".repeat(MAX_LENGTH).as_str());
+		assert!(!input.push('x'));
+		assert_eq!(MAX_LENGTH, input.as_bytes().len());
+	}
+}
@@ -1,5 +1,6 @@
 use colorsys::Rgb;
 use ratatui::style::Color as TuiColor;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::str::FromStr;
 
 /// Wrapper for widget colors.
@@ -51,6 +52,27 @@ impl<'a> From<&'a str> for Color {
 	}
 }
 
+impl From<TuiColor> for Color {
+	fn from(inner: TuiColor) -> Self {
+		Self { inner }
+	}
+}
+
+impl Display for Color {
+	/// Formats the color in the form that can be parsed back.
+	///
+	/// Colors without a name are shown as the default color.
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		match self.inner {
+			TuiColor::Rgb(r, g, b) => write!(f, "{r:02X}{g:02X}{b:02X}"),
+			TuiColor::Reset | TuiColor::Indexed(_) => {
+				write!(f, "{}", Self::default())
+			}
+			color => write!(f, "{}", format!("{color:?}").to_lowercase()),
+		}
+	}
+}
+
 impl FromStr for Color {
 	type Err = std::convert::Infallible;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -80,5 +102,8 @@ mod tests {
 		assert_eq!(TuiColor::Rgb(18, 49, 47), Color::from("12312F").get());
 		assert_eq!(TuiColor::Rgb(255, 242, 255), Color::from("FFF2FF").get());
 		assert_eq!(TuiColor::Gray, Color::from("FF00FX").get());
+		assert_eq!("lightred", Color::from("lightred").to_string());
+		assert_eq!("12312F", Color::from("12312f").to_string());
+		assert_eq!("gray", Color::from(TuiColor::Reset).to_string());
 	}
 }
@@ -1,8 +1,14 @@
 use crate::widget::row::{ScrollAmount, ScrollDirection};
+use clap::ValueEnum;
 use ratatui::widgets::TableState as TuiState;
+use serde::{Deserialize, Serialize};
+use std::cmp;
 
 /// Table size mode.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(
+	Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum TableSize {
 	/// Normal sized table.
 	Normal,
@@ -43,6 +49,12 @@ pub struct TableState {
 	pub size: TableSize,
 	/// Threshold value (width) for minimizing.
 	pub minimize_threshold: u16,
+	/// Whether if the table is rendered in pages.
+	pub paging: bool,
+	/// Number of items in a page.
+	pub page_size: usize,
+	/// Identifiers of the collapsed items.
+	pub collapsed: Vec<String>,
 }
 
 impl Default for TableState {
@@ -52,6 +64,32 @@ impl Default for TableState {
 			scroll: ScrollAmount::default(),
 			size: TableSize::Normal,
 			minimize_threshold: 90,
+			paging: false,
+			page_size: 1,
+			collapsed: Vec::new(),
+		}
+	}
+}
+
+impl TableState {
+	/// Checks if the item with the given identifier is collapsed.
+	pub fn is_collapsed(&self, id: &str) -> bool {
+		self.collapsed.iter().any(|v| v == id)
+	}
+
+	/// Collapses/expands the item with the given identifier.
+	///
+	/// Returns `true` if the item is collapsed.
+	pub fn toggle_collapse(&mut self, id: String) -> bool {
+		match self.collapsed.iter().position(|v| v == &id) {
+			Some(index) => {
+				self.collapsed.remove(index);
+				false
+			}
+			None => {
+				self.collapsed.push(id);
+				true
+			}
 		}
 	}
 }
@@ -65,6 +103,10 @@ pub struct StatefulTable<T: Clone> {
 	pub items: Vec<T>,
 	/// Table state.
 	pub state: TableState,
+	/// Identifiers of the marked items.
+	pub marked: Vec<String>,
+	/// Index of the last (un)marked item.
+	pub mark_anchor: Option<usize>,
 }
 
 impl<T: Clone> StatefulTable<T> {
@@ -75,6 +117,8 @@ impl<T: Clone> StatefulTable<T> {
 			default_items: items.clone(),
 			items,
 			state,
+			marked: Vec::new(),
+			mark_anchor: None,
 		}
 	}
 
@@ -120,6 +164,37 @@ impl<T: Clone> StatefulTable<T> {
 		self.reset_scroll();
 	}
 
+	/// Returns the current page and the total number of pages.
+	pub fn get_page(&self) -> (usize, usize) {
+		let page_size = cmp::max(self.state.page_size, 1);
+		(
+			self.state.tui.selected().unwrap_or_default() / page_size + 1,
+			cmp::max((self.items.len() + page_size - 1) / page_size, 1),
+		)
+	}
+
+	/// Selects the first item of the next page.
+	pub fn next_page(&mut self) {
+		let (page, page_count) = self.get_page();
+		let i = if page >= page_count {
+			0
+		} else {
+			page * cmp::max(self.state.page_size, 1)
+		};
+		self.state.tui.select(Some(i));
+		self.reset_scroll();
+	}
+
+	/// Selects the first item of the previous page.
+	pub fn previous_page(&mut self) {
+		let (page, page_count) = self.get_page();
+		let i = if page <= 1 { page_count } else { page - 1 } - 1;
+		self.state
+			.tui
+			.select(Some(i * cmp::max(self.state.page_size, 1)));
+		self.reset_scroll();
+	}
+
 	/// Sets the scrolling state of the table row
 	/// depending on the given direction and offset.
 	pub fn scroll_row(&mut self, direction: ScrollDirection) {
@@ -170,6 +245,51 @@ impl<T: Clone> StatefulTable<T> {
 	pub fn reset_scroll(&mut self) {
 		self.state.scroll = ScrollAmount::default();
 	}
+
+	/// Checks if the item with the given identifier is marked.
+	pub fn is_marked(&self, id: &str) -> bool {
+		self.marked.iter().any(|v| v == id)
+	}
+
+	/// Toggles the mark of the selected item with the given identifier.
+	pub fn toggle_mark(&mut self, id: String) {
+		match self.marked.iter().position(|v| v == &id) {
+			Some(index) => {
+				self.marked.remove(index);
+			}
+			None => self.marked.push(id),
+		}
+		self.mark_anchor = self.state.tui.selected();
+	}
+
+	/// Marks the items with the given identifiers.
+	pub fn mark(&mut self, ids: Vec<String>) {
+		for id in ids {
+			if !self.is_marked(&id) {
+				self.marked.push(id);
+			}
+		}
+		self.mark_anchor = self.state.tui.selected();
+	}
+
+	/// Returns the items between the last marked and the selected item.
+	pub fn get_mark_range(&self) -> &[T] {
+		let selected = match self.state.tui.selected() {
+			Some(selected) if selected < self.items.len() => selected,
+			_ => return &[],
+		};
+		let anchor = self
+			.mark_anchor
+			.filter(|anchor| *anchor < self.items.len())
+			.unwrap_or(selected);
+		&self.items[cmp::min(anchor, selected)..=cmp::max(anchor, selected)]
+	}
+
+	/// Clears the marks.
+	pub fn clear_marks(&mut self) {
+		self.marked.clear();
+		self.mark_anchor = None;
+	}
 }
 
 #[cfg(test)]
@@ -182,6 +302,10 @@ mod tests {
 			StatefulTable::with_items(vec!["data1", "data2", "data3"]);
 		table.state.tui.select(Some(1));
 		assert_eq!(Some(&"data2"), table.selected());
+		assert!(table.state.toggle_collapse(String::from("data2")));
+		assert!(table.state.is_collapsed("data2"));
+		assert!(!table.state.toggle_collapse(String::from("data2")));
+		assert!(!table.state.is_collapsed("data2"));
 		table.next();
 		assert_eq!(Some(2), table.state.tui.selected());
 		table.previous();
@@ -210,5 +334,27 @@ mod tests {
 		table.state.size = TableSize::Minimized;
 		table.state.size.set_minimized(false);
 		assert_eq!(TableSize::Compact, table.state.size.next());
+		table.state.page_size = 2;
+		assert_eq!((1, 2), table.get_page());
+		table.next_page();
+		assert_eq!(Some(2), table.state.tui.selected());
+		assert_eq!((2, 2), table.get_page());
+		table.next_page();
+		assert_eq!(Some(0), table.state.tui.selected());
+		table.previous_page();
+		assert_eq!(Some(2), table.state.tui.selected());
+		table.previous_page();
+		assert_eq!(Some(0), table.state.tui.selected());
+		table.toggle_mark(String::from("data1"));
+		assert!(table.is_marked("data1"));
+		table.state.tui.select(Some(2));
+		assert_eq!(&["data1", "data2", "data3"], table.get_mark_range());
+		table.mark(vec![String::from("data2"), String::from("data3")]);
+		assert_eq!(vec!["data1", "data2", "data3"], table.marked);
+		assert_eq!(&["data3"], table.get_mark_range());
+		table.toggle_mark(String::from("data2"));
+		assert!(!table.is_marked("data2"));
+		table.clear_marks();
+		assert!(table.marked.is_empty());
 	}
 }